@@ -0,0 +1,449 @@
+//! Compares storing chart data as a row per harvest, as it used to be, against the column-based
+//! store, on a machine with many CPU cores and a fast refresh rate.  Run with:
+//!
+//! ```bash
+//! cargo bench --bench time_series
+//! ```
+//!
+//! Both go through the same harvests, cleaning, and conversion of CPU usage to chart points as
+//! bottom does.  The row-based side is the code from before the store, copied into [`rows`].
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    hint::black_box,
+    sync::atomic::{AtomicUsize, Ordering},
+    time::{Duration, Instant},
+};
+
+use bottom::{
+    app::{
+        data_farmer::DataCollection,
+        data_harvester::{cpu::CpuData, memory::MemHarvest, network::NetworkHarvest, Data},
+    },
+    constants::STALE_MAX_MILLISECONDS,
+    data_conversion::{convert_cpu_data_points, ConvertedCpuData},
+};
+
+const CPU_COUNT: usize = 128;
+const REFRESH_RATE_MILLISECONDS: u64 = 250;
+const HARVEST_COUNT: u64 = 60 * 60 * 1000 / REFRESH_RATE_MILLISECONDS;
+/// Data is cleaned as often as bottom does with the default retention, a minute after it goes
+/// stale.
+const CLEAN_EVERY: u64 = (STALE_MAX_MILLISECONDS + 60 * 1000) / REFRESH_RATE_MILLISECONDS;
+const CONVERSION_COUNT: usize = 50;
+
+/// Keeps track of how much is allocated, and in how many allocations.
+struct CountingAllocator;
+
+static BYTES_IN_USE: AtomicUsize = AtomicUsize::new(0);
+static PEAK_BYTES_IN_USE: AtomicUsize = AtomicUsize::new(0);
+static ALLOCATIONS_IN_USE: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            let in_use = BYTES_IN_USE.fetch_add(layout.size(), Ordering::Relaxed) + layout.size();
+            PEAK_BYTES_IN_USE.fetch_max(in_use, Ordering::Relaxed);
+            ALLOCATIONS_IN_USE.fetch_add(1, Ordering::Relaxed);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        BYTES_IN_USE.fetch_sub(layout.size(), Ordering::Relaxed);
+        ALLOCATIONS_IN_USE.fetch_sub(1, Ordering::Relaxed);
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = System.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() {
+            BYTES_IN_USE.fetch_sub(layout.size(), Ordering::Relaxed);
+            let in_use = BYTES_IN_USE.fetch_add(new_size, Ordering::Relaxed) + new_size;
+            PEAK_BYTES_IN_USE.fetch_max(in_use, Ordering::Relaxed);
+        }
+        new_ptr
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+/// What one way of storing the data took.
+struct Measurement {
+    harvest_time: Duration,
+    conversion_time: Duration,
+    bytes_in_use: usize,
+    peak_bytes_in_use: usize,
+    allocations_in_use: usize,
+}
+
+/// A harvest of everything that goes into the charts.
+fn get_harvest(harvest: u64, instant: Instant) -> Box<Data> {
+    let mem = MemHarvest {
+        use_percent: Some(50.0),
+        ..MemHarvest::default()
+    };
+    let swap = MemHarvest {
+        use_percent: Some(10.0),
+        ..MemHarvest::default()
+    };
+
+    Box::new(Data {
+        last_collection_time: instant,
+        cpu: Some(
+            (0..CPU_COUNT)
+                .map(|itx| CpuData {
+                    cpu_prefix: "CPU".to_string(),
+                    cpu_count: Some(itx),
+                    cpu_usage: ((harvest as usize * 7 + itx) % 100) as f64,
+                })
+                .collect(),
+        ),
+        load_avg: Some([1.0, 1.0, 1.0]),
+        memory: Some(mem),
+        swap: Some(swap),
+        network: Some(NetworkHarvest {
+            rx: harvest * 1000,
+            tx: harvest * 500,
+            total_rx: 0,
+            total_tx: 0,
+        }),
+        ..Data::default()
+    })
+}
+
+/// Runs through an hour of harvests, then converts CPU usage to chart points a number of times,
+/// like drawing does.  Only eating the harvests and cleaning count towards the harvest time, not
+/// making them.
+fn measure<T>(
+    start: Instant, mut harvest: impl FnMut(&mut T, Box<Data>), mut clean: impl FnMut(&mut T),
+    mut convert: impl FnMut(&T), mut data: T,
+) -> Measurement {
+    let (bytes_before, allocations_before) = (
+        BYTES_IN_USE.load(Ordering::Relaxed),
+        ALLOCATIONS_IN_USE.load(Ordering::Relaxed),
+    );
+    PEAK_BYTES_IN_USE.store(bytes_before, Ordering::Relaxed);
+
+    let mut harvest_time = Duration::default();
+    for index in 0..HARVEST_COUNT {
+        let harvested_data = get_harvest(
+            index,
+            start + Duration::from_millis(index * REFRESH_RATE_MILLISECONDS),
+        );
+        let harvest_start = Instant::now();
+        harvest(&mut data, harvested_data);
+        if (index + 1) % CLEAN_EVERY == 0 {
+            clean(&mut data);
+        }
+        harvest_time += harvest_start.elapsed();
+    }
+    let bytes_in_use = BYTES_IN_USE.load(Ordering::Relaxed) - bytes_before;
+    let peak_bytes_in_use = PEAK_BYTES_IN_USE.load(Ordering::Relaxed) - bytes_before;
+    let allocations_in_use = ALLOCATIONS_IN_USE.load(Ordering::Relaxed) - allocations_before;
+
+    let conversion_start = Instant::now();
+    for _ in 0..CONVERSION_COUNT {
+        convert(&data);
+    }
+    let conversion_time = conversion_start.elapsed();
+    black_box(data);
+
+    Measurement {
+        harvest_time,
+        conversion_time,
+        bytes_in_use,
+        peak_bytes_in_use,
+        allocations_in_use,
+    }
+}
+
+/// How chart data was kept before the column-based store, copied from that version of bottom
+/// with everything the charts don't use left out.  Downsampling is also left out, as it does
+/// nothing with the default retention.
+mod rows {
+    use std::time::Instant;
+
+    use bottom::app::{
+        data_farmer::{TimedData, Value},
+        data_harvester::{cpu, memory, network, Data},
+    };
+    use bottom::data_conversion::ConvertedCpuData;
+
+    type Point = (f64, f64);
+
+    #[derive(Default)]
+    pub struct DataCollection {
+        pub current_instant: Option<Instant>,
+        pub timed_data_vec: Vec<(Instant, TimedData)>,
+        pub network_harvest: network::NetworkHarvest,
+        pub memory_harvest: memory::MemHarvest,
+        pub swap_harvest: memory::MemHarvest,
+        pub cpu_harvest: cpu::CpuHarvest,
+        pub load_avg_harvest: cpu::LoadAvgHarvest,
+    }
+
+    impl DataCollection {
+        pub fn clean_data(&mut self, max_time_millis: u64) {
+            let current_time = self.current_instant.unwrap();
+
+            let remove_index =
+                match self
+                    .timed_data_vec
+                    .binary_search_by(|(instant, _timed_data)| {
+                        current_time
+                            .saturating_duration_since(*instant)
+                            .as_millis()
+                            .cmp(&(max_time_millis as u128))
+                            .reverse()
+                    }) {
+                    Ok(index) => index,
+                    Err(index) => index,
+                };
+
+            self.timed_data_vec.drain(0..remove_index);
+        }
+
+        pub fn eat_data(&mut self, harvested_data: Box<Data>) {
+            let harvested_time = harvested_data.last_collection_time;
+            let mut new_entry = TimedData::default();
+
+            // Network
+            if let Some(network) = harvested_data.network {
+                self.eat_network(network, &mut new_entry);
+            }
+
+            // Memory and Swap
+            if let (Some(memory), Some(swap)) = (harvested_data.memory, harvested_data.swap) {
+                self.eat_memory_and_swap(memory, swap, &mut new_entry);
+            }
+
+            // CPU
+            if let Some(cpu) = harvested_data.cpu {
+                self.eat_cpu(cpu, &mut new_entry);
+            }
+
+            // Load Average
+            if let Some(load_avg) = harvested_data.load_avg {
+                self.eat_load_avg(load_avg, &mut new_entry);
+            }
+
+            // And we're done eating.  Update time and push the new entry!
+            self.current_instant = Some(harvested_time);
+            self.timed_data_vec.push((harvested_time, new_entry));
+        }
+
+        fn eat_memory_and_swap(
+            &mut self, memory: memory::MemHarvest, swap: memory::MemHarvest,
+            new_entry: &mut TimedData,
+        ) {
+            // Memory
+            new_entry.mem_data = memory.use_percent;
+
+            // Swap
+            new_entry.swap_data = swap.use_percent;
+
+            // In addition copy over latest data for easy reference
+            self.memory_harvest = memory;
+            self.swap_harvest = swap;
+        }
+
+        fn eat_network(&mut self, network: network::NetworkHarvest, new_entry: &mut TimedData) {
+            // RX
+            if network.rx > 0 {
+                new_entry.rx_data = network.rx as f64;
+            }
+
+            // TX
+            if network.tx > 0 {
+                new_entry.tx_data = network.tx as f64;
+            }
+
+            // In addition copy over latest data for easy reference
+            self.network_harvest = network;
+        }
+
+        fn eat_cpu(&mut self, cpu: Vec<cpu::CpuData>, new_entry: &mut TimedData) {
+            // Note this only pre-calculates the data points - the names will be
+            // within the local copy of cpu_harvest.  Since it's all sequential
+            // it probably doesn't matter anyways.
+            cpu.iter()
+                .for_each(|cpu| new_entry.cpu_data.push(cpu.cpu_usage));
+
+            self.cpu_harvest = cpu.to_vec();
+        }
+
+        fn eat_load_avg(&mut self, load_avg: cpu::LoadAvgHarvest, new_entry: &mut TimedData) {
+            new_entry.load_avg_data = load_avg;
+
+            self.load_avg_harvest = load_avg;
+        }
+    }
+
+    fn get_range(
+        timed_data: &TimedData, get: impl Fn(&TimedData) -> Option<Value>,
+    ) -> Option<(Value, Value)> {
+        let range = timed_data.range.as_ref()?;
+        Some((get(&range.min)?, get(&range.max)?))
+    }
+
+    fn push_point(points: &mut Vec<Point>, time: f64, value: f64, range: Option<(f64, f64)>) {
+        points.push((time, value));
+        if let Some((min, max)) = range {
+            points.extend(&[(time, min), (time, max), (time, value)]);
+        }
+    }
+
+    pub fn convert_cpu_data_points(
+        current_data: &DataCollection, existing_cpu_data: &mut Vec<ConvertedCpuData>,
+    ) {
+        let current_time = current_data.current_instant.unwrap();
+
+        // Initialize cpu_data_vector if the lengths don't match...
+        if let Some((_time, data)) = &current_data.timed_data_vec.last() {
+            if data.cpu_data.len() + 1 != existing_cpu_data.len() {
+                *existing_cpu_data = vec![ConvertedCpuData {
+                    cpu_name: "All".to_string(),
+                    short_cpu_name: "All".to_string(),
+                    cpu_data: vec![],
+                    legend_value: String::new(),
+                }];
+
+                existing_cpu_data.extend(
+                    data.cpu_data
+                        .iter()
+                        .enumerate()
+                        .map(|(itx, cpu_usage)| ConvertedCpuData {
+                            cpu_name: if let Some(cpu_harvest) = current_data.cpu_harvest.get(itx) {
+                                if let Some(cpu_count) = cpu_harvest.cpu_count {
+                                    format!("{}{}", cpu_harvest.cpu_prefix, cpu_count)
+                                } else {
+                                    cpu_harvest.cpu_prefix.to_string()
+                                }
+                            } else {
+                                String::default()
+                            },
+                            short_cpu_name: if let Some(cpu_harvest) =
+                                current_data.cpu_harvest.get(itx)
+                            {
+                                if let Some(cpu_count) = cpu_harvest.cpu_count {
+                                    cpu_count.to_string()
+                                } else {
+                                    cpu_harvest.cpu_prefix.to_string()
+                                }
+                            } else {
+                                String::default()
+                            },
+                            legend_value: format!("{:.0}%", cpu_usage.round()),
+                            cpu_data: vec![],
+                        })
+                        .collect::<Vec<ConvertedCpuData>>(),
+                );
+            } else {
+                existing_cpu_data
+                    .iter_mut()
+                    .skip(1)
+                    .zip(&data.cpu_data)
+                    .for_each(|(cpu, cpu_usage)| {
+                        cpu.cpu_data = vec![];
+                        cpu.legend_value = format!("{:.0}%", cpu_usage.round());
+                    });
+            }
+        }
+
+        for (time, data) in &current_data.timed_data_vec {
+            let time_from_start: f64 =
+                (current_time.duration_since(*time).as_millis() as f64).floor();
+
+            for (itx, cpu) in data.cpu_data.iter().enumerate() {
+                if let Some(cpu_data) = existing_cpu_data.get_mut(itx + 1) {
+                    push_point(
+                        &mut cpu_data.cpu_data,
+                        -time_from_start,
+                        *cpu,
+                        get_range(data, |data| data.cpu_data.get(itx).copied()),
+                    );
+                }
+            }
+
+            if *time == current_time {
+                break;
+            }
+        }
+    }
+}
+
+fn measure_rows(start: Instant) -> Measurement {
+    measure(
+        start,
+        |data_collection: &mut rows::DataCollection, harvested_data| {
+            data_collection.eat_data(harvested_data)
+        },
+        |data_collection| data_collection.clean_data(STALE_MAX_MILLISECONDS),
+        |data_collection| {
+            let mut cpu_data: Vec<ConvertedCpuData> = Vec::new();
+            rows::convert_cpu_data_points(data_collection, &mut cpu_data);
+            black_box(cpu_data);
+        },
+        rows::DataCollection::default(),
+    )
+}
+
+fn measure_columns(start: Instant) -> Measurement {
+    measure(
+        start,
+        |data_collection: &mut DataCollection, harvested_data| {
+            data_collection.eat_data(harvested_data)
+        },
+        |data_collection| data_collection.clean_data(STALE_MAX_MILLISECONDS),
+        |data_collection| {
+            let mut cpu_data: Vec<ConvertedCpuData> = Vec::new();
+            convert_cpu_data_points(data_collection, &mut cpu_data, false);
+            black_box(cpu_data);
+        },
+        DataCollection::default(),
+    )
+}
+
+fn main() {
+    let start = Instant::now();
+    let rows = measure_rows(start);
+    let columns = measure_columns(start);
+
+    println!(
+        "{} CPU cores, harvested every {}ms for an hour, keeping {} minutes\n",
+        CPU_COUNT,
+        REFRESH_RATE_MILLISECONDS,
+        STALE_MAX_MILLISECONDS / 60 / 1000
+    );
+    println!("{:<32}{:>16}{:>16}", "", "Rows", "Columns");
+    let print_time = |name: &str, get: fn(&Measurement) -> Duration| {
+        println!(
+            "{:<32}{:>14.1}ms{:>14.1}ms",
+            name,
+            get(&rows).as_secs_f64() * 1000.0,
+            get(&columns).as_secs_f64() * 1000.0
+        );
+    };
+    print_time("Harvesting and cleaning", |measurement| {
+        measurement.harvest_time
+    });
+    print_time(
+        &format!("Converting CPU points {} times", CONVERSION_COUNT),
+        |measurement| measurement.conversion_time,
+    );
+    let print_count = |name: &str, get: fn(&Measurement) -> usize| {
+        println!("{:<32}{:>16}{:>16}", name, get(&rows), get(&columns));
+    };
+    print_count("KiB in use after an hour", |measurement| {
+        measurement.bytes_in_use / 1024
+    });
+    print_count("Peak KiB in use", |measurement| {
+        measurement.peak_bytes_in_use / 1024
+    });
+    print_count("Allocations after an hour", |measurement| {
+        measurement.allocations_in_use
+    });
+}
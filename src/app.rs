@@ -1,6 +1,6 @@
 use std::{
     cmp::{max, min},
-    collections::HashMap,
+    collections::{BTreeMap, HashMap, HashSet},
     // io::Write,
     path::PathBuf,
     time::Instant,
@@ -11,6 +11,7 @@ use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
 
 use typed_builder::*;
 
+use completion::CompletionValues;
 use data_farmer::*;
 use data_harvester::{processes, temperature};
 use layout_manager::*;
@@ -18,20 +19,26 @@ pub use states::*;
 
 use crate::{
     canvas, constants,
+    data_conversion::{ConvertedProcessData, ProcessGroupingType},
     options::Config,
+    options::ConfigColumnWidths,
     options::ConfigFlags,
     options::WidgetIdEnabled,
+    replay::ReplayStatus,
     units::data_units::DataUnit,
     utils::error::{BottomError, Result},
     Pid,
 };
 
+pub mod completion;
 pub mod data_farmer;
 pub mod data_harvester;
+pub mod history;
 pub mod layout_manager;
-mod process_killer;
+pub mod process_killer;
 pub mod query;
 pub mod states;
+pub mod time_series;
 
 const MAX_SEARCH_LENGTH: usize = 200;
 
@@ -54,6 +61,7 @@ pub struct AppConfigFields {
     pub use_basic_mode: bool,
     pub default_time_value: u64,
     pub time_interval: u64,
+    pub retention: u64,
     pub hide_time: bool,
     pub autohide_time: bool,
     pub use_old_network_legend: bool,
@@ -62,6 +70,8 @@ pub struct AppConfigFields {
     pub no_write: bool,
     pub show_table_scroll_position: bool,
     pub is_advanced_kill: bool,
+    pub persist_column_widths: bool,
+    pub persist_history: bool,
     // TODO: Remove these, move network details state-side.
     pub network_unit_type: DataUnit,
     pub network_scale_type: AxisScaling,
@@ -100,6 +110,15 @@ pub struct App {
     #[builder(default = false, setter(skip))]
     pub is_frozen: bool,
 
+    /// The latest failure to export, record, or save the history of a collection, and when it
+    /// happened.
+    #[builder(default, setter(skip))]
+    pub collection_error: Option<(String, Instant)>,
+
+    /// Where the replay is at, if replaying a recording.
+    #[builder(default, setter(skip))]
+    pub replay_status: Option<ReplayStatus>,
+
     #[builder(default = Instant::now(), setter(skip))]
     last_key_press: Instant,
 
@@ -115,6 +134,15 @@ pub struct App {
     #[builder(default, setter(skip))]
     pub help_dialog_state: AppHelpDialogState,
 
+    #[builder(default, setter(skip))]
+    pub saved_query_dialog_state: AppSavedQueryDialogState,
+
+    #[builder(default, setter(skip))]
+    pub goto_pid_dialog_state: AppGotoPidDialogState,
+
+    #[builder(default, setter(skip))]
+    pub exited_process_dialog_state: AppExitedProcessDialogState,
+
     #[builder(default = false, setter(skip))]
     pub is_expanded: bool,
 
@@ -143,6 +171,7 @@ pub struct App {
     pub proc_state: ProcState,
     pub temp_state: TempState,
     pub disk_state: DiskState,
+    pub user_state: UserState,
     pub battery_state: BatteryState,
     pub basic_table_widget_state: Option<BasicTableWidgetState>,
     pub app_config_fields: AppConfigFields,
@@ -169,6 +198,9 @@ impl App {
         // Reset dialog state
         self.help_dialog_state.is_showing_help = false;
         self.delete_dialog_state.is_showing_dd = false;
+        self.saved_query_dialog_state.is_showing_saved_queries = false;
+        self.goto_pid_dialog_state.is_showing_goto_pid = false;
+        self.exited_process_dialog_state.is_showing_exited_processes = false;
 
         // Close all searches and reset it
         self.proc_state
@@ -178,6 +210,18 @@ impl App {
                 state.process_search_state.search_state.reset();
             });
         self.proc_state.force_update_all = true;
+        self.disk_state
+            .widget_states
+            .values_mut()
+            .for_each(|state| state.search_state.reset());
+        self.temp_state
+            .widget_states
+            .values_mut()
+            .for_each(|state| state.search_state.reset());
+        self.user_state
+            .widget_states
+            .values_mut()
+            .for_each(|state| state.search_state.reset());
 
         // Clear current delete list
         self.to_delete_process_list = None;
@@ -199,6 +243,18 @@ impl App {
         self.is_force_redraw || self.is_determining_widget_boundary
     }
 
+    /// Returns the latest failure to export or record, if it happened recently enough to still
+    /// be shown.
+    pub fn get_recent_collection_error(&self) -> Option<&str> {
+        self.collection_error
+            .as_ref()
+            .filter(|(_, error_time)| {
+                error_time.elapsed().as_millis()
+                    < u128::from(constants::COLLECTION_ERROR_DISPLAY_MILLISECONDS)
+            })
+            .map(|(collection_error, _)| collection_error.as_str())
+    }
+
     fn close_dd(&mut self) {
         self.delete_dialog_state.is_showing_dd = false;
         self.delete_dialog_state.selected_signal = KillSignal::default();
@@ -213,6 +269,12 @@ impl App {
             if self.help_dialog_state.is_showing_help {
                 self.help_dialog_state.is_showing_help = false;
                 self.help_dialog_state.scroll_state.current_scroll_index = 0;
+            } else if self.saved_query_dialog_state.is_showing_saved_queries {
+                self.saved_query_dialog_state.is_showing_saved_queries = false;
+            } else if self.goto_pid_dialog_state.is_showing_goto_pid {
+                self.goto_pid_dialog_state.is_showing_goto_pid = false;
+            } else if self.exited_process_dialog_state.is_showing_exited_processes {
+                self.exited_process_dialog_state.is_showing_exited_processes = false;
             } else {
                 self.close_dd();
             }
@@ -244,7 +306,12 @@ impl App {
                         .proc_state
                         .get_mut_widget_state(self.current_widget.widget_id - 1)
                     {
-                        if current_proc_state.is_search_enabled() {
+                        if current_proc_state.is_completion_showing() {
+                            current_proc_state.close_completion();
+                            self.is_force_redraw = true;
+                            return;
+                        } else if current_proc_state.is_search_enabled() {
+                            current_proc_state.add_query_to_history();
                             current_proc_state
                                 .process_search_state
                                 .search_state
@@ -255,6 +322,14 @@ impl App {
                         }
                     }
                 }
+                BottomWidgetType::Disk | BottomWidgetType::Temp | BottomWidgetType::User => {
+                    if let Some(search_state) = self.get_mut_table_search_state() {
+                        // Like with processes, the query still applies after closing.
+                        search_state.is_enabled = false;
+                        self.is_force_redraw = true;
+                        return;
+                    }
+                }
                 BottomWidgetType::ProcSort => {
                     if let Some(current_proc_state) = self
                         .proc_state
@@ -273,6 +348,14 @@ impl App {
                 _ => {}
             }
 
+            if let Some(width_state) = self.get_mut_table_width_state() {
+                if width_state.selected_column.is_some() {
+                    width_state.selected_column = None;
+                    self.is_force_redraw = true;
+                    return;
+                }
+            }
+
             if self.is_expanded {
                 self.is_expanded = false;
                 self.is_force_redraw = true;
@@ -284,7 +367,98 @@ impl App {
         matches!(
             self.current_widget.widget_type,
             BottomWidgetType::ProcSearch
-        )
+        ) || self.get_table_search_state().is_some()
+    }
+
+    /// Returns the search state of the current disk, temperature or user widget, if it is being searched.
+    fn get_table_search_state(&self) -> Option<&AppSearchState> {
+        let widget_id = self.current_widget.widget_id;
+        match self.current_widget.widget_type {
+            BottomWidgetType::Disk => self
+                .disk_state
+                .get_widget_state(widget_id)
+                .map(|disk_widget_state| &disk_widget_state.search_state),
+            BottomWidgetType::Temp => self
+                .temp_state
+                .get_widget_state(widget_id)
+                .map(|temp_widget_state| &temp_widget_state.search_state),
+            BottomWidgetType::User => self
+                .user_state
+                .get_widget_state(widget_id)
+                .map(|user_widget_state| &user_widget_state.search_state),
+            _ => None,
+        }
+        .filter(|search_state| search_state.is_enabled)
+    }
+
+    /// Returns the column width state of the current process, disk, temperature or user widget.
+    fn get_mut_table_width_state(&mut self) -> Option<&mut CanvasTableWidthState> {
+        let widget_id = self.current_widget.widget_id;
+        match self.current_widget.widget_type {
+            BottomWidgetType::Proc => self
+                .proc_state
+                .get_mut_widget_state(widget_id)
+                .map(|proc_widget_state| &mut proc_widget_state.table_width_state),
+            BottomWidgetType::Disk => self
+                .disk_state
+                .get_mut_widget_state(widget_id)
+                .map(|disk_widget_state| &mut disk_widget_state.table_width_state),
+            BottomWidgetType::Temp => self
+                .temp_state
+                .get_mut_widget_state(widget_id)
+                .map(|temp_widget_state| &mut temp_widget_state.table_width_state),
+            BottomWidgetType::User => self
+                .user_state
+                .get_mut_widget_state(widget_id)
+                .map(|user_widget_state| &mut user_widget_state.table_width_state),
+            _ => None,
+        }
+    }
+
+    fn get_mut_table_search_state(&mut self) -> Option<&mut AppSearchState> {
+        let widget_id = self.current_widget.widget_id;
+        match self.current_widget.widget_type {
+            BottomWidgetType::Disk => self
+                .disk_state
+                .get_mut_widget_state(widget_id)
+                .map(|disk_widget_state| &mut disk_widget_state.search_state),
+            BottomWidgetType::Temp => self
+                .temp_state
+                .get_mut_widget_state(widget_id)
+                .map(|temp_widget_state| &mut temp_widget_state.search_state),
+            BottomWidgetType::User => self
+                .user_state
+                .get_mut_widget_state(widget_id)
+                .map(|user_widget_state| &mut user_widget_state.search_state),
+            _ => None,
+        }
+        .filter(|search_state| search_state.is_enabled)
+    }
+
+    /// Re-parses the query of the current disk, temperature or user widget after it was edited.
+    fn update_table_query(&mut self) {
+        let widget_id = self.current_widget.widget_id;
+        match self.current_widget.widget_type {
+            BottomWidgetType::Disk => {
+                if let Some(disk_widget_state) = self.disk_state.get_mut_widget_state(widget_id) {
+                    disk_widget_state.update_query();
+                    self.disk_state.force_update = Some(widget_id);
+                }
+            }
+            BottomWidgetType::Temp => {
+                if let Some(temp_widget_state) = self.temp_state.get_mut_widget_state(widget_id) {
+                    temp_widget_state.update_query();
+                    self.temp_state.force_update = Some(widget_id);
+                }
+            }
+            BottomWidgetType::User => {
+                if let Some(user_widget_state) = self.user_state.get_mut_widget_state(widget_id) {
+                    user_widget_state.update_query();
+                    self.user_state.force_update = Some(widget_id);
+                }
+            }
+            _ => {}
+        }
     }
 
     fn reset_multi_tap_keys(&mut self) {
@@ -293,7 +467,11 @@ impl App {
     }
 
     fn is_in_dialog(&self) -> bool {
-        self.help_dialog_state.is_showing_help || self.delete_dialog_state.is_showing_dd
+        self.help_dialog_state.is_showing_help
+            || self.delete_dialog_state.is_showing_dd
+            || self.saved_query_dialog_state.is_showing_saved_queries
+            || self.goto_pid_dialog_state.is_showing_goto_pid
+            || self.exited_process_dialog_state.is_showing_exited_processes
     }
 
     fn ignore_normal_keybinds(&self) -> bool {
@@ -322,64 +500,87 @@ impl App {
                         // Do NOT allow when in tree mode!
                         if !proc_widget_state.is_tree_mode {
                             // Toggles process widget grouping state
-                            proc_widget_state.is_grouped = !(proc_widget_state.is_grouped);
-
-                            // Forcefully switch off column if we were on it...
-                            if (proc_widget_state.is_grouped
-                                && (proc_widget_state.process_sorting_type
-                                    == processes::ProcessSorting::Pid
-                                    || proc_widget_state.process_sorting_type
-                                        == processes::ProcessSorting::User
-                                    || proc_widget_state.process_sorting_type
-                                        == processes::ProcessSorting::State))
-                                || (!proc_widget_state.is_grouped
-                                    && proc_widget_state.process_sorting_type
-                                        == processes::ProcessSorting::Count)
-                            {
-                                proc_widget_state.process_sorting_type =
-                                    processes::ProcessSorting::CpuPercent; // Go back to default, negate PID for group
-                                proc_widget_state.is_process_sort_descending = true;
-                            }
-
-                            proc_widget_state.columns.set_to_sorted_index_from_type(
-                                &proc_widget_state.process_sorting_type,
-                            );
-
-                            proc_widget_state.columns.try_set(
-                                &processes::ProcessSorting::State,
-                                !(proc_widget_state.is_grouped),
-                            );
-
-                            #[cfg(target_family = "unix")]
-                            proc_widget_state.columns.try_set(
-                                &processes::ProcessSorting::User,
-                                !(proc_widget_state.is_grouped),
+                            proc_widget_state.set_grouping_type(
+                                if proc_widget_state.is_grouped() {
+                                    ProcessGroupingType::Ungrouped
+                                } else {
+                                    ProcessGroupingType::Name
+                                },
                             );
 
-                            proc_widget_state
-                                .columns
-                                .toggle(&processes::ProcessSorting::Count);
-                            proc_widget_state
-                                .columns
-                                .toggle(&processes::ProcessSorting::Pid);
-
                             proc_widget_state.requires_redraw = true;
                             self.proc_state.force_update = Some(self.current_widget.widget_id);
                         }
                     }
                 }
+                BottomWidgetType::ProcSearch => {
+                    let completion_values = self.get_completion_values();
+                    if let Some(proc_widget_state) = self
+                        .proc_state
+                        .get_mut_widget_state(self.current_widget.widget_id - 1)
+                    {
+                        proc_widget_state.complete_search(&completion_values);
+                        self.proc_state.force_update = Some(self.current_widget.widget_id - 1);
+                    }
+                }
                 _ => {}
             }
         }
     }
 
+    /// Gathers the names, users, and states of the current processes for search completion.
+    fn get_completion_values(&mut self) -> CompletionValues {
+        let mut completion_values = CompletionValues::default();
+        for process in &self.data_collection.process_harvest {
+            completion_values.names.push(process.name.clone());
+            completion_values.states.push(process.process_state.clone());
+
+            #[cfg(target_family = "unix")]
+            {
+                if let Some(uid) = process.uid {
+                    if let Ok(user) = self.user_table.get_uid_to_username_mapping(uid) {
+                        completion_values.users.push(user);
+                    }
+                }
+            }
+        }
+        completion_values.dedup();
+
+        completion_values
+    }
+
+    /// Cycles the current process widget through each grouping type.
+    pub fn on_back_tab(&mut self) {
+        if self.ignore_normal_keybinds() {
+            return;
+        }
+
+        if let BottomWidgetType::Proc = self.current_widget.widget_type {
+            if let Some(proc_widget_state) = self
+                .proc_state
+                .get_mut_widget_state(self.current_widget.widget_id)
+            {
+                // Do NOT allow when in tree mode!
+                if !proc_widget_state.is_tree_mode {
+                    proc_widget_state.set_grouping_type(proc_widget_state.grouping_type.next());
+                    proc_widget_state.requires_redraw = true;
+                    self.proc_state.force_update = Some(self.current_widget.widget_id);
+                }
+            }
+        }
+    }
+
     /// I don't like this, but removing it causes a bunch of breakage.
-    /// Use ``proc_widget_state.is_grouped`` if possible!
+    /// Use ``proc_widget_state.is_grouped()`` if possible!
     pub fn is_grouped(&self, widget_id: u64) -> bool {
+        self.get_grouping_type(widget_id) != ProcessGroupingType::Ungrouped
+    }
+
+    pub fn get_grouping_type(&self, widget_id: u64) -> ProcessGroupingType {
         if let Some(proc_widget_state) = self.proc_state.widget_states.get(&widget_id) {
-            proc_widget_state.is_grouped
+            proc_widget_state.grouping_type
         } else {
-            false
+            ProcessGroupingType::Ungrouped
         }
     }
 
@@ -403,6 +604,33 @@ impl App {
                         self.is_force_redraw = true;
                     }
                 }
+                BottomWidgetType::Disk => {
+                    if let Some(disk_widget_state) = self
+                        .disk_state
+                        .get_mut_widget_state(self.current_widget.widget_id)
+                    {
+                        disk_widget_state.search_state.is_enabled = true;
+                        self.is_force_redraw = true;
+                    }
+                }
+                BottomWidgetType::Temp => {
+                    if let Some(temp_widget_state) = self
+                        .temp_state
+                        .get_mut_widget_state(self.current_widget.widget_id)
+                    {
+                        temp_widget_state.search_state.is_enabled = true;
+                        self.is_force_redraw = true;
+                    }
+                }
+                BottomWidgetType::User => {
+                    if let Some(user_widget_state) = self
+                        .user_state
+                        .get_mut_widget_state(self.current_widget.widget_id)
+                    {
+                        user_widget_state.search_state.is_enabled = true;
+                        self.is_force_redraw = true;
+                    }
+                }
                 _ => {}
             }
         }
@@ -460,6 +688,13 @@ impl App {
                     self.proc_state.force_update = Some(widget_id);
                 }
             }
+            BottomWidgetType::User => {
+                let widget_id = self.current_widget.widget_id;
+                if let Some(user_widget_state) = self.user_state.get_mut_widget_state(widget_id) {
+                    user_widget_state.is_sort_descending = !user_widget_state.is_sort_descending;
+                    self.user_state.force_update = Some(widget_id);
+                }
+            }
             _ => {}
         }
     }
@@ -482,17 +717,25 @@ impl App {
                         .columns
                         .toggle(&processes::ProcessSorting::MemPercent)
                     {
+                        let new_mem_sort_type = if mem_percent_state {
+                            processes::ProcessSorting::MemPercent
+                        } else {
+                            processes::ProcessSorting::Mem
+                        };
+
                         if proc_widget_state.process_sorting_type
                             == processes::ProcessSorting::MemPercent
                             || proc_widget_state.process_sorting_type
                                 == processes::ProcessSorting::Mem
                         {
-                            if mem_percent_state {
-                                proc_widget_state.process_sorting_type =
-                                    processes::ProcessSorting::MemPercent;
-                            } else {
-                                proc_widget_state.process_sorting_type =
-                                    processes::ProcessSorting::Mem;
+                            proc_widget_state.process_sorting_type = new_mem_sort_type.clone();
+                        }
+
+                        for (sort_type, _) in &mut proc_widget_state.secondary_sort_keys {
+                            if let processes::ProcessSorting::MemPercent
+                            | processes::ProcessSorting::Mem = sort_type
+                            {
+                                *sort_type = new_mem_sort_type.clone();
                             }
                         }
                     }
@@ -687,7 +930,7 @@ impl App {
             // FIXME: For consistency, either disable tree mode if grouped, or allow grouped mode if in tree mode.
             if proc_widget_state.is_tree_mode {
                 // Disable grouping if so!
-                proc_widget_state.is_grouped = false;
+                proc_widget_state.grouping_type = ProcessGroupingType::Ungrouped;
 
                 proc_widget_state
                     .columns
@@ -709,6 +952,7 @@ impl App {
                 // We enabled... set PID sort type to ascending.
                 proc_widget_state.process_sorting_type = processes::ProcessSorting::Pid;
                 proc_widget_state.is_process_sort_descending = false;
+                proc_widget_state.secondary_sort_keys.clear();
             }
 
             self.proc_state.force_update = Some(self.current_widget.widget_id);
@@ -716,6 +960,26 @@ impl App {
         }
     }
 
+    /// Adds the selected column in the sort widget as a secondary sort key, keeping the sort
+    /// widget open so more keys can be picked.
+    pub fn on_alt_enter(&mut self) {
+        if self.is_in_dialog() {
+            return;
+        }
+
+        if let BottomWidgetType::ProcSort = self.current_widget.widget_type {
+            if let Some(proc_widget_state) = self
+                .proc_state
+                .widget_states
+                .get_mut(&(self.current_widget.widget_id - 2))
+            {
+                proc_widget_state.add_sort_key_with_columns();
+                proc_widget_state.requires_redraw = true;
+                self.proc_state.force_update = Some(self.current_widget.widget_id - 2);
+            }
+        }
+    }
+
     /// One of two functions allowed to run while in a dialog...
     pub fn on_enter(&mut self) {
         if self.delete_dialog_state.is_showing_dd {
@@ -742,23 +1006,277 @@ impl App {
                 self.delete_dialog_state.is_showing_dd = false;
             }
             self.is_force_redraw = true;
+        } else if self.saved_query_dialog_state.is_showing_saved_queries {
+            self.apply_selected_saved_query();
+        } else if self.goto_pid_dialog_state.is_showing_goto_pid {
+            self.apply_goto_pid();
         } else if !self.is_in_dialog() {
-            if let BottomWidgetType::ProcSort = self.current_widget.widget_type {
-                if let Some(proc_widget_state) = self
-                    .proc_state
-                    .widget_states
-                    .get_mut(&(self.current_widget.widget_id - 2))
-                {
-                    proc_widget_state.update_sorting_with_columns();
-                    self.proc_state.force_update = Some(self.current_widget.widget_id - 2);
-                    self.toggle_sort();
+            match self.current_widget.widget_type {
+                BottomWidgetType::ProcSort => {
+                    if let Some(proc_widget_state) = self
+                        .proc_state
+                        .widget_states
+                        .get_mut(&(self.current_widget.widget_id - 2))
+                    {
+                        proc_widget_state.update_sorting_with_columns();
+                        self.proc_state.force_update = Some(self.current_widget.widget_id - 2);
+                        self.toggle_sort();
+                    }
+                }
+                BottomWidgetType::ProcSearch => {
+                    if let Some(proc_widget_state) = self
+                        .proc_state
+                        .widget_states
+                        .get_mut(&(self.current_widget.widget_id - 1))
+                    {
+                        if proc_widget_state.is_completion_showing() {
+                            proc_widget_state.close_completion();
+                        } else {
+                            proc_widget_state.add_query_to_history();
+                        }
+                    }
                 }
+                BottomWidgetType::User => self.show_selected_user_processes(),
+                _ => {}
             }
         }
     }
 
+    /// Makes the selection of the current process widget stick to the selected process, or stops
+    /// doing so.
+    pub fn toggle_follow(&mut self) {
+        if let BottomWidgetType::Proc = self.current_widget.widget_type {
+            let widget_id = self.current_widget.widget_id;
+            if let Some(proc_widget_state) = self.proc_state.get_mut_widget_state(widget_id) {
+                let selected_process = self
+                    .canvas_data
+                    .finalized_process_data_map
+                    .get(&widget_id)
+                    .and_then(|processes| {
+                        processes.get(proc_widget_state.scroll_state.current_scroll_position)
+                    });
+                proc_widget_state.toggle_follow(selected_process);
+                self.is_force_redraw = true;
+            }
+        }
+    }
+
+    /// Opens the prompt for jumping to a PID in the current process widget.
+    pub fn open_goto_pid(&mut self) {
+        if let BottomWidgetType::Proc = self.current_widget.widget_type {
+            self.goto_pid_dialog_state = AppGotoPidDialogState {
+                is_showing_goto_pid: true,
+                widget_id: self.current_widget.widget_id,
+                ..AppGotoPidDialogState::default()
+            };
+            self.is_force_redraw = true;
+        }
+    }
+
+    /// Opens the list of processes that have exited since bottom started.
+    pub fn open_exited_processes(&mut self) {
+        if let BottomWidgetType::Proc = self.current_widget.widget_type {
+            self.exited_process_dialog_state.is_showing_exited_processes = true;
+            self.exited_process_dialog_state.table_state.select(Some(0));
+            self.is_force_redraw = true;
+        }
+    }
+
+    fn change_exited_process_position(&mut self, num_to_change_by: i64) {
+        let num_exited = self.data_collection.exited_processes.len();
+        let current_posn = self
+            .exited_process_dialog_state
+            .table_state
+            .selected()
+            .unwrap_or(0);
+        let new_posn =
+            (current_posn as i64 + num_to_change_by).clamp(0, num_exited.saturating_sub(1) as i64);
+        self.exited_process_dialog_state
+            .table_state
+            .select(Some(new_posn as usize));
+    }
+
+    fn apply_goto_pid(&mut self) {
+        let input = self.goto_pid_dialog_state.input.clone();
+        if input.is_empty() {
+            return;
+        }
+
+        match input.parse::<Pid>() {
+            Ok(pid) if self.go_to_pid(self.goto_pid_dialog_state.widget_id, pid) => {
+                self.goto_pid_dialog_state.is_showing_goto_pid = false;
+            }
+            _ => {
+                self.goto_pid_dialog_state.error = Some(format!("No process with PID {}", input));
+            }
+        }
+        self.is_force_redraw = true;
+    }
+
+    /// Selects the process with the given PID in the given process widget, clearing the search,
+    /// ungrouping, or expanding the tree if that's what it takes to show it.  Returns whether
+    /// there is such a process.
+    pub fn go_to_pid(&mut self, widget_id: u64, pid: Pid) -> bool {
+        if let Some(proc_widget_state) = self.proc_state.widget_states.get_mut(&widget_id) {
+            if proc_widget_state.go_to_pid(pid, &self.canvas_data.single_process_data) {
+                self.proc_state.force_update = Some(widget_id);
+                self.is_force_redraw = true;
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Returns the process selected in the current process widget, unless processes are grouped.
+    fn get_selected_process(&self) -> Option<&ConvertedProcessData> {
+        if let BottomWidgetType::Proc = self.current_widget.widget_type {
+            let widget_id = self.current_widget.widget_id;
+            let proc_widget_state = self.proc_state.get_widget_state(widget_id)?;
+            if !proc_widget_state.is_grouped() {
+                return self
+                    .canvas_data
+                    .finalized_process_data_map
+                    .get(&widget_id)?
+                    .get(proc_widget_state.scroll_state.current_scroll_position);
+            }
+        }
+        None
+    }
+
+    /// Moves the selection of the current process widget to the parent of the selected process.
+    pub fn select_parent_process(&mut self) {
+        if let Some(ppid) = self.get_selected_process().and_then(|process| process.ppid) {
+            self.go_to_pid(self.current_widget.widget_id, ppid);
+        }
+    }
+
+    /// Moves the selection of the current process widget to the first child of the selected
+    /// process - the first one in the table if any are shown, or the lowest PID otherwise.
+    pub fn select_first_child_process(&mut self) {
+        let widget_id = self.current_widget.widget_id;
+        let child_pid = self.get_selected_process().and_then(|selected| {
+            let is_child = |process: &&ConvertedProcessData| process.ppid == Some(selected.pid);
+            self.canvas_data
+                .finalized_process_data_map
+                .get(&widget_id)
+                .and_then(|processes| processes.iter().find(is_child))
+                .or_else(|| {
+                    self.canvas_data
+                        .single_process_data
+                        .values()
+                        .filter(is_child)
+                        .min_by_key(|process| process.pid)
+                })
+                .map(|child| child.pid)
+        });
+
+        if let Some(child_pid) = child_pid {
+            self.go_to_pid(widget_id, child_pid);
+        }
+    }
+
+    /// Jumps to the first process widget, filtered down to the processes of the user selected in
+    /// the current user widget.
+    fn show_selected_user_processes(&mut self) {
+        let widget_id = self.current_widget.widget_id;
+        let selected_user = match (
+            self.user_state.get_widget_state(widget_id),
+            self.canvas_data.user_data_map.get(&widget_id),
+        ) {
+            (Some(user_widget_state), Some(user_data)) => user_data
+                .get(user_widget_state.scroll_state.current_scroll_position)
+                .map(|user| user.user.clone()),
+            _ => None,
+        };
+
+        let proc_widget = self
+            .widget_map
+            .values()
+            .filter(|widget| widget.widget_type == BottomWidgetType::Proc)
+            .min_by_key(|widget| widget.widget_id)
+            .cloned();
+
+        if let (Some(user), Some(proc_widget)) = (selected_user, proc_widget) {
+            if let Some(proc_widget_state) =
+                self.proc_state.get_mut_widget_state(proc_widget.widget_id)
+            {
+                proc_widget_state.search_for_user(&user);
+                self.proc_state.force_update = Some(proc_widget.widget_id);
+            }
+
+            self.is_expanded = false;
+            self.current_widget = proc_widget;
+            self.is_force_redraw = true;
+        }
+    }
+
+    /// Opens the saved query picker for the currently selected process widget.
+    pub fn open_saved_queries(&mut self) {
+        if !self.ignore_normal_keybinds() {
+            let widget_id = self.current_widget.widget_id
+                - match self.current_widget.widget_type {
+                    BottomWidgetType::Proc => 0,
+                    BottomWidgetType::ProcSearch => 1,
+                    BottomWidgetType::ProcSort => 2,
+                    _ => return,
+                };
+
+            self.saved_query_dialog_state.is_showing_saved_queries = true;
+            self.saved_query_dialog_state.widget_id = widget_id;
+            self.saved_query_dialog_state.list_state.select(
+                if self.config.get_saved_queries().next().is_some() {
+                    Some(0)
+                } else {
+                    None
+                },
+            );
+            self.is_force_redraw = true;
+        }
+    }
+
+    fn change_saved_query_position(&mut self, num_to_change_by: i64) {
+        let num_queries = self.config.get_saved_queries().count();
+        if let Some(current_posn) = self.saved_query_dialog_state.list_state.selected() {
+            let new_posn = (current_posn as i64 + num_to_change_by)
+                .clamp(0, num_queries.saturating_sub(1) as i64);
+            self.saved_query_dialog_state
+                .list_state
+                .select(Some(new_posn as usize));
+        }
+    }
+
+    /// Replaces the search query of the picker's process widget with the selected saved query.
+    fn apply_selected_saved_query(&mut self) {
+        let widget_id = self.saved_query_dialog_state.widget_id;
+        let selected_query = self
+            .saved_query_dialog_state
+            .list_state
+            .selected()
+            .and_then(|index| self.config.get_saved_queries().nth(index))
+            .map(|(_name, query)| query.to_string());
+
+        if let Some(query) = selected_query {
+            if let Some(proc_widget_state) = self.proc_state.get_mut_widget_state(widget_id) {
+                proc_widget_state
+                    .process_search_state
+                    .search_state
+                    .is_enabled = true;
+                proc_widget_state.set_search_query(query);
+                proc_widget_state.add_query_to_history();
+                self.proc_state.force_update = Some(widget_id);
+            }
+        }
+
+        self.saved_query_dialog_state.is_showing_saved_queries = false;
+        self.is_force_redraw = true;
+    }
+
     pub fn on_delete(&mut self) {
-        if let BottomWidgetType::ProcSearch = self.current_widget.widget_type {
+        if let Some(search_state) = self.get_mut_table_search_state() {
+            if search_state.delete_next_char() {
+                self.update_table_query();
+            }
+        } else if let BottomWidgetType::ProcSearch = self.current_widget.widget_type {
             let is_in_search_widget = self.is_in_search_widget();
             if let Some(proc_widget_state) = self
                 .proc_state
@@ -812,7 +1330,14 @@ impl App {
     }
 
     pub fn on_backspace(&mut self) {
-        if let BottomWidgetType::ProcSearch = self.current_widget.widget_type {
+        if self.goto_pid_dialog_state.is_showing_goto_pid {
+            self.goto_pid_dialog_state.input.pop();
+            self.goto_pid_dialog_state.error = None;
+        } else if let Some(search_state) = self.get_mut_table_search_state() {
+            if search_state.delete_previous_char() {
+                self.update_table_query();
+            }
+        } else if let BottomWidgetType::ProcSearch = self.current_widget.widget_type {
             let is_in_search_widget = self.is_in_search_widget();
             if let Some(proc_widget_state) = self
                 .proc_state
@@ -915,6 +1440,10 @@ impl App {
             self.decrement_position_count();
         } else if self.help_dialog_state.is_showing_help {
             self.help_scroll_up();
+        } else if self.saved_query_dialog_state.is_showing_saved_queries {
+            self.change_saved_query_position(-1);
+        } else if self.exited_process_dialog_state.is_showing_exited_processes {
+            self.change_exited_process_position(-1);
         } else if self.delete_dialog_state.is_showing_dd {
             #[cfg(target_os = "windows")]
             self.on_right_key();
@@ -937,6 +1466,10 @@ impl App {
             self.increment_position_count();
         } else if self.help_dialog_state.is_showing_help {
             self.help_scroll_down();
+        } else if self.saved_query_dialog_state.is_showing_saved_queries {
+            self.change_saved_query_position(1);
+        } else if self.exited_process_dialog_state.is_showing_exited_processes {
+            self.change_exited_process_position(1);
         } else if self.delete_dialog_state.is_showing_dd {
             #[cfg(target_os = "windows")]
             self.on_left_key();
@@ -985,6 +1518,19 @@ impl App {
                         }
                     }
                 }
+                BottomWidgetType::Proc => {
+                    if let Some(proc_widget_state) = self
+                        .proc_state
+                        .get_mut_widget_state(self.current_widget.widget_id)
+                    {
+                        proc_widget_state.scroll_horizontally(false);
+                    }
+                }
+                BottomWidgetType::Disk | BottomWidgetType::Temp | BottomWidgetType::User => {
+                    if let Some(search_state) = self.get_mut_table_search_state() {
+                        search_state.move_cursor_left();
+                    }
+                }
                 BottomWidgetType::Battery => {
                     if !self.canvas_data.battery_data.is_empty() {
                         if let Some(battery_widget_state) = self
@@ -1058,6 +1604,19 @@ impl App {
                         }
                     }
                 }
+                BottomWidgetType::Proc => {
+                    if let Some(proc_widget_state) = self
+                        .proc_state
+                        .get_mut_widget_state(self.current_widget.widget_id)
+                    {
+                        proc_widget_state.scroll_horizontally(true);
+                    }
+                }
+                BottomWidgetType::Disk | BottomWidgetType::Temp | BottomWidgetType::User => {
+                    if let Some(search_state) = self.get_mut_table_search_state() {
+                        search_state.move_cursor_right();
+                    }
+                }
                 BottomWidgetType::Battery => {
                     if !self.canvas_data.battery_data.is_empty() {
                         let battery_count = self.canvas_data.battery_data.len();
@@ -1160,7 +1719,9 @@ impl App {
 
     pub fn skip_cursor_beginning(&mut self) {
         if !self.ignore_normal_keybinds() {
-            if let BottomWidgetType::ProcSearch = self.current_widget.widget_type {
+            if let Some(search_state) = self.get_mut_table_search_state() {
+                search_state.move_cursor_to_start();
+            } else if let BottomWidgetType::ProcSearch = self.current_widget.widget_type {
                 let is_in_search_widget = self.is_in_search_widget();
                 if let Some(proc_widget_state) = self
                     .proc_state
@@ -1197,7 +1758,9 @@ impl App {
 
     pub fn skip_cursor_end(&mut self) {
         if !self.ignore_normal_keybinds() {
-            if let BottomWidgetType::ProcSearch = self.current_widget.widget_type {
+            if let Some(search_state) = self.get_mut_table_search_state() {
+                search_state.move_cursor_to_end();
+            } else if let BottomWidgetType::ProcSearch = self.current_widget.widget_type {
                 let is_in_search_widget = self.is_in_search_widget();
                 if let Some(proc_widget_state) = self
                     .proc_state
@@ -1243,7 +1806,10 @@ impl App {
     }
 
     pub fn clear_search(&mut self) {
-        if let BottomWidgetType::ProcSearch = self.current_widget.widget_type {
+        if let Some(search_state) = self.get_mut_table_search_state() {
+            search_state.reset();
+            self.update_table_query();
+        } else if let BottomWidgetType::ProcSearch = self.current_widget.widget_type {
             if let Some(proc_widget_state) = self
                 .proc_state
                 .widget_states
@@ -1256,7 +1822,11 @@ impl App {
     }
 
     pub fn clear_previous_word(&mut self) {
-        if let BottomWidgetType::ProcSearch = self.current_widget.widget_type {
+        if let Some(search_state) = self.get_mut_table_search_state() {
+            if search_state.delete_previous_word() {
+                self.update_table_query();
+            }
+        } else if let BottomWidgetType::ProcSearch = self.current_widget.widget_type {
             if let Some(proc_widget_state) = self
                 .proc_state
                 .widget_states
@@ -1389,6 +1959,16 @@ impl App {
             }
             self.last_key_press = current_key_press_inst;
 
+            if let Some(search_state) = self.get_mut_table_search_state() {
+                if UnicodeWidthStr::width(search_state.current_search_query.as_str())
+                    <= MAX_SEARCH_LENGTH
+                {
+                    search_state.insert_char(caught_char);
+                    self.update_table_query();
+                }
+                return;
+            }
+
             if let BottomWidgetType::ProcSearch = self.current_widget.widget_type {
                 let is_in_search_widget = self.is_in_search_widget();
                 if let Some(proc_widget_state) = self
@@ -1461,6 +2041,35 @@ impl App {
                 'j' | 'k' | 'g' | 'G' => self.handle_char(caught_char),
                 _ => {}
             }
+        } else if self.saved_query_dialog_state.is_showing_saved_queries {
+            match caught_char {
+                'j' => self.on_down_key(),
+                'k' => self.on_up_key(),
+                _ => {}
+            }
+        } else if self.exited_process_dialog_state.is_showing_exited_processes {
+            match caught_char {
+                'j' => self.on_down_key(),
+                'k' => self.on_up_key(),
+                'g' => self.exited_process_dialog_state.table_state.select(Some(0)),
+                'G' => {
+                    let last_posn = self
+                        .data_collection
+                        .exited_processes
+                        .len()
+                        .saturating_sub(1);
+                    self.exited_process_dialog_state
+                        .table_state
+                        .select(Some(last_posn));
+                }
+                _ => {}
+            }
+        } else if self.goto_pid_dialog_state.is_showing_goto_pid {
+            // PIDs fit in 10 digits everywhere we support.
+            if caught_char.is_ascii_digit() && self.goto_pid_dialog_state.input.len() < 10 {
+                self.goto_pid_dialog_state.input.push(caught_char);
+                self.goto_pid_dialog_state.error = None;
+            }
         } else if self.delete_dialog_state.is_showing_dd {
             match caught_char {
                 'h' => self.on_left_key(),
@@ -1495,6 +2104,12 @@ impl App {
     }
 
     fn handle_char(&mut self, caught_char: char) {
+        if self.awaiting_second_char && self.second_char == Some('z') {
+            self.reset_multi_tap_keys();
+            self.on_tree_fold_key(caught_char);
+            return;
+        }
+
         match caught_char {
             '/' => {
                 self.on_slash();
@@ -1535,14 +2150,35 @@ impl App {
                 }
             }
             'G' => self.skip_to_last(),
+            'z' => {
+                if let BottomWidgetType::Proc = self.current_widget.widget_type {
+                    self.awaiting_second_char = true;
+                    self.second_char = Some('z');
+                }
+            }
             'k' => self.on_up_key(),
             'j' => self.on_down_key(),
+            'h' => {
+                if let BottomWidgetType::Proc = self.current_widget.widget_type {
+                    self.on_left_key();
+                }
+            }
+            'l' => {
+                if let BottomWidgetType::Proc = self.current_widget.widget_type {
+                    self.on_right_key();
+                }
+            }
             'f' => {
                 self.is_frozen = !self.is_frozen;
                 if self.is_frozen {
                     self.data_collection.set_frozen_time();
                 }
             }
+            'F' => self.toggle_follow(),
+            '#' => self.open_goto_pid(),
+            'x' => self.open_exited_processes(),
+            'u' => self.select_parent_process(),
+            'o' => self.select_first_child_process(),
             'C' => {
                 // self.open_config(),
             }
@@ -1558,6 +2194,8 @@ impl App {
                         proc_widget_state.update_sorting_with_columns();
                         self.proc_state.force_update = Some(self.current_widget.widget_id);
                     }
+                } else {
+                    self.set_user_sort_type(processes::ProcessSorting::CpuPercent);
                 }
             }
             'm' => {
@@ -1579,6 +2217,8 @@ impl App {
                         proc_widget_state.update_sorting_with_columns();
                         self.proc_state.force_update = Some(self.current_widget.widget_id);
                     }
+                } else {
+                    self.set_user_sort_type(processes::ProcessSorting::Mem);
                 }
             }
             'p' => {
@@ -1588,7 +2228,7 @@ impl App {
                         .get_mut_widget_state(self.current_widget.widget_id)
                     {
                         // Skip if grouped
-                        if !proc_widget_state.is_grouped {
+                        if !proc_widget_state.is_grouped() {
                             proc_widget_state
                                 .columns
                                 .set_to_sorted_index_from_type(&processes::ProcessSorting::Pid);
@@ -1608,19 +2248,27 @@ impl App {
                         proc_widget_state
                             .toggle_command_and_name(proc_widget_state.is_using_command);
 
+                        let new_name_sort_type = if proc_widget_state.is_using_command {
+                            processes::ProcessSorting::Command
+                        } else {
+                            processes::ProcessSorting::ProcessName
+                        };
+
                         match &proc_widget_state.process_sorting_type {
                             processes::ProcessSorting::Command
                             | processes::ProcessSorting::ProcessName => {
-                                if proc_widget_state.is_using_command {
-                                    proc_widget_state.process_sorting_type =
-                                        processes::ProcessSorting::Command;
-                                } else {
-                                    proc_widget_state.process_sorting_type =
-                                        processes::ProcessSorting::ProcessName;
-                                }
+                                proc_widget_state.process_sorting_type = new_name_sort_type.clone();
                             }
                             _ => {}
                         }
+
+                        for (sort_type, _) in &mut proc_widget_state.secondary_sort_keys {
+                            if let processes::ProcessSorting::Command
+                            | processes::ProcessSorting::ProcessName = sort_type
+                            {
+                                *sort_type = new_name_sort_type.clone();
+                            }
+                        }
                         proc_widget_state.requires_redraw = true;
                         self.proc_state.force_update = Some(self.current_widget.widget_id);
                     }
@@ -1642,6 +2290,8 @@ impl App {
                         proc_widget_state.update_sorting_with_columns();
                         self.proc_state.force_update = Some(self.current_widget.widget_id);
                     }
+                } else {
+                    self.set_user_sort_type(processes::ProcessSorting::User);
                 }
             }
             '?' => {
@@ -1661,6 +2311,12 @@ impl App {
             'I' => self.invert_sort(),
             '%' => self.toggle_percentages(),
             ' ' => self.on_space(),
+            '[' => self.move_sort_column(true),
+            ']' => self.move_sort_column(false),
+            ',' => self.change_selected_table_column(false),
+            '.' => self.change_selected_table_column(true),
+            '<' => self.change_table_column_width(-1),
+            '>' => self.change_table_column_width(1),
             _ => {}
         }
 
@@ -1671,7 +2327,133 @@ impl App {
         }
     }
 
-    pub fn on_space(&mut self) {}
+    pub fn on_space(&mut self) {
+        if let BottomWidgetType::ProcSort = self.current_widget.widget_type {
+            if let Some(proc_widget_state) = self
+                .proc_state
+                .get_mut_widget_state(self.current_widget.widget_id - 2)
+            {
+                proc_widget_state.toggle_selected_column_hidden();
+                self.proc_state.force_update = Some(self.current_widget.widget_id - 2);
+            }
+        }
+    }
+
+    /// Moves the selected column in the sort widget up or down in the process table.
+    fn move_sort_column(&mut self, is_moving_up: bool) {
+        if let BottomWidgetType::ProcSort = self.current_widget.widget_type {
+            if let Some(proc_widget_state) = self
+                .proc_state
+                .get_mut_widget_state(self.current_widget.widget_id - 2)
+            {
+                proc_widget_state.move_selected_column(is_moving_up);
+                self.proc_state.force_update = Some(self.current_widget.widget_id - 2);
+            }
+        } else if let BottomWidgetType::User = self.current_widget.widget_type {
+            if let Some(user_widget_state) = self
+                .user_state
+                .get_mut_widget_state(self.current_widget.widget_id)
+            {
+                user_widget_state.move_sort_column(is_moving_up);
+                self.user_state.force_update = Some(self.current_widget.widget_id);
+            }
+        }
+    }
+
+    /// Sorts the current user widget by the given column, if the current widget is one.
+    fn set_user_sort_type(&mut self, sort_type: processes::ProcessSorting) {
+        if let BottomWidgetType::User = self.current_widget.widget_type {
+            if let Some(user_widget_state) = self
+                .user_state
+                .get_mut_widget_state(self.current_widget.widget_id)
+            {
+                user_widget_state.set_sort_type(sort_type);
+                self.user_state.force_update = Some(self.current_widget.widget_id);
+            }
+        }
+    }
+
+    /// Selects the next or previous column of the current table for resizing.
+    fn change_selected_table_column(&mut self, is_moving_right: bool) {
+        if let Some(width_state) = self.get_mut_table_width_state() {
+            width_state.change_selected_column(is_moving_right);
+            self.is_force_redraw = true;
+        }
+    }
+
+    /// Grows or shrinks the column of the current table selected for resizing.
+    fn change_table_column_width(&mut self, delta: i32) {
+        if let Some(width_state) = self.get_mut_table_width_state() {
+            if width_state.change_selected_column_width(delta) {
+                self.on_table_column_widths_changed();
+            } else {
+                self.is_force_redraw = true;
+            }
+        }
+    }
+
+    /// Goes back to automatically sizing the columns of the current table.
+    fn reset_table_column_widths(&mut self) {
+        if let Some(width_state) = self.get_mut_table_width_state() {
+            if width_state.reset_column_widths() {
+                self.on_table_column_widths_changed();
+            }
+        }
+    }
+
+    pub fn on_mouse_drag(&mut self, x: u16) {
+        if let Some(width_state) = self.get_mut_table_width_state() {
+            if width_state.drag_to(x) {
+                self.is_force_redraw = true;
+            }
+        }
+    }
+
+    pub fn on_left_mouse_release(&mut self) {
+        if let Some(width_state) = self.get_mut_table_width_state() {
+            if width_state.dragged_column.take().is_some() {
+                self.on_table_column_widths_changed();
+            }
+        }
+    }
+
+    fn on_table_column_widths_changed(&mut self) {
+        self.is_force_redraw = true;
+        if self.app_config_fields.persist_column_widths {
+            // Carry on with the widths for this session, but say that they weren't saved.
+            if let Err(err) = self.write_column_widths() {
+                self.collection_error = Some((
+                    format!("Could not save column widths: {}", err),
+                    Instant::now(),
+                ));
+            }
+        }
+    }
+
+    /// Saves the column widths of the current table, along with those already saved for the
+    /// other tables.  An empty table is saved too, so that resetting sticks.
+    fn write_column_widths(&mut self) -> anyhow::Result<()> {
+        if self.app_config_fields.no_write {
+            return Ok(());
+        }
+
+        let widget_type = self.current_widget.widget_type.clone();
+        let column_widths = match self.get_mut_table_width_state() {
+            Some(width_state) => width_state
+                .column_width_overrides
+                .iter()
+                .map(|(name, width)| (name.clone(), *width))
+                .collect::<BTreeMap<_, _>>(),
+            None => return Ok(()),
+        };
+
+        if let Some(path) = ConfigColumnWidths::default_saved_path() {
+            let mut saved_widths = ConfigColumnWidths::load_saved(&path);
+            saved_widths.set_widths(&widget_type, column_widths);
+            saved_widths.save(&path)?;
+        }
+        Ok(())
+    }
 
     pub fn open_config_screen(&mut self) {
         self.is_config_open = true;
@@ -2251,6 +3033,15 @@ impl App {
                         disk_widget_state.scroll_state.scroll_direction = ScrollDirection::Up;
                     }
                 }
+                BottomWidgetType::User => {
+                    if let Some(user_widget_state) = self
+                        .user_state
+                        .get_mut_widget_state(self.current_widget.widget_id)
+                    {
+                        user_widget_state.scroll_state.current_scroll_position = 0;
+                        user_widget_state.scroll_state.scroll_direction = ScrollDirection::Up;
+                    }
+                }
                 BottomWidgetType::CpuLegend => {
                     if let Some(cpu_widget_state) = self
                         .cpu_state
@@ -2305,29 +3096,53 @@ impl App {
                     }
                 }
                 BottomWidgetType::Temp => {
+                    let num_rows = self
+                        .canvas_data
+                        .temp_sensor_data_map
+                        .get(&self.current_widget.widget_id)
+                        .map_or(0, |data| data.len());
                     if let Some(temp_widget_state) = self
                         .temp_state
                         .get_mut_widget_state(self.current_widget.widget_id)
                     {
-                        if !self.canvas_data.temp_sensor_data.is_empty() {
-                            temp_widget_state.scroll_state.current_scroll_position =
-                                self.canvas_data.temp_sensor_data.len() - 1;
+                        if num_rows > 0 {
+                            temp_widget_state.scroll_state.current_scroll_position = num_rows - 1;
                             temp_widget_state.scroll_state.scroll_direction = ScrollDirection::Down;
                         }
                     }
                 }
                 BottomWidgetType::Disk => {
+                    let num_rows = self
+                        .canvas_data
+                        .disk_data_map
+                        .get(&self.current_widget.widget_id)
+                        .map_or(0, |data| data.len());
                     if let Some(disk_widget_state) = self
                         .disk_state
                         .get_mut_widget_state(self.current_widget.widget_id)
                     {
-                        if !self.canvas_data.disk_data.is_empty() {
-                            disk_widget_state.scroll_state.current_scroll_position =
-                                self.canvas_data.disk_data.len() - 1;
+                        if num_rows > 0 {
+                            disk_widget_state.scroll_state.current_scroll_position = num_rows - 1;
                             disk_widget_state.scroll_state.scroll_direction = ScrollDirection::Down;
                         }
                     }
                 }
+                BottomWidgetType::User => {
+                    let num_rows = self
+                        .canvas_data
+                        .user_data_map
+                        .get(&self.current_widget.widget_id)
+                        .map_or(0, |data| data.len());
+                    if let Some(user_widget_state) = self
+                        .user_state
+                        .get_mut_widget_state(self.current_widget.widget_id)
+                    {
+                        if num_rows > 0 {
+                            user_widget_state.scroll_state.current_scroll_position = num_rows - 1;
+                            user_widget_state.scroll_state.scroll_direction = ScrollDirection::Down;
+                        }
+                    }
+                }
                 BottomWidgetType::CpuLegend => {
                     if let Some(cpu_widget_state) = self
                         .cpu_state
@@ -2370,8 +3185,10 @@ impl App {
                     self.change_process_position(amount);
                 }
                 BottomWidgetType::ProcSort => self.change_process_sort_position(amount),
+                BottomWidgetType::ProcSearch => self.change_search_history_position(amount),
                 BottomWidgetType::Temp => self.change_temp_position(amount),
                 BottomWidgetType::Disk => self.increment_disk_position(amount),
+                BottomWidgetType::User => self.change_user_position(amount),
                 BottomWidgetType::CpuLegend => self.change_cpu_legend_position(amount),
                 _ => {}
             }
@@ -2403,6 +3220,20 @@ impl App {
         }
     }
 
+    fn change_search_history_position(&mut self, num_to_change_by: i64) {
+        if let Some(proc_widget_state) = self
+            .proc_state
+            .get_mut_widget_state(self.current_widget.widget_id - 1)
+        {
+            if proc_widget_state.is_completion_showing() {
+                proc_widget_state.change_completion_position(num_to_change_by);
+            } else {
+                proc_widget_state.change_history_position(num_to_change_by);
+            }
+            self.proc_state.force_update = Some(self.current_widget.widget_id - 1);
+        }
+    }
+
     fn change_cpu_legend_position(&mut self, num_to_change_by: i64) {
         if let Some(cpu_widget_state) = self
             .cpu_state
@@ -2467,6 +3298,11 @@ impl App {
     }
 
     fn change_temp_position(&mut self, num_to_change_by: i64) {
+        let num_rows = self
+            .canvas_data
+            .temp_sensor_data_map
+            .get(&self.current_widget.widget_id)
+            .map_or(0, |data| data.len());
         if let Some(temp_widget_state) = self
             .temp_state
             .widget_states
@@ -2476,11 +3312,8 @@ impl App {
 
             if current_posn as i64 + num_to_change_by < 0 {
                 temp_widget_state.scroll_state.current_scroll_position = 0;
-            } else if current_posn as i64 + num_to_change_by
-                >= self.canvas_data.temp_sensor_data.len() as i64
-            {
-                temp_widget_state.scroll_state.current_scroll_position =
-                    self.canvas_data.temp_sensor_data.len().saturating_sub(1);
+            } else if current_posn as i64 + num_to_change_by >= num_rows as i64 {
+                temp_widget_state.scroll_state.current_scroll_position = num_rows.saturating_sub(1);
             } else {
                 temp_widget_state.scroll_state.current_scroll_position =
                     (current_posn as i64 + num_to_change_by) as usize;
@@ -2495,6 +3328,11 @@ impl App {
     }
 
     fn increment_disk_position(&mut self, num_to_change_by: i64) {
+        let num_rows = self
+            .canvas_data
+            .disk_data_map
+            .get(&self.current_widget.widget_id)
+            .map_or(0, |data| data.len());
         if let Some(disk_widget_state) = self
             .disk_state
             .widget_states
@@ -2503,7 +3341,7 @@ impl App {
             let current_posn = disk_widget_state.scroll_state.current_scroll_position;
 
             if current_posn as i64 + num_to_change_by >= 0
-                && current_posn as i64 + num_to_change_by < self.canvas_data.disk_data.len() as i64
+                && current_posn as i64 + num_to_change_by < num_rows as i64
             {
                 disk_widget_state.scroll_state.current_scroll_position =
                     (current_posn as i64 + num_to_change_by) as usize;
@@ -2517,6 +3355,34 @@ impl App {
         }
     }
 
+    fn change_user_position(&mut self, num_to_change_by: i64) {
+        let num_rows = self
+            .canvas_data
+            .user_data_map
+            .get(&self.current_widget.widget_id)
+            .map_or(0, |data| data.len());
+        if let Some(user_widget_state) = self
+            .user_state
+            .widget_states
+            .get_mut(&self.current_widget.widget_id)
+        {
+            let current_posn = user_widget_state.scroll_state.current_scroll_position;
+
+            if current_posn as i64 + num_to_change_by >= 0
+                && current_posn as i64 + num_to_change_by < num_rows as i64
+            {
+                user_widget_state.scroll_state.current_scroll_position =
+                    (current_posn as i64 + num_to_change_by) as usize;
+            }
+
+            if num_to_change_by < 0 {
+                user_widget_state.scroll_state.scroll_direction = ScrollDirection::Up;
+            } else {
+                user_widget_state.scroll_state.scroll_direction = ScrollDirection::Down;
+            }
+        }
+    }
+
     fn help_scroll_up(&mut self) {
         if self.help_dialog_state.scroll_state.current_scroll_index > 0 {
             self.help_dialog_state.scroll_state.current_scroll_index -= 1;
@@ -2606,21 +3472,83 @@ impl App {
                 .get(&self.current_widget.widget_id)
             {
                 if let Some(corresponding_process) = displayed_process_list.get(current_posn) {
-                    let corresponding_pid = corresponding_process.pid;
+                    proc_widget_state
+                        .tree_collapse_state
+                        .toggle(corresponding_process.pid, &corresponding_process.name);
+                    self.proc_state.force_update = Some(self.current_widget.widget_id);
+                }
+            }
+        }
+    }
 
-                    if let Some(process_data) = self
-                        .canvas_data
-                        .single_process_data
-                        .get_mut(&corresponding_pid)
-                    {
-                        process_data.is_collapsed_entry = !process_data.is_collapsed_entry;
-                        self.proc_state.force_update = Some(self.current_widget.widget_id);
+    /// Collapses or expands every branch of the process tree at once.
+    fn set_process_tree_collapse_default(&mut self, default: TreeCollapseDefault) {
+        if let Some(proc_widget_state) = self
+            .proc_state
+            .widget_states
+            .get_mut(&self.current_widget.widget_id)
+        {
+            if proc_widget_state.is_tree_mode {
+                proc_widget_state.tree_collapse_state.set_default(default);
+                self.proc_state.force_update = Some(self.current_widget.widget_id);
+            }
+        }
+    }
+
+    /// Collapses every other branch with the same parent as the selected process.  Siblings
+    /// without children are left alone, so they don't start out collapsed if they get some.
+    fn collapse_sibling_process_branches(&mut self) {
+        if let Some(proc_widget_state) = self
+            .proc_state
+            .widget_states
+            .get_mut(&self.current_widget.widget_id)
+        {
+            let current_posn = proc_widget_state.scroll_state.current_scroll_position;
+
+            if let Some(displayed_process_list) = self
+                .canvas_data
+                .finalized_process_data_map
+                .get(&self.current_widget.widget_id)
+            {
+                if let Some(selected_process) = displayed_process_list.get(current_posn) {
+                    // Expanded branches have their children shown right below them.
+                    let shown_parents = displayed_process_list
+                        .iter()
+                        .filter_map(|process| process.ppid)
+                        .collect::<HashSet<_>>();
+                    for sibling in displayed_process_list.iter().filter(|process| {
+                        process.ppid == selected_process.ppid
+                            && process.pid != selected_process.pid
+                            && !process.is_collapsed_entry
+                            && shown_parents.contains(&process.pid)
+                    }) {
+                        proc_widget_state
+                            .tree_collapse_state
+                            .toggle(sibling.pid, &sibling.name);
                     }
+                    self.proc_state.force_update = Some(self.current_widget.widget_id);
                 }
             }
         }
     }
 
+    /// Handles the key pressed after 'z' in tree mode, which acts on multiple branches at once.
+    fn on_tree_fold_key(&mut self, caught_char: char) {
+        match caught_char {
+            'M' => self.set_process_tree_collapse_default(TreeCollapseDefault::CollapseAll),
+            'R' => self.set_process_tree_collapse_default(TreeCollapseDefault::ExpandAll),
+            's' => self.collapse_sibling_process_branches(),
+            '1'..='9' => {
+                if let Some(depth) = caught_char.to_digit(10) {
+                    self.set_process_tree_collapse_default(TreeCollapseDefault::ExpandToDepth(
+                        depth as usize,
+                    ));
+                }
+            }
+            _ => {}
+        }
+    }
+
     fn zoom_out(&mut self) {
         match self.current_widget.widget_type {
             BottomWidgetType::Cpu => {
@@ -2629,18 +3557,20 @@ impl App {
                     .widget_states
                     .get_mut(&self.current_widget.widget_id)
                 {
-                    let new_time = cpu_widget_state.current_display_time
-                        + self.app_config_fields.time_interval;
-                    if new_time <= constants::STALE_MAX_MILLISECONDS {
+                    let new_time = get_zoomed_out_time(
+                        cpu_widget_state.current_display_time,
+                        self.app_config_fields.time_interval,
+                    );
+                    if new_time <= self.app_config_fields.retention {
                         cpu_widget_state.current_display_time = new_time;
                         self.cpu_state.force_update = Some(self.current_widget.widget_id);
                         if self.app_config_fields.autohide_time {
                             cpu_widget_state.autohide_timer = Some(Instant::now());
                         }
                     } else if cpu_widget_state.current_display_time
-                        != constants::STALE_MAX_MILLISECONDS
+                        != self.app_config_fields.retention
                     {
-                        cpu_widget_state.current_display_time = constants::STALE_MAX_MILLISECONDS;
+                        cpu_widget_state.current_display_time = self.app_config_fields.retention;
                         self.cpu_state.force_update = Some(self.current_widget.widget_id);
                         if self.app_config_fields.autohide_time {
                             cpu_widget_state.autohide_timer = Some(Instant::now());
@@ -2654,18 +3584,20 @@ impl App {
                     .widget_states
                     .get_mut(&self.current_widget.widget_id)
                 {
-                    let new_time = mem_widget_state.current_display_time
-                        + self.app_config_fields.time_interval;
-                    if new_time <= constants::STALE_MAX_MILLISECONDS {
+                    let new_time = get_zoomed_out_time(
+                        mem_widget_state.current_display_time,
+                        self.app_config_fields.time_interval,
+                    );
+                    if new_time <= self.app_config_fields.retention {
                         mem_widget_state.current_display_time = new_time;
                         self.mem_state.force_update = Some(self.current_widget.widget_id);
                         if self.app_config_fields.autohide_time {
                             mem_widget_state.autohide_timer = Some(Instant::now());
                         }
                     } else if mem_widget_state.current_display_time
-                        != constants::STALE_MAX_MILLISECONDS
+                        != self.app_config_fields.retention
                     {
-                        mem_widget_state.current_display_time = constants::STALE_MAX_MILLISECONDS;
+                        mem_widget_state.current_display_time = self.app_config_fields.retention;
                         self.mem_state.force_update = Some(self.current_widget.widget_id);
                         if self.app_config_fields.autohide_time {
                             mem_widget_state.autohide_timer = Some(Instant::now());
@@ -2679,18 +3611,20 @@ impl App {
                     .widget_states
                     .get_mut(&self.current_widget.widget_id)
                 {
-                    let new_time = net_widget_state.current_display_time
-                        + self.app_config_fields.time_interval;
-                    if new_time <= constants::STALE_MAX_MILLISECONDS {
+                    let new_time = get_zoomed_out_time(
+                        net_widget_state.current_display_time,
+                        self.app_config_fields.time_interval,
+                    );
+                    if new_time <= self.app_config_fields.retention {
                         net_widget_state.current_display_time = new_time;
                         self.net_state.force_update = Some(self.current_widget.widget_id);
                         if self.app_config_fields.autohide_time {
                             net_widget_state.autohide_timer = Some(Instant::now());
                         }
                     } else if net_widget_state.current_display_time
-                        != constants::STALE_MAX_MILLISECONDS
+                        != self.app_config_fields.retention
                     {
-                        net_widget_state.current_display_time = constants::STALE_MAX_MILLISECONDS;
+                        net_widget_state.current_display_time = self.app_config_fields.retention;
                         self.net_state.force_update = Some(self.current_widget.widget_id);
                         if self.app_config_fields.autohide_time {
                             net_widget_state.autohide_timer = Some(Instant::now());
@@ -2710,8 +3644,10 @@ impl App {
                     .widget_states
                     .get_mut(&self.current_widget.widget_id)
                 {
-                    let new_time = cpu_widget_state.current_display_time
-                        - self.app_config_fields.time_interval;
+                    let new_time = get_zoomed_in_time(
+                        cpu_widget_state.current_display_time,
+                        self.app_config_fields.time_interval,
+                    );
                     if new_time >= constants::STALE_MIN_MILLISECONDS {
                         cpu_widget_state.current_display_time = new_time;
                         self.cpu_state.force_update = Some(self.current_widget.widget_id);
@@ -2735,8 +3671,10 @@ impl App {
                     .widget_states
                     .get_mut(&self.current_widget.widget_id)
                 {
-                    let new_time = mem_widget_state.current_display_time
-                        - self.app_config_fields.time_interval;
+                    let new_time = get_zoomed_in_time(
+                        mem_widget_state.current_display_time,
+                        self.app_config_fields.time_interval,
+                    );
                     if new_time >= constants::STALE_MIN_MILLISECONDS {
                         mem_widget_state.current_display_time = new_time;
                         self.mem_state.force_update = Some(self.current_widget.widget_id);
@@ -2760,8 +3698,10 @@ impl App {
                     .widget_states
                     .get_mut(&self.current_widget.widget_id)
                 {
-                    let new_time = net_widget_state.current_display_time
-                        - self.app_config_fields.time_interval;
+                    let new_time = get_zoomed_in_time(
+                        net_widget_state.current_display_time,
+                        self.app_config_fields.time_interval,
+                    );
                     if new_time >= constants::STALE_MIN_MILLISECONDS {
                         net_widget_state.current_display_time = new_time;
                         self.net_state.force_update = Some(self.current_widget.widget_id);
@@ -2830,6 +3770,10 @@ impl App {
             BottomWidgetType::Cpu => self.reset_cpu_zoom(),
             BottomWidgetType::Mem => self.reset_mem_zoom(),
             BottomWidgetType::Net => self.reset_net_zoom(),
+            BottomWidgetType::Proc
+            | BottomWidgetType::Disk
+            | BottomWidgetType::Temp
+            | BottomWidgetType::User => self.reset_table_column_widths(),
             _ => {}
         }
     }
@@ -2968,6 +3912,13 @@ impl App {
             return;
         }
 
+        // Grabbing the gap right after a column header starts resizing that column instead.
+        if let Some(width_state) = self.get_mut_table_width_state() {
+            if width_state.try_start_drag(x, y) {
+                return;
+            }
+        }
+
         // Now handle click propagation down to widget.
         if let (Some((_tlc_x, tlc_y)), Some((_brc_x, brc_y))) = (
             &self.current_widget.top_left_corner,
@@ -2982,7 +3933,8 @@ impl App {
                     | BottomWidgetType::ProcSort
                     | BottomWidgetType::CpuLegend
                     | BottomWidgetType::Temp
-                    | BottomWidgetType::Disk => {
+                    | BottomWidgetType::Disk
+                    | BottomWidgetType::User => {
                         // Get our index...
                         let clicked_entry = y - *tlc_y;
                         // + 1 so we start at 0.
@@ -3083,13 +4035,26 @@ impl App {
                                         }
                                     }
                                 }
+                                BottomWidgetType::User => {
+                                    if let Some(user_widget_state) = self
+                                        .user_state
+                                        .get_widget_state(self.current_widget.widget_id)
+                                    {
+                                        if let Some(visual_index) =
+                                            user_widget_state.scroll_state.table_state.selected()
+                                        {
+                                            self.change_user_position(
+                                                offset_clicked_entry as i64 - visual_index as i64,
+                                            );
+                                        }
+                                    }
+                                }
                                 _ => {}
                             }
                         } else {
                             // We might have clicked on a header!  Check if we only exceeded the table + border offset, and
                             // it's implied we exceeded the gap offset.
                             if clicked_entry == border_offset {
-                                #[allow(clippy::single_match)]
                                 match &self.current_widget.widget_type {
                                     BottomWidgetType::Proc => {
                                         if let Some(proc_widget_state) = self
@@ -3098,8 +4063,12 @@ impl App {
                                         {
                                             // Let's now check if it's a column header.
                                             if let (Some(y_loc), Some(x_locs)) = (
-                                                &proc_widget_state.columns.column_header_y_loc,
-                                                &proc_widget_state.columns.column_header_x_locs,
+                                                &proc_widget_state
+                                                    .table_width_state
+                                                    .column_header_y_loc,
+                                                &proc_widget_state
+                                                    .table_width_state
+                                                    .column_header_x_locs,
                                             ) {
                                                 // debug!("x, y: {}, {}", x, y);
                                                 // debug!("y_loc: {}", y_loc);
@@ -3110,12 +4079,19 @@ impl App {
                                                         x_locs.iter().enumerate()
                                                     {
                                                         if x >= *x_left && x <= *x_right {
-                                                            // Found our column!
-                                                            proc_widget_state
-                                                            .columns
-                                                            .set_to_sorted_index_from_visual_index(
-                                                                itx,
-                                                            );
+                                                            // Found our column!  Hidden columns are
+                                                            // still in the sort list, so go by type.
+                                                            if let Some(column) = proc_widget_state
+                                                                .columns
+                                                                .get_shown_columns()
+                                                                .get(itx)
+                                                            {
+                                                                proc_widget_state
+                                                                    .columns
+                                                                    .set_to_sorted_index_from_type(
+                                                                        column,
+                                                                    );
+                                                            }
                                                             proc_widget_state
                                                                 .update_sorting_with_columns();
                                                             self.proc_state.force_update =
@@ -3127,6 +4103,37 @@ impl App {
                                             }
                                         }
                                     }
+                                    BottomWidgetType::User => {
+                                        if let Some(user_widget_state) = self
+                                            .user_state
+                                            .get_mut_widget_state(self.current_widget.widget_id)
+                                        {
+                                            if let (Some(y_loc), Some(x_locs)) = (
+                                                &user_widget_state
+                                                    .table_width_state
+                                                    .column_header_y_loc,
+                                                &user_widget_state
+                                                    .table_width_state
+                                                    .column_header_x_locs,
+                                            ) {
+                                                if y == *y_loc {
+                                                    if let Some(itx) = x_locs.iter().position(
+                                                        |(x_left, x_right)| {
+                                                            x >= *x_left && x <= *x_right
+                                                        },
+                                                    ) {
+                                                        if let Some(column) = USER_COLUMNS.get(itx)
+                                                        {
+                                                            user_widget_state
+                                                                .set_sort_type(column.clone());
+                                                            self.user_state.force_update =
+                                                                Some(self.current_widget.widget_id);
+                                                        }
+                                                    }
+                                                }
+                                            }
+                                        }
+                                    }
                                     _ => {}
                                 }
                             }
@@ -3170,3 +4177,22 @@ impl App {
         }
     }
 }
+
+/// Zooming out goes by the time delta until the graph shows [`constants::STALE_MAX_MILLISECONDS`],
+/// then doubles each time, so longer retentions can be zoomed out to in a few steps.
+fn get_zoomed_out_time(display_time: u64, time_interval: u64) -> u64 {
+    if display_time < constants::STALE_MAX_MILLISECONDS {
+        display_time + time_interval
+    } else {
+        display_time.saturating_mul(2)
+    }
+}
+
+/// The opposite of [`get_zoomed_out_time`].
+fn get_zoomed_in_time(display_time: u64, time_interval: u64) -> u64 {
+    if display_time > constants::STALE_MAX_MILLISECONDS {
+        std::cmp::max(display_time / 2, constants::STALE_MAX_MILLISECONDS)
+    } else {
+        display_time.saturating_sub(time_interval)
+    }
+}
@@ -0,0 +1,283 @@
+//! Tab completion for process search queries.
+
+use std::str::FromStr;
+
+use super::query::PrefixType;
+
+/// Prefixes that are offered when starting a new condition.
+const PREFIX_LIST: [&str; 15] = [
+    "ancestor", "children", "cpu", "mem", "memb", "name", "pid", "ppid", "read", "state", "time",
+    "tread", "twrite", "user", "write",
+];
+
+/// Comparisons that make sense for prefixes that match against text.
+const STRING_COMPARISON_LIST: [&str; 1] = ["="];
+
+/// Comparisons that make sense for prefixes that match against numbers.
+const NUMERICAL_COMPARISON_LIST: [&str; 5] = ["=", ">", "<", ">=", "<="];
+
+/// Characters that split up words in a query, mirroring what the query parser uses.
+const WORD_DELIMITER_LIST: [char; 7] = ['=', '>', '<', '(', ')', '\"', '!'];
+
+const COMPARISON_CHAR_LIST: [char; 3] = ['=', '>', '<'];
+
+/// Live values that can be suggested for a prefix, taken from the current processes.
+#[derive(Debug, Default)]
+pub struct CompletionValues {
+    pub names: Vec<String>,
+    pub users: Vec<String>,
+    pub states: Vec<String>,
+}
+
+impl CompletionValues {
+    /// Sorts and removes duplicates from all values.
+    pub fn dedup(&mut self) {
+        for values in [&mut self.names, &mut self.users, &mut self.states].iter_mut() {
+            values.sort_unstable();
+            values.dedup();
+        }
+    }
+}
+
+/// What sort of thing the word being completed is.
+#[derive(Debug, PartialEq)]
+pub enum CompletionKind {
+    /// The start of a new condition; either a prefix or a process name.
+    Condition,
+    /// A comparison after the given prefix.
+    Comparison(PrefixType),
+    /// A value for the given prefix.
+    Value(PrefixType),
+}
+
+/// The part of a query that is being completed, and what it can be replaced with.
+#[derive(Debug, PartialEq)]
+pub struct Completion {
+    /// The byte index at which the word being completed starts.
+    pub start: usize,
+    /// The byte index at which the word being completed ends; this is always the cursor.
+    pub end: usize,
+    pub kind: CompletionKind,
+    pub candidates: Vec<String>,
+}
+
+impl Completion {
+    /// Returns the longest prefix shared by all candidates, if there are any.
+    pub fn get_common_prefix(&self) -> Option<&str> {
+        let first = self.candidates.first()?;
+        let common_len = self
+            .candidates
+            .iter()
+            .skip(1)
+            .fold(first.len(), |len, candidate| {
+                first[..len]
+                    .char_indices()
+                    .zip(candidate.chars())
+                    .find(|((_, a), b)| a != b)
+                    .map_or_else(|| len.min(candidate.len()), |((index, _), _)| index)
+            });
+
+        Some(&first[..common_len])
+    }
+}
+
+/// Returns the prefix type for the given word, if it's actually a prefix rather than a name.
+fn get_prefix_type(word: &str) -> Option<PrefixType> {
+    if word.eq_ignore_ascii_case("name") {
+        Some(PrefixType::Name)
+    } else {
+        match PrefixType::from_str(word) {
+            Ok(PrefixType::Name) | Err(_) => None,
+            Ok(prefix_type) => Some(prefix_type),
+        }
+    }
+}
+
+fn is_string_prefix(prefix_type: &PrefixType) -> bool {
+    matches!(
+        prefix_type,
+        PrefixType::Name
+            | PrefixType::Pid
+            | PrefixType::Ppid
+            | PrefixType::State
+            | PrefixType::User
+            | PrefixType::Ancestor
+    )
+}
+
+/// Splits up the part of a query before the word being completed, in the same way as the query
+/// parser would.  Quoted strings are kept whole.
+fn split_query(query: &str) -> Vec<&str> {
+    let mut tokens = Vec::new();
+    let mut char_indices = query.char_indices().peekable();
+
+    while let Some((start, c)) = char_indices.next() {
+        if c.is_whitespace() {
+            continue;
+        }
+
+        let mut end = start + c.len_utf8();
+        if c == '\"' {
+            for (index, c) in char_indices.by_ref() {
+                end = index + c.len_utf8();
+                if c == '\"' {
+                    break;
+                }
+            }
+        } else if COMPARISON_CHAR_LIST.contains(&c) {
+            while let Some((index, c)) = char_indices.peek() {
+                if !COMPARISON_CHAR_LIST.contains(c) {
+                    break;
+                }
+                end = index + c.len_utf8();
+                char_indices.next();
+            }
+        } else if !WORD_DELIMITER_LIST.contains(&c) {
+            while let Some((index, c)) = char_indices.peek() {
+                if c.is_whitespace() || WORD_DELIMITER_LIST.contains(c) {
+                    break;
+                }
+                end = index + c.len_utf8();
+                char_indices.next();
+            }
+        }
+
+        tokens.push(&query[start..end]);
+    }
+
+    tokens
+}
+
+/// Gets possible completions for the word right before `cursor_position` (a byte index) in the
+/// query.
+pub fn get_completion(
+    query: &str, cursor_position: usize, values: &CompletionValues,
+) -> Completion {
+    let before_cursor = &query[..cursor_position];
+    let is_in_quotes = before_cursor.matches('\"').count() % 2 == 1;
+
+    // Figure out where the word being completed starts.
+    let mut start = if is_in_quotes {
+        before_cursor.rfind('\"').map_or(0, |index| index + 1)
+    } else if before_cursor.ends_with(&COMPARISON_CHAR_LIST[..]) {
+        before_cursor
+            .trim_end_matches(&COMPARISON_CHAR_LIST[..])
+            .len()
+    } else {
+        before_cursor
+            .rfind(|c: char| c.is_whitespace() || WORD_DELIMITER_LIST.contains(&c))
+            .map_or(0, |index| {
+                index
+                    + before_cursor[index..]
+                        .chars()
+                        .next()
+                        .map_or(1, char::len_utf8)
+            })
+    };
+    let preceding = if is_in_quotes {
+        &before_cursor[..start.saturating_sub(1)]
+    } else {
+        &before_cursor[..start]
+    };
+
+    let tokens = split_query(preceding);
+    let mut previous_tokens = tokens.iter().rev();
+    let last_token = previous_tokens.next();
+    let second_last_token = previous_tokens.next();
+
+    let is_comparison = |token: &str| token.chars().all(|c| COMPARISON_CHAR_LIST.contains(&c));
+
+    let kind = if before_cursor[start..].starts_with(&COMPARISON_CHAR_LIST[..]) {
+        match last_token.and_then(|token| get_prefix_type(token)) {
+            Some(prefix_type) if is_string_prefix(&prefix_type) => {
+                // There's only one comparison for these, so skip straight to the value.
+                start = cursor_position;
+                CompletionKind::Value(prefix_type)
+            }
+            Some(prefix_type) => CompletionKind::Comparison(prefix_type),
+            None => CompletionKind::Condition,
+        }
+    } else {
+        match (last_token, second_last_token) {
+            (Some(last_token), Some(second_last_token)) if is_comparison(last_token) => {
+                match get_prefix_type(second_last_token) {
+                    Some(prefix_type) => CompletionKind::Value(prefix_type),
+                    None => CompletionKind::Condition,
+                }
+            }
+            (Some(last_token), _) => match get_prefix_type(last_token) {
+                Some(prefix_type) if start == cursor_position && !is_in_quotes => {
+                    CompletionKind::Comparison(prefix_type)
+                }
+                Some(prefix_type) if is_string_prefix(&prefix_type) => {
+                    CompletionKind::Value(prefix_type)
+                }
+                _ => CompletionKind::Condition,
+            },
+            _ => CompletionKind::Condition,
+        }
+    };
+    let word = &before_cursor[start..];
+
+    let lowercase_word = word.to_lowercase();
+    let matches_word = |candidate: &&str| candidate.to_lowercase().starts_with(&lowercase_word);
+
+    // Values with spaces or delimiters have to be quoted to be searched for.
+    let quote_value = |value: &str| {
+        if is_in_quotes {
+            format!("{}\"", value)
+        } else if value.contains(|c: char| c.is_whitespace() || WORD_DELIMITER_LIST.contains(&c)) {
+            format!("\"{}\"", value)
+        } else {
+            value.to_string()
+        }
+    };
+    let get_values = |values: &[String]| -> Vec<String> {
+        values
+            .iter()
+            .map(|value| value.as_str())
+            .filter(matches_word)
+            .map(quote_value)
+            .collect()
+    };
+
+    let candidates = match &kind {
+        CompletionKind::Condition => {
+            let mut candidates: Vec<String> = if is_in_quotes {
+                Vec::new()
+            } else {
+                PREFIX_LIST
+                    .iter()
+                    .copied()
+                    .filter(matches_word)
+                    .map(|prefix| prefix.to_string())
+                    .collect()
+            };
+            candidates.extend(get_values(&values.names));
+            candidates
+        }
+        CompletionKind::Comparison(prefix_type) => if is_string_prefix(prefix_type) {
+            &STRING_COMPARISON_LIST[..]
+        } else {
+            &NUMERICAL_COMPARISON_LIST[..]
+        }
+        .iter()
+        .copied()
+        .filter(matches_word)
+        .map(|comparison| comparison.to_string())
+        .collect(),
+        CompletionKind::Value(prefix_type) => match prefix_type {
+            PrefixType::Name | PrefixType::Ancestor => get_values(&values.names),
+            PrefixType::User => get_values(&values.users),
+            PrefixType::State => get_values(&values.states),
+            _ => Vec::new(),
+        },
+    };
+
+    Completion {
+        start,
+        end: cursor_position,
+        kind,
+        candidates,
+    }
+}
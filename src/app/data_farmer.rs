@@ -14,21 +14,31 @@
 /// more points as this is used!
 use once_cell::sync::Lazy;
 
-use std::{time::Instant, vec::Vec};
+use std::{
+    collections::{HashMap, VecDeque},
+    time::{Duration, Instant},
+    vec::Vec,
+};
 
 #[cfg(feature = "battery")]
 use crate::data_harvester::batteries;
 
 use crate::{
+    app::time_series::TimeSeriesStore,
+    constants::{
+        DOWNSAMPLED_BUCKET_COUNT, MAX_EXITED_PROCESSES, NEW_PROCESS_HIGHLIGHT_TICKS,
+        STALE_MAX_MILLISECONDS,
+    },
     data_harvester::{cpu, disks, memory, network, processes, temperature, Data},
     utils::gen_util::{get_decimal_bytes, GIGA_LIMIT},
+    Pid,
 };
 use regex::Regex;
 
 pub type TimeOffset = f64;
 pub type Value = f64;
 
-#[derive(Debug, Default)]
+#[derive(Clone, Debug, Default)]
 pub struct TimedData {
     pub rx_data: Value,
     pub tx_data: Value,
@@ -36,6 +46,90 @@ pub struct TimedData {
     pub load_avg_data: [f32; 3],
     pub mem_data: Option<Value>,
     pub swap_data: Option<Value>,
+
+    /// Set if this is a bucket of older harvests that were downsampled, in which case the values
+    /// above are their averages.
+    pub range: Option<Box<TimedRange>>,
+}
+
+/// The lowest and highest values across a downsampled bucket of harvests.
+#[derive(Clone, Debug, Default)]
+pub struct TimedRange {
+    pub min: TimedData,
+    pub max: TimedData,
+}
+
+/// A process that was seen in the latest harvest, along with what has been
+/// recorded about it over its lifetime so far.
+#[derive(Clone, Debug)]
+pub struct TrackedProcess {
+    pub name: String,
+    pub command: String,
+
+    /// How many harvests ago the process first showed up.
+    pub age_in_ticks: usize,
+
+    /// Whether the process showed up after bottom started, rather than being
+    /// there from the first harvest.
+    pub is_spawned: bool,
+
+    pub first_seen_instant: Instant,
+    pub time: Duration,
+    pub peak_cpu_percent: f64,
+    pub peak_mem_bytes: u64,
+}
+
+impl TrackedProcess {
+    fn new(process: &processes::ProcessHarvest, is_spawned: bool, instant: Instant) -> Self {
+        TrackedProcess {
+            name: process.name.clone(),
+            command: process.command.clone(),
+            age_in_ticks: 0,
+            is_spawned,
+            first_seen_instant: instant,
+            time: process.time,
+            peak_cpu_percent: process.cpu_usage_percent,
+            peak_mem_bytes: process.mem_usage_bytes,
+        }
+    }
+
+    /// Whether the process spawned recently enough that it should still be highlighted.
+    pub fn is_new(&self) -> bool {
+        self.is_spawned && self.age_in_ticks < NEW_PROCESS_HIGHLIGHT_TICKS
+    }
+}
+
+/// A process that was in one harvest but gone by the next.
+#[derive(Clone, Debug)]
+pub struct ExitedProcess {
+    pub pid: Pid,
+    pub name: String,
+    pub command: String,
+    pub lifetime: Duration,
+    pub peak_cpu_percent: f64,
+    pub peak_mem_bytes: u64,
+    pub exit_instant: Instant,
+}
+
+impl ExitedProcess {
+    fn new(pid: Pid, tracked: TrackedProcess, exit_instant: Instant) -> Self {
+        // Not every platform reports how long a process has been running, so fall back
+        // to how long we saw it for.
+        let lifetime = std::cmp::max(
+            tracked.time,
+            exit_instant.saturating_duration_since(tracked.first_seen_instant),
+        );
+
+        ExitedProcess {
+            pid,
+            name: tracked.name,
+            command: tracked.command,
+            lifetime,
+            peak_cpu_percent: tracked.peak_cpu_percent,
+            peak_mem_bytes: tracked.peak_mem_bytes,
+            exit_instant,
+        }
+    }
 }
 
 /// AppCollection represents the pooled data stored within the main app
@@ -51,7 +145,7 @@ pub struct TimedData {
 pub struct DataCollection {
     pub current_instant: Instant,
     pub frozen_instant: Option<Instant>,
-    pub timed_data_vec: Vec<(Instant, TimedData)>,
+    pub timed_data: TimeSeriesStore,
     pub network_harvest: network::NetworkHarvest,
     pub memory_harvest: memory::MemHarvest,
     pub swap_harvest: memory::MemHarvest,
@@ -65,6 +159,12 @@ pub struct DataCollection {
     pub temp_harvest: Vec<temperature::TempHarvest>,
     #[cfg(feature = "battery")]
     pub battery_harvest: Vec<batteries::BatteryHarvest>,
+
+    /// Every process in the latest harvest, keyed by PID.
+    pub tracked_processes: HashMap<Pid, TrackedProcess>,
+
+    /// Processes that have exited since bottom started, most recent first.
+    pub exited_processes: VecDeque<ExitedProcess>,
 }
 
 impl Default for DataCollection {
@@ -72,7 +172,7 @@ impl Default for DataCollection {
         DataCollection {
             current_instant: Instant::now(),
             frozen_instant: None,
-            timed_data_vec: Vec::default(),
+            timed_data: TimeSeriesStore::default(),
             network_harvest: network::NetworkHarvest::default(),
             memory_harvest: memory::MemHarvest::default(),
             swap_harvest: memory::MemHarvest::default(),
@@ -86,13 +186,15 @@ impl Default for DataCollection {
             temp_harvest: Vec::default(),
             #[cfg(feature = "battery")]
             battery_harvest: Vec::default(),
+            tracked_processes: HashMap::default(),
+            exited_processes: VecDeque::default(),
         }
     }
 }
 
 impl DataCollection {
     pub fn reset(&mut self) {
-        self.timed_data_vec = Vec::default();
+        self.timed_data = TimeSeriesStore::default();
         self.network_harvest = network::NetworkHarvest::default();
         self.memory_harvest = memory::MemHarvest::default();
         self.swap_harvest = memory::MemHarvest::default();
@@ -106,29 +208,64 @@ impl DataCollection {
         {
             self.battery_harvest = Vec::default();
         }
+        self.tracked_processes = HashMap::default();
+        self.exited_processes = VecDeque::default();
+    }
+
+    /// Fills in harvests from before bottom started, like saved history.  If CPUs were harvested,
+    /// only harvests with the same number of CPUs are kept, as others can't be lined up with the
+    /// current ones.
+    pub fn prepend_history(&mut self, history: Vec<(Instant, TimedData)>) {
+        let first_instant = self.timed_data.first_instant();
+        let cpu_count = self
+            .timed_data
+            .last()
+            .map_or(0, |(_, timed_data)| timed_data.cpu_data.len());
+
+        let mut timed_data = TimeSeriesStore::default();
+        for (instant, history_data) in &history {
+            if first_instant.is_none_or(|first_instant| *instant < first_instant)
+                && (cpu_count == 0 || history_data.cpu_data.len() == cpu_count)
+            {
+                timed_data.push(*instant, history_data);
+            }
+        }
+        for (instant, current_data) in self.timed_data.rows() {
+            timed_data.push(instant, &current_data);
+        }
+        self.timed_data = timed_data;
     }
 
     pub fn set_frozen_time(&mut self) {
         self.frozen_instant = Some(self.current_instant);
     }
 
+    /// Removes anything older than `max_time_millis`, measured from the latest harvest rather
+    /// than the current time so replayed data ages at the pace it was recorded.  Anything older
+    /// than [`STALE_MAX_MILLISECONDS`] is downsampled, so only so much is kept however long
+    /// `max_time_millis` is.
     pub fn clean_data(&mut self, max_time_millis: u64) {
-        let current_time = Instant::now();
-
-        let remove_index = match self
-            .timed_data_vec
-            .binary_search_by(|(instant, _timed_data)| {
-                current_time
-                    .duration_since(*instant)
-                    .as_millis()
-                    .cmp(&(max_time_millis as u128))
-                    .reverse()
-            }) {
-            Ok(index) => index,
-            Err(index) => index,
-        };
-
-        self.timed_data_vec.drain(0..remove_index);
+        self.timed_data
+            .remove_older_than(self.current_instant, Duration::from_millis(max_time_millis));
+        self.downsample(max_time_millis);
+        self.timed_data
+            .shrink(Duration::from_millis(max_time_millis));
+    }
+
+    /// Merges harvests older than [`STALE_MAX_MILLISECONDS`] into buckets, with each bucket
+    /// covering an equal part of the rest of `max_time_millis`.
+    fn downsample(&mut self, max_time_millis: u64) {
+        let bucket_width = Duration::from_millis(
+            max_time_millis.saturating_sub(STALE_MAX_MILLISECONDS) / DOWNSAMPLED_BUCKET_COUNT,
+        );
+        let cutoff = self
+            .current_instant
+            .checked_sub(Duration::from_millis(STALE_MAX_MILLISECONDS));
+        if let Some(cutoff) = cutoff {
+            if bucket_width > Duration::default() {
+                self.timed_data.downsample(cutoff, bucket_width);
+            }
+        }
     }
 
     pub fn eat_data(&mut self, harvested_data: Box<Data>) {
@@ -148,8 +285,9 @@ impl DataCollection {
         }
 
         // CPU
+        let has_cpu = harvested_data.cpu.is_some();
         if let Some(cpu) = harvested_data.cpu {
-            self.eat_cpu(cpu, &mut new_entry);
+            self.eat_cpu(cpu);
         }
 
         // Load Average
@@ -171,7 +309,7 @@ impl DataCollection {
 
         // Processes
         if let Some(list_of_processes) = harvested_data.list_of_processes {
-            self.eat_proc(list_of_processes);
+            self.eat_proc(list_of_processes, harvested_time);
         }
 
         #[cfg(feature = "battery")]
@@ -182,9 +320,15 @@ impl DataCollection {
             }
         }
 
-        // And we're done eating.  Update time and push the new entry!
+        // And we're done eating.  Update time and push the new entry, with CPU usage read
+        // straight from the harvest!
         self.current_instant = harvested_time;
-        self.timed_data_vec.push((harvested_time, new_entry));
+        let cpu_harvest = if has_cpu { &self.cpu_harvest[..] } else { &[] };
+        self.timed_data.push_harvest(
+            harvested_time,
+            &new_entry,
+            cpu_harvest.iter().map(|cpu| cpu.cpu_usage),
+        );
     }
 
     fn eat_memory_and_swap(
@@ -216,14 +360,9 @@ impl DataCollection {
         self.network_harvest = network;
     }
 
-    fn eat_cpu(&mut self, cpu: Vec<cpu::CpuData>, new_entry: &mut TimedData) {
-        // Note this only pre-calculates the data points - the names will be
-        // within the local copy of cpu_harvest.  Since it's all sequential
-        // it probably doesn't matter anyways.
-        cpu.iter()
-            .for_each(|cpu| new_entry.cpu_data.push(cpu.cpu_usage));
-
-        self.cpu_harvest = cpu.to_vec();
+    fn eat_cpu(&mut self, cpu: Vec<cpu::CpuData>) {
+        // The usage is added to the graph data from here once everything else is eaten.
+        self.cpu_harvest = cpu;
     }
 
     fn eat_load_avg(&mut self, load_avg: cpu::LoadAvgHarvest, new_entry: &mut TimedData) {
@@ -319,10 +458,53 @@ impl DataCollection {
         self.io_harvest = io;
     }
 
-    fn eat_proc(&mut self, list_of_processes: Vec<processes::ProcessHarvest>) {
+    fn eat_proc(
+        &mut self, list_of_processes: Vec<processes::ProcessHarvest>, harvested_time: Instant,
+    ) {
+        // Everything is there from the start on the first harvest, so nothing is "new" yet.
+        let is_first_harvest = self.tracked_processes.is_empty();
+        let mut previous_processes = std::mem::take(&mut self.tracked_processes);
+
+        for process in &list_of_processes {
+            let tracked = match previous_processes.remove(&process.pid) {
+                // A PID that comes back with a different name was reused.
+                Some(tracked) if tracked.name != process.name => {
+                    self.record_exited_process(process.pid, tracked, harvested_time);
+                    TrackedProcess::new(process, !is_first_harvest, harvested_time)
+                }
+                Some(mut tracked) => {
+                    tracked.age_in_ticks = tracked.age_in_ticks.saturating_add(1);
+                    tracked.command = process.command.clone();
+                    tracked.time = process.time;
+                    if process.cpu_usage_percent > tracked.peak_cpu_percent {
+                        tracked.peak_cpu_percent = process.cpu_usage_percent;
+                    }
+                    if process.mem_usage_bytes > tracked.peak_mem_bytes {
+                        tracked.peak_mem_bytes = process.mem_usage_bytes;
+                    }
+                    tracked
+                }
+                None => TrackedProcess::new(process, !is_first_harvest, harvested_time),
+            };
+            self.tracked_processes.insert(process.pid, tracked);
+        }
+
+        // Whatever is left over is gone.  Sort them so the log is stable.
+        let mut exited = previous_processes.into_iter().collect::<Vec<_>>();
+        exited.sort_by_key(|(pid, _)| *pid);
+        for (pid, tracked) in exited {
+            self.record_exited_process(pid, tracked, harvested_time);
+        }
+
         self.process_harvest = list_of_processes;
     }
 
+    fn record_exited_process(&mut self, pid: Pid, tracked: TrackedProcess, exit_instant: Instant) {
+        self.exited_processes
+            .push_front(ExitedProcess::new(pid, tracked, exit_instant));
+        self.exited_processes.truncate(MAX_EXITED_PROCESSES);
+    }
+
     #[cfg(feature = "battery")]
     fn eat_battery(&mut self, list_of_batteries: Vec<batteries::BatteryHarvest>) {
         self.battery_harvest = list_of_batteries;
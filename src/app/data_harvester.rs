@@ -14,6 +14,7 @@ use battery::{Battery, Manager};
 use crate::app::layout_manager::UsedWidgets;
 
 use futures::join;
+use serde::{Deserialize, Serialize};
 
 use super::DataFilters;
 
@@ -26,19 +27,32 @@ pub mod network;
 pub mod processes;
 pub mod temperature;
 
-#[derive(Clone, Debug)]
+/// Anything that wasn't collected (for example, because no widget uses it) is left out when
+/// serialized.
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Data {
+    #[serde(skip, default = "Instant::now")]
     pub last_collection_time: Instant,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub cpu: Option<cpu::CpuHarvest>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub load_avg: Option<cpu::LoadAvgHarvest>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub memory: Option<memory::MemHarvest>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub swap: Option<memory::MemHarvest>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub temperature_sensors: Option<Vec<temperature::TempHarvest>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub network: Option<network::NetworkHarvest>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub list_of_processes: Option<Vec<processes::ProcessHarvest>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub disks: Option<Vec<disks::DiskHarvest>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub io: Option<disks::IoHarvest>,
     #[cfg(feature = "battery")]
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub list_of_batteries: Option<Vec<batteries::BatteryHarvest>>,
 }
 
@@ -13,8 +13,9 @@ use battery::{
     units::{power::watt, ratio::percent, time::second},
     Battery, Manager,
 };
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BatteryHarvest {
     pub charge_percent: f64,
     pub secs_until_full: Option<i64>,
@@ -18,7 +18,9 @@ cfg_if::cfg_if! {
     }
 }
 
-#[derive(Default, Debug, Clone)]
+use serde::{Deserialize, Serialize};
+
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
 pub struct CpuData {
     pub cpu_prefix: String,
     pub cpu_count: Option<usize>,
@@ -1,4 +1,5 @@
 use crate::app::Filter;
+use serde::{Deserialize, Serialize};
 
 cfg_if::cfg_if! {
     if #[cfg(target_os = "linux")] {
@@ -10,16 +11,17 @@ cfg_if::cfg_if! {
     }
 }
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct DiskHarvest {
     pub name: String,
     pub mount_point: String,
+    pub file_system: String,
     pub free_space: Option<u64>,
     pub used_space: Option<u64>,
     pub total_space: Option<u64>,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct IoData {
     pub read_bytes: u64,
     pub write_bytes: u64,
@@ -80,6 +82,8 @@ pub async fn get_disk_usage(
                 .unwrap_or("Name Unavailable"))
             .to_string();
 
+            let file_system = partition.file_system().as_str().to_string();
+
             // Precedence ordering in the case where name and mount filters disagree, "allow" takes precedence over "deny".
             //
             // For implementation, we do this as follows:
@@ -133,6 +137,7 @@ pub async fn get_disk_usage(
                         used_space: Some(usage.used().get::<heim::units::information::byte>()),
                         total_space: Some(usage.total().get::<heim::units::information::byte>()),
                         mount_point,
+                        file_system,
                         name,
                     });
                 } else {
@@ -141,6 +146,7 @@ pub async fn get_disk_usage(
                         used_space: None,
                         total_space: None,
                         mount_point,
+                        file_system,
                         name,
                     });
                 }
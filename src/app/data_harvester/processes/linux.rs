@@ -1,6 +1,7 @@
 //! Process data collection for Linux.
 
 use std::collections::hash_map::Entry;
+use std::time::Duration;
 
 use crate::utils::error::{self, BottomError};
 use crate::Pid;
@@ -23,19 +24,53 @@ pub struct PrevProcDetails {
     pub total_write_bytes: u64,
     pub cpu_time: u64,
     pub process: Process,
+
+    /// The cgroup of the process, read once when the process is first seen.
+    pub cgroup: Option<String>,
 }
 
 impl PrevProcDetails {
     fn new(pid: Pid) -> error::Result<Self> {
+        let process = Process::new(pid)?;
+        let cgroup = get_cgroup(&process);
+
         Ok(Self {
             total_read_bytes: 0,
             total_write_bytes: 0,
             cpu_time: 0,
-            process: Process::new(pid)?,
+            process,
+            cgroup,
         })
     }
 }
 
+/// Returns the cgroup path of a process. The unified (v2) hierarchy is preferred; otherwise the first
+/// v1 hierarchy is used.
+fn get_cgroup(process: &Process) -> Option<String> {
+    let cgroups = process.cgroups().ok()?;
+    cgroups
+        .iter()
+        .find(|cgroup| cgroup.hierarchy == 0)
+        .or_else(|| cgroups.first())
+        .map(|cgroup| cgroup.pathname.clone())
+}
+
+/// Returns the name of a process' controlling terminal, if it has one.
+fn get_tty(stat: &Stat) -> Option<String> {
+    if stat.tty_nr == 0 {
+        return None;
+    }
+
+    match stat.tty_nr() {
+        (4, minor) if minor < 64 => Some(format!("tty{}", minor)),
+        (4, minor) => Some(format!("ttyS{}", minor - 64)),
+        (major, minor) if (136..=143).contains(&major) => {
+            Some(format!("pts/{}", (major - 136) * 256 + minor))
+        }
+        (major, minor) => Some(format!("{}:{}", major, minor)),
+    }
+}
+
 fn calculate_idle_values(line: String) -> (f64, f64) {
     /// Converts a `Option<&str>` value to an f64. If it fails to parse or is `None`, then it will return `0_f64`.
     fn str_to_f64(val: Option<&str>) -> f64 {
@@ -119,7 +154,7 @@ fn get_linux_cpu_usage(
 #[allow(clippy::too_many_arguments)]
 fn read_proc(
     prev_proc: &PrevProcDetails, stat: &Stat, cpu_usage: f64, cpu_fraction: f64,
-    use_current_cpu_total: bool, time_difference_in_secs: u64, mem_total_kb: u64,
+    use_current_cpu_total: bool, time_difference_in_secs: u64, mem_total_kb: u64, uptime_secs: f64,
 ) -> error::Result<(ProcessHarvest, u64)> {
     use std::convert::TryFrom;
 
@@ -200,6 +235,16 @@ fn read_proc(
         };
 
     let uid = Some(process.owner);
+    let tty = get_tty(stat);
+    let cgroup = prev_proc.cgroup.clone();
+
+    // The start time is given in clock ticks since boot.
+    let time = match procfs::ticks_per_second() {
+        Ok(ticks_per_sec) if ticks_per_sec > 0 => Duration::from_secs_f64(
+            (uptime_secs - stat.starttime as f64 / ticks_per_sec as f64).max(0.0),
+        ),
+        _ => Duration::default(),
+    };
 
     Ok((
         ProcessHarvest {
@@ -216,7 +261,10 @@ fn read_proc(
             total_write_bytes,
             process_state,
             process_state_char,
+            time,
             uid,
+            tty,
+            cgroup,
         },
         new_process_times,
     ))
@@ -231,6 +279,9 @@ pub fn get_process_data(
 
     if let Ok((cpu_usage, cpu_fraction)) = cpu_usage_calculation(prev_idle, prev_non_idle) {
         let mut pids_to_clear: FxHashSet<Pid> = pid_mapping.keys().cloned().collect();
+        let uptime_secs = procfs::Uptime::new()
+            .map(|uptime| uptime.uptime)
+            .unwrap_or(0.0);
 
         let process_vector: Vec<ProcessHarvest> = std::fs::read_dir("/proc")?
             .filter_map(|dir| {
@@ -268,6 +319,7 @@ pub fn get_process_data(
                                 use_current_cpu_total,
                                 time_difference_in_secs,
                                 mem_total_kb,
+                                uptime_secs,
                             ) {
                                 prev_proc_details.cpu_time = new_process_times;
                                 prev_proc_details.total_read_bytes =
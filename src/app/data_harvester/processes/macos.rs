@@ -1,6 +1,7 @@
 //! Process data collection for macOS.  Uses sysinfo.
 
 use super::ProcessHarvest;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use sysinfo::{ProcessExt, ProcessStatus, ProcessorExt, System, SystemExt};
 
 fn get_macos_process_cpu_usage(
@@ -41,6 +42,10 @@ pub fn get_process_data(
     let process_hashmap = sys.get_processes();
     let cpu_usage = sys.get_global_processor_info().get_cpu_usage() as f64 / 100.0;
     let num_processors = sys.get_processors().len() as f64;
+    let current_time = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|time| time.as_secs())
+        .unwrap_or(0);
     for process_val in process_hashmap.values() {
         let name = if process_val.name().is_empty() {
             let process_cmd = process_val.cmd();
@@ -104,7 +109,10 @@ pub fn get_process_data(
             total_write_bytes: disk_usage.total_written_bytes,
             process_state: process_val.status().to_string(),
             process_state_char: convert_process_status_to_char(process_val.status()),
+            time: Duration::from_secs(current_time.saturating_sub(process_val.start_time())),
             uid: Some(process_val.uid),
+            tty: None,
+            cgroup: None,
         });
     }
 
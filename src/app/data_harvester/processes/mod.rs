@@ -24,6 +24,8 @@ cfg_if::cfg_if! {
 }
 
 use crate::Pid;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
 
 // TODO: Add value so we know if it's sorted ascending or descending by default?
 #[derive(Clone, PartialEq, Eq, Hash, Debug)]
@@ -73,7 +75,47 @@ impl Default for ProcessSorting {
     }
 }
 
-#[derive(Debug, Clone, Default)]
+impl ProcessSorting {
+    /// Whether sorting by this column should start in descending order.  Anything that
+    /// is alphabetical (or an ID) starts ascending, everything else descending.
+    pub fn is_descending_by_default(&self) -> bool {
+        !matches!(
+            self,
+            ProcessSorting::State
+                | ProcessSorting::Pid
+                | ProcessSorting::ProcessName
+                | ProcessSorting::Command
+        )
+    }
+}
+
+impl std::str::FromStr for ProcessSorting {
+    type Err = crate::utils::error::BottomError;
+
+    fn from_str(s: &str) -> crate::utils::error::Result<Self> {
+        match s.to_lowercase().as_str() {
+            "cpu" | "cpu%" => Ok(ProcessSorting::CpuPercent),
+            "mem" | "memory" => Ok(ProcessSorting::Mem),
+            "mem%" | "memory%" => Ok(ProcessSorting::MemPercent),
+            "pid" => Ok(ProcessSorting::Pid),
+            "name" => Ok(ProcessSorting::ProcessName),
+            "command" | "cmd" => Ok(ProcessSorting::Command),
+            "r/s" | "read" | "rps" => Ok(ProcessSorting::ReadPerSecond),
+            "w/s" | "write" | "wps" => Ok(ProcessSorting::WritePerSecond),
+            "t.read" | "tread" => Ok(ProcessSorting::TotalRead),
+            "t.write" | "twrite" => Ok(ProcessSorting::TotalWrite),
+            "state" => Ok(ProcessSorting::State),
+            "user" => Ok(ProcessSorting::User),
+            "count" => Ok(ProcessSorting::Count),
+            _ => Err(crate::utils::error::BottomError::ConfigError(format!(
+                "\"{}\" is an invalid process column, use one of \"<cpu%|mem|mem%|pid|name|command|r/s|w/s|t.read|t.write|state|user|count>\".",
+                s
+            ))),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct ProcessHarvest {
     pub pid: Pid,
     pub parent_pid: Option<Pid>, // Remember, parent_pid 0 is root...
@@ -91,7 +133,16 @@ pub struct ProcessHarvest {
     pub process_state: String,
     pub process_state_char: char,
 
+    /// How long the process has been running for.
+    pub time: Duration,
+
     /// This is the *effective* user ID.
     #[cfg(target_family = "unix")]
     pub uid: Option<libc::uid_t>,
+
+    /// The controlling terminal of the process, if any.
+    pub tty: Option<String>,
+
+    /// The cgroup of the process. Only gathered on Linux.
+    pub cgroup: Option<String>,
 }
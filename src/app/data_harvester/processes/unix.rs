@@ -16,7 +16,10 @@ impl UserTable {
             let passwd = unsafe { libc::getpwuid(uid) };
 
             if passwd.is_null() {
-                return Err(error::BottomError::QueryError("Missing passwd".into()));
+                return Err(error::BottomError::QueryError(
+                    "Missing passwd".into(),
+                    None,
+                ));
             }
 
             let username = unsafe { std::ffi::CStr::from_ptr((*passwd).pw_name) }
@@ -16,8 +16,9 @@ cfg_if::cfg_if! {
 use std::cmp::Ordering;
 
 use crate::app::Filter;
+use serde::{Deserialize, Serialize};
 
-#[derive(Default, Debug, Clone)]
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
 pub struct TempHarvest {
     pub name: String,
     pub temperature: f32,
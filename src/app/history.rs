@@ -0,0 +1,307 @@
+//! Saves the data behind the charts to the cache directory, so the charts can be filled back in
+//! when bottom starts again.
+//!
+//! The file starts with [`MAGIC`] and a version byte, followed by a record for each harvest.  A
+//! record is its length as a `u32`, followed by when it was harvested in milliseconds since the
+//! Unix epoch as a `u64`, then the values as `f32`s.  Downsampled records are followed by their
+//! lowest and highest values, laid out the same way.  Numbers are little endian.  Records are
+//! only ever appended, except when the file is rewritten to drop ones that are too old to show.
+//!
+//! Only one instance of bottom can save history at a time, which is enforced with a lock on a
+//! file next to the history file.  Any other instances just don't save history.
+
+use std::{
+    convert::TryInto,
+    fs::{self, File, OpenOptions, TryLockError},
+    io::{ErrorKind, Read, Write},
+    path::{Path, PathBuf},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use crate::{
+    app::{
+        data_farmer::{TimedData, TimedRange},
+        time_series::{HarvestRow, Series},
+    },
+    constants::DEFAULT_HISTORY_FILE_PATH,
+    utils::error::{self, BottomError},
+};
+
+const MAGIC: &[u8; 7] = b"BTMHIST";
+const VERSION: u8 = 1;
+
+/// The history file, which is opened on the first write.
+pub struct History {
+    path: PathBuf,
+    file: Option<File>,
+    /// Held for as long as this exists, so that no other instance writes to the same file.
+    _lock: File,
+    /// Reused for each record appended.
+    record: Vec<u8>,
+}
+
+impl History {
+    /// Takes the history file for this instance.  Returns nothing if another instance already
+    /// has it.
+    pub fn open(path: PathBuf) -> error::Result<Option<Self>> {
+        let get_error = |err| get_error(&path, err);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(get_error)?;
+        }
+        let lock = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(path.with_extension("lock"))
+            .map_err(get_error)?;
+
+        match lock.try_lock() {
+            Ok(()) => Ok(Some(History {
+                path,
+                file: None,
+                _lock: lock,
+                record: Vec::new(),
+            })),
+            Err(TryLockError::WouldBlock) => Ok(None),
+            Err(TryLockError::Error(err)) => Err(get_error(err)),
+        }
+    }
+
+    /// The history file in the cache directory, if there is one.
+    pub fn default_path() -> Option<PathBuf> {
+        dirs::cache_dir().map(|cache_dir| cache_dir.join(DEFAULT_HISTORY_FILE_PATH))
+    }
+
+    /// Reads back everything harvested within the last `max_age_millis`, oldest first, and
+    /// rewrites the file with just that.  A missing file, or one written by an incompatible
+    /// version, has nothing to read back.
+    pub fn load(&mut self, max_age_millis: u64) -> error::Result<Vec<(Instant, TimedData)>> {
+        let contents = match fs::read(&self.path) {
+            Ok(contents) => contents,
+            Err(err) if err.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(err) => return Err(self.get_error(err)),
+        };
+
+        let (now_instant, now_time) = (Instant::now(), SystemTime::now());
+        let max_age = Duration::from_millis(max_age_millis);
+        let mut history = Vec::new();
+        if contents.starts_with(&get_header()) {
+            let mut remaining = &contents[MAGIC.len() + 1..];
+            // A record cut short, like from bottom being killed while writing, ends things.
+            while let Some((timestamp, timed_data)) = read_record(&mut remaining) {
+                let age = now_time
+                    .duration_since(UNIX_EPOCH + Duration::from_millis(timestamp))
+                    .unwrap_or_default();
+                if age <= max_age {
+                    if let Some(instant) = now_instant.checked_sub(age) {
+                        history.push((instant, timed_data));
+                    }
+                }
+            }
+        }
+
+        self.rewrite(history.iter().cloned())?;
+        Ok(history)
+    }
+
+    /// Adds a harvest to the end of the file, reading it straight from where it is stored.
+    pub fn append_harvest(&mut self, instant: Instant, row: &HarvestRow<'_>) -> error::Result<()> {
+        self.record.clear();
+        write_sized(&mut self.record, |payload| {
+            payload.extend(&get_timestamp(instant).to_le_bytes());
+            write_row_values(payload, row);
+        });
+        self.write_record()
+    }
+
+    fn write_record(&mut self) -> error::Result<()> {
+        if self.file.is_none() {
+            if let Some(parent) = self.path.parent() {
+                fs::create_dir_all(parent).map_err(|err| self.get_error(err))?;
+            }
+            let mut file = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&self.path)
+                .map_err(|err| self.get_error(err))?;
+            if file.metadata().map_err(|err| self.get_error(err))?.len() == 0 {
+                file.write_all(&get_header())
+                    .map_err(|err| self.get_error(err))?;
+            }
+            self.file = Some(file);
+        }
+
+        let result = match &mut self.file {
+            Some(file) => file.write_all(&self.record),
+            None => Ok(()),
+        };
+        result.map_err(|err| {
+            self.file = None;
+            self.get_error(err)
+        })
+    }
+
+    /// Replaces the file with just the given harvests, so it doesn't keep growing.
+    pub fn rewrite(
+        &mut self, history: impl IntoIterator<Item = (Instant, TimedData)>,
+    ) -> error::Result<()> {
+        self.file = None;
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent).map_err(|err| self.get_error(err))?;
+        }
+
+        let mut contents = get_header();
+        for (instant, timed_data) in history {
+            write_record(&mut contents, get_timestamp(instant), &timed_data);
+        }
+
+        // Write everything out first, so the old file is left alone if that fails partway.
+        let temp_path = self.path.with_extension("tmp");
+        fs::write(&temp_path, contents)
+            .and_then(|_| fs::rename(&temp_path, &self.path))
+            .map_err(|err| self.get_error(err))
+    }
+
+    fn get_error(&self, err: std::io::Error) -> BottomError {
+        get_error(&self.path, err)
+    }
+}
+
+fn get_error(path: &Path, err: std::io::Error) -> BottomError {
+    BottomError::GenericError(format!(
+        "Could not save history to {}: {}",
+        path.display(),
+        err
+    ))
+}
+
+fn get_header() -> Vec<u8> {
+    let mut header = MAGIC.to_vec();
+    header.push(VERSION);
+    header
+}
+
+/// Converts an instant into milliseconds since the Unix epoch.
+fn get_timestamp(instant: Instant) -> u64 {
+    let time = SystemTime::now() - Instant::now().saturating_duration_since(instant);
+    time.duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+fn write_record(record: &mut Vec<u8>, timestamp: u64, timed_data: &TimedData) {
+    write_sized(record, |payload| {
+        payload.extend(&timestamp.to_le_bytes());
+        write_values(payload, timed_data);
+        if let Some(range) = &timed_data.range {
+            write_values(payload, &range.min);
+            write_values(payload, &range.max);
+        }
+    });
+}
+
+/// Adds whatever `write_payload` writes to the end of `record`, preceded by its length.
+fn write_sized(record: &mut Vec<u8>, write_payload: impl FnOnce(&mut Vec<u8>)) {
+    let start = record.len();
+    record.extend(&0_u32.to_le_bytes());
+    write_payload(record);
+    let length = (record.len() - start - 4) as u32;
+    record[start..start + 4].copy_from_slice(&length.to_le_bytes());
+}
+
+fn write_values(payload: &mut Vec<u8>, timed_data: &TimedData) {
+    let values = [
+        timed_data.rx_data as f32,
+        timed_data.tx_data as f32,
+        timed_data.load_avg_data[0],
+        timed_data.load_avg_data[1],
+        timed_data.load_avg_data[2],
+        // Anything missing is stored as NaN.
+        timed_data.mem_data.map_or(f32::NAN, |mem| mem as f32),
+        timed_data.swap_data.map_or(f32::NAN, |swap| swap as f32),
+    ];
+    let cpu_data = timed_data.cpu_data.iter().map(|cpu| *cpu as f32);
+    write_value_list(payload, values, cpu_data);
+}
+
+fn write_row_values(payload: &mut Vec<u8>, row: &HarvestRow<'_>) {
+    let [load_avg_one, load_avg_five, load_avg_fifteen] = row.load_avg();
+    let values = [
+        row.get(Series::Rx),
+        row.get(Series::Tx),
+        load_avg_one,
+        load_avg_five,
+        load_avg_fifteen,
+        row.get(Series::Mem),
+        row.get(Series::Swap),
+    ];
+    let cpu_data = (0..row.cpu_count()).map(|itx| row.get(Series::Cpu(itx)));
+    write_value_list(payload, values, cpu_data);
+}
+
+fn write_value_list(
+    payload: &mut Vec<u8>, values: [f32; 7], cpu_data: impl ExactSizeIterator<Item = f32>,
+) {
+    for value in values {
+        payload.extend(&value.to_le_bytes());
+    }
+    payload.extend(&(cpu_data.len() as u16).to_le_bytes());
+    for cpu in cpu_data {
+        payload.extend(&cpu.to_le_bytes());
+    }
+}
+
+/// Reads the next record, moving past it.  Returns nothing if there isn't a whole record left.
+fn read_record(remaining: &mut &[u8]) -> Option<(u64, TimedData)> {
+    let mut length = [0; 4];
+    remaining.read_exact(&mut length).ok()?;
+    let length = u32::from_le_bytes(length) as usize;
+    if remaining.len() < length {
+        return None;
+    }
+    let (mut payload, rest) = remaining.split_at(length);
+    *remaining = rest;
+
+    let timestamp = u64::from_le_bytes(take(&mut payload)?);
+    let mut timed_data = read_values(&mut payload)?;
+    if !payload.is_empty() {
+        let min = read_values(&mut payload)?;
+        let max = read_values(&mut payload)?;
+        timed_data.range = Some(Box::new(TimedRange { min, max }));
+    }
+
+    Some((timestamp, timed_data))
+}
+
+fn read_values(payload: &mut &[u8]) -> Option<TimedData> {
+    let mut next_value = || Some(f32::from_le_bytes(take(payload)?));
+    let rx_data = next_value()?;
+    let tx_data = next_value()?;
+    let load_avg_data = [next_value()?, next_value()?, next_value()?];
+    let mem_data = Some(next_value()?).filter(|mem| !mem.is_nan());
+    let swap_data = Some(next_value()?).filter(|swap| !swap.is_nan());
+    let cpu_count = u16::from_le_bytes(take(payload)?);
+    let cpu_data = (0..cpu_count)
+        .map(|_| Some(f32::from_le_bytes(take(payload)?).into()))
+        .collect::<Option<Vec<_>>>()?;
+
+    Some(TimedData {
+        rx_data: rx_data.into(),
+        tx_data: tx_data.into(),
+        cpu_data,
+        load_avg_data,
+        mem_data: mem_data.map(f64::from),
+        swap_data: swap_data.map(f64::from),
+        range: None,
+    })
+}
+
+/// Takes the next `N` bytes.
+fn take<const N: usize>(payload: &mut &[u8]) -> Option<[u8; N]> {
+    if payload.len() < N {
+        return None;
+    }
+    let (bytes, rest) = payload.split_at(N);
+    *payload = rest;
+    bytes.try_into().ok()
+}
@@ -879,6 +879,14 @@ pub struct BottomWidget {
     /// Bottom right corner when drawn, for mouse click detection.  (x, y)
     #[builder(default = None)]
     pub bottom_right_corner: Option<(u16, u16)>,
+
+    /// The search query a process widget starts with, if any.
+    #[builder(default = None)]
+    pub default_query: Option<String>,
+
+    /// The columns a process widget shows, in order, if set.
+    #[builder(default = None)]
+    pub columns: Option<Vec<String>>,
 }
 
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
@@ -893,6 +901,7 @@ pub enum BottomWidgetType {
     ProcSort,
     Temp,
     Disk,
+    User,
     BasicCpu,
     BasicMem,
     BasicNet,
@@ -903,7 +912,7 @@ pub enum BottomWidgetType {
 impl BottomWidgetType {
     pub fn is_widget_table(&self) -> bool {
         use BottomWidgetType::*;
-        matches!(self, Disk | Proc | ProcSort | Temp | CpuLegend)
+        matches!(self, Disk | Proc | ProcSort | Temp | User | CpuLegend)
     }
 
     pub fn is_widget_graph(&self) -> bool {
@@ -920,6 +929,7 @@ impl BottomWidgetType {
             Proc => "Processes",
             Temp => "Temperature",
             Disk => "Disks",
+            User => "Users",
             Battery => "Battery",
             _ => "",
         }
@@ -944,6 +954,7 @@ impl std::str::FromStr for BottomWidgetType {
             "proc" | "process" | "processes" => Ok(BottomWidgetType::Proc),
             "temp" | "temperature" => Ok(BottomWidgetType::Temp),
             "disk" => Ok(BottomWidgetType::Disk),
+            "user" | "users" => Ok(BottomWidgetType::User),
             "empty" => Ok(BottomWidgetType::Empty),
             "battery" | "batt" if cfg!(feature = "battery") => Ok(BottomWidgetType::Battery),
             _ => {
@@ -965,6 +976,8 @@ Supported widget names:
 +--------------------------+
 |           disk           |
 +--------------------------+
+|        user, users       |
++--------------------------+
 |       batt, battery      |
 +--------------------------+
                 ",
@@ -987,6 +1000,8 @@ Supported widget names:
 |     temp, temperature    |
 +--------------------------+
 |           disk           |
++--------------------------+
+|        user, users       |
 +--------------------------+
                 ",
                         s
@@ -1005,5 +1020,6 @@ pub struct UsedWidgets {
     pub use_proc: bool,
     pub use_disk: bool,
     pub use_temp: bool,
+    pub use_user: bool,
     pub use_battery: bool,
 }
@@ -1,18 +1,45 @@
-use super::ProcWidgetState;
+use super::{
+    data_harvester::{disks::DiskHarvest, temperature::TempHarvest},
+    ProcWidgetState,
+};
 use crate::{
-    data_conversion::ConvertedProcessData,
+    data_conversion::{get_parent_child_mapping, ConvertedProcessData, ConvertedUserData},
     utils::error::{
         BottomError::{self, QueryError},
         Result,
     },
+    Pid,
 };
+use fxhash::FxBuildHasher;
+use indexmap::IndexSet;
+use once_cell::unsync::OnceCell;
+use regex::Regex;
 use std::fmt::Debug;
-use std::{borrow::Cow, collections::VecDeque};
+use std::{
+    borrow::Cow,
+    collections::{HashMap, VecDeque},
+    ops::Range,
+};
 
-const DELIMITER_LIST: [char; 6] = ['=', '>', '<', '(', ')', '\"'];
+const DELIMITER_LIST: [char; 7] = ['=', '>', '<', '(', ')', '\"', '!'];
 const COMPARISON_LIST: [&str; 3] = [">", "=", "<"];
 const OR_LIST: [&str; 2] = ["or", "||"];
 const AND_LIST: [&str; 2] = ["and", "&&"];
+const NOT_LIST: [&str; 2] = ["not", "!"];
+
+/// A single token from a query, along with the span of characters it covers in the original query.
+/// The span is what lets us point at exactly where a query went wrong.
+#[derive(Debug)]
+struct QueryToken {
+    value: String,
+    span: Range<usize>,
+}
+
+impl QueryToken {
+    fn new(value: String, span: Range<usize>) -> Self {
+        QueryToken { value, span }
+    }
+}
 
 /// I only separated this as otherwise, the states.rs file gets huge... and this should
 /// belong in another file anyways, IMO.
@@ -22,7 +49,7 @@ pub trait ProcessQuery {
     ///
     /// - Process names: No prefix required, can use regex, match word, or case.
     ///   Enclosing anything, including prefixes, in quotes, means we treat it as an entire process
-    ///   rather than a prefix.
+    ///   rather than a prefix.  Can also be explicitly written with the prefix `name`.
     /// - PIDs: Use prefix `pid`, can use regex or match word (case is irrelevant).
     /// - CPU: Use prefix `cpu`, cannot use r/m/c (regex, match word, case).  Can compare.
     /// - MEM: Use prefix `mem`, cannot use r/m/c.  Can compare.
@@ -32,417 +59,744 @@ pub trait ProcessQuery {
     /// - Write/s: Use prefix `w`.  Can compare.
     /// - Total read: Use prefix `read`.  Can compare.
     /// - Total write: Use prefix `write`.  Can compare.
+    /// - Time: Use prefix `time`, for how long a process has been running.  Can compare.
+    /// - Parent PID: Use prefix `ppid`, can use regex or match word (case is irrelevant).
+    /// - Children: Use prefix `children`, for the number of direct children.  Can compare.
+    /// - Ancestors: Use prefix `ancestor`, matches if any process up the parent chain matches by
+    ///   name or PID.  Can use regex, match word, or case for names; PIDs must match exactly.
+    ///
+    /// Values being compared can have units, depending on the prefix - percentages for `cpu` and
+    /// `mem`, sizes for `memb`, `tread`, and `twrite`, rates for `read` and `write`, and durations
+    /// for `time`.
     ///
     /// For queries, whitespaces are our delimiters.  We will merge together any adjacent non-prefixed
     /// or quoted elements after splitting to treat as process names.
-    /// Furthermore, we want to support boolean joiners like AND and OR, and brackets.
+    /// Furthermore, we want to support boolean joiners like AND and OR, negation via NOT (or `!`),
+    /// and brackets.
+    ///
+    /// Any errors will carry the span of the offending token, if there is one.
+    ///
+    /// The disk, temperature, and user tables use the same language with their own prefixes; see
+    /// [`PrefixSet`].
     fn parse_query(&self) -> Result<Query>;
 }
 
 impl ProcessQuery for ProcWidgetState {
     fn parse_query(&self) -> Result<Query> {
-        fn process_string_to_filter(query: &mut VecDeque<String>) -> Result<Query> {
-            let lhs = process_or(query)?;
-            let mut list_of_ors = vec![lhs];
+        parse_query(
+            self.get_current_search_query(),
+            PrefixSet::Process,
+            self.process_search_state.is_searching_whole_word,
+            self.process_search_state.is_ignoring_case,
+            self.process_search_state.is_searching_with_regex,
+        )
+    }
+}
 
-            while query.front().is_some() {
-                list_of_ors.push(process_or(query)?);
-            }
+/// Parses a query using the language described in [`ProcessQuery::parse_query`], with the given set
+/// of prefixes.
+pub fn parse_query(
+    search_query: &str, prefix_set: PrefixSet, is_searching_whole_word: bool,
+    is_ignoring_case: bool, is_searching_with_regex: bool,
+) -> Result<Query> {
+    fn process_string_to_filter(
+        query: &mut VecDeque<QueryToken>, prefix_set: PrefixSet,
+    ) -> Result<Query> {
+        let lhs = process_or(query, prefix_set)?;
+        let mut list_of_ors = vec![lhs];
 
-            Ok(Query { query: list_of_ors })
+        while query.front().is_some() {
+            list_of_ors.push(process_or(query, prefix_set)?);
         }
 
-        fn process_or(query: &mut VecDeque<String>) -> Result<Or> {
-            let mut lhs = process_and(query)?;
-            let mut rhs: Option<Box<And>> = None;
+        Ok(Query { query: list_of_ors })
+    }
 
-            while let Some(queue_top) = query.front() {
-                // debug!("OR QT: {:?}", queue_top);
-                if OR_LIST.contains(&queue_top.to_lowercase().as_str()) {
-                    query.pop_front();
-                    rhs = Some(Box::new(process_and(query)?));
+    fn process_or(query: &mut VecDeque<QueryToken>, prefix_set: PrefixSet) -> Result<Or> {
+        let mut lhs = process_and(query, prefix_set)?;
+        let mut rhs: Option<Box<And>> = None;
 
-                    if let Some(queue_next) = query.front() {
-                        if OR_LIST.contains(&queue_next.to_lowercase().as_str()) {
-                            // Must merge LHS and RHS
-                            lhs = And {
-                                lhs: Prefix {
-                                    or: Some(Box::new(Or { lhs, rhs })),
-                                    regex_prefix: None,
-                                    compare_prefix: None,
-                                },
-                                rhs: None,
-                            };
-                            rhs = None;
-                        }
-                    } else {
-                        break;
+        while let Some(queue_top) = query.front() {
+            // debug!("OR QT: {:?}", queue_top);
+            if OR_LIST.contains(&queue_top.value.to_lowercase().as_str()) {
+                query.pop_front();
+                rhs = Some(Box::new(process_and(query, prefix_set)?));
+
+                if let Some(queue_next) = query.front() {
+                    if OR_LIST.contains(&queue_next.value.to_lowercase().as_str()) {
+                        // Must merge LHS and RHS
+                        lhs = And {
+                            lhs: Prefix {
+                                or: Some(Box::new(Or { lhs, rhs })),
+                                not: None,
+                                regex_prefix: None,
+                                compare_prefix: None,
+                            },
+                            rhs: None,
+                        };
+                        rhs = None;
                     }
-                } else if COMPARISON_LIST.contains(&queue_top.to_lowercase().as_str()) {
-                    return Err(QueryError(Cow::Borrowed("Comparison not valid here")));
                 } else {
                     break;
                 }
+            } else if COMPARISON_LIST.contains(&queue_top.value.to_lowercase().as_str()) {
+                return Err(QueryError(
+                    Cow::Borrowed("Comparison not valid here"),
+                    Some(queue_top.span.clone()),
+                ));
+            } else {
+                break;
             }
-
-            Ok(Or { lhs, rhs })
         }
 
-        fn process_and(query: &mut VecDeque<String>) -> Result<And> {
-            let mut lhs = process_prefix(query, false)?;
-            let mut rhs: Option<Box<Prefix>> = None;
+        Ok(Or { lhs, rhs })
+    }
 
-            while let Some(queue_top) = query.front() {
-                // debug!("AND QT: {:?}", queue_top);
-                if AND_LIST.contains(&queue_top.to_lowercase().as_str()) {
-                    query.pop_front();
+    fn process_and(query: &mut VecDeque<QueryToken>, prefix_set: PrefixSet) -> Result<And> {
+        let mut lhs = process_prefix(query, prefix_set, false)?;
+        let mut rhs: Option<Box<Prefix>> = None;
 
-                    rhs = Some(Box::new(process_prefix(query, false)?));
+        while let Some(queue_top) = query.front() {
+            // debug!("AND QT: {:?}", queue_top);
+            if AND_LIST.contains(&queue_top.value.to_lowercase().as_str()) {
+                query.pop_front();
 
-                    if let Some(next_queue_top) = query.front() {
-                        if AND_LIST.contains(&next_queue_top.to_lowercase().as_str()) {
-                            // Must merge LHS and RHS
-                            lhs = Prefix {
-                                or: Some(Box::new(Or {
-                                    lhs: And { lhs, rhs },
-                                    rhs: None,
-                                })),
-                                regex_prefix: None,
-                                compare_prefix: None,
-                            };
-                            rhs = None;
-                        } else {
-                            break;
-                        }
+                rhs = Some(Box::new(process_prefix(query, prefix_set, false)?));
+
+                if let Some(next_queue_top) = query.front() {
+                    if AND_LIST.contains(&next_queue_top.value.to_lowercase().as_str()) {
+                        // Must merge LHS and RHS
+                        lhs = Prefix {
+                            or: Some(Box::new(Or {
+                                lhs: And { lhs, rhs },
+                                rhs: None,
+                            })),
+                            not: None,
+                            regex_prefix: None,
+                            compare_prefix: None,
+                        };
+                        rhs = None;
                     } else {
                         break;
                     }
-                } else if COMPARISON_LIST.contains(&queue_top.to_lowercase().as_str()) {
-                    return Err(QueryError(Cow::Borrowed("Comparison not valid here")));
                 } else {
                     break;
                 }
+            } else if COMPARISON_LIST.contains(&queue_top.value.to_lowercase().as_str()) {
+                return Err(QueryError(
+                    Cow::Borrowed("Comparison not valid here"),
+                    Some(queue_top.span.clone()),
+                ));
+            } else {
+                break;
+            }
+        }
+
+        Ok(And { lhs, rhs })
+    }
+
+    /// Parses the value of a comparison, along with its unit if it has one.  Units can either be
+    /// attached to the value (`1GiB`) or be a separate token (`1 GiB`); a missing unit means the
+    /// value is in the prefix's base unit.
+    fn parse_numerical_value(
+        prefix_type: &PrefixType, value_token: QueryToken, query: &mut VecDeque<QueryToken>,
+        prefix_end: &mut usize,
+    ) -> Result<f64> {
+        if let Ok(value) = value_token.value.parse::<f64>() {
+            // No attached unit, but it might be the next token.
+            if let Some(multiplier) = query
+                .front()
+                .and_then(|token| prefix_type.get_unit_multiplier(&token.value))
+            {
+                if let Some(unit_token) = query.pop_front() {
+                    *prefix_end = unit_token.span.end;
+                }
+                return Ok(value * multiplier);
             }
 
-            Ok(And { lhs, rhs })
+            return Ok(value);
         }
 
-        fn process_prefix(query: &mut VecDeque<String>, inside_quotation: bool) -> Result<Prefix> {
-            if let Some(queue_top) = query.pop_front() {
-                if inside_quotation {
-                    if queue_top == "\"" {
-                        // This means we hit something like "".  Return an empty prefix, and to deal with
-                        // the close quote checker, add one to the top of the stack.  Ugly fix but whatever.
-                        query.push_front("\"".to_string());
-                        return Ok(Prefix {
-                            or: None,
-                            regex_prefix: Some((
-                                PrefixType::Name,
-                                StringQuery::Value(String::default()),
-                            )),
-                            compare_prefix: None,
-                        });
-                    } else {
-                        let mut quoted_string = queue_top;
-                        while let Some(next_str) = query.front() {
-                            if next_str == "\"" {
-                                // Stop!
-                                break;
-                            } else {
-                                quoted_string.push_str(next_str);
-                                query.pop_front();
-                            }
-                        }
-                        return Ok(Prefix {
-                            or: None,
-                            regex_prefix: Some((
-                                PrefixType::Name,
-                                StringQuery::Value(quoted_string),
-                            )),
-                            compare_prefix: None,
-                        });
-                    }
-                } else if queue_top == "(" {
-                    if query.is_empty() {
-                        return Err(QueryError(Cow::Borrowed("Missing closing parentheses")));
-                    }
+        let value_len = value_token
+            .value
+            .chars()
+            .take_while(|c| c.is_ascii_digit() || *c == '.')
+            .count();
+        let (value, unit) = value_token.value.split_at(value_len);
 
-                    let mut list_of_ors = VecDeque::new();
+        if let Ok(value) = value.parse::<f64>() {
+            if let Some(multiplier) = prefix_type.get_unit_multiplier(unit) {
+                Ok(value * multiplier)
+            } else {
+                Err(QueryError(
+                    format!("Invalid unit \"{}\"", unit).into(),
+                    Some(value_token.span.start + value_len..value_token.span.end),
+                ))
+            }
+        } else {
+            Err(QueryError("Invalid number".into(), Some(value_token.span)))
+        }
+    }
 
-                    while let Some(in_paren_query_top) = query.front() {
-                        if in_paren_query_top != ")" {
-                            list_of_ors.push_back(process_or(query)?);
-                        } else {
+    fn process_prefix(
+        query: &mut VecDeque<QueryToken>, prefix_set: PrefixSet, inside_quotation: bool,
+    ) -> Result<Prefix> {
+        if let Some(queue_top) = query.pop_front() {
+            if inside_quotation {
+                if queue_top.value == "\"" {
+                    // This means we hit something like "".  Return an empty prefix, and to deal with
+                    // the close quote checker, add one to the top of the stack.  Ugly fix but whatever.
+                    query.push_front(queue_top);
+                    return Ok(Prefix {
+                        or: None,
+                        not: None,
+                        regex_prefix: Some((
+                            PrefixType::Name,
+                            StringQuery::Value(String::default()),
+                        )),
+                        compare_prefix: None,
+                    });
+                } else {
+                    let mut quoted_string = queue_top.value;
+                    while let Some(next_str) = query.front() {
+                        if next_str.value == "\"" {
+                            // Stop!
                             break;
+                        } else {
+                            quoted_string.push_str(&next_str.value);
+                            query.pop_front();
                         }
                     }
+                    return Ok(Prefix {
+                        or: None,
+                        not: None,
+                        regex_prefix: Some((PrefixType::Name, StringQuery::Value(quoted_string))),
+                        compare_prefix: None,
+                    });
+                }
+            } else if NOT_LIST.contains(&queue_top.value.to_lowercase().as_str()) {
+                // Negate whatever the next prefix is, be it a single condition or a group.
+                if query.is_empty() {
+                    return Err(QueryError(
+                        "Missing condition to negate".into(),
+                        Some(queue_top.span),
+                    ));
+                }
+
+                let negated_prefix = process_prefix(query, prefix_set, false)?;
+                return Ok(Prefix {
+                    or: None,
+                    not: Some(Box::new(negated_prefix)),
+                    regex_prefix: None,
+                    compare_prefix: None,
+                });
+            } else if queue_top.value == "(" {
+                if query.is_empty() {
+                    return Err(QueryError(
+                        Cow::Borrowed("Missing closing parentheses"),
+                        Some(queue_top.span),
+                    ));
+                }
+
+                let mut list_of_ors = VecDeque::new();
 
-                    // Ensure not empty
-                    if list_of_ors.is_empty() {
-                        return Err(QueryError("No values within parentheses group".into()));
+                while let Some(in_paren_query_top) = query.front() {
+                    if in_paren_query_top.value != ")" {
+                        list_of_ors.push_back(process_or(query, prefix_set)?);
+                    } else {
+                        break;
                     }
+                }
 
-                    // Now convert this back to a OR...
-                    let initial_or = Or {
-                        lhs: And {
-                            lhs: Prefix {
-                                or: list_of_ors.pop_front().map(Box::new),
-                                compare_prefix: None,
-                                regex_prefix: None,
-                            },
-                            rhs: None,
+                // Ensure not empty
+                if list_of_ors.is_empty() {
+                    let group_span = queue_top.span.start
+                        ..query
+                            .front()
+                            .map_or(queue_top.span.end, |close_paren| close_paren.span.end);
+                    return Err(QueryError(
+                        "No values within parentheses group".into(),
+                        Some(group_span),
+                    ));
+                }
+
+                // Now convert this back to a OR...
+                let initial_or = Or {
+                    lhs: And {
+                        lhs: Prefix {
+                            or: list_of_ors.pop_front().map(Box::new),
+                            not: None,
+                            compare_prefix: None,
+                            regex_prefix: None,
                         },
                         rhs: None,
-                    };
-                    let returned_or = list_of_ors.into_iter().fold(initial_or, |lhs, rhs| Or {
-                        lhs: And {
-                            lhs: Prefix {
-                                or: Some(Box::new(lhs)),
-                                compare_prefix: None,
-                                regex_prefix: None,
-                            },
-                            rhs: Some(Box::new(Prefix {
-                                or: Some(Box::new(rhs)),
-                                compare_prefix: None,
-                                regex_prefix: None,
-                            })),
+                    },
+                    rhs: None,
+                };
+                let returned_or = list_of_ors.into_iter().fold(initial_or, |lhs, rhs| Or {
+                    lhs: And {
+                        lhs: Prefix {
+                            or: Some(Box::new(lhs)),
+                            not: None,
+                            compare_prefix: None,
+                            regex_prefix: None,
                         },
-                        rhs: None,
-                    });
+                        rhs: Some(Box::new(Prefix {
+                            or: Some(Box::new(rhs)),
+                            not: None,
+                            compare_prefix: None,
+                            regex_prefix: None,
+                        })),
+                    },
+                    rhs: None,
+                });
 
-                    if let Some(close_paren) = query.pop_front() {
-                        if close_paren == ")" {
-                            return Ok(Prefix {
-                                or: Some(Box::new(returned_or)),
-                                regex_prefix: None,
-                                compare_prefix: None,
-                            });
-                        } else {
-                            return Err(QueryError("Missing closing parentheses".into()));
-                        }
+                if let Some(close_paren) = query.pop_front() {
+                    if close_paren.value == ")" {
+                        return Ok(Prefix {
+                            or: Some(Box::new(returned_or)),
+                            not: None,
+                            regex_prefix: None,
+                            compare_prefix: None,
+                        });
                     } else {
-                        return Err(QueryError("Missing closing parentheses".into()));
+                        return Err(QueryError(
+                            "Missing closing parentheses".into(),
+                            Some(queue_top.span),
+                        ));
                     }
-                } else if queue_top == ")" {
-                    return Err(QueryError("Missing opening parentheses".into()));
-                } else if queue_top == "\"" {
-                    // Similar to parentheses, trap and check for missing closing quotes.  Note, however, that we
-                    // will DIRECTLY call another process_prefix call...
-
-                    let prefix = process_prefix(query, true)?;
-                    if let Some(close_paren) = query.pop_front() {
-                        if close_paren == "\"" {
-                            return Ok(prefix);
-                        } else {
-                            return Err(QueryError("Missing closing quotation".into()));
-                        }
+                } else {
+                    return Err(QueryError(
+                        "Missing closing parentheses".into(),
+                        Some(queue_top.span),
+                    ));
+                }
+            } else if queue_top.value == ")" {
+                return Err(QueryError(
+                    "Missing opening parentheses".into(),
+                    Some(queue_top.span),
+                ));
+            } else if queue_top.value == "\"" {
+                // Similar to parentheses, trap and check for missing closing quotes.  Note, however, that we
+                // will DIRECTLY call another process_prefix call...
+                if query.is_empty() {
+                    return Err(QueryError(
+                        "Missing closing quotation".into(),
+                        Some(queue_top.span),
+                    ));
+                }
+
+                let prefix = process_prefix(query, prefix_set, true)?;
+                if let Some(close_paren) = query.pop_front() {
+                    if close_paren.value == "\"" {
+                        return Ok(prefix);
                     } else {
-                        return Err(QueryError("Missing closing quotation".into()));
+                        return Err(QueryError(
+                            "Missing closing quotation".into(),
+                            Some(queue_top.span),
+                        ));
                     }
                 } else {
-                    //  Get prefix type...
-                    let prefix_type = queue_top.parse::<PrefixType>()?;
-                    let content = if let PrefixType::Name = prefix_type {
-                        Some(queue_top)
+                    return Err(QueryError(
+                        "Missing closing quotation".into(),
+                        Some(queue_top.span),
+                    ));
+                }
+            } else {
+                //  Get prefix type...
+                let prefix_type = prefix_set.get_prefix_type(&queue_top.value)?;
+
+                // Keep track of how far this prefix spans, so we can point at all of it if it's invalid.
+                let prefix_start = queue_top.span.start;
+                let mut prefix_end = queue_top.span.end;
+                fn pop_token(
+                    query: &mut VecDeque<QueryToken>, prefix_end: &mut usize,
+                ) -> Option<QueryToken> {
+                    let token = query.pop_front()?;
+                    *prefix_end = token.span.end;
+                    Some(token)
+                }
+
+                let content = if let PrefixType::Name = prefix_type {
+                    // A bare word is a name, unless it was explicitly written as `name=...`.
+                    if queue_top.value.eq_ignore_ascii_case("name")
+                        && matches!(query.front(), Some(token) if token.value == "=")
+                    {
+                        pop_token(query, &mut prefix_end);
+                        pop_token(query, &mut prefix_end).map(|token| token.value)
                     } else {
-                        query.pop_front()
-                    };
+                        Some(queue_top.value)
+                    }
+                } else {
+                    pop_token(query, &mut prefix_end).map(|token| token.value)
+                };
+
+                if let Some(content) = content {
+                    match &prefix_type {
+                        PrefixType::Name => {
+                            return Ok(Prefix {
+                                or: None,
+                                not: None,
+                                regex_prefix: Some((prefix_type, StringQuery::Value(content))),
+                                compare_prefix: None,
+                            })
+                        }
+                        PrefixType::Pid
+                        | PrefixType::Ppid
+                        | PrefixType::State
+                        | PrefixType::User
+                        | PrefixType::Ancestor
+                        | PrefixType::Mount
+                        | PrefixType::FileSystem => {
+                            // We have to check if someone put an "="...
+                            if content == "=" {
+                                // Check next string if possible
+                                if let Some(queue_next) = pop_token(query, &mut prefix_end) {
+                                    // TODO: Need to consider the following cases:
+                                    // - (test)
+                                    // - (test
+                                    // - test)
+                                    // These are split into 2 to 3 different strings due to parentheses being
+                                    // delimiters in our query system.
+                                    //
+                                    // Do we want these to be valid?  They should, as a string, right?
 
-                    if let Some(content) = content {
-                        match &prefix_type {
-                            PrefixType::Name => {
-                                return Ok(Prefix {
-                                    or: None,
-                                    regex_prefix: Some((prefix_type, StringQuery::Value(content))),
-                                    compare_prefix: None,
-                                })
-                            }
-                            PrefixType::Pid | PrefixType::State | PrefixType::User => {
-                                // We have to check if someone put an "="...
-                                if content == "=" {
-                                    // Check next string if possible
-                                    if let Some(queue_next) = query.pop_front() {
-                                        // TODO: Need to consider the following cases:
-                                        // - (test)
-                                        // - (test
-                                        // - test)
-                                        // These are split into 2 to 3 different strings due to parentheses being
-                                        // delimiters in our query system.
-                                        //
-                                        // Do we want these to be valid?  They should, as a string, right?
-
-                                        return Ok(Prefix {
-                                            or: None,
-                                            regex_prefix: Some((
-                                                prefix_type,
-                                                StringQuery::Value(queue_next),
-                                            )),
-                                            compare_prefix: None,
-                                        });
-                                    }
-                                } else {
                                     return Ok(Prefix {
                                         or: None,
+                                        not: None,
                                         regex_prefix: Some((
                                             prefix_type,
-                                            StringQuery::Value(content),
+                                            StringQuery::Value(queue_next.value),
                                         )),
                                         compare_prefix: None,
                                     });
                                 }
+                            } else {
+                                return Ok(Prefix {
+                                    or: None,
+                                    not: None,
+                                    regex_prefix: Some((prefix_type, StringQuery::Value(content))),
+                                    compare_prefix: None,
+                                });
                             }
-                            _ => {
-                                // Now we gotta parse the content... yay.
-
-                                let mut condition: Option<QueryComparison> = None;
-                                let mut value: Option<f64> = None;
-
-                                if content == "=" {
-                                    condition = Some(QueryComparison::Equal);
-                                    if let Some(queue_next) = query.pop_front() {
-                                        value = queue_next.parse::<f64>().ok();
-                                    } else {
-                                        return Err(QueryError("Missing value".into()));
-                                    }
-                                } else if content == ">" || content == "<" {
+                        }
+                        _ => {
+                            // Now we gotta parse the content... yay.
+                            let condition = match content.as_str() {
+                                "=" => Some(QueryComparison::Equal),
+                                ">" | "<" => {
                                     // We also have to check if the next string is an "="...
-                                    if let Some(queue_next) = query.pop_front() {
-                                        if queue_next == "=" {
-                                            condition = Some(if content == ">" {
-                                                QueryComparison::GreaterOrEqual
-                                            } else {
-                                                QueryComparison::LessOrEqual
-                                            });
-                                            if let Some(queue_next_next) = query.pop_front() {
-                                                value = queue_next_next.parse::<f64>().ok();
-                                            } else {
-                                                return Err(QueryError("Missing value".into()));
-                                            }
+                                    if matches!(query.front(), Some(token) if token.value == "=") {
+                                        pop_token(query, &mut prefix_end);
+                                        Some(if content == ">" {
+                                            QueryComparison::GreaterOrEqual
                                         } else {
-                                            condition = Some(if content == ">" {
-                                                QueryComparison::Greater
-                                            } else {
-                                                QueryComparison::Less
-                                            });
-                                            value = queue_next.parse::<f64>().ok();
-                                        }
+                                            QueryComparison::LessOrEqual
+                                        })
                                     } else {
-                                        return Err(QueryError("Missing value".into()));
+                                        Some(if content == ">" {
+                                            QueryComparison::Greater
+                                        } else {
+                                            QueryComparison::Less
+                                        })
                                     }
                                 }
+                                _ => None,
+                            };
 
-                                if let Some(condition) = condition {
-                                    if let Some(read_value) = value {
-                                        // Now we want to check one last thing - is there a unit?
-                                        // If no unit, assume base.
-                                        // Furthermore, base must be PEEKED at initially, and will
-                                        // require (likely) prefix_type specific checks
-                                        // Lastly, if it *is* a unit, remember to POP!
-
-                                        let mut value = read_value;
-
-                                        match prefix_type {
-                                            PrefixType::MemBytes
-                                            | PrefixType::Rps
-                                            | PrefixType::Wps
-                                            | PrefixType::TRead
-                                            | PrefixType::TWrite => {
-                                                if let Some(potential_unit) = query.front() {
-                                                    match potential_unit.to_lowercase().as_str() {
-                                                        "tb" => {
-                                                            value *= 1_000_000_000_000.0;
-                                                            query.pop_front();
-                                                        }
-                                                        "tib" => {
-                                                            value *= 1_099_511_627_776.0;
-                                                            query.pop_front();
-                                                        }
-                                                        "gb" => {
-                                                            value *= 1_000_000_000.0;
-                                                            query.pop_front();
-                                                        }
-                                                        "gib" => {
-                                                            value *= 1_073_741_824.0;
-                                                            query.pop_front();
-                                                        }
-                                                        "mb" => {
-                                                            value *= 1_000_000.0;
-                                                            query.pop_front();
-                                                        }
-                                                        "mib" => {
-                                                            value *= 1_048_576.0;
-                                                            query.pop_front();
-                                                        }
-                                                        "kb" => {
-                                                            value *= 1000.0;
-                                                            query.pop_front();
-                                                        }
-                                                        "kib" => {
-                                                            value *= 1024.0;
-                                                            query.pop_front();
-                                                        }
-                                                        "b" => {
-                                                            // Just gotta pop.
-                                                            query.pop_front();
-                                                        }
-                                                        _ => {}
-                                                    }
-                                                }
-                                            }
-                                            _ => {}
-                                        }
-
-                                        return Ok(Prefix {
-                                            or: None,
-                                            regex_prefix: None,
-                                            compare_prefix: Some((
-                                                prefix_type,
-                                                NumericalQuery { condition, value },
-                                            )),
-                                        });
-                                    }
+                            if let Some(condition) = condition {
+                                if let Some(value_token) = pop_token(query, &mut prefix_end) {
+                                    let value = parse_numerical_value(
+                                        &prefix_type,
+                                        value_token,
+                                        query,
+                                        &mut prefix_end,
+                                    )?;
+
+                                    return Ok(Prefix {
+                                        or: None,
+                                        not: None,
+                                        regex_prefix: None,
+                                        compare_prefix: Some((
+                                            prefix_type,
+                                            NumericalQuery { condition, value },
+                                        )),
+                                    });
+                                } else {
+                                    return Err(QueryError(
+                                        "Missing value".into(),
+                                        Some(prefix_start..prefix_end),
+                                    ));
                                 }
                             }
                         }
-                    } else {
-                        return Err(QueryError("Missing argument for search prefix".into()));
                     }
+
+                    return Err(QueryError(
+                        "Invalid query".into(),
+                        Some(prefix_start..prefix_end),
+                    ));
+                } else {
+                    return Err(QueryError(
+                        "Missing argument for search prefix".into(),
+                        Some(prefix_start..prefix_end),
+                    ));
                 }
-            } else if inside_quotation {
-                // Uh oh, it's empty with quotes!
-                return Err(QueryError("Missing closing quotation".into()));
             }
+        } else if inside_quotation {
+            // Uh oh, it's empty with quotes!
+            return Err(QueryError("Missing closing quotation".into(), None));
+        }
 
-            Err(QueryError("Invalid query".into()))
+        Err(QueryError("Invalid query".into(), None))
+    }
+
+    let mut split_query = VecDeque::new();
+
+    {
+        // Whitespace separates tokens, and the delimiters are tokens of their own.  We keep track of
+        // where (in characters) each token lies so that errors can point back into the query.
+        let mut current_word = String::new();
+        let mut word_start = 0;
+        let mut num_chars = 0;
+
+        for (char_index, c) in search_query.chars().enumerate() {
+            num_chars = char_index + 1;
+            if c.is_whitespace() || DELIMITER_LIST.contains(&c) {
+                if !current_word.is_empty() {
+                    split_query.push_back(QueryToken::new(
+                        std::mem::take(&mut current_word),
+                        word_start..char_index,
+                    ));
+                }
+                if !c.is_whitespace() {
+                    split_query
+                        .push_back(QueryToken::new(c.to_string(), char_index..char_index + 1));
+                }
+            } else {
+                if current_word.is_empty() {
+                    word_start = char_index;
+                }
+                current_word.push(c);
+            }
         }
 
-        let mut split_query = VecDeque::new();
+        if !current_word.is_empty() {
+            split_query.push_back(QueryToken::new(current_word, word_start..num_chars));
+        }
+    }
 
-        self.get_current_search_query()
-            .split_whitespace()
-            .for_each(|s| {
-                // From https://stackoverflow.com/a/56923739 in order to get a split but include the parentheses
-                let mut last = 0;
-                for (index, matched) in s.match_indices(|x| DELIMITER_LIST.contains(&x)) {
-                    if last != index {
-                        split_query.push_back(s[last..index].to_owned());
-                    }
-                    split_query.push_back(matched.to_owned());
-                    last = index + matched.len();
+    let mut process_filter = process_string_to_filter(&mut split_query, prefix_set)?;
+    process_filter.process_regexes(
+        is_searching_whole_word,
+        is_ignoring_case,
+        is_searching_with_regex,
+    )?;
+
+    Ok(process_filter)
+}
+
+/// How processes relate to each other, for prefixes like `children` or `ancestor` that can't be
+/// checked by looking at a single process alone.
+pub struct ProcessRelations<'a> {
+    processes: &'a HashMap<Pid, ConvertedProcessData>,
+    /// Only built the first time a query asks for children, as most queries never do.
+    parent_child_mapping: OnceCell<HashMap<Pid, IndexSet<Pid, FxBuildHasher>>>,
+}
+
+impl<'a> ProcessRelations<'a> {
+    pub fn new(processes: &'a HashMap<Pid, ConvertedProcessData>) -> Self {
+        ProcessRelations {
+            processes,
+            parent_child_mapping: OnceCell::new(),
+        }
+    }
+
+    /// Returns the number of direct children of the given PID.
+    pub fn get_num_children(&self, pid: Pid) -> usize {
+        self.parent_child_mapping
+            .get_or_init(|| get_parent_child_mapping(self.processes.values()))
+            .get(&pid)
+            .map(|children| children.len())
+            .unwrap_or(0)
+    }
+
+    /// Returns whether any process up the parent chain of the given process (not including the
+    /// process itself) satisfies the given predicate.
+    pub fn has_ancestor(
+        &self, process: &ConvertedProcessData,
+        mut predicate: impl FnMut(&ConvertedProcessData) -> bool,
+    ) -> bool {
+        let mut current_ppid = process.ppid;
+
+        // Bound how far we walk, in case of a parent cycle (say, due to PID reuse).
+        for _ in 0..self.processes.len() {
+            if let Some(parent) = current_ppid.and_then(|ppid| self.processes.get(&ppid)) {
+                if predicate(parent) {
+                    return true;
                 }
-                if last < s.len() {
-                    split_query.push_back(s[last..].to_owned());
+                current_ppid = parent.ppid;
+            } else {
+                break;
+            }
+        }
+
+        false
+    }
+}
+
+/// Something that a query can be checked against, such as a process or a disk.
+pub trait QueryTarget {
+    /// Returns whether the text for the given prefix matches, or `None` if the prefix doesn't
+    /// apply to this target (in which case the condition is ignored).
+    fn matches_regex(&self, prefix_type: &PrefixType, regex: &Regex) -> Option<bool>;
+
+    /// Returns the value to compare for the given prefix, or `None` if the prefix doesn't apply
+    /// to this target or the value is unavailable (in which case the condition is ignored).
+    fn get_numerical_value(&self, prefix_type: &PrefixType) -> Option<f64>;
+}
+
+struct ProcessQueryTarget<'a> {
+    process: &'a ConvertedProcessData,
+    is_using_command: bool,
+    relations: &'a ProcessRelations<'a>,
+}
+
+impl QueryTarget for ProcessQueryTarget<'_> {
+    fn matches_regex(&self, prefix_type: &PrefixType, r: &Regex) -> Option<bool> {
+        let process = self.process;
+        let is_using_command = self.is_using_command;
+
+        match prefix_type {
+            PrefixType::Name => Some(r.is_match(if is_using_command {
+                process.command.as_str()
+            } else {
+                process.name.as_str()
+            })),
+            PrefixType::Pid => Some(r.is_match(process.pid.to_string().as_str())),
+            PrefixType::Ppid => Some(if let Some(ppid) = process.ppid {
+                r.is_match(ppid.to_string().as_str())
+            } else {
+                false
+            }),
+            PrefixType::Ancestor => Some(self.relations.has_ancestor(process, |ancestor| {
+                r.is_match(ancestor.pid.to_string().as_str())
+                    || r.is_match(if is_using_command {
+                        ancestor.command.as_str()
+                    } else {
+                        ancestor.name.as_str()
+                    })
+            })),
+            PrefixType::State => Some(r.is_match(process.process_state.as_str())),
+            PrefixType::User => Some(if let Some(user) = &process.user {
+                r.is_match(user.as_str())
+            } else {
+                false
+            }),
+            _ => None,
+        }
+    }
+
+    fn get_numerical_value(&self, prefix_type: &PrefixType) -> Option<f64> {
+        let process = self.process;
+
+        match prefix_type {
+            PrefixType::PCpu => Some(process.cpu_percent_usage),
+            PrefixType::PMem => Some(process.mem_percent_usage),
+            PrefixType::MemBytes => Some(process.mem_usage_bytes as f64),
+            PrefixType::Rps => Some(process.rps_f64),
+            PrefixType::Wps => Some(process.wps_f64),
+            PrefixType::TRead => Some(process.tr_f64),
+            PrefixType::TWrite => Some(process.tw_f64),
+            PrefixType::Time => Some(process.time.as_secs_f64()),
+            PrefixType::Children => Some(self.relations.get_num_children(process.pid) as f64),
+            _ => None,
+        }
+    }
+}
+
+/// A disk, along with its current read and write rates in bytes per second, if known.
+pub struct DiskQueryTarget<'a> {
+    pub disk: &'a DiskHarvest,
+    pub io_rates: Option<(u64, u64)>,
+}
+
+impl QueryTarget for DiskQueryTarget<'_> {
+    fn matches_regex(&self, prefix_type: &PrefixType, r: &Regex) -> Option<bool> {
+        match prefix_type {
+            PrefixType::Name => {
+                Some(r.is_match(&self.disk.name) || r.is_match(&self.disk.mount_point))
+            }
+            PrefixType::Mount => Some(r.is_match(&self.disk.mount_point)),
+            PrefixType::FileSystem => Some(r.is_match(&self.disk.file_system)),
+            _ => None,
+        }
+    }
+
+    fn get_numerical_value(&self, prefix_type: &PrefixType) -> Option<f64> {
+        match prefix_type {
+            PrefixType::PUsed => match (self.disk.used_space, self.disk.total_space) {
+                (Some(used_space), Some(total_space)) if total_space > 0 => {
+                    Some(used_space as f64 / total_space as f64 * 100.0)
                 }
-            });
+                _ => None,
+            },
+            PrefixType::UsedBytes => self.disk.used_space.map(|used| used as f64),
+            PrefixType::FreeBytes => self.disk.free_space.map(|free| free as f64),
+            PrefixType::TotalBytes => self.disk.total_space.map(|total| total as f64),
+            PrefixType::Rps => self.io_rates.map(|(read, _)| read as f64),
+            PrefixType::Wps => self.io_rates.map(|(_, write)| write as f64),
+            _ => None,
+        }
+    }
+}
 
-        let mut process_filter = process_string_to_filter(&mut split_query)?;
-        process_filter.process_regexes(
-            self.process_search_state.is_searching_whole_word,
-            self.process_search_state.is_ignoring_case,
-            self.process_search_state.is_searching_with_regex,
-        )?;
+/// A temperature sensor; temperatures are compared in whatever unit is being displayed.
+pub struct TempQueryTarget<'a> {
+    pub sensor: &'a TempHarvest,
+}
+
+impl QueryTarget for TempQueryTarget<'_> {
+    fn matches_regex(&self, prefix_type: &PrefixType, r: &Regex) -> Option<bool> {
+        match prefix_type {
+            PrefixType::Name => Some(r.is_match(&self.sensor.name)),
+            _ => None,
+        }
+    }
 
-        Ok(process_filter)
+    fn get_numerical_value(&self, prefix_type: &PrefixType) -> Option<f64> {
+        match prefix_type {
+            PrefixType::Temp => Some(self.sensor.temperature as f64),
+            _ => None,
+        }
+    }
+}
+
+/// The combined usage of all of a user's processes.
+pub struct UserQueryTarget<'a> {
+    pub user: &'a ConvertedUserData,
+}
+
+impl QueryTarget for UserQueryTarget<'_> {
+    fn matches_regex(&self, prefix_type: &PrefixType, r: &Regex) -> Option<bool> {
+        match prefix_type {
+            PrefixType::Name | PrefixType::User => Some(r.is_match(&self.user.user)),
+            _ => None,
+        }
+    }
+
+    fn get_numerical_value(&self, prefix_type: &PrefixType) -> Option<f64> {
+        let user = self.user;
+
+        match prefix_type {
+            PrefixType::Count => Some(user.process_count as f64),
+            PrefixType::PCpu => Some(user.cpu_percent_usage),
+            PrefixType::PMem => Some(user.mem_percent_usage),
+            PrefixType::MemBytes => Some(user.mem_usage_bytes as f64),
+            PrefixType::Rps => Some(user.rps_f64),
+            PrefixType::Wps => Some(user.wps_f64),
+            _ => None,
+        }
     }
 }
 
@@ -467,10 +821,20 @@ impl Query {
         Ok(())
     }
 
-    pub fn check(&self, process: &ConvertedProcessData, is_using_command: bool) -> bool {
-        self.query
-            .iter()
-            .all(|ok| ok.check(process, is_using_command))
+    pub fn check(
+        &self, process: &ConvertedProcessData, is_using_command: bool,
+        relations: &ProcessRelations<'_>,
+    ) -> bool {
+        self.check_target(&ProcessQueryTarget {
+            process,
+            is_using_command,
+            relations,
+        })
+    }
+
+    /// Checks the query against anything that can be queried, like a disk or a sensor.
+    pub fn check_target<T: QueryTarget>(&self, target: &T) -> bool {
+        self.query.iter().all(|ok| ok.check(target))
     }
 }
 
@@ -507,11 +871,11 @@ impl Or {
         Ok(())
     }
 
-    pub fn check(&self, process: &ConvertedProcessData, is_using_command: bool) -> bool {
+    pub fn check<T: QueryTarget>(&self, target: &T) -> bool {
         if let Some(rhs) = &self.rhs {
-            self.lhs.check(process, is_using_command) || rhs.check(process, is_using_command)
+            self.lhs.check(target) || rhs.check(target)
         } else {
-            self.lhs.check(process, is_using_command)
+            self.lhs.check(target)
         }
     }
 }
@@ -552,11 +916,11 @@ impl And {
         Ok(())
     }
 
-    pub fn check(&self, process: &ConvertedProcessData, is_using_command: bool) -> bool {
+    pub fn check<T: QueryTarget>(&self, target: &T) -> bool {
         if let Some(rhs) = &self.rhs {
-            self.lhs.check(process, is_using_command) && rhs.check(process, is_using_command)
+            self.lhs.check(target) && rhs.check(target)
         } else {
-            self.lhs.check(process, is_using_command)
+            self.lhs.check(target)
         }
     }
 }
@@ -570,7 +934,7 @@ impl Debug for And {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
 pub enum PrefixType {
     Pid,
     PCpu,
@@ -583,6 +947,18 @@ pub enum PrefixType {
     Name,
     State,
     User,
+    Time,
+    Ppid,
+    Children,
+    Ancestor,
+    PUsed,
+    UsedBytes,
+    FreeBytes,
+    TotalBytes,
+    Mount,
+    FileSystem,
+    Temp,
+    Count,
     __Nonexhaustive,
 }
 
@@ -606,14 +982,115 @@ impl std::str::FromStr for PrefixType {
             "pid" => Ok(Pid),
             "state" => Ok(State),
             "user" => Ok(User),
+            "time" => Ok(Time),
+            "ppid" => Ok(Ppid),
+            "children" => Ok(Children),
+            "ancestor" => Ok(Ancestor),
             _ => Ok(Name),
         }
     }
 }
 
+/// Which prefixes a query understands, as each searchable table has its own set of columns.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PrefixSet {
+    Process,
+    /// Supports `mount`, `fs`, `used%`, `used`, `free`, `total`, `read`, and `write`.  Names match
+    /// against both the disk name and the mount point.
+    Disk,
+    /// Supports `temp`.
+    Temp,
+    /// Supports `user`, `count`, `cpu`, `mem`, `memb`, `read`, and `write`.
+    User,
+}
+
+impl PrefixSet {
+    fn get_prefix_type(self, word: &str) -> Result<PrefixType> {
+        use PrefixType::*;
+
+        match self {
+            PrefixSet::Process => word.parse::<PrefixType>(),
+            PrefixSet::Disk => match word.to_lowercase().as_str() {
+                "mount" => Ok(Mount),
+                "fs" => Ok(FileSystem),
+                "used%" | "use%" => Ok(PUsed),
+                "used" => Ok(UsedBytes),
+                "free" => Ok(FreeBytes),
+                "total" => Ok(TotalBytes),
+                "read" | "r/s" => Ok(Rps),
+                "write" | "w/s" => Ok(Wps),
+                _ => Ok(Name),
+            },
+            PrefixSet::Temp => match word.to_lowercase().as_str() {
+                "temp" => Ok(Temp),
+                _ => Ok(Name),
+            },
+            PrefixSet::User => match word.to_lowercase().as_str() {
+                "user" => Ok(User),
+                "count" => Ok(Count),
+                "cpu" | "cpu%" => Ok(PCpu),
+                "mem" | "mem%" => Ok(PMem),
+                "memb" => Ok(MemBytes),
+                "read" | "r/s" => Ok(Rps),
+                "write" | "w/s" => Ok(Wps),
+                _ => Ok(Name),
+            },
+        }
+    }
+}
+
+impl PrefixType {
+    /// Returns what a value written in the given unit has to be multiplied by to get it in terms of
+    /// what this prefix compares against, or `None` if the unit is not valid for this prefix.
+    fn get_unit_multiplier(&self, unit: &str) -> Option<f64> {
+        fn get_bytes_multiplier(unit: &str) -> Option<f64> {
+            match unit {
+                "b" => Some(1.0),
+                "kb" => Some(1000.0),
+                "kib" => Some(1024.0),
+                "mb" => Some(1_000_000.0),
+                "mib" => Some(1_048_576.0),
+                "gb" => Some(1_000_000_000.0),
+                "gib" => Some(1_073_741_824.0),
+                "tb" => Some(1_000_000_000_000.0),
+                "tib" => Some(1_099_511_627_776.0),
+                _ => None,
+            }
+        }
+
+        let unit = unit.to_lowercase();
+        match self {
+            PrefixType::PCpu | PrefixType::PMem | PrefixType::PUsed => match unit.as_str() {
+                "%" => Some(1.0),
+                _ => None,
+            },
+            PrefixType::MemBytes
+            | PrefixType::TRead
+            | PrefixType::TWrite
+            | PrefixType::UsedBytes
+            | PrefixType::FreeBytes
+            | PrefixType::TotalBytes => get_bytes_multiplier(&unit),
+            PrefixType::Rps | PrefixType::Wps => {
+                // Rates can optionally be written per second, like `mb/s`.
+                get_bytes_multiplier(unit.strip_suffix("/s").unwrap_or(&unit))
+            }
+            PrefixType::Time => match unit.as_str() {
+                "ms" => Some(0.001),
+                "s" | "sec" => Some(1.0),
+                "m" | "min" => Some(60.0),
+                "h" | "hr" => Some(3600.0),
+                "d" => Some(86400.0),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+}
+
 #[derive(Default)]
 pub struct Prefix {
     pub or: Option<Box<Or>>,
+    pub not: Option<Box<Prefix>>,
     pub regex_prefix: Option<(PrefixType, StringQuery)>,
     pub compare_prefix: Option<(PrefixType, NumericalQuery)>,
 }
@@ -629,10 +1106,35 @@ impl Prefix {
                 is_ignoring_case,
                 is_searching_with_regex,
             );
+        } else if let Some(not) = &mut self.not {
+            return not.process_regexes(
+                is_searching_whole_word,
+                is_ignoring_case,
+                is_searching_with_regex,
+            );
         } else if let Some((prefix_type, StringQuery::Value(regex_string))) = &mut self.regex_prefix
         {
             match prefix_type {
-                PrefixType::Pid | PrefixType::Name | PrefixType::State | PrefixType::User => {
+                PrefixType::Ancestor if regex_string.parse::<Pid>().is_ok() => {
+                    // Matching ancestors by a partial PID isn't very useful, so PIDs are exact.
+                    let final_regex_string = format!("^{}$", regex_string);
+
+                    let taken_pwc = self.regex_prefix.take();
+                    if let Some((taken_pt, _)) = taken_pwc {
+                        self.regex_prefix = Some((
+                            taken_pt,
+                            StringQuery::Regex(regex::Regex::new(&final_regex_string)?),
+                        ));
+                    }
+                }
+                PrefixType::Pid
+                | PrefixType::Ppid
+                | PrefixType::Name
+                | PrefixType::State
+                | PrefixType::User
+                | PrefixType::Ancestor
+                | PrefixType::Mount
+                | PrefixType::FileSystem => {
                     let escaped_regex: String;
                     let final_regex_string = &format!(
                         "{}{}{}{}",
@@ -662,7 +1164,7 @@ impl Prefix {
         Ok(())
     }
 
-    pub fn check(&self, process: &ConvertedProcessData, is_using_command: bool) -> bool {
+    pub fn check<T: QueryTarget>(&self, target: &T) -> bool {
         fn matches_condition(condition: &QueryComparison, lhs: f64, rhs: f64) -> bool {
             match condition {
                 QueryComparison::Equal => (lhs - rhs).abs() < std::f64::EPSILON,
@@ -674,67 +1176,21 @@ impl Prefix {
         }
 
         if let Some(and) = &self.or {
-            and.check(process, is_using_command)
+            and.check(target)
+        } else if let Some(not) = &self.not {
+            !not.check(target)
         } else if let Some((prefix_type, query_content)) = &self.regex_prefix {
             if let StringQuery::Regex(r) = query_content {
-                match prefix_type {
-                    PrefixType::Name => r.is_match(if is_using_command {
-                        process.command.as_str()
-                    } else {
-                        process.name.as_str()
-                    }),
-                    PrefixType::Pid => r.is_match(process.pid.to_string().as_str()),
-                    PrefixType::State => r.is_match(process.process_state.as_str()),
-                    PrefixType::User => {
-                        if let Some(user) = &process.user {
-                            r.is_match(user.as_str())
-                        } else {
-                            false
-                        }
-                    }
-                    _ => true,
-                }
+                target.matches_regex(prefix_type, r).unwrap_or(true)
             } else {
                 true
             }
         } else if let Some((prefix_type, numerical_query)) = &self.compare_prefix {
-            match prefix_type {
-                PrefixType::PCpu => matches_condition(
-                    &numerical_query.condition,
-                    process.cpu_percent_usage,
-                    numerical_query.value,
-                ),
-                PrefixType::PMem => matches_condition(
-                    &numerical_query.condition,
-                    process.mem_percent_usage,
-                    numerical_query.value,
-                ),
-                PrefixType::MemBytes => matches_condition(
-                    &numerical_query.condition,
-                    process.mem_usage_bytes as f64,
-                    numerical_query.value,
-                ),
-                PrefixType::Rps => matches_condition(
-                    &numerical_query.condition,
-                    process.rps_f64,
-                    numerical_query.value,
-                ),
-                PrefixType::Wps => matches_condition(
-                    &numerical_query.condition,
-                    process.wps_f64,
-                    numerical_query.value,
-                ),
-                PrefixType::TRead => matches_condition(
-                    &numerical_query.condition,
-                    process.tr_f64,
-                    numerical_query.value,
-                ),
-                PrefixType::TWrite => matches_condition(
-                    &numerical_query.condition,
-                    process.tw_f64,
-                    numerical_query.value,
-                ),
-                _ => true,
+            match target.get_numerical_value(prefix_type) {
+                Some(value) => {
+                    matches_condition(&numerical_query.condition, value, numerical_query.value)
+                }
+                None => true,
             }
         } else {
             // Somehow we have an empty condition... oh well.  Return true.
@@ -747,6 +1203,8 @@ impl Debug for Prefix {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         if let Some(or) = &self.or {
             f.write_fmt(format_args!("{:?}", or))
+        } else if let Some(not) = &self.not {
+            f.write_fmt(format_args!("(NOT {:?})", not))
         } else if let Some(regex_prefix) = &self.regex_prefix {
             f.write_fmt(format_args!("{:?}", regex_prefix))
         } else if let Some(compare_prefix) = &self.compare_prefix {
@@ -769,7 +1227,7 @@ pub enum QueryComparison {
 #[derive(Debug)]
 pub enum StringQuery {
     Value(String),
-    Regex(regex::Regex),
+    Regex(Regex),
 }
 
 #[derive(Debug)]
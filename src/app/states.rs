@@ -1,16 +1,39 @@
-use std::{collections::HashMap, time::Instant};
+use std::{
+    cmp::{max, min},
+    collections::{HashMap, VecDeque},
+    ops::Range,
+    time::Instant,
+};
 
 use unicode_segmentation::GraphemeCursor;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
 
-use tui::widgets::TableState;
+use tui::widgets::{ListState, TableState};
 
 use crate::{
-    app::{layout_manager::BottomWidgetType, query::*},
+    app::{
+        completion::{get_completion, CompletionValues},
+        layout_manager::BottomWidgetType,
+        query::*,
+    },
     constants,
+    data_conversion::{ConvertedProcessData, ProcessGroupingType},
     data_harvester::processes::{self, ProcessSorting},
+    utils::error::{BottomError, Result},
+    Pid,
 };
 use ProcessSorting::*;
 
+/// The most queries we'll remember in a process widget's search history.
+const MAX_SEARCH_HISTORY_LENGTH: usize = 50;
+
+/// How many sort keys can be used on top of the main one.
+pub const MAX_SECONDARY_SORT_KEYS: usize = 2;
+
+/// How many characters the name/command column of a process widget moves by when scrolling it
+/// horizontally.
+pub const HORIZONTAL_SCROLL_STEP: usize = 4;
+
 #[derive(Debug)]
 pub enum ScrollDirection {
     // UP means scrolling up --- this usually DECREMENTS
@@ -84,6 +107,32 @@ impl Default for AppHelpDialogState {
     }
 }
 
+/// The state of the saved query picker.
+#[derive(Default)]
+pub struct AppSavedQueryDialogState {
+    pub is_showing_saved_queries: bool,
+    /// The process widget that the picked query will be applied to.
+    pub widget_id: u64,
+    pub list_state: ListState,
+}
+
+/// The state of the prompt for jumping to a PID.
+#[derive(Default)]
+pub struct AppGotoPidDialogState {
+    pub is_showing_goto_pid: bool,
+    /// The process widget that the PID will be selected in.
+    pub widget_id: u64,
+    pub input: String,
+    pub error: Option<String>,
+}
+
+/// The state of the list of recently exited processes.
+#[derive(Default)]
+pub struct AppExitedProcessDialogState {
+    pub is_showing_exited_processes: bool,
+    pub table_state: TableState,
+}
+
 /// AppSearchState deals with generic searching (I might do this in the future).
 pub struct AppSearchState {
     pub is_enabled: bool,
@@ -98,6 +147,8 @@ pub struct AppSearchState {
     /// The query
     pub query: Option<Query>,
     pub error_message: Option<String>,
+    /// The span of characters in the query that caused the error, if any.
+    pub error_span: Option<Range<usize>>,
 }
 
 impl Default for AppSearchState {
@@ -113,6 +164,7 @@ impl Default for AppSearchState {
             char_cursor_position: 0,
             query: None,
             error_message: None,
+            error_span: None,
         }
     }
 }
@@ -129,6 +181,183 @@ impl AppSearchState {
     pub fn is_invalid_or_blank_search(&self) -> bool {
         self.is_blank_search || self.is_invalid_search
     }
+
+    /// Returns the query to filter with, if the search is valid and not blank.
+    pub fn get_valid_query(&self) -> Option<&Query> {
+        if self.is_invalid_or_blank_search() {
+            None
+        } else {
+            self.query.as_ref()
+        }
+    }
+
+    /// Re-parses the current query using the given prefixes.  Unlike process searches, these are
+    /// always case-insensitive, and don't support whole word or regex matching.
+    pub fn update_query(&mut self, prefix_set: PrefixSet) {
+        if self.current_search_query.is_empty() {
+            self.set_parsed_query(None);
+        } else {
+            let parsed_query =
+                parse_query(&self.current_search_query, prefix_set, false, true, false);
+            self.set_parsed_query(Some(parsed_query));
+        }
+    }
+
+    /// Updates the query and its error state from the result of parsing, where `None` means the
+    /// query is blank.
+    fn set_parsed_query(&mut self, parsed_query: Option<Result<Query>>) {
+        match parsed_query {
+            None => {
+                self.is_blank_search = true;
+                self.is_invalid_search = false;
+                self.error_message = None;
+                self.error_span = None;
+            }
+            Some(Ok(parsed_query)) => {
+                self.query = Some(parsed_query);
+                self.is_blank_search = false;
+                self.is_invalid_search = false;
+                self.error_message = None;
+                self.error_span = None;
+            }
+            Some(Err(err)) => {
+                self.is_blank_search = false;
+                self.is_invalid_search = true;
+                self.error_span = if let BottomError::QueryError(_, span) = &err {
+                    span.clone()
+                } else {
+                    None
+                };
+                self.error_message = Some(err.to_string());
+            }
+        }
+    }
+
+    fn get_cursor_position(&self) -> usize {
+        self.grapheme_cursor.cur_cursor()
+    }
+
+    fn walk_forward(&mut self) {
+        let start_position = self.get_cursor_position();
+        self.grapheme_cursor
+            .next_boundary(&self.current_search_query[start_position..], start_position)
+            .unwrap();
+    }
+
+    fn walk_back(&mut self) {
+        let start_position = self.get_cursor_position();
+        self.grapheme_cursor
+            .prev_boundary(&self.current_search_query[..start_position], 0)
+            .unwrap();
+    }
+
+    /// Inserts a character at the cursor, moving the cursor past it.
+    pub fn insert_char(&mut self, caught_char: char) {
+        self.current_search_query
+            .insert(self.get_cursor_position(), caught_char);
+        self.grapheme_cursor = GraphemeCursor::new(
+            self.get_cursor_position(),
+            self.current_search_query.len(),
+            true,
+        );
+        self.walk_forward();
+        self.char_cursor_position += UnicodeWidthChar::width(caught_char).unwrap_or(0);
+        self.cursor_direction = CursorDirection::Right;
+    }
+
+    /// Removes the grapheme before the cursor.  Returns whether anything was removed.
+    pub fn delete_previous_char(&mut self) -> bool {
+        let current_cursor = self.get_cursor_position();
+        if current_cursor == 0 {
+            return false;
+        }
+
+        self.walk_back();
+        let new_cursor = self.get_cursor_position();
+        let removed_chars: String = self
+            .current_search_query
+            .drain(new_cursor..current_cursor)
+            .collect();
+        self.grapheme_cursor =
+            GraphemeCursor::new(new_cursor, self.current_search_query.len(), true);
+        self.char_cursor_position -= UnicodeWidthStr::width(removed_chars.as_str());
+        self.cursor_direction = CursorDirection::Left;
+
+        true
+    }
+
+    /// Removes the grapheme after the cursor.  Returns whether anything was removed.
+    pub fn delete_next_char(&mut self) -> bool {
+        let current_cursor = self.get_cursor_position();
+        if current_cursor >= self.current_search_query.len() {
+            return false;
+        }
+
+        self.walk_forward();
+        let end_cursor = self.get_cursor_position();
+        self.current_search_query.drain(current_cursor..end_cursor);
+        self.grapheme_cursor =
+            GraphemeCursor::new(current_cursor, self.current_search_query.len(), true);
+
+        true
+    }
+
+    /// Removes everything from the start of the word before the cursor up to the cursor.
+    /// Returns whether anything was removed.
+    pub fn delete_previous_word(&mut self) -> bool {
+        let end_index = self.get_cursor_position();
+        let before_cursor = self.current_search_query[..end_index].trim_end();
+        let start_index = before_cursor
+            .rfind(char::is_whitespace)
+            .map_or(0, |index| index + 1);
+        if start_index == end_index {
+            return false;
+        }
+
+        let removed_chars: String = self
+            .current_search_query
+            .drain(start_index..end_index)
+            .collect();
+        self.grapheme_cursor =
+            GraphemeCursor::new(start_index, self.current_search_query.len(), true);
+        self.char_cursor_position -= UnicodeWidthStr::width(removed_chars.as_str());
+        self.cursor_direction = CursorDirection::Left;
+
+        true
+    }
+
+    pub fn move_cursor_left(&mut self) {
+        let prev_cursor = self.get_cursor_position();
+        self.walk_back();
+        if self.get_cursor_position() < prev_cursor {
+            let str_slice = &self.current_search_query[self.get_cursor_position()..prev_cursor];
+            self.char_cursor_position -= UnicodeWidthStr::width(str_slice);
+            self.cursor_direction = CursorDirection::Left;
+        }
+    }
+
+    pub fn move_cursor_right(&mut self) {
+        let prev_cursor = self.get_cursor_position();
+        self.walk_forward();
+        if self.get_cursor_position() > prev_cursor {
+            let str_slice = &self.current_search_query[prev_cursor..self.get_cursor_position()];
+            self.char_cursor_position += UnicodeWidthStr::width(str_slice);
+            self.cursor_direction = CursorDirection::Right;
+        }
+    }
+
+    pub fn move_cursor_to_start(&mut self) {
+        self.grapheme_cursor = GraphemeCursor::new(0, self.current_search_query.len(), true);
+        self.char_cursor_position = 0;
+        self.cursor_direction = CursorDirection::Left;
+    }
+
+    pub fn move_cursor_to_end(&mut self) {
+        let query_len = self.current_search_query.len();
+        self.grapheme_cursor = GraphemeCursor::new(query_len, query_len, true);
+        self.char_cursor_position = UnicodeWidthStr::width(self.current_search_query.as_str());
+        self.cursor_direction = CursorDirection::Right;
+    }
 }
 
 /// Meant for canvas operations involving table column widths.
@@ -136,6 +365,118 @@ impl AppSearchState {
 pub struct CanvasTableWidthState {
     pub desired_column_widths: Vec<u16>,
     pub calculated_column_widths: Vec<u16>,
+    /// The names of the currently drawn columns, used to look up width overrides.
+    pub column_names: Vec<String>,
+    /// Widths the user has set by resizing columns, by column name.
+    pub column_width_overrides: HashMap<String, u16>,
+    /// The column currently selected for resizing with the keyboard, if any.
+    pub selected_column: Option<usize>,
+    /// The column currently being resized by dragging with the mouse, if any.
+    pub dragged_column: Option<usize>,
+    /// The y location of headers.  Since they're all aligned, it's just one value.
+    pub column_header_y_loc: Option<u16>,
+    /// The x start and end bounds for each header.
+    pub column_header_x_locs: Option<Vec<(u16, u16)>>,
+}
+
+impl CanvasTableWidthState {
+    /// Returns the width override for each of the given columns, if there is one.
+    pub fn get_width_overrides(&self) -> Vec<Option<u16>> {
+        self.column_names
+            .iter()
+            .map(|name| self.column_width_overrides.get(name).cloned())
+            .collect()
+    }
+
+    /// Moves the column selected for resizing left or right, selecting the first column if none is.
+    pub fn change_selected_column(&mut self, is_moving_right: bool) {
+        let num_columns = self.calculated_column_widths.len();
+        if num_columns == 0 {
+            return;
+        }
+
+        self.selected_column = Some(match self.selected_column {
+            Some(selected_column) if is_moving_right => min(selected_column + 1, num_columns - 1),
+            Some(selected_column) => selected_column.saturating_sub(1),
+            None => 0,
+        });
+    }
+
+    /// Sets the width of the given column.  Returns whether anything changed.
+    pub fn set_column_width(&mut self, column: usize, width: u16) -> bool {
+        if let Some(name) = self.column_names.get(column) {
+            let width = max(width, 1);
+            if self.column_width_overrides.get(name) != Some(&width) {
+                self.column_width_overrides.insert(name.clone(), width);
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Grows or shrinks the column selected for resizing, selecting the first column if none is.
+    /// Returns whether anything changed.
+    pub fn change_selected_column_width(&mut self, delta: i32) -> bool {
+        if self.selected_column.is_none() {
+            self.change_selected_column(true);
+        }
+
+        if let Some(selected_column) = self.selected_column {
+            if let Some(current_width) = self.calculated_column_widths.get(selected_column) {
+                let new_width = (i32::from(*current_width) + delta).clamp(1, i32::from(u16::MAX));
+                return self.set_column_width(selected_column, new_width as u16);
+            }
+        }
+        false
+    }
+
+    /// Clears all width overrides.  Returns whether anything changed.
+    pub fn reset_column_widths(&mut self) -> bool {
+        let had_overrides = !self.column_width_overrides.is_empty();
+        self.column_width_overrides.clear();
+        had_overrides
+    }
+
+    /// Starts dragging a column if the given location is on the gap right after a column header.
+    /// Returns whether a drag was started.
+    pub fn try_start_drag(&mut self, x: u16, y: u16) -> bool {
+        if let (Some(y_loc), Some(x_locs)) = (self.column_header_y_loc, &self.column_header_x_locs)
+        {
+            if y == y_loc {
+                self.dragged_column = x_locs
+                    .iter()
+                    .position(|(x_left, x_right)| x == *x_right && x > *x_left);
+                return self.dragged_column.is_some();
+            }
+        }
+        false
+    }
+
+    /// Resizes the column being dragged so it ends at the given x location.  Returns whether
+    /// anything changed.
+    pub fn drag_to(&mut self, x: u16) -> bool {
+        if let (Some(dragged_column), Some(x_locs)) =
+            (self.dragged_column, &self.column_header_x_locs)
+        {
+            if let Some((x_left, _)) = x_locs.get(dragged_column) {
+                let new_width = x.saturating_sub(*x_left);
+                return self.set_column_width(dragged_column, new_width);
+            }
+        }
+        false
+    }
+}
+
+/// The tab completion popup of a process search widget.
+#[derive(Default)]
+pub struct SearchCompletionState {
+    pub candidates: Vec<String>,
+    pub list_state: ListState,
+    /// Where the word being completed starts, as a byte index.
+    pub start: usize,
+    /// The query and cursor the popup was last updated for; if either changes, the popup is stale.
+    pub query: String,
+    pub cursor_position: usize,
 }
 
 /// ProcessSearchState only deals with process' search's current settings and state.
@@ -144,6 +485,13 @@ pub struct ProcessSearchState {
     pub is_ignoring_case: bool,
     pub is_searching_whole_word: bool,
     pub is_searching_with_regex: bool,
+    /// Previously used queries, from oldest to newest.
+    pub history: VecDeque<String>,
+    /// The entry of the history currently shown, if we're going through it.
+    pub history_index: Option<usize>,
+    /// Whatever was typed before going through the history, so it can be restored.
+    pub history_draft: String,
+    pub completion_state: SearchCompletionState,
 }
 
 impl Default for ProcessSearchState {
@@ -153,6 +501,10 @@ impl Default for ProcessSearchState {
             is_ignoring_case: true,
             is_searching_whole_word: false,
             is_searching_with_regex: false,
+            history: VecDeque::default(),
+            history_index: None,
+            history_draft: String::default(),
+            completion_state: SearchCompletionState::default(),
         }
     }
 }
@@ -172,7 +524,10 @@ impl ProcessSearchState {
 }
 
 pub struct ColumnInfo {
+    /// Whether the column applies to the current mode (for example, PID vs. count when grouping).
     pub enabled: bool,
+    /// Whether the user has chosen to hide the column.
+    pub is_hidden: bool,
     pub shortcut: Option<&'static str>,
     // FIXME: Move column width logic here!
     // pub hard_width: Option<u16>,
@@ -181,10 +536,6 @@ pub struct ColumnInfo {
 
 pub struct ProcColumn {
     pub ordered_columns: Vec<ProcessSorting>,
-    /// The y location of headers.  Since they're all aligned, it's just one value.
-    pub column_header_y_loc: Option<u16>,
-    /// The x start and end bounds for each header.
-    pub column_header_x_locs: Option<Vec<(u16, u16)>>,
     pub column_mapping: HashMap<ProcessSorting, ColumnInfo>,
     pub longest_header_len: u16,
     pub column_state: TableState,
@@ -222,6 +573,7 @@ impl Default for ProcColumn {
                         column,
                         ColumnInfo {
                             enabled: true,
+                            is_hidden: false,
                             shortcut: Some("c"),
                             // hard_width: None,
                             // max_soft_width: None,
@@ -233,6 +585,7 @@ impl Default for ProcColumn {
                         column,
                         ColumnInfo {
                             enabled: true,
+                            is_hidden: false,
                             shortcut: Some("m"),
                             // hard_width: None,
                             // max_soft_width: None,
@@ -244,6 +597,7 @@ impl Default for ProcColumn {
                         column,
                         ColumnInfo {
                             enabled: false,
+                            is_hidden: false,
                             shortcut: Some("m"),
                             // hard_width: None,
                             // max_soft_width: None,
@@ -255,6 +609,7 @@ impl Default for ProcColumn {
                         column,
                         ColumnInfo {
                             enabled: true,
+                            is_hidden: false,
                             shortcut: Some("n"),
                             // hard_width: None,
                             // max_soft_width: None,
@@ -266,6 +621,7 @@ impl Default for ProcColumn {
                         column,
                         ColumnInfo {
                             enabled: false,
+                            is_hidden: false,
                             shortcut: Some("n"),
                             // hard_width: None,
                             // max_soft_width: None,
@@ -277,6 +633,7 @@ impl Default for ProcColumn {
                         column,
                         ColumnInfo {
                             enabled: true,
+                            is_hidden: false,
                             shortcut: Some("p"),
                             // hard_width: None,
                             // max_soft_width: None,
@@ -288,6 +645,7 @@ impl Default for ProcColumn {
                         column,
                         ColumnInfo {
                             enabled: false,
+                            is_hidden: false,
                             shortcut: None,
                             // hard_width: None,
                             // max_soft_width: None,
@@ -299,6 +657,7 @@ impl Default for ProcColumn {
                         column,
                         ColumnInfo {
                             enabled: cfg!(target_family = "unix"),
+                            is_hidden: false,
                             shortcut: None,
                         },
                     );
@@ -308,6 +667,7 @@ impl Default for ProcColumn {
                         column,
                         ColumnInfo {
                             enabled: true,
+                            is_hidden: false,
                             shortcut: None,
                             // hard_width: None,
                             // max_soft_width: None,
@@ -327,12 +687,21 @@ impl Default for ProcColumn {
             current_scroll_position: 0,
             previous_scroll_position: 0,
             backup_prev_scroll_position: 0,
-            column_header_y_loc: None,
-            column_header_x_locs: None,
         }
     }
 }
 
+/// Returns a column along with its counterpart, if it has one.  Only one of the two is
+/// enabled at a time, and they share a place in the column order.
+fn get_column_slot(column: &ProcessSorting) -> Vec<ProcessSorting> {
+    match column {
+        Pid | Count => vec![Count, Pid],
+        ProcessName | Command => vec![ProcessName, Command],
+        Mem | MemPercent => vec![Mem, MemPercent],
+        _ => vec![column.clone()],
+    }
+}
+
 impl ProcColumn {
     /// Returns its new status.
     pub fn toggle(&mut self, column: &ProcessSorting) -> Option<bool> {
@@ -379,6 +748,117 @@ impl ProcColumn {
         }
     }
 
+    /// Whether the column is actually drawn; that is, it's enabled and not hidden.
+    pub fn is_shown(&self, column: &ProcessSorting) -> bool {
+        if let Some(mapping) = self.column_mapping.get(column) {
+            mapping.enabled && !mapping.is_hidden
+        } else {
+            false
+        }
+    }
+
+    /// Returns the columns that are drawn, in order.
+    pub fn get_shown_columns(&self) -> Vec<ProcessSorting> {
+        self.ordered_columns
+            .iter()
+            .filter(|column| self.is_shown(column))
+            .cloned()
+            .collect()
+    }
+
+    /// Sets the order of the columns, hiding any that aren't listed.  Each column is
+    /// kept next to its counterpart (e.g. PID and count), which takes its place when toggled.
+    pub fn set_columns(&mut self, columns: &[ProcessSorting]) {
+        let mut ordered_columns = Vec::with_capacity(self.ordered_columns.len());
+        for column in columns {
+            for column in get_column_slot(column) {
+                if !ordered_columns.contains(&column) {
+                    if let Some(mapping) = self.column_mapping.get_mut(&column) {
+                        mapping.is_hidden = false;
+                    }
+                    ordered_columns.push(column);
+                }
+            }
+        }
+
+        for column in &self.ordered_columns {
+            if !ordered_columns.contains(column) {
+                ordered_columns.push(column.clone());
+                if let Some(mapping) = self.column_mapping.get_mut(column) {
+                    mapping.is_hidden = true;
+                }
+            }
+        }
+
+        self.ordered_columns = ordered_columns;
+    }
+
+    /// Hides or shows a column (along with its counterpart).  The last shown column can't be hidden.
+    /// Returns whether anything changed.
+    pub fn toggle_hidden(&mut self, column: &ProcessSorting) -> bool {
+        let is_hiding = self.is_shown(column);
+        if is_hiding && self.get_shown_columns().len() <= 1 {
+            return false;
+        }
+
+        for column in get_column_slot(column) {
+            if let Some(mapping) = self.column_mapping.get_mut(&column) {
+                mapping.is_hidden = is_hiding;
+            }
+        }
+        true
+    }
+
+    /// Moves a column (along with its counterpart) past the previous or next enabled column.
+    /// Returns whether anything changed.
+    pub fn move_column(&mut self, column: &ProcessSorting, is_moving_up: bool) -> bool {
+        // Work on slots, so counterparts always stay together.
+        let mut slots: Vec<Vec<ProcessSorting>> = Vec::new();
+        for ordered_column in &self.ordered_columns {
+            match slots.last_mut() {
+                Some(slot) if get_column_slot(&slot[0]).contains(ordered_column) => {
+                    slot.push(ordered_column.clone())
+                }
+                _ => slots.push(vec![ordered_column.clone()]),
+            }
+        }
+
+        let is_slot_enabled = |slot: &Vec<ProcessSorting>| {
+            slot.iter().any(|column| {
+                if let Some(mapping) = self.column_mapping.get(column) {
+                    mapping.enabled
+                } else {
+                    false
+                }
+            })
+        };
+
+        if let Some(current_index) = slots.iter().position(|slot| slot.contains(column)) {
+            let target_index = if is_moving_up {
+                slots[..current_index].iter().rposition(is_slot_enabled)
+            } else {
+                slots[current_index + 1..]
+                    .iter()
+                    .position(is_slot_enabled)
+                    .map(|index| index + current_index + 1)
+            };
+
+            if let Some(target_index) = target_index {
+                slots.swap(current_index, target_index);
+                self.ordered_columns = slots.into_iter().flatten().collect();
+                self.set_to_sorted_index_from_type(column);
+                return true;
+            }
+        }
+
+        false
+    }
+
+    /// Whether two columns share a place in the column order.
+    pub fn is_same_slot(a: &ProcessSorting, b: &ProcessSorting) -> bool {
+        get_column_slot(a).contains(b)
+    }
+
     pub fn get_enabled_columns_len(&self) -> usize {
         self.ordered_columns
             .iter()
@@ -419,9 +899,9 @@ impl ProcColumn {
         self.backup_prev_scroll_position = self.previous_scroll_position;
     }
 
-    pub fn get_column_headers(
-        &self, proc_sorting_type: &ProcessSorting, sort_reverse: bool,
-    ) -> Vec<String> {
+    /// Gets the column headers, with sort indicators.  The main sort column just gets an arrow,
+    /// while any secondary sort columns also get their position in the sort order.
+    pub fn get_column_headers(&self, sort_keys: &[(ProcessSorting, bool)]) -> Vec<String> {
         const DOWN_ARROW: char = '▼';
         const UP_ARROW: char = '▲';
 
@@ -435,20 +915,31 @@ impl ProcColumn {
                     command_str = format!("({})", command);
                 }
 
-                if mapping.enabled {
-                    Some(format!(
-                        "{}{}{}",
-                        column_type.to_string(),
-                        command_str.as_str(),
-                        if proc_sorting_type == column_type {
-                            if sort_reverse {
+                if mapping.enabled && !mapping.is_hidden {
+                    let sort_str = match sort_keys
+                        .iter()
+                        .position(|(sort_type, _)| sort_type == column_type)
+                    {
+                        Some(index) => {
+                            let arrow = if sort_keys[index].1 {
                                 DOWN_ARROW
                             } else {
                                 UP_ARROW
+                            };
+                            if index == 0 {
+                                arrow.to_string()
+                            } else {
+                                format!("{}{}", arrow, index + 1)
                             }
-                        } else {
-                            ' '
                         }
+                        None => " ".to_string(),
+                    };
+
+                    Some(format!(
+                        "{}{}{}",
+                        column_type.to_string(),
+                        command_str.as_str(),
+                        sort_str
                     ))
                 } else {
                     None
@@ -458,18 +949,136 @@ impl ProcColumn {
     }
 }
 
+/// What happens to the branches of a process tree that haven't been toggled individually.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TreeCollapseDefault {
+    ExpandAll,
+    CollapseAll,
+    /// Only show this many levels of the tree; anything deeper is collapsed.
+    ExpandToDepth(usize),
+}
+
+/// Which branches of a process tree are collapsed.
+#[derive(Debug)]
+pub struct TreeCollapseState {
+    pub default: TreeCollapseDefault,
+    /// Processes that have been toggled away from the default, along with their names so that a
+    /// reused PID doesn't inherit whatever state the process that had it before was in.
+    toggled: HashMap<Pid, String>,
+}
+
+impl Default for TreeCollapseState {
+    fn default() -> Self {
+        TreeCollapseState {
+            default: TreeCollapseDefault::ExpandAll,
+            toggled: HashMap::new(),
+        }
+    }
+}
+
+impl TreeCollapseState {
+    /// Returns whether the given process is collapsed, where `depth` is how far down the tree it
+    /// is (top-level processes are at depth 0).
+    pub fn is_collapsed(&self, pid: Pid, name: &str, depth: usize) -> bool {
+        let is_collapsed_by_default = match self.default {
+            TreeCollapseDefault::ExpandAll => false,
+            TreeCollapseDefault::CollapseAll => true,
+            TreeCollapseDefault::ExpandToDepth(max_depth) => depth + 1 >= max_depth,
+        };
+        let is_toggled =
+            matches!(self.toggled.get(&pid), Some(toggled_name) if toggled_name == name);
+
+        is_collapsed_by_default != is_toggled
+    }
+
+    /// Collapses the given process if it's expanded, or expands it if it's collapsed.
+    pub fn toggle(&mut self, pid: Pid, name: &str) {
+        match self.toggled.get(&pid) {
+            Some(toggled_name) if toggled_name == name => {
+                self.toggled.remove(&pid);
+            }
+            _ => {
+                self.toggled.insert(pid, name.to_string());
+            }
+        }
+    }
+
+    /// Sets what all branches do, undoing anything toggled individually.
+    pub fn set_default(&mut self, default: TreeCollapseDefault) {
+        self.default = default;
+        self.toggled.clear();
+    }
+
+    /// Expands the given process if it's collapsed.
+    pub fn expand(&mut self, pid: Pid, name: &str, depth: usize) {
+        if self.is_collapsed(pid, name, depth) {
+            self.toggle(pid, name);
+        }
+    }
+
+    /// Forgets about processes that have exited, or whose PIDs now belong to something else.
+    pub fn retain(&mut self, mut is_alive: impl FnMut(Pid, &str) -> bool) {
+        self.toggled.retain(|pid, name| is_alive(*pid, name));
+    }
+}
+
+/// What the selection of a process widget sticks to while following.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum FollowTarget {
+    Pid(Pid),
+    /// The name of a group, when processes are grouped.
+    Group(String),
+}
+
+impl FollowTarget {
+    fn new(process: &ConvertedProcessData, is_grouped: bool) -> Self {
+        if is_grouped {
+            FollowTarget::Group(process.name.clone())
+        } else {
+            FollowTarget::Pid(process.pid)
+        }
+    }
+
+    fn matches(&self, process: &ConvertedProcessData) -> bool {
+        match self {
+            FollowTarget::Pid(pid) => process.pid == *pid,
+            FollowTarget::Group(name) => process.name == *name,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct FollowState {
+    pub target: FollowTarget,
+    /// Where the followed process was last selected.  If the selection is somewhere else, it was
+    /// moved by hand, and whatever is selected now gets followed instead.
+    pub followed_position: usize,
+    /// Whether the followed process has exited (or no longer matches the search).
+    pub has_exited: bool,
+}
+
 pub struct ProcWidgetState {
     pub process_search_state: ProcessSearchState,
-    pub is_grouped: bool,
+    pub grouping_type: ProcessGroupingType,
     pub scroll_state: AppScrollWidgetState,
     pub process_sorting_type: processes::ProcessSorting,
     pub is_process_sort_descending: bool,
+    /// Extra sort keys used to break ties, in order, along with whether each is descending.
+    pub secondary_sort_keys: Vec<(processes::ProcessSorting, bool)>,
     pub is_using_command: bool,
     pub current_column_index: usize,
     pub is_sort_open: bool,
     pub columns: ProcColumn,
     pub is_tree_mode: bool,
+    pub tree_collapse_state: TreeCollapseState,
     pub table_width_state: CanvasTableWidthState,
+    /// How far the name/command column is scrolled to the right, in characters.  The canvas caps
+    /// this to however far the longest entry can actually be scrolled.
+    pub horizontal_scroll_offset: usize,
+    /// If set, the selection sticks to a process rather than a position as the rows move around.
+    pub follow_state: Option<FollowState>,
+    /// A process to select once the rows are next built, as it might not be in the current ones.
+    pub pid_to_select: Option<Pid>,
     pub requires_redraw: bool,
 }
 
@@ -517,27 +1126,208 @@ impl ProcWidgetState {
 
         ProcWidgetState {
             process_search_state,
-            is_grouped,
+            grouping_type: if is_grouped {
+                ProcessGroupingType::Name
+            } else {
+                ProcessGroupingType::Ungrouped
+            },
             scroll_state: AppScrollWidgetState::default(),
             process_sorting_type,
             is_process_sort_descending,
+            secondary_sort_keys: Vec::new(),
             is_using_command,
             current_column_index: 0,
             is_sort_open: false,
             columns,
             is_tree_mode,
+            tree_collapse_state: TreeCollapseState::default(),
             table_width_state: CanvasTableWidthState::default(),
+            horizontal_scroll_offset: 0,
+            follow_state: None,
+            pid_to_select: None,
             requires_redraw: false,
         }
     }
 
-    /// Updates sorting when using the column list.
-    /// ...this really should be part of the ProcColumn struct (along with the sorting fields),
-    /// but I'm too lazy.
-    ///
-    /// Sorry, future me, you're gonna have to refactor this later.  Too busy getting
-    /// the feature to work in the first place!  :)
-    pub fn update_sorting_with_columns(&mut self) {
+    pub fn is_grouped(&self) -> bool {
+        self.grouping_type != ProcessGroupingType::Ungrouped
+    }
+
+    /// Switches to a new grouping type.  Going between grouped and ungrouped swaps the PID column
+    /// for the count column, and hides the columns that don't make sense for a group.
+    pub fn set_grouping_type(&mut self, grouping_type: ProcessGroupingType) {
+        if grouping_type != self.grouping_type {
+            // Group names don't carry over between grouping types.
+            self.follow_state = None;
+        }
+
+        let was_grouped = self.is_grouped();
+        self.grouping_type = grouping_type;
+        let is_grouped = self.is_grouped();
+
+        if was_grouped == is_grouped {
+            return;
+        }
+
+        // Forcefully switch off column if we were on it...
+        if (is_grouped
+            && (self.process_sorting_type == ProcessSorting::Pid
+                || self.process_sorting_type == ProcessSorting::User
+                || self.process_sorting_type == ProcessSorting::State))
+            || (!is_grouped && self.process_sorting_type == ProcessSorting::Count)
+        {
+            self.process_sorting_type = ProcessSorting::CpuPercent; // Go back to default, negate PID for group
+            self.is_process_sort_descending = true;
+        }
+
+        self.columns
+            .set_to_sorted_index_from_type(&self.process_sorting_type);
+
+        self.columns.try_set(&ProcessSorting::State, !is_grouped);
+
+        #[cfg(target_family = "unix")]
+        self.columns.try_set(&ProcessSorting::User, !is_grouped);
+
+        self.columns.toggle(&ProcessSorting::Count);
+        self.columns.toggle(&ProcessSorting::Pid);
+        self.prune_secondary_sort_keys();
+    }
+
+    /// Starts following the given (selected) process, or stops following if already doing so.
+    pub fn toggle_follow(&mut self, selected_process: Option<&ConvertedProcessData>) {
+        self.follow_state = match (&self.follow_state, selected_process) {
+            (None, Some(process)) => Some(FollowState {
+                target: FollowTarget::new(process, self.is_grouped()),
+                followed_position: self.scroll_state.current_scroll_position,
+                has_exited: false,
+            }),
+            _ => None,
+        };
+    }
+
+    /// Sets up selecting the process with the given PID once the rows are next built, clearing
+    /// the search, ungrouping, or expanding the tree if that's what it takes to show it.  Returns
+    /// whether there is such a process.
+    pub fn go_to_pid(
+        &mut self, pid: Pid, all_processes: &HashMap<Pid, ConvertedProcessData>,
+    ) -> bool {
+        let process = match all_processes.get(&pid) {
+            Some(process) => process,
+            None => return false,
+        };
+
+        let search_state = &self.process_search_state.search_state;
+        if !search_state.is_invalid_or_blank_search() {
+            if let Some(query) = &search_state.query {
+                let relations = ProcessRelations::new(all_processes);
+                if !query.check(process, self.is_using_command, &relations) {
+                    self.set_search_query(String::new());
+                }
+            }
+        }
+
+        if self.is_grouped() {
+            self.set_grouping_type(ProcessGroupingType::Ungrouped);
+        }
+
+        if self.is_tree_mode {
+            // Expand everything above it, from the top of the tree down.
+            let mut ancestors = Vec::new();
+            let mut current = process;
+            while let Some(parent) = current
+                .ppid
+                .and_then(|ppid| all_processes.get(&ppid))
+                .filter(|parent| parent.pid != current.pid && ancestors.len() < all_processes.len())
+            {
+                ancestors.push(parent);
+                current = parent;
+            }
+            for (depth, ancestor) in ancestors.iter().rev().enumerate() {
+                self.tree_collapse_state
+                    .expand(ancestor.pid, &ancestor.name, depth);
+            }
+        }
+
+        self.pid_to_select = Some(pid);
+        true
+    }
+
+    /// Selects the process that was asked for (say, by going to a PID), now that the rows that
+    /// should contain it have been built.  Following switches over to it too.
+    pub fn select_pending_pid(&mut self, processes: &[ConvertedProcessData]) {
+        if let Some(pid) = self.pid_to_select.take() {
+            if let Some(position) = processes.iter().position(|process| process.pid == pid) {
+                let scroll_state = &mut self.scroll_state;
+                if position < scroll_state.current_scroll_position {
+                    scroll_state.scroll_direction = ScrollDirection::Up;
+                } else if position > scroll_state.current_scroll_position {
+                    scroll_state.scroll_direction = ScrollDirection::Down;
+                }
+                scroll_state.current_scroll_position = position;
+
+                if let Some(follow_state) = &mut self.follow_state {
+                    follow_state.target = FollowTarget::Pid(pid);
+                    follow_state.followed_position = position;
+                    follow_state.has_exited = false;
+                }
+            }
+        }
+    }
+
+    /// Moves the selection to wherever the followed process ended up in the newly built rows.
+    /// The previous rows are used to tell what the user selected, if they moved the selection.
+    pub fn update_follow(
+        &mut self, previous_processes: Option<&Vec<ConvertedProcessData>>,
+        processes: &[ConvertedProcessData],
+    ) {
+        let is_grouped = self.is_grouped();
+        let scroll_state = &mut self.scroll_state;
+        if let Some(follow_state) = &mut self.follow_state {
+            let current_position = scroll_state.current_scroll_position;
+            if current_position != follow_state.followed_position {
+                if let Some(process) =
+                    previous_processes.and_then(|previous| previous.get(current_position))
+                {
+                    follow_state.target = FollowTarget::new(process, is_grouped);
+                }
+            }
+
+            match processes
+                .iter()
+                .position(|process| follow_state.target.matches(process))
+            {
+                Some(new_position) => {
+                    if new_position < current_position {
+                        scroll_state.scroll_direction = ScrollDirection::Up;
+                    } else if new_position > current_position {
+                        scroll_state.scroll_direction = ScrollDirection::Down;
+                    }
+                    scroll_state.current_scroll_position = new_position;
+                    follow_state.followed_position = new_position;
+                    follow_state.has_exited = false;
+                }
+                None => {
+                    // Stay put, so the user can see where it was.
+                    follow_state.followed_position =
+                        min(current_position, processes.len().saturating_sub(1));
+                    follow_state.has_exited = true;
+                }
+            }
+        }
+    }
+
+    /// Scrolls the name/command column left or right, so the rest of long commands can be seen.
+    pub fn scroll_horizontally(&mut self, is_scrolling_right: bool) {
+        self.horizontal_scroll_offset = if is_scrolling_right {
+            self.horizontal_scroll_offset + HORIZONTAL_SCROLL_STEP
+        } else {
+            self.horizontal_scroll_offset
+                .saturating_sub(HORIZONTAL_SCROLL_STEP)
+        };
+    }
+
+    /// Returns the column currently selected in the sort widget, if any.
+    fn get_selected_sort_column(&self) -> Option<ProcessSorting> {
         let mut true_index = 0;
         let mut enabled_index = 0;
         let target_itx = self.columns.current_scroll_position;
@@ -552,26 +1342,114 @@ impl ProcWidgetState {
             true_index += 1;
         }
 
-        if let Some(new_sort_type) = self.columns.ordered_columns.get(true_index) {
-            if *new_sort_type == self.process_sorting_type {
+        self.columns.ordered_columns.get(true_index).cloned()
+    }
+
+    /// Updates sorting when using the column list.
+    /// ...this really should be part of the ProcColumn struct (along with the sorting fields),
+    /// but I'm too lazy.
+    ///
+    /// Sorry, future me, you're gonna have to refactor this later.  Too busy getting
+    /// the feature to work in the first place!  :)
+    pub fn update_sorting_with_columns(&mut self) {
+        if let Some(new_sort_type) = self.get_selected_sort_column() {
+            if new_sort_type == self.process_sorting_type {
                 // Just reverse the search if we're reselecting!
                 self.is_process_sort_descending = !(self.is_process_sort_descending);
             } else {
-                self.process_sorting_type = new_sort_type.clone();
-                match self.process_sorting_type {
-                    ProcessSorting::State
-                    | ProcessSorting::Pid
-                    | ProcessSorting::ProcessName
-                    | ProcessSorting::Command => {
-                        // Also invert anything that uses alphabetical sorting by default.
-                        self.is_process_sort_descending = false;
-                    }
-                    _ => {
-                        self.is_process_sort_descending = true;
-                    }
+                // Picking a new main column starts a fresh sort order.
+                self.is_process_sort_descending = new_sort_type.is_descending_by_default();
+                self.process_sorting_type = new_sort_type;
+                self.secondary_sort_keys.clear();
+            }
+        }
+    }
+
+    /// Adds the column selected in the sort widget as a secondary sort key.  If it is already a
+    /// secondary key, its order is reversed instead.  Once the maximum number of keys is reached,
+    /// the last key is replaced.
+    pub fn add_sort_key_with_columns(&mut self) {
+        if let Some(new_sort_type) = self.get_selected_sort_column() {
+            if new_sort_type == self.process_sorting_type {
+                return;
+            }
+
+            if let Some((_, is_descending)) = self
+                .secondary_sort_keys
+                .iter_mut()
+                .find(|(sort_type, _)| *sort_type == new_sort_type)
+            {
+                *is_descending = !*is_descending;
+            } else {
+                if self.secondary_sort_keys.len() >= MAX_SECONDARY_SORT_KEYS {
+                    self.secondary_sort_keys.pop();
                 }
+                let is_descending = new_sort_type.is_descending_by_default();
+                self.secondary_sort_keys
+                    .push((new_sort_type, is_descending));
+            }
+        }
+    }
+
+    /// Hides or shows the column selected in the sort widget.
+    pub fn toggle_selected_column_hidden(&mut self) {
+        if let Some(column) = self.get_selected_sort_column() {
+            if self.columns.toggle_hidden(&column) {
+                self.requires_redraw = true;
+            }
+        }
+    }
+
+    /// Moves the column selected in the sort widget up or down in the column order.
+    pub fn move_selected_column(&mut self, is_moving_up: bool) {
+        if let Some(column) = self.get_selected_sort_column() {
+            if self.columns.move_column(&column, is_moving_up) {
+                self.requires_redraw = true;
+            }
+        }
+    }
+
+    /// Returns every sort key in order, starting with the main one.
+    pub fn get_sort_keys(&self) -> Vec<(ProcessSorting, bool)> {
+        std::iter::once((
+            self.process_sorting_type.clone(),
+            self.is_process_sort_descending,
+        ))
+        .chain(self.secondary_sort_keys.iter().cloned())
+        .collect()
+    }
+
+    /// Replaces the sort order with the given keys, starting with the main one.  Keys for
+    /// columns that aren't shown (or that are repeated) are skipped.
+    pub fn set_sort_keys(&mut self, sort_keys: &[(ProcessSorting, bool)]) {
+        let mut usable_keys: Vec<(ProcessSorting, bool)> = Vec::new();
+        for (sort_type, is_descending) in sort_keys {
+            if self.columns.is_enabled(sort_type)
+                && !usable_keys
+                    .iter()
+                    .any(|(existing, _)| existing == sort_type)
+            {
+                usable_keys.push((sort_type.clone(), *is_descending));
             }
         }
+        let mut sort_keys = usable_keys.into_iter();
+
+        if let Some((sort_type, is_descending)) = sort_keys.next() {
+            self.process_sorting_type = sort_type;
+            self.is_process_sort_descending = is_descending;
+            self.secondary_sort_keys = sort_keys.take(MAX_SECONDARY_SORT_KEYS).collect();
+            self.columns
+                .set_to_sorted_index_from_type(&self.process_sorting_type);
+        }
+    }
+
+    /// Drops any secondary sort keys whose columns are no longer shown.
+    pub fn prune_secondary_sort_keys(&mut self) {
+        let columns = &self.columns;
+        let process_sorting_type = &self.process_sorting_type;
+        self.secondary_sort_keys.retain(|(sort_type, _)| {
+            sort_type != process_sorting_type && columns.is_enabled(sort_type)
+        });
     }
 
     pub fn toggle_command_and_name(&mut self, is_using_command: bool) {
@@ -617,32 +1495,223 @@ impl ProcWidgetState {
             .current_search_query
             .is_empty()
         {
-            self.process_search_state.search_state.is_blank_search = true;
-            self.process_search_state.search_state.is_invalid_search = false;
-            self.process_search_state.search_state.error_message = None;
+            self.process_search_state
+                .search_state
+                .set_parsed_query(None);
         } else {
             let parsed_query = self.parse_query();
             // debug!("Parsed query: {:#?}", parsed_query);
 
-            if let Ok(parsed_query) = parsed_query {
-                self.process_search_state.search_state.query = Some(parsed_query);
-                self.process_search_state.search_state.is_blank_search = false;
-                self.process_search_state.search_state.is_invalid_search = false;
-                self.process_search_state.search_state.error_message = None;
-            } else if let Err(err) = parsed_query {
-                self.process_search_state.search_state.is_blank_search = false;
-                self.process_search_state.search_state.is_invalid_search = true;
-                self.process_search_state.search_state.error_message = Some(err.to_string());
-            }
+            self.process_search_state
+                .search_state
+                .set_parsed_query(Some(parsed_query));
         }
         self.scroll_state.previous_scroll_position = 0;
         self.scroll_state.current_scroll_position = 0;
+        if let Some(follow_state) = &mut self.follow_state {
+            // Not a move by the user, so keep following the same process.
+            follow_state.followed_position = 0;
+        }
     }
 
     pub fn clear_search(&mut self) {
         self.process_search_state.search_state.reset();
     }
 
+    /// Replaces the current search query, moving the cursor to the end of it.
+    pub fn set_search_query(&mut self, query: String) {
+        let search_state = &mut self.process_search_state.search_state;
+        search_state.grapheme_cursor = GraphemeCursor::new(query.len(), query.len(), true);
+        search_state.char_cursor_position = UnicodeWidthStr::width(query.as_str());
+        search_state.cursor_direction = CursorDirection::Right;
+        search_state.current_search_query = query;
+
+        self.update_query();
+    }
+
+    /// Searches for just the processes of `user`.  Whole word matching is turned on so users
+    /// whose names contain it don't match as well, and the name is escaped if searching with
+    /// regex.
+    pub fn search_for_user(&mut self, user: &str) {
+        let process_search_state = &mut self.process_search_state;
+        process_search_state.search_state.is_enabled = true;
+        if !process_search_state.is_searching_whole_word {
+            process_search_state.search_toggle_whole_word();
+        }
+        let user = if process_search_state.is_searching_with_regex {
+            regex::escape(user)
+        } else {
+            user.to_string()
+        };
+
+        self.set_search_query(format!("user={}", user));
+    }
+
+    /// Replaces the given byte range of the search query, moving the cursor to the end of the
+    /// replacement.
+    fn replace_search_range(&mut self, start: usize, end: usize, replacement: &str) {
+        let search_state = &mut self.process_search_state.search_state;
+        search_state
+            .current_search_query
+            .replace_range(start..end, replacement);
+
+        let new_cursor_position = start + replacement.len();
+        search_state.grapheme_cursor = GraphemeCursor::new(
+            new_cursor_position,
+            search_state.current_search_query.len(),
+            true,
+        );
+        search_state.char_cursor_position =
+            UnicodeWidthStr::width(&search_state.current_search_query[..new_cursor_position]);
+        search_state.cursor_direction = CursorDirection::Right;
+
+        self.update_query();
+    }
+
+    /// Whether the completion popup should be shown; it goes away as soon as the query is edited
+    /// or the cursor moves.
+    pub fn is_completion_showing(&self) -> bool {
+        let completion_state = &self.process_search_state.completion_state;
+        !completion_state.candidates.is_empty()
+            && completion_state.query == *self.get_current_search_query()
+            && completion_state.cursor_position == self.get_search_cursor_position()
+    }
+
+    pub fn close_completion(&mut self) {
+        self.process_search_state.completion_state = SearchCompletionState::default();
+    }
+
+    /// Completes the word before the cursor.  A single candidate is filled in straight away,
+    /// otherwise as much as all candidates share is filled in and the candidates are shown.
+    /// Completing again while the candidates are shown goes through them instead.
+    pub fn complete_search(&mut self, values: &CompletionValues) {
+        if self.is_completion_showing() {
+            self.change_completion_position(1);
+            return;
+        }
+
+        let completion = get_completion(
+            self.get_current_search_query(),
+            self.get_search_cursor_position(),
+            values,
+        );
+
+        match completion.candidates.as_slice() {
+            [] => {}
+            [candidate] => {
+                self.replace_search_range(completion.start, completion.end, candidate);
+                self.close_completion();
+            }
+            _ => {
+                if let Some(common_prefix) = completion.get_common_prefix() {
+                    if common_prefix.len() > completion.end - completion.start {
+                        let common_prefix = common_prefix.to_string();
+                        self.replace_search_range(completion.start, completion.end, &common_prefix);
+                    }
+                }
+
+                self.process_search_state.completion_state = SearchCompletionState {
+                    candidates: completion.candidates,
+                    list_state: ListState::default(),
+                    start: completion.start,
+                    query: self.get_current_search_query().clone(),
+                    cursor_position: self.get_search_cursor_position(),
+                };
+            }
+        }
+    }
+
+    /// Selects another completion candidate and fills it in, wrapping around at either end.
+    pub fn change_completion_position(&mut self, num_to_change_by: i64) {
+        let completion_state = &self.process_search_state.completion_state;
+        let num_candidates = completion_state.candidates.len() as i64;
+        if num_candidates == 0 {
+            return;
+        }
+
+        let new_index = match completion_state.list_state.selected() {
+            Some(index) => (index as i64 + num_to_change_by).rem_euclid(num_candidates),
+            None if num_to_change_by < 0 => num_candidates - 1,
+            None => 0,
+        } as usize;
+
+        let start = completion_state.start;
+        let candidate = completion_state.candidates[new_index].clone();
+        self.replace_search_range(start, self.get_search_cursor_position(), &candidate);
+
+        let completion_state = &mut self.process_search_state.completion_state;
+        completion_state.list_state.select(Some(new_index));
+        completion_state.query = self
+            .process_search_state
+            .search_state
+            .current_search_query
+            .clone();
+        completion_state.cursor_position = self
+            .process_search_state
+            .search_state
+            .grapheme_cursor
+            .cur_cursor();
+    }
+
+    /// Adds the current query to the search history, as long as it is valid.  If the query was
+    /// already in the history, it is moved to be the newest entry instead.
+    pub fn add_query_to_history(&mut self) {
+        if !self
+            .process_search_state
+            .search_state
+            .is_invalid_or_blank_search()
+        {
+            let query = self.get_current_search_query().clone();
+            let history = &mut self.process_search_state.history;
+
+            history.retain(|entry| *entry != query);
+            history.push_back(query);
+            if history.len() > MAX_SEARCH_HISTORY_LENGTH {
+                history.pop_front();
+            }
+        }
+
+        self.process_search_state.history_index = None;
+    }
+
+    /// Moves through the search history, where a negative amount goes towards older queries.
+    /// Going past the newest query brings back whatever was being typed beforehand.
+    pub fn change_history_position(&mut self, num_to_change_by: i64) {
+        let history_len = self.process_search_state.history.len();
+        if history_len == 0 {
+            return;
+        }
+
+        // If the shown entry was edited, treat it as something new being typed.
+        let current_index = match self.process_search_state.history_index {
+            Some(index)
+                if self.process_search_state.history.get(index)
+                    == Some(self.get_current_search_query()) =>
+            {
+                index
+            }
+            _ => {
+                self.process_search_state.history_draft = self.get_current_search_query().clone();
+                history_len
+            }
+        };
+
+        let new_index = (current_index as i64 + num_to_change_by).clamp(0, history_len as i64);
+        if new_index as usize == current_index {
+            return;
+        }
+
+        if new_index as usize == history_len {
+            self.process_search_state.history_index = None;
+            let draft = std::mem::take(&mut self.process_search_state.history_draft);
+            self.set_search_query(draft);
+        } else {
+            self.process_search_state.history_index = Some(new_index as usize);
+            let query = self.process_search_state.history[new_index as usize].clone();
+            self.set_search_query(query);
+        }
+    }
+
     pub fn search_walk_forward(&mut self, start_position: usize) {
         self.process_search_state
             .search_state
@@ -824,6 +1893,7 @@ impl MemState {
 pub struct TempWidgetState {
     pub scroll_state: AppScrollWidgetState,
     pub table_width_state: CanvasTableWidthState,
+    pub search_state: AppSearchState,
 }
 
 impl TempWidgetState {
@@ -831,17 +1901,28 @@ impl TempWidgetState {
         TempWidgetState {
             scroll_state: AppScrollWidgetState::default(),
             table_width_state: CanvasTableWidthState::default(),
+            search_state: AppSearchState::default(),
         }
     }
+
+    pub fn update_query(&mut self) {
+        self.search_state.update_query(PrefixSet::Temp);
+        self.scroll_state.previous_scroll_position = 0;
+        self.scroll_state.current_scroll_position = 0;
+    }
 }
 
 pub struct TempState {
     pub widget_states: HashMap<u64, TempWidgetState>,
+    pub force_update: Option<u64>,
 }
 
 impl TempState {
     pub fn init(widget_states: HashMap<u64, TempWidgetState>) -> Self {
-        TempState { widget_states }
+        TempState {
+            widget_states,
+            force_update: None,
+        }
     }
 
     pub fn get_mut_widget_state(&mut self, widget_id: u64) -> Option<&mut TempWidgetState> {
@@ -856,6 +1937,7 @@ impl TempState {
 pub struct DiskWidgetState {
     pub scroll_state: AppScrollWidgetState,
     pub table_width_state: CanvasTableWidthState,
+    pub search_state: AppSearchState,
 }
 
 impl DiskWidgetState {
@@ -863,17 +1945,28 @@ impl DiskWidgetState {
         DiskWidgetState {
             scroll_state: AppScrollWidgetState::default(),
             table_width_state: CanvasTableWidthState::default(),
+            search_state: AppSearchState::default(),
         }
     }
+
+    pub fn update_query(&mut self) {
+        self.search_state.update_query(PrefixSet::Disk);
+        self.scroll_state.previous_scroll_position = 0;
+        self.scroll_state.current_scroll_position = 0;
+    }
 }
 
 pub struct DiskState {
     pub widget_states: HashMap<u64, DiskWidgetState>,
+    pub force_update: Option<u64>,
 }
 
 impl DiskState {
     pub fn init(widget_states: HashMap<u64, DiskWidgetState>) -> Self {
-        DiskState { widget_states }
+        DiskState {
+            widget_states,
+            force_update: None,
+        }
     }
 
     pub fn get_mut_widget_state(&mut self, widget_id: u64) -> Option<&mut DiskWidgetState> {
@@ -884,6 +1977,111 @@ impl DiskState {
         self.widget_states.get(&widget_id)
     }
 }
+
+/// The columns of the user widget, in order.  These reuse the process sorting types of the
+/// matching process columns.
+pub const USER_COLUMNS: [ProcessSorting; 7] = [
+    ProcessSorting::User,
+    ProcessSorting::Count,
+    ProcessSorting::CpuPercent,
+    ProcessSorting::Mem,
+    ProcessSorting::MemPercent,
+    ProcessSorting::ReadPerSecond,
+    ProcessSorting::WritePerSecond,
+];
+
+pub struct UserWidgetState {
+    pub scroll_state: AppScrollWidgetState,
+    pub table_width_state: CanvasTableWidthState,
+    pub search_state: AppSearchState,
+    pub sort_type: ProcessSorting,
+    pub is_sort_descending: bool,
+}
+
+impl UserWidgetState {
+    pub fn init() -> Self {
+        UserWidgetState {
+            scroll_state: AppScrollWidgetState::default(),
+            table_width_state: CanvasTableWidthState::default(),
+            search_state: AppSearchState::default(),
+            sort_type: ProcessSorting::CpuPercent,
+            is_sort_descending: true,
+        }
+    }
+
+    pub fn update_query(&mut self) {
+        self.search_state.update_query(PrefixSet::User);
+        self.scroll_state.previous_scroll_position = 0;
+        self.scroll_state.current_scroll_position = 0;
+    }
+
+    /// Sorts by the given column, or reverses the sort if already sorting by it.
+    pub fn set_sort_type(&mut self, sort_type: ProcessSorting) {
+        if self.sort_type == sort_type {
+            self.is_sort_descending = !self.is_sort_descending;
+        } else {
+            self.is_sort_descending = sort_type.is_descending_by_default();
+            self.sort_type = sort_type;
+        }
+    }
+
+    /// Moves the sort to the column to the left or right of the current one.
+    pub fn move_sort_column(&mut self, is_moving_left: bool) {
+        let current_index = USER_COLUMNS
+            .iter()
+            .position(|column| *column == self.sort_type)
+            .unwrap_or(0);
+        let new_index = if is_moving_left {
+            current_index.saturating_sub(1)
+        } else {
+            min(current_index + 1, USER_COLUMNS.len() - 1)
+        };
+
+        if new_index != current_index {
+            self.set_sort_type(USER_COLUMNS[new_index].clone());
+        }
+    }
+
+    /// Gets the column headers, with an arrow on the column being sorted by.
+    pub fn get_column_headers(&self) -> Vec<String> {
+        USER_COLUMNS
+            .iter()
+            .map(|column| {
+                let sort_str = if *column != self.sort_type {
+                    " "
+                } else if self.is_sort_descending {
+                    "▼"
+                } else {
+                    "▲"
+                };
+
+                format!("{}{}", column, sort_str)
+            })
+            .collect()
+    }
+}
+
+pub struct UserState {
+    pub widget_states: HashMap<u64, UserWidgetState>,
+    pub force_update: Option<u64>,
+}
+
+impl UserState {
+    pub fn init(widget_states: HashMap<u64, UserWidgetState>) -> Self {
+        UserState {
+            widget_states,
+            force_update: None,
+        }
+    }
+
+    pub fn get_mut_widget_state(&mut self, widget_id: u64) -> Option<&mut UserWidgetState> {
+        self.widget_states.get_mut(&widget_id)
+    }
+
+    pub fn get_widget_state(&self, widget_id: u64) -> Option<&UserWidgetState> {
+        self.widget_states.get(&widget_id)
+    }
+}
 pub struct BasicTableWidgetState {
     // Since this is intended (currently) to only be used for ONE widget, that's
     // how it's going to be written.  If we want to allow for multiple of these,
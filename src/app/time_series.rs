@@ -0,0 +1,448 @@
+//! Stores the data behind the charts by column rather than by harvest: each series, like the
+//! usage of one CPU core, gets its own ring buffer.  Adding a harvest appends a value to each,
+//! and dropping old data moves the start of each along, so nothing is allocated per harvest once
+//! the buffers have grown to fit the retention.  Once the retention is known, harvests that fall
+//! out of it are dropped as new ones are added, so the buffers don't grow past it between
+//! cleanings.  The buffers are left at that size, and only shrunk when the retention drops or
+//! when the buffers for CPU cores that are gone empty out.
+//!
+//! Values are kept as `f32`s, which is plenty for charts at half the size.  Missing values, like
+//! memory that wasn't harvested or a CPU core that wasn't there at the time, are stored as NaN
+//! and skipped when reading a series back.
+
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+use crate::app::data_farmer::{TimedData, TimedRange, Value};
+
+/// A series of values that can be read back for a chart.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Series {
+    Rx,
+    Tx,
+    Mem,
+    Swap,
+    Cpu(usize),
+}
+
+impl Series {
+    fn get_column(self) -> usize {
+        match self {
+            Series::Rx => 0,
+            Series::Tx => 1,
+            Series::Mem => 2,
+            Series::Swap => 3,
+            Series::Cpu(itx) => CPU_COLUMN + itx,
+        }
+    }
+}
+
+/// Where the load averages start, followed by the CPU cores.
+const LOAD_AVG_COLUMN: usize = 4;
+const CPU_COLUMN: usize = LOAD_AVG_COLUMN + 3;
+
+/// A point in a series: when it was harvested, its value, and the lowest and highest values if it
+/// is a downsampled bucket.
+pub type SeriesPoint = (Instant, Value, Option<(Value, Value)>);
+
+/// One ring buffer per series, all of the same length.
+#[derive(Debug, Default)]
+struct Columns {
+    len: usize,
+    columns: Vec<VecDeque<f32>>,
+    /// How many values were last added, which is less than the number of columns if CPU cores
+    /// have gone since.
+    width: usize,
+}
+
+impl Columns {
+    /// Adds `timed_data`, with CPU usage taken from `cpu_data`.
+    fn push(&mut self, timed_data: &TimedData, cpu_data: impl Iterator<Item = Value>) {
+        let values = [
+            timed_data.rx_data as f32,
+            timed_data.tx_data as f32,
+            timed_data.mem_data.map_or(f32::NAN, |mem| mem as f32),
+            timed_data.swap_data.map_or(f32::NAN, |swap| swap as f32),
+        ];
+
+        self.push_values(
+            values
+                .iter()
+                .chain(&timed_data.load_avg_data)
+                .copied()
+                .chain(cpu_data.map(|cpu| cpu as f32)),
+        );
+    }
+
+    /// Adds a value to each column, in order.
+    fn push_values(&mut self, mut values: impl Iterator<Item = f32>) {
+        let mut value_count = 0;
+        for (column, value) in self.columns.iter_mut().zip(&mut values) {
+            column.push_back(value);
+            value_count += 1;
+        }
+        // More CPU cores than before, so there was nothing for them until now.
+        for value in values {
+            let mut column = std::iter::repeat_n(f32::NAN, self.len).collect::<VecDeque<_>>();
+            column.push_back(value);
+            self.columns.push(column);
+            value_count += 1;
+        }
+        for column in &mut self.columns[value_count..] {
+            column.push_back(f32::NAN);
+        }
+        self.len += 1;
+        self.width = value_count;
+    }
+
+    fn pop_front(&mut self, count: usize) {
+        for column in &mut self.columns {
+            column.drain(..count);
+        }
+        self.len -= count;
+    }
+
+    /// Drops the columns of CPU cores that are gone once they have nothing left in them, and if
+    /// `shrink_to_fit`, frees the space the rest no longer need.
+    fn shrink(&mut self, shrink_to_fit: bool) {
+        while self.columns.len() > self.width
+            && self
+                .columns
+                .last()
+                .is_some_and(|column| column.iter().all(|value| value.is_nan()))
+        {
+            self.columns.pop();
+        }
+        if shrink_to_fit {
+            for column in &mut self.columns {
+                column.shrink_to_fit();
+            }
+        }
+    }
+
+    /// Every value in a column, or NaN for each if there is no such column yet.
+    fn get_column(&self, column: usize) -> impl Iterator<Item = Value> + '_ {
+        let values = self.columns.get(column);
+        let missing_count = if values.is_some() { 0 } else { self.len };
+        values
+            .into_iter()
+            .flatten()
+            .map(|value| Value::from(*value))
+            .chain(std::iter::repeat_n(Value::NAN, missing_count))
+    }
+
+    /// A column's values as two slices, like [`VecDeque::as_slices`], or nothing if there is no
+    /// such column yet.
+    fn get_slices(&self, column: usize) -> (&[f32], &[f32]) {
+        self.columns
+            .get(column)
+            .map_or((&[], &[]), |column| column.as_slices())
+    }
+
+    fn get_value(&self, column: usize, index: usize) -> f32 {
+        self.columns
+            .get(column)
+            .and_then(|column| column.get(index))
+            .copied()
+            .unwrap_or(f32::NAN)
+    }
+
+    fn get_row(&self, index: usize) -> TimedData {
+        let value = |column| Value::from(self.get_value(column, index));
+        let mut cpu_data = (CPU_COLUMN..self.columns.len())
+            .map(value)
+            .collect::<Vec<_>>();
+        while cpu_data.last().is_some_and(|cpu| cpu.is_nan()) {
+            cpu_data.pop();
+        }
+
+        TimedData {
+            rx_data: value(0),
+            tx_data: value(1),
+            cpu_data,
+            load_avg_data: [
+                self.get_value(LOAD_AVG_COLUMN, index),
+                self.get_value(LOAD_AVG_COLUMN + 1, index),
+                self.get_value(LOAD_AVG_COLUMN + 2, index),
+            ],
+            mem_data: Some(value(2)).filter(|mem| !mem.is_nan()),
+            swap_data: Some(value(3)).filter(|swap| !swap.is_nan()),
+            range: None,
+        }
+    }
+}
+
+/// A harvest read straight from the columns it is stored in, without copying it out.
+pub struct HarvestRow<'a> {
+    columns: &'a Columns,
+    index: usize,
+}
+
+impl HarvestRow<'_> {
+    /// A series' value, or NaN if it is missing.
+    pub fn get(&self, series: Series) -> f32 {
+        self.columns.get_value(series.get_column(), self.index)
+    }
+
+    pub fn load_avg(&self) -> [f32; 3] {
+        [
+            self.columns.get_value(LOAD_AVG_COLUMN, self.index),
+            self.columns.get_value(LOAD_AVG_COLUMN + 1, self.index),
+            self.columns.get_value(LOAD_AVG_COLUMN + 2, self.index),
+        ]
+    }
+
+    /// How many CPU cores there were, not counting any missing at the end.
+    pub fn cpu_count(&self) -> usize {
+        (CPU_COLUMN..self.columns.columns.len())
+            .rev()
+            .find(|column| !self.columns.get_value(*column, self.index).is_nan())
+            .map_or(0, |column| column + 1 - CPU_COLUMN)
+    }
+}
+
+/// Harvests and downsampled buckets for the charts, oldest first.  Buckets are always older than
+/// harvests, as only the oldest harvests are downsampled.
+#[derive(Debug, Default)]
+pub struct TimeSeriesStore {
+    bucket_instants: VecDeque<Instant>,
+    bucket_averages: Columns,
+    bucket_mins: Columns,
+    bucket_maxes: Columns,
+    harvest_instants: VecDeque<Instant>,
+    harvests: Columns,
+    /// How long data was kept for when last shrunk, after which harvests are dropped as new
+    /// ones are added.
+    max_age: Option<Duration>,
+}
+
+impl TimeSeriesStore {
+    pub fn len(&self) -> usize {
+        self.bucket_instants.len() + self.harvest_instants.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn first_instant(&self) -> Option<Instant> {
+        self.bucket_instants
+            .front()
+            .or_else(|| self.harvest_instants.front())
+            .copied()
+    }
+
+    /// Adds to the end.  A downsampled bucket can only be added before any harvests, so one added
+    /// after is kept as just its averages.
+    pub fn push(&mut self, instant: Instant, timed_data: &TimedData) {
+        match &timed_data.range {
+            Some(range) if self.harvest_instants.is_empty() => {
+                self.bucket_instants.push_back(instant);
+                self.bucket_averages
+                    .push(timed_data, timed_data.cpu_data.iter().copied());
+                self.bucket_mins
+                    .push(&range.min, range.min.cpu_data.iter().copied());
+                self.bucket_maxes
+                    .push(&range.max, range.max.cpu_data.iter().copied());
+            }
+            _ => self.push_harvest(instant, timed_data, timed_data.cpu_data.iter().copied()),
+        }
+    }
+
+    /// Adds a harvest to the end, with CPU usage taken from `cpu_data` rather than `timed_data`
+    /// so it doesn't have to be collected first.  Anything that has fallen out of the retention
+    /// is dropped.
+    pub fn push_harvest(
+        &mut self, instant: Instant, timed_data: &TimedData, cpu_data: impl Iterator<Item = Value>,
+    ) {
+        if let Some(max_age) = self.max_age {
+            let is_old =
+                |old_instant: &Instant| instant.saturating_duration_since(*old_instant) > max_age;
+            if self.bucket_instants.front().is_some_and(is_old)
+                || self.harvest_instants.front().is_some_and(is_old)
+            {
+                self.remove_older_than(instant, max_age);
+            }
+        }
+
+        self.harvest_instants.push_back(instant);
+        self.harvests.push(timed_data, cpu_data);
+    }
+
+    /// The latest harvest, or bucket if there are no harvests.
+    pub fn last(&self) -> Option<(Instant, TimedData)> {
+        self.len().checked_sub(1).map(|index| self.get_row(index))
+    }
+
+    /// The latest harvest, without copying it out of the store.
+    pub fn last_harvest(&self) -> Option<(Instant, HarvestRow<'_>)> {
+        let index = self.harvest_instants.len().checked_sub(1)?;
+        let row = HarvestRow {
+            columns: &self.harvests,
+            index,
+        };
+        Some((self.harvest_instants[index], row))
+    }
+
+    /// Everything stored, oldest first.
+    pub fn rows(&self) -> impl Iterator<Item = (Instant, TimedData)> + '_ {
+        (0..self.len()).map(move |index| self.get_row(index))
+    }
+
+    fn get_row(&self, index: usize) -> (Instant, TimedData) {
+        match index.checked_sub(self.bucket_instants.len()) {
+            Some(index) => (self.harvest_instants[index], self.harvests.get_row(index)),
+            None => {
+                let mut timed_data = self.bucket_averages.get_row(index);
+                timed_data.range = Some(Box::new(TimedRange {
+                    min: self.bucket_mins.get_row(index),
+                    max: self.bucket_maxes.get_row(index),
+                }));
+                (self.bucket_instants[index], timed_data)
+            }
+        }
+    }
+
+    /// When everything stored was harvested, oldest first.
+    pub fn instants(&self) -> impl Iterator<Item = Instant> + '_ {
+        self.bucket_instants
+            .iter()
+            .chain(&self.harvest_instants)
+            .copied()
+    }
+
+    pub fn bucket_count(&self) -> usize {
+        self.bucket_instants.len()
+    }
+
+    /// A series' values for the downsampled buckets, lined up with the start of
+    /// [`TimeSeriesStore::instants`], along with their lowest and highest values.  Missing values
+    /// are NaN.
+    pub fn bucket_values(
+        &self, series: Series,
+    ) -> impl Iterator<Item = (Value, (Value, Value))> + '_ {
+        let column = series.get_column();
+        self.bucket_averages.get_column(column).zip(
+            self.bucket_mins
+                .get_column(column)
+                .zip(self.bucket_maxes.get_column(column)),
+        )
+    }
+
+    /// A series' harvested values, lined up with [`TimeSeriesStore::instants`] after the
+    /// buckets, as two slices like [`VecDeque::as_slices`].  Missing values are NaN, and both
+    /// are empty if the series was never harvested.
+    pub fn harvest_values(&self, series: Series) -> (&[f32], &[f32]) {
+        self.harvests.get_slices(series.get_column())
+    }
+
+    /// A series' values, lined up with [`TimeSeriesStore::instants`], along with the lowest and
+    /// highest values for downsampled buckets.  Missing values are NaN.
+    pub fn values(
+        &self, series: Series,
+    ) -> impl Iterator<Item = (Value, Option<(Value, Value)>)> + '_ {
+        let buckets = self
+            .bucket_values(series)
+            .map(|(value, range)| (value, Some(range)));
+        let harvests = self
+            .harvests
+            .get_column(series.get_column())
+            .map(|value| (value, None));
+
+        buckets.chain(harvests)
+    }
+
+    /// Reads back a series, oldest first, skipping anything missing.
+    pub fn points(&self, series: Series) -> impl Iterator<Item = SeriesPoint> + '_ {
+        self.instants()
+            .zip(self.values(series))
+            .filter(|(_instant, (value, _range))| !value.is_nan())
+            .map(|(instant, (value, range))| (instant, value, range))
+    }
+
+    /// Drops anything more than `max_age` older than `current_instant`.
+    pub fn remove_older_than(&mut self, current_instant: Instant, max_age: Duration) {
+        let is_old =
+            |instant: &Instant| current_instant.saturating_duration_since(*instant) > max_age;
+
+        let old_buckets = self.bucket_instants.partition_point(is_old);
+        self.bucket_instants.drain(..old_buckets);
+        self.bucket_averages.pop_front(old_buckets);
+        self.bucket_mins.pop_front(old_buckets);
+        self.bucket_maxes.pop_front(old_buckets);
+
+        let old_harvests = self.harvest_instants.partition_point(is_old);
+        self.harvest_instants.drain(..old_harvests);
+        self.harvests.pop_front(old_harvests);
+    }
+
+    /// Frees space that is no longer needed now that data is kept for `max_age`.  Space is kept
+    /// for as much as `max_age` holds, so it is only freed if `max_age` is shorter than before,
+    /// or for CPU cores that are gone.
+    pub fn shrink(&mut self, max_age: Duration) {
+        let shrink_to_fit = self
+            .max_age
+            .is_some_and(|old_max_age| max_age < old_max_age);
+        self.max_age = Some(max_age);
+
+        if shrink_to_fit {
+            self.bucket_instants.shrink_to_fit();
+            self.harvest_instants.shrink_to_fit();
+        }
+        self.bucket_averages.shrink(shrink_to_fit);
+        self.bucket_mins.shrink(shrink_to_fit);
+        self.bucket_maxes.shrink(shrink_to_fit);
+        self.harvests.shrink(shrink_to_fit);
+    }
+
+    /// Merges harvests older than `cutoff` into buckets of `bucket_width`, each placed halfway
+    /// between its first and last harvest.  A bucket is only made once all of its harvests are
+    /// older than `cutoff`, so it isn't made again as more age.
+    pub fn downsample(&mut self, cutoff: Instant, bucket_width: Duration) {
+        while let Some(first_instant) = self.harvest_instants.front().copied() {
+            if first_instant + bucket_width > cutoff {
+                break;
+            }
+            let count = self
+                .harvest_instants
+                .partition_point(|instant| *instant < first_instant + bucket_width);
+            let last_instant = self.harvest_instants[count - 1];
+            let summaries = self
+                .harvests
+                .columns
+                .iter()
+                .map(|column| summarize(column.range(..count)))
+                .collect::<Vec<_>>();
+
+            self.bucket_instants
+                .push_back(first_instant + last_instant.duration_since(first_instant) / 2);
+            self.bucket_averages
+                .push_values(summaries.iter().map(|(average, _, _)| *average));
+            self.bucket_mins
+                .push_values(summaries.iter().map(|(_, min, _)| *min));
+            self.bucket_maxes
+                .push_values(summaries.iter().map(|(_, _, max)| *max));
+            self.harvest_instants.drain(..count);
+            self.harvests.pop_front(count);
+        }
+    }
+}
+
+/// The average, lowest, and highest of the values that aren't missing, or NaN if they all are.
+fn summarize<'a>(values: impl Iterator<Item = &'a f32>) -> (f32, f32, f32) {
+    let (mut count, mut sum, mut min, mut max) = (0, 0.0, f32::INFINITY, f32::NEG_INFINITY);
+    for value in values.filter(|value| !value.is_nan()) {
+        count += 1;
+        sum += Value::from(*value);
+        min = min.min(*value);
+        max = max.max(*value);
+    }
+
+    if count == 0 {
+        (f32::NAN, f32::NAN, f32::NAN)
+    } else {
+        ((sum / count as Value) as f32, min, max)
+    }
+}
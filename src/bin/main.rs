@@ -9,13 +9,15 @@ use bottom::{canvas, constants::*, data_conversion::*, options::*, *};
 use std::{
     boxed::Box,
     io::{stdout, Write},
+    net::TcpListener,
     panic,
+    path::Path,
     sync::{
         atomic::{AtomicBool, Ordering},
         mpsc, Arc, Condvar, Mutex,
     },
     thread,
-    time::Duration,
+    time::{Duration, Instant},
 };
 
 use anyhow::{Context, Result};
@@ -53,6 +55,111 @@ fn main() -> Result<()> {
         config_path,
     )?;
 
+    if let Some(ps_matches) = matches.subcommand_matches("ps") {
+        let num_matched =
+            headless::print_processes(&mut app, &get_ps_options(ps_matches, &config)?)?;
+        // Like pgrep, exit with 1 if nothing matched.
+        if num_matched == 0 {
+            std::process::exit(1);
+        }
+        return Ok(());
+    } else if matches.is_present("json") {
+        headless::print_json_lines(
+            &app.app_config_fields,
+            app.filters.clone(),
+            app.used_widgets.clone(),
+            get_iterations(&matches)?,
+        )?;
+        return Ok(());
+    } else if matches.is_present("snapshot") {
+        headless::print_snapshot(&mut app, get_snapshot_process_count(&matches)?)?;
+        return Ok(());
+    }
+
+    // Start serving metrics before touching the terminal, so any problem with the address is
+    // reported normally.
+    let metrics = match matches.value_of("serve_metrics") {
+        Some(address) => {
+            let listener = TcpListener::bind(address)
+                .with_context(|| format!("Unable to serve metrics at {}.", address))?;
+            let metrics = metrics::SharedMetrics::default();
+            let _metrics_thread = metrics::create_metrics_server_thread(listener, metrics.clone());
+            Some(metrics)
+        }
+        None => None,
+    };
+    let exporter = get_exporter(&config).context("Update 'export' in your config file.")?;
+    let recorder = match matches.value_of("record") {
+        Some(path) => Some(
+            replay::Recorder::new(Path::new(path))
+                .with_context(|| format!("Unable to record to {}.", path))?,
+        ),
+        None => None,
+    };
+    let collected_widgets = if metrics.is_some() || exporter.is_some() || recorder.is_some() {
+        app::layout_manager::UsedWidgets {
+            use_cpu: true,
+            use_mem: true,
+            use_net: true,
+            use_proc: true,
+            use_disk: true,
+            use_temp: true,
+            use_user: app.used_widgets.use_user,
+            use_battery: cfg!(feature = "battery"),
+        }
+    } else {
+        app.used_widgets.clone()
+    };
+
+    if matches.is_present("headless") {
+        if metrics.is_none() && exporter.is_none() && recorder.is_none() {
+            return Err(anyhow::anyhow!(
+                "--headless needs --serve-metrics, --record, or an [export] table in the config file."
+            ));
+        }
+        headless::collect_without_tui(
+            &app.app_config_fields,
+            app.filters.clone(),
+            collected_widgets,
+            metrics,
+            exporter,
+            recorder,
+        );
+        return Ok(());
+    }
+
+    let recording = match matches.value_of("replay") {
+        Some(path) => Some(
+            replay::Recording::open(Path::new(path))
+                .with_context(|| format!("Unable to replay {}.", path))?,
+        ),
+        None => None,
+    };
+    let replay_speed = get_replay_speed(&matches)?;
+
+    // Replayed data isn't saved as history, as it didn't happen now.  Nor is it saved if
+    // another instance already is.
+    let history_path = app::history::History::default_path()
+        .filter(|_| app.app_config_fields.persist_history && recording.is_none());
+    let mut history = match history_path.map(app::history::History::open) {
+        Some(Ok(history)) => history,
+        Some(Err(err)) => {
+            app.collection_error = Some((err.to_string(), Instant::now()));
+            None
+        }
+        None => None,
+    };
+    let mut saved_history = match &mut history {
+        Some(history) => match history.load(app.app_config_fields.retention) {
+            Ok(saved_history) => Some(saved_history),
+            Err(err) => {
+                app.collection_error = Some((err.to_string(), Instant::now()));
+                None
+            }
+        },
+        None => None,
+    };
+
     // Create painter and set colours.
     let mut painter = canvas::Painter::init(
         widget_layout,
@@ -76,13 +183,17 @@ fn main() -> Result<()> {
         let lock = thread_termination_lock.clone();
         let cvar = thread_termination_cvar.clone();
         let cleaning_sender = sender.clone();
-        const OFFSET_WAIT_TIME: u64 = constants::STALE_MAX_MILLISECONDS + 60000;
+        // Clean a minute after data leaves the retention, or after it goes stale and should be
+        // downsampled if the retention is longer than that.
+        let clean_interval = Duration::from_millis(
+            app.app_config_fields
+                .retention
+                .min(constants::STALE_MAX_MILLISECONDS)
+                + 60000,
+        );
         thread::spawn(move || {
             loop {
-                let result = cvar.wait_timeout(
-                    lock.lock().unwrap(),
-                    Duration::from_millis(OFFSET_WAIT_TIME),
-                );
+                let result = cvar.wait_timeout(lock.lock().unwrap(), clean_interval);
                 if let Ok(result) = result {
                     if *(result.0) {
                         break;
@@ -98,15 +209,29 @@ fn main() -> Result<()> {
 
     // Event loop
     let (collection_thread_ctrl_sender, collection_thread_ctrl_receiver) = mpsc::channel();
-    let _collection_thread = create_collection_thread(
-        sender,
-        collection_thread_ctrl_receiver,
-        thread_termination_lock.clone(),
-        thread_termination_cvar.clone(),
-        &app.app_config_fields,
-        app.filters.clone(),
-        app.used_widgets.clone(),
-    );
+    let _collection_thread = match recording {
+        // Replayed data takes the place of anything newly collected.
+        Some(recording) => replay::create_replay_thread(
+            sender,
+            collection_thread_ctrl_receiver,
+            thread_termination_lock.clone(),
+            recording,
+            replay_speed,
+            app.app_config_fields.retention,
+        ),
+        None => create_collection_thread(
+            sender,
+            collection_thread_ctrl_receiver,
+            thread_termination_lock.clone(),
+            thread_termination_cvar.clone(),
+            &app.app_config_fields,
+            app.filters.clone(),
+            collected_widgets,
+            metrics,
+            exporter,
+            recorder,
+        ),
+    };
 
     // Set up up tui and crossterm
     let mut stdout_val = stdout();
@@ -144,6 +269,20 @@ fn main() -> Result<()> {
                 BottomEvent::Update(data) => {
                     app.data_collection.eat_data(data);
 
+                    if let Some(history) = &mut history {
+                        // Wait for the first harvest before filling in saved history, so it can be
+                        // checked against what is harvested now.
+                        if let Some(saved_history) = saved_history.take() {
+                            app.data_collection.prepend_history(saved_history);
+                        }
+                        if let Some((instant, row)) = app.data_collection.timed_data.last_harvest()
+                        {
+                            if let Err(err) = history.append_harvest(instant, &row) {
+                                app.collection_error = Some((err.to_string(), Instant::now()));
+                            }
+                        }
+                    }
+
                     // This thing is required as otherwise, some widgets can't draw correctly w/o
                     // some data (or they need to be re-drawn).
                     if first_run {
@@ -179,12 +318,12 @@ fn main() -> Result<()> {
 
                         // Disk
                         if app.used_widgets.use_disk {
-                            app.canvas_data.disk_data = convert_disk_row(&app.data_collection);
+                            update_all_disk_lists(&mut app);
                         }
 
                         // Temperatures
                         if app.used_widgets.use_temp {
-                            app.canvas_data.temp_sensor_data = convert_temp_row(&app);
+                            update_all_temp_lists(&mut app);
                         }
 
                         // Memory
@@ -216,6 +355,11 @@ fn main() -> Result<()> {
                             update_all_process_lists(&mut app);
                         }
 
+                        // Users
+                        if app.used_widgets.use_user {
+                            update_all_user_lists(&mut app);
+                        }
+
                         // Battery
                         #[cfg(feature = "battery")]
                         {
@@ -228,7 +372,21 @@ fn main() -> Result<()> {
                 }
                 BottomEvent::Clean => {
                     app.data_collection
-                        .clean_data(constants::STALE_MAX_MILLISECONDS);
+                        .clean_data(app.app_config_fields.retention);
+                    if let Some(history) = &mut history {
+                        if let Err(err) = history.rewrite(app.data_collection.timed_data.rows()) {
+                            app.collection_error = Some((err.to_string(), Instant::now()));
+                        }
+                    }
+                }
+                BottomEvent::CollectionError(collection_error) => {
+                    app.collection_error = Some((collection_error, Instant::now()));
+                }
+                BottomEvent::ReplayStatus(replay_status) => {
+                    app.replay_status = Some(replay_status);
+                }
+                BottomEvent::ReplaySeek => {
+                    app.data_collection.reset();
                 }
             }
         }
@@ -23,7 +23,10 @@ use crate::{
         App,
     },
     constants::*,
-    data_conversion::{ConvertedBatteryData, ConvertedCpuData, ConvertedProcessData},
+    data_conversion::{
+        stringify_replay_status, ConvertedBatteryData, ConvertedCpuData, ConvertedProcessData,
+        ConvertedUserData,
+    },
     options::Config,
     utils::error,
     utils::error::BottomError,
@@ -47,8 +50,9 @@ pub struct DisplayableData {
     pub total_tx_display: String,
     pub network_data_rx: Vec<Point>,
     pub network_data_tx: Vec<Point>,
-    pub disk_data: Vec<Vec<String>>,
-    pub temp_sensor_data: Vec<Vec<String>>,
+    pub disk_data_map: HashMap<u64, Vec<Vec<String>>>, // Key is the widget ID, as each widget is filtered separately
+    pub temp_sensor_data_map: HashMap<u64, Vec<Vec<String>>>, // Key is the widget ID
+    pub user_data_map: HashMap<u64, Vec<ConvertedUserData>>, // Key is the widget ID
     pub single_process_data: HashMap<Pid, ConvertedProcessData>, // Contains single process data, key is PID
     pub finalized_process_data_map: HashMap<u64, Vec<ConvertedProcessData>>, // What's actually displayed, key is the widget ID.
     pub stringified_process_data_map: HashMap<u64, Vec<(Vec<(String, Option<String>)>, bool)>>, // Represents the row and whether it is disabled, key is the widget ID
@@ -285,12 +289,39 @@ impl Painter {
     // FIXME: [CONFIG] write this, should call painter init and any changed colour functions...
     pub fn update_painter_colours(&mut self) {}
 
-    fn draw_frozen_indicator<B: Backend>(&self, f: &mut Frame<'_, B>, draw_loc: Rect) {
-        f.render_widget(
-            Paragraph::new(Span::styled(
+    /// Draws a line at the bottom for anything that needs attention but doesn't belong to a
+    /// widget, such as being frozen or failing to export.
+    fn draw_status_indicator<B: Backend>(
+        &self, f: &mut Frame<'_, B>, app_state: &app::App, draw_loc: Rect,
+    ) {
+        let mut spans = Vec::new();
+        if let Some(replay_status) = &app_state.replay_status {
+            spans.push(Span::styled(
+                stringify_replay_status(replay_status),
+                self.colours.currently_selected_text_style,
+            ));
+        }
+        if app_state.is_frozen {
+            if !spans.is_empty() {
+                spans.push(Span::raw("  "));
+            }
+            spans.push(Span::styled(
                 "Frozen, press 'f' to unfreeze",
                 self.colours.currently_selected_text_style,
-            )),
+            ));
+        }
+        if let Some(collection_error) = app_state.get_recent_collection_error() {
+            if !spans.is_empty() {
+                spans.push(Span::raw("  "));
+            }
+            spans.push(Span::styled(
+                collection_error.to_string(),
+                self.colours.invalid_query_style,
+            ));
+        }
+
+        f.render_widget(
+            Paragraph::new(Spans::from(spans)),
             Layout::default()
                 .horizontal_margin(1)
                 .constraints([Constraint::Length(1)])
@@ -304,7 +335,10 @@ impl Painter {
         use BottomWidgetType::*;
 
         terminal.draw(|f| {
-            let (terminal_size, frozen_draw_loc) = if app_state.is_frozen {
+            let (terminal_size, status_draw_loc) = if app_state.is_frozen
+                || app_state.replay_status.is_some()
+                || app_state.get_recent_collection_error().is_some()
+            {
                 let split_loc = Layout::default()
                     .constraints([Constraint::Min(0), Constraint::Length(1)])
                     .split(f.size());
@@ -340,8 +374,22 @@ impl Painter {
 
                 // Reset column headers for sorting in process widget...
                 for proc_widget in app_state.proc_state.widget_states.values_mut() {
-                    proc_widget.columns.column_header_y_loc = None;
-                    proc_widget.columns.column_header_x_locs = None;
+                    proc_widget.table_width_state.column_header_y_loc = None;
+                    proc_widget.table_width_state.column_header_x_locs = None;
+                }
+
+                // ...and the column headers for resizing in the disk, temperature and user widgets.
+                for disk_widget in app_state.disk_state.widget_states.values_mut() {
+                    disk_widget.table_width_state.column_header_y_loc = None;
+                    disk_widget.table_width_state.column_header_x_locs = None;
+                }
+                for temp_widget in app_state.temp_state.widget_states.values_mut() {
+                    temp_widget.table_width_state.column_header_y_loc = None;
+                    temp_widget.table_width_state.column_header_x_locs = None;
+                }
+                for user_widget in app_state.user_state.widget_states.values_mut() {
+                    user_widget.table_width_state.column_header_y_loc = None;
+                    user_widget.table_width_state.column_header_x_locs = None;
                 }
             }
 
@@ -376,6 +424,93 @@ impl Painter {
                     .split(vertical_dialog_chunk[1]);
 
                 self.draw_help_dialog(f, app_state, middle_dialog_chunk[1]);
+            } else if app_state.saved_query_dialog_state.is_showing_saved_queries {
+                let num_queries = app_state.config.get_saved_queries().count().max(1) as u16;
+                let text_height = std::cmp::min(num_queries + 2, terminal_height);
+                let text_width = if terminal_width < 100 {
+                    terminal_width * 90 / 100
+                } else {
+                    terminal_width * 50 / 100
+                };
+
+                let vertical_bordering = terminal_height.saturating_sub(text_height) / 2;
+                let vertical_dialog_chunk = Layout::default()
+                    .direction(Direction::Vertical)
+                    .constraints([
+                        Constraint::Length(vertical_bordering),
+                        Constraint::Length(text_height),
+                        Constraint::Length(vertical_bordering),
+                    ])
+                    .split(terminal_size);
+
+                let horizontal_bordering = terminal_width.saturating_sub(text_width) / 2;
+                let middle_dialog_chunk = Layout::default()
+                    .direction(Direction::Horizontal)
+                    .constraints([
+                        Constraint::Length(horizontal_bordering),
+                        Constraint::Length(text_width),
+                        Constraint::Length(horizontal_bordering),
+                    ])
+                    .split(vertical_dialog_chunk[1]);
+
+                self.draw_saved_query_dialog(f, app_state, middle_dialog_chunk[1]);
+            } else if app_state.goto_pid_dialog_state.is_showing_goto_pid {
+                let text_height = std::cmp::min(4, terminal_height);
+                let text_width = std::cmp::min(60, terminal_width);
+
+                let vertical_bordering = terminal_height.saturating_sub(text_height) / 2;
+                let vertical_dialog_chunk = Layout::default()
+                    .direction(Direction::Vertical)
+                    .constraints([
+                        Constraint::Length(vertical_bordering),
+                        Constraint::Length(text_height),
+                        Constraint::Length(vertical_bordering),
+                    ])
+                    .split(terminal_size);
+
+                let horizontal_bordering = terminal_width.saturating_sub(text_width) / 2;
+                let middle_dialog_chunk = Layout::default()
+                    .direction(Direction::Horizontal)
+                    .constraints([
+                        Constraint::Length(horizontal_bordering),
+                        Constraint::Length(text_width),
+                        Constraint::Length(horizontal_bordering),
+                    ])
+                    .split(vertical_dialog_chunk[1]);
+
+                self.draw_goto_pid_dialog(f, app_state, middle_dialog_chunk[1]);
+            } else if app_state
+                .exited_process_dialog_state
+                .is_showing_exited_processes
+            {
+                let text_height = terminal_height * 60 / 100;
+                let text_width = if terminal_width < 100 {
+                    terminal_width * 90 / 100
+                } else {
+                    terminal_width * 60 / 100
+                };
+
+                let vertical_bordering = terminal_height.saturating_sub(text_height) / 2;
+                let vertical_dialog_chunk = Layout::default()
+                    .direction(Direction::Vertical)
+                    .constraints([
+                        Constraint::Length(vertical_bordering),
+                        Constraint::Length(text_height),
+                        Constraint::Length(vertical_bordering),
+                    ])
+                    .split(terminal_size);
+
+                let horizontal_bordering = terminal_width.saturating_sub(text_width) / 2;
+                let middle_dialog_chunk = Layout::default()
+                    .direction(Direction::Horizontal)
+                    .constraints([
+                        Constraint::Length(horizontal_bordering),
+                        Constraint::Length(text_width),
+                        Constraint::Length(horizontal_bordering),
+                    ])
+                    .split(vertical_dialog_chunk[1]);
+
+                self.draw_exited_process_dialog(f, app_state, middle_dialog_chunk[1]);
             } else if app_state.delete_dialog_state.is_showing_dd {
                 // TODO: This needs the paragraph wrap feature from tui-rs to be pushed to complete... but for now it's pretty close!
                 // The main problem right now is that I cannot properly calculate the height offset since
@@ -454,8 +589,8 @@ impl Painter {
                 app_state.delete_dialog_state.is_showing_dd =
                     self.draw_dd_dialog(f, dd_text, app_state, middle_dialog_chunk[1]);
             } else if app_state.is_expanded {
-                if let Some(frozen_draw_loc) = frozen_draw_loc {
-                    self.draw_frozen_indicator(f, frozen_draw_loc);
+                if let Some(status_draw_loc) = status_draw_loc {
+                    self.draw_status_indicator(f, app_state, status_draw_loc);
                 }
 
                 let rect = Layout::default()
@@ -483,6 +618,13 @@ impl Painter {
                         true,
                         app_state.current_widget.widget_id,
                     ),
+                    User => self.draw_user_table(
+                        f,
+                        app_state,
+                        rect[0],
+                        true,
+                        app_state.current_widget.widget_id,
+                    ),
                     Temp => self.draw_temp_table(
                         f,
                         app_state,
@@ -526,8 +668,8 @@ impl Painter {
             } else if app_state.app_config_fields.use_basic_mode {
                 // Basic mode.  This basically removes all graphs but otherwise
                 // the same info.
-                if let Some(frozen_draw_loc) = frozen_draw_loc {
-                    self.draw_frozen_indicator(f, frozen_draw_loc);
+                if let Some(status_draw_loc) = status_draw_loc {
+                    self.draw_status_indicator(f, app_state, status_draw_loc);
                 }
 
                 let actual_cpu_data_len = app_state.canvas_data.cpu_data.len().saturating_sub(1);
@@ -605,14 +747,14 @@ impl Painter {
                 }
             } else {
                 // Draws using the passed in (or default) layout.
-                if let Some(frozen_draw_loc) = frozen_draw_loc {
-                    self.draw_frozen_indicator(f, frozen_draw_loc);
+                if let Some(status_draw_loc) = status_draw_loc {
+                    self.draw_status_indicator(f, app_state, status_draw_loc);
                 }
 
                 if self.derived_widget_draw_locs.is_empty() || app_state.is_force_redraw {
                     let draw_locs = Layout::default()
                         .margin(0)
-                        .constraints(self.row_constraints.as_ref())
+                        .constraints(self.row_constraints.as_slice())
                         .direction(Direction::Vertical)
                         .split(terminal_size);
 
@@ -633,7 +775,7 @@ impl Painter {
                         )| {
                             izip!(
                                 Layout::default()
-                                    .constraints(col_constraint.as_ref())
+                                    .constraints(col_constraint.as_slice())
                                     .direction(Direction::Horizontal)
                                     .split(draw_loc)
                                     .into_iter(),
@@ -644,7 +786,7 @@ impl Painter {
                             .map(|(split_loc, constraint, col_constraint_vec, col_rows)| {
                                 izip!(
                                     Layout::default()
-                                        .constraints(constraint.as_ref())
+                                        .constraints(constraint.as_slice())
                                         .direction(Direction::Vertical)
                                         .split(split_loc)
                                         .into_iter(),
@@ -654,7 +796,7 @@ impl Painter {
                                 .map(|(draw_loc, col_row_constraint_vec, widgets)| {
                                     // Note that col_row_constraint_vec CONTAINS the widget constraints
                                     let widget_draw_locs = Layout::default()
-                                        .constraints(col_row_constraint_vec.as_ref())
+                                        .constraints(col_row_constraint_vec.as_slice())
                                         .direction(Direction::Horizontal)
                                         .split(draw_loc);
 
@@ -725,6 +867,9 @@ impl Painter {
                 Disk => {
                     self.draw_disk_table(f, app_state, *widget_draw_loc, true, widget.widget_id)
                 }
+                User => {
+                    self.draw_user_table(f, app_state, *widget_draw_loc, true, widget.widget_id)
+                }
                 Proc => self.draw_process_features(
                     f,
                     app_state,
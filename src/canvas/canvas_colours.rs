@@ -28,6 +28,7 @@ pub struct CanvasColours {
     pub low_battery_colour: Style,
     pub invalid_query_style: Style,
     pub disabled_text_style: Style,
+    pub new_process_style: Style,
 }
 
 impl Default for CanvasColours {
@@ -71,6 +72,7 @@ impl Default for CanvasColours {
             low_battery_colour: Style::default().fg(Color::Red),
             invalid_query_style: Style::default().fg(tui::style::Color::Red),
             disabled_text_style: Style::default().fg(Color::DarkGray),
+            new_process_style: Style::default().fg(Color::Green),
         }
     }
 }
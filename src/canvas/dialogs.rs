@@ -1,5 +1,11 @@
 pub mod dd_dialog;
+pub mod exited_process_dialog;
+pub mod goto_pid_dialog;
 pub mod help_dialog;
+pub mod saved_query_dialog;
 
 pub use dd_dialog::KillDialog;
+pub use exited_process_dialog::ExitedProcessDialog;
+pub use goto_pid_dialog::GotoPidDialog;
 pub use help_dialog::HelpDialog;
+pub use saved_query_dialog::SavedQueryDialog;
@@ -11,6 +11,7 @@ use tui::{
 use crate::{
     app::{App, KillSignal},
     canvas::Painter,
+    data_conversion::ProcessGroupingType,
 };
 
 const DD_BASE: &str = " Confirm Kill Process ── Esc to close ";
@@ -42,16 +43,23 @@ impl KillDialog for Painter {
                 return Some(Text::from(vec![
                     Spans::from(""),
                     if app_state.is_grouped(app_state.current_widget.widget_id) {
+                        let group_description =
+                            match app_state.get_grouping_type(app_state.current_widget.widget_id) {
+                                ProcessGroupingType::Name => "with the name",
+                                _ => "in the group",
+                            };
+
                         if to_kill_processes.1.len() != 1 {
                             Spans::from(format!(
-                                "Kill {} processes with the name \"{}\"?  Press ENTER to confirm.",
+                                "Kill {} processes {} \"{}\"?  Press ENTER to confirm.",
                                 to_kill_processes.1.len(),
+                                group_description,
                                 to_kill_processes.0
                             ))
                         } else {
                             Spans::from(format!(
-                                "Kill 1 process with the name \"{}\"?  Press ENTER to confirm.",
-                                to_kill_processes.0
+                                "Kill 1 process {} \"{}\"?  Press ENTER to confirm.",
+                                group_description, to_kill_processes.0
                             ))
                         }
                     } else {
@@ -382,7 +390,7 @@ impl KillDialog for Painter {
                     } else {
                         vec![Constraint::Min(3), Constraint::Length(btn_height)]
                     }
-                    .as_ref(),
+                    .as_slice(),
                 )
                 .split(draw_loc);
 
@@ -0,0 +1,88 @@
+use crate::{app::App, canvas::Painter, data_conversion::stringify_exited_process};
+use tui::{
+    backend::Backend,
+    layout::{Constraint, Rect},
+    terminal::Frame,
+    text::{Span, Spans},
+    widgets::{Block, Borders, Paragraph, Row, Table},
+};
+
+const EXITED_PROCESS_BASE: &str = " Recently Exited ── Esc to close ";
+const EXITED_PROCESS_HEADERS: [&str; 6] =
+    ["Name", "PID", "Lifetime", "Peak CPU%", "Peak Mem", "Exited"];
+
+pub trait ExitedProcessDialog {
+    fn draw_exited_process_dialog<B: Backend>(
+        &self, f: &mut Frame<'_, B>, app_state: &mut App, draw_loc: Rect,
+    );
+}
+
+impl ExitedProcessDialog for Painter {
+    fn draw_exited_process_dialog<B: Backend>(
+        &self, f: &mut Frame<'_, B>, app_state: &mut App, draw_loc: Rect,
+    ) {
+        let exited_process_title = Spans::from(vec![
+            Span::styled(" Recently Exited ", self.colours.widget_title_style),
+            Span::styled(
+                format!(
+                    "─{}─ Esc to close ",
+                    "─".repeat(
+                        usize::from(draw_loc.width)
+                            .saturating_sub(EXITED_PROCESS_BASE.chars().count() + 2)
+                    )
+                ),
+                self.colours.border_style,
+            ),
+        ]);
+
+        let block = Block::default()
+            .title(exited_process_title)
+            .style(self.colours.border_style)
+            .borders(Borders::ALL)
+            .border_style(self.colours.border_style);
+
+        let exited_processes = &app_state.data_collection.exited_processes;
+        if exited_processes.is_empty() {
+            f.render_widget(
+                Paragraph::new(Span::styled(
+                    "No processes have exited yet",
+                    self.colours.disabled_text_style,
+                ))
+                .block(block),
+                draw_loc,
+            );
+            return;
+        }
+
+        // Show how long ago processes exited relative to the frozen time if frozen.
+        let current_instant = app_state
+            .data_collection
+            .frozen_instant
+            .filter(|_| app_state.is_frozen)
+            .unwrap_or(app_state.data_collection.current_instant);
+        let rows = exited_processes.iter().map(|exited_process| {
+            Row::new(stringify_exited_process(exited_process, current_instant))
+        });
+
+        f.render_stateful_widget(
+            Table::new(rows)
+                .block(block)
+                .header(
+                    Row::new(EXITED_PROCESS_HEADERS.iter().copied())
+                        .style(self.colours.table_header_style),
+                )
+                .highlight_style(self.colours.currently_selected_text_style)
+                .style(self.colours.text_style)
+                .widths(&[
+                    Constraint::Min(10),
+                    Constraint::Length(8),
+                    Constraint::Length(9),
+                    Constraint::Length(10),
+                    Constraint::Length(9),
+                    Constraint::Length(10),
+                ]),
+            draw_loc,
+            &mut app_state.exited_process_dialog_state.table_state,
+        );
+    }
+}
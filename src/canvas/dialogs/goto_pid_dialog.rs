@@ -0,0 +1,66 @@
+use crate::{app::App, canvas::Painter};
+use tui::{
+    backend::Backend,
+    layout::Rect,
+    terminal::Frame,
+    text::{Span, Spans},
+    widgets::{Block, Borders, Paragraph},
+};
+
+const GOTO_PID_BASE: &str = " Go to PID ── Esc to close ";
+
+pub trait GotoPidDialog {
+    fn draw_goto_pid_dialog<B: Backend>(
+        &self, f: &mut Frame<'_, B>, app_state: &mut App, draw_loc: Rect,
+    );
+}
+
+impl GotoPidDialog for Painter {
+    fn draw_goto_pid_dialog<B: Backend>(
+        &self, f: &mut Frame<'_, B>, app_state: &mut App, draw_loc: Rect,
+    ) {
+        let goto_pid_title = Spans::from(vec![
+            Span::styled(" Go to PID ", self.colours.widget_title_style),
+            Span::styled(
+                format!(
+                    "─{}─ Esc to close ",
+                    "─".repeat(
+                        usize::from(draw_loc.width)
+                            .saturating_sub(GOTO_PID_BASE.chars().count() + 2)
+                    )
+                ),
+                self.colours.border_style,
+            ),
+        ]);
+
+        let dialog_state = &app_state.goto_pid_dialog_state;
+        let text = vec![
+            Spans::from(vec![
+                Span::styled("PID: ", self.colours.table_header_style),
+                Span::styled(dialog_state.input.as_str(), self.colours.text_style),
+                Span::styled(" ", self.colours.currently_selected_text_style),
+            ]),
+            match &dialog_state.error {
+                Some(error) => Spans::from(Span::styled(
+                    error.as_str(),
+                    self.colours.invalid_query_style,
+                )),
+                None => Spans::from(Span::styled(
+                    "Enter to select it, clearing anything hiding it",
+                    self.colours.disabled_text_style,
+                )),
+            },
+        ];
+
+        f.render_widget(
+            Paragraph::new(text).block(
+                Block::default()
+                    .title(goto_pid_title)
+                    .style(self.colours.border_style)
+                    .borders(Borders::ALL)
+                    .border_style(self.colours.border_style),
+            ),
+            draw_loc,
+        );
+    }
+}
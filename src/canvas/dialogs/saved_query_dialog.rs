@@ -0,0 +1,80 @@
+use crate::{app::App, canvas::Painter};
+use tui::{
+    backend::Backend,
+    layout::Rect,
+    terminal::Frame,
+    text::{Span, Spans},
+    widgets::{Block, Borders, List, ListItem},
+};
+
+const SAVED_QUERY_BASE: &str = " Saved Queries ── Esc to close ";
+
+pub trait SavedQueryDialog {
+    fn draw_saved_query_dialog<B: Backend>(
+        &self, f: &mut Frame<'_, B>, app_state: &mut App, draw_loc: Rect,
+    );
+}
+
+impl SavedQueryDialog for Painter {
+    fn draw_saved_query_dialog<B: Backend>(
+        &self, f: &mut Frame<'_, B>, app_state: &mut App, draw_loc: Rect,
+    ) {
+        let saved_query_title = Spans::from(vec![
+            Span::styled(" Saved Queries ", self.colours.widget_title_style),
+            Span::styled(
+                format!(
+                    "─{}─ Esc to close ",
+                    "─".repeat(
+                        usize::from(draw_loc.width)
+                            .saturating_sub(SAVED_QUERY_BASE.chars().count() + 2)
+                    )
+                ),
+                self.colours.border_style,
+            ),
+        ]);
+
+        let name_width = app_state
+            .config
+            .get_saved_queries()
+            .map(|(name, _query)| name.chars().count())
+            .max()
+            .unwrap_or(0);
+
+        let items: Vec<ListItem<'_>> = app_state
+            .config
+            .get_saved_queries()
+            .map(|(name, query)| {
+                ListItem::new(Spans::from(vec![
+                    Span::styled(
+                        format!("{:width$}  ", name, width = name_width),
+                        self.colours.text_style,
+                    ),
+                    Span::styled(query.to_string(), self.colours.disabled_text_style),
+                ]))
+            })
+            .collect();
+
+        let items = if items.is_empty() {
+            vec![ListItem::new(Span::styled(
+                "No saved queries - add some under [queries] in the config file",
+                self.colours.disabled_text_style,
+            ))]
+        } else {
+            items
+        };
+
+        f.render_stateful_widget(
+            List::new(items)
+                .block(
+                    Block::default()
+                        .title(saved_query_title)
+                        .style(self.colours.border_style)
+                        .borders(Borders::ALL)
+                        .border_style(self.colours.border_style),
+                )
+                .highlight_style(self.colours.currently_selected_text_style),
+            draw_loc,
+            &mut app_state.saved_query_dialog_state.list_state,
+        );
+    }
+}
@@ -1,5 +1,9 @@
-use crate::app;
-use std::cmp::{max, min};
+use crate::{app, constants::STALE_MAX_MILLISECONDS, data_conversion::format_duration};
+use std::{
+    cmp::{max, min},
+    time::Duration,
+};
+use tui::layout::Rect;
 
 /// Return a (hard)-width vector for column widths.
 ///
@@ -22,6 +26,29 @@ pub fn get_column_widths(
     total_width: u16, hard_widths: &[Option<u16>], soft_widths_min: &[Option<u16>],
     soft_widths_max: &[Option<f64>], soft_widths_desired: &[Option<u16>], left_to_right: bool,
 ) -> Vec<u16> {
+    get_column_widths_with_overrides(
+        total_width,
+        hard_widths,
+        soft_widths_min,
+        soft_widths_max,
+        soft_widths_desired,
+        &vec![None; hard_widths.len()],
+        left_to_right,
+    )
+}
+
+/// The same as [`get_column_widths`], but with `width_overrides`, which are widths that the user
+/// has explicitly set.  Use `None` to not override a column.  Overridden columns take exactly that
+/// width (or whatever is left, if there isn't enough room), and never grow to fill extra space.
+pub fn get_column_widths_with_overrides(
+    total_width: u16, hard_widths: &[Option<u16>], soft_widths_min: &[Option<u16>],
+    soft_widths_max: &[Option<f64>], soft_widths_desired: &[Option<u16>],
+    width_overrides: &[Option<u16>], left_to_right: bool,
+) -> Vec<u16> {
+    debug_assert!(
+        hard_widths.len() == width_overrides.len(),
+        "hard width length != width override length!"
+    );
     debug_assert!(
         hard_widths.len() == soft_widths_min.len(),
         "hard width length != soft width min length!"
@@ -45,7 +72,17 @@ pub fn get_column_widths(
     };
 
     for itx in &range {
-        if let Some(Some(hard_width)) = hard_widths.get(*itx) {
+        if let Some(Some(width_override)) = width_overrides.get(*itx) {
+            // Overridden width...
+            let space_taken = min(*width_override, total_width_left);
+            if space_taken == 0 {
+                break;
+            }
+
+            column_widths[*itx] = space_taken;
+            total_width_left -= space_taken;
+            total_width_left = total_width_left.saturating_sub(1);
+        } else if let Some(Some(hard_width)) = hard_widths.get(*itx) {
             // Hard width...
             let space_taken = min(*hard_width, total_width_left);
 
@@ -88,15 +125,18 @@ pub fn get_column_widths(
         }
     }
 
-    // Redistribute remaining.
-    while total_width_left > 0 {
-        for itx in &range {
-            if column_widths[*itx] > 0 {
-                column_widths[*itx] += 1;
-                total_width_left -= 1;
-                if total_width_left == 0 {
-                    break;
-                }
+    // Redistribute remaining, skipping anything the user has explicitly sized.
+    let growable_columns = range
+        .iter()
+        .filter(|itx| column_widths[**itx] > 0 && width_overrides[**itx].is_none())
+        .cloned()
+        .collect::<Vec<_>>();
+    while total_width_left > 0 && !growable_columns.is_empty() {
+        for itx in &growable_columns {
+            column_widths[*itx] += 1;
+            total_width_left -= 1;
+            if total_width_left == 0 {
+                break;
             }
         }
     }
@@ -117,6 +157,28 @@ pub fn get_column_widths(
     filtered_column_widths
 }
 
+/// Returns the x bounds of each column in a table drawn at `draw_loc`.  The right bound of each
+/// column is the gap after it, and the last column is cut off by the right border.
+pub fn get_column_x_bounds(draw_loc: Rect, column_widths: &[u16]) -> Vec<(u16, u16)> {
+    let mut current_x_left = draw_loc.x + 1;
+    let max_x_right = draw_loc.x + draw_loc.width - 1;
+
+    let mut x_locs = vec![];
+    for width in column_widths {
+        let right_bound = current_x_left + width;
+
+        if right_bound < max_x_right {
+            x_locs.push((current_x_left, right_bound));
+            current_x_left = right_bound + 1;
+        } else {
+            x_locs.push((current_x_left, max_x_right));
+            break;
+        }
+    }
+
+    x_locs
+}
+
 /// FIXME: [command move] This is a greedy method of determining column widths.  This is reserved for columns where we are okay with
 /// shoving information as far right as required.
 // pub fn greedy_get_column_widths() -> Vec<u16> {
@@ -216,3 +278,13 @@ pub fn interpolate_points(point_one: &(f64, f64), point_two: &(f64, f64), time:
 
     (point_one.1 + (time - point_one.0) * slope).max(0.0)
 }
+
+/// Labels how far back a graph goes, in seconds up to [`STALE_MAX_MILLISECONDS`] and in larger
+/// units past that, like `2h 30m`.
+pub fn get_time_label(display_time: u64) -> String {
+    if display_time <= STALE_MAX_MILLISECONDS {
+        format!("{}s", display_time / 1000)
+    } else {
+        format_duration(Duration::from_millis(display_time))
+    }
+}
@@ -8,7 +8,9 @@ pub mod mem_graph;
 pub mod network_basic;
 pub mod network_graph;
 pub mod process_table;
+pub mod table_search;
 pub mod temp_table;
+pub mod user_table;
 
 pub use basic_table_arrows::BasicTableArrows;
 pub use battery_display::BatteryDisplayWidget;
@@ -20,4 +22,6 @@ pub use mem_graph::MemGraphWidget;
 pub use network_basic::NetworkBasicWidget;
 pub use network_graph::NetworkGraphWidget;
 pub use process_table::ProcessTableWidget;
+pub use table_search::TableSearchWidget;
 pub use temp_table::TempTableWidget;
+pub use user_table::UserTableWidget;
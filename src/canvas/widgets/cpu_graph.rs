@@ -4,7 +4,9 @@ use unicode_segmentation::UnicodeSegmentation;
 use crate::{
     app::{layout_manager::WidgetDirection, App},
     canvas::{
-        drawing_utils::{get_column_widths, get_start_position, interpolate_points},
+        drawing_utils::{
+            get_column_widths, get_start_position, get_time_label, interpolate_points,
+        },
         Painter,
     },
     constants::*,
@@ -139,7 +141,7 @@ impl CpuGraphWidget for Painter {
 
             let display_time_labels = vec![
                 Span::styled(
-                    format!("{}s", cpu_widget_state.current_display_time / 1000),
+                    get_time_label(cpu_widget_state.current_display_time),
                     self.colours.graph_style,
                 ),
                 Span::styled("0s".to_string(), self.colours.graph_style),
@@ -5,13 +5,16 @@ use tui::{
     terminal::Frame,
     text::Span,
     text::{Spans, Text},
-    widgets::{Block, Borders, Row, Table},
+    widgets::{Block, Borders, Cell, Row, Table},
 };
 
 use crate::{
     app,
     canvas::{
-        drawing_utils::{get_column_widths, get_start_position},
+        drawing_utils::{
+            get_column_widths_with_overrides, get_column_x_bounds, get_start_position,
+        },
+        widgets::TableSearchWidget,
         Painter,
     },
     constants::*,
@@ -41,6 +44,30 @@ impl DiskTableWidget for Painter {
     ) {
         let recalculate_column_widths = app_state.should_get_widget_bounds();
         if let Some(disk_widget_state) = app_state.disk_state.widget_states.get_mut(&widget_id) {
+            let is_on_widget = app_state.current_widget.widget_id == widget_id;
+            let draw_loc = if disk_widget_state.search_state.is_enabled {
+                let disk_chunk = Layout::default()
+                    .direction(Direction::Vertical)
+                    .constraints([
+                        Constraint::Min(0),
+                        Constraint::Length(self.get_table_search_height(draw_border)),
+                    ])
+                    .split(draw_loc);
+
+                self.draw_table_search(
+                    f,
+                    &mut disk_widget_state.search_state,
+                    disk_chunk[1],
+                    draw_border,
+                    is_on_widget,
+                    app_state.is_force_redraw,
+                );
+
+                disk_chunk[0]
+            } else {
+                draw_loc
+            };
+
             let table_gap = if draw_loc.height < TABLE_GAP_HEIGHT_LIMIT {
                 0
             } else {
@@ -55,7 +82,6 @@ impl DiskTableWidget for Painter {
                 disk_widget_state.scroll_state.current_scroll_position,
                 app_state.is_force_redraw,
             );
-            let is_on_widget = app_state.current_widget.widget_id == widget_id;
             let disk_table_state = &mut disk_widget_state.scroll_state.table_state;
             disk_table_state.select(Some(
                 disk_widget_state
@@ -63,10 +89,20 @@ impl DiskTableWidget for Painter {
                     .current_scroll_position
                     .saturating_sub(start_position),
             ));
-            let sliced_vec = &app_state.canvas_data.disk_data[start_position..];
+            let disk_data = app_state
+                .canvas_data
+                .disk_data_map
+                .get(&widget_id)
+                .map_or(&[][..], |data| data.as_slice());
+            let sliced_vec = &disk_data[start_position..];
 
             // Calculate widths
             let hard_widths = [None, None, Some(4), Some(6), Some(6), Some(7), Some(7)];
+            disk_widget_state.table_width_state.column_names = DISK_HEADERS
+                .iter()
+                .map(|header| header.to_string())
+                .collect();
+            let width_overrides = disk_widget_state.table_width_state.get_width_overrides();
             if recalculate_column_widths {
                 disk_widget_state.table_width_state.desired_column_widths = {
                     let mut column_widths = DISK_HEADERS_LENS.clone();
@@ -97,22 +133,24 @@ impl DiskTableWidget for Painter {
                     })
                     .collect::<Vec<_>>();
 
-                disk_widget_state.table_width_state.calculated_column_widths = get_column_widths(
-                    draw_loc.width,
-                    &hard_widths,
-                    &(DISK_HEADERS_LENS
-                        .iter()
-                        .map(|w| Some(*w))
-                        .collect::<Vec<_>>()),
-                    &[Some(0.2), Some(0.2), None, None, None, None, None],
-                    &(disk_widget_state
-                        .table_width_state
-                        .desired_column_widths
-                        .iter()
-                        .map(|w| Some(*w))
-                        .collect::<Vec<_>>()),
-                    true,
-                );
+                disk_widget_state.table_width_state.calculated_column_widths =
+                    get_column_widths_with_overrides(
+                        draw_loc.width,
+                        &hard_widths,
+                        &(DISK_HEADERS_LENS
+                            .iter()
+                            .map(|w| Some(*w))
+                            .collect::<Vec<_>>()),
+                        &[Some(0.2), Some(0.2), None, None, None, None, None],
+                        &(disk_widget_state
+                            .table_width_state
+                            .desired_column_widths
+                            .iter()
+                            .map(|w| Some(*w))
+                            .collect::<Vec<_>>()),
+                        &width_overrides,
+                        true,
+                    );
             }
 
             let dcw = &disk_widget_state.table_width_state.desired_column_widths;
@@ -121,7 +159,8 @@ impl DiskTableWidget for Painter {
                 sliced_vec.iter().map(|disk_row| {
                     let truncated_data = disk_row.iter().zip(&hard_widths).enumerate().map(
                         |(itx, (entry, width))| {
-                            if width.is_none() {
+                            let is_overridden = matches!(width_overrides.get(itx), Some(Some(_)));
+                            if width.is_none() || is_overridden {
                                 if let (Some(desired_col_width), Some(calculated_col_width)) =
                                     (dcw.get(itx), ccw.get(itx))
                                 {
@@ -168,7 +207,7 @@ impl DiskTableWidget for Painter {
                         .scroll_state
                         .current_scroll_position
                         .saturating_add(1),
-                    app_state.canvas_data.disk_data.len()
+                    disk_data.len()
                 );
 
                 if title_string.len() <= draw_loc.width as usize {
@@ -238,14 +277,24 @@ impl DiskTableWidget for Painter {
                 .direction(Direction::Horizontal)
                 .split(draw_loc)[0];
 
+            let selected_column = disk_widget_state.table_width_state.selected_column;
+
             // Draw!
             f.render_stateful_widget(
                 Table::new(disk_rows)
                     .block(disk_block)
                     .header(
-                        Row::new(DISK_HEADERS.to_vec())
-                            .style(self.colours.table_header_style)
-                            .bottom_margin(table_gap),
+                        Row::new(DISK_HEADERS.iter().enumerate().map(|(itx, header)| {
+                            // Highlight the header of the column being resized, if any.
+                            if is_on_widget && selected_column == Some(itx) {
+                                Cell::from(*header)
+                                    .style(self.colours.currently_selected_text_style)
+                            } else {
+                                Cell::from(*header)
+                            }
+                        }))
+                        .style(self.colours.table_header_style)
+                        .bottom_margin(table_gap),
                     )
                     .highlight_style(highlight_style)
                     .style(self.colours.text_style)
@@ -261,6 +310,27 @@ impl DiskTableWidget for Painter {
                 disk_table_state,
             );
 
+            // Check if we need to update columnar bounds...
+            if recalculate_column_widths
+                || disk_widget_state
+                    .table_width_state
+                    .column_header_x_locs
+                    .is_none()
+                || disk_widget_state
+                    .table_width_state
+                    .column_header_y_loc
+                    .is_none()
+            {
+                // y location is just the y location of the widget + border size (1 normally, 0 in basic)
+                disk_widget_state.table_width_state.column_header_y_loc =
+                    Some(draw_loc.y + if draw_border { 1 } else { 0 });
+                disk_widget_state.table_width_state.column_header_x_locs =
+                    Some(get_column_x_bounds(
+                        draw_loc,
+                        &disk_widget_state.table_width_state.calculated_column_widths,
+                    ));
+            }
+
             if app_state.should_get_widget_bounds() {
                 // Update draw loc in widget map
                 if let Some(widget) = app_state.widget_map.get_mut(&widget_id) {
@@ -1,6 +1,9 @@
 use crate::{
     app::App,
-    canvas::{drawing_utils::interpolate_points, Painter},
+    canvas::{
+        drawing_utils::{get_time_label, interpolate_points},
+        Painter,
+    },
     constants::*,
 };
 
@@ -33,7 +36,7 @@ impl MemGraphWidget for Painter {
 
             let display_time_labels = vec![
                 Span::styled(
-                    format!("{}s", mem_widget_state.current_display_time / 1000),
+                    get_time_label(mem_widget_state.current_display_time),
                     self.colours.graph_style,
                 ),
                 Span::styled("0s".to_string(), self.colours.graph_style),
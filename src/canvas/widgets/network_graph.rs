@@ -5,7 +5,7 @@ use unicode_segmentation::UnicodeSegmentation;
 use crate::{
     app::{App, AxisScaling},
     canvas::{
-        drawing_utils::{get_column_widths, interpolate_points},
+        drawing_utils::{get_column_widths, get_time_label, interpolate_points},
         Painter,
     },
     constants::*,
@@ -426,7 +426,7 @@ impl NetworkGraphWidget for Painter {
 
             let display_time_labels = vec![
                 Span::styled(
-                    format!("{}s", network_widget_state.current_display_time / 1000),
+                    get_time_label(network_widget_state.current_display_time),
                     self.colours.graph_style,
                 ),
                 Span::styled("0s".to_string(), self.colours.graph_style),
@@ -1,103 +1,84 @@
 use crate::{
-    app::App,
+    app::{data_harvester::processes::ProcessSorting, App, FollowTarget},
     canvas::{
-        drawing_utils::{get_column_widths, get_search_start_position, get_start_position},
+        drawing_utils::{
+            get_column_widths_with_overrides, get_column_x_bounds, get_search_start_position,
+            get_start_position,
+        },
         Painter,
     },
     constants::*,
 };
 
+use super::table_search::build_query;
+
 use tui::{
     backend::Backend,
     layout::{Alignment, Constraint, Direction, Layout, Rect},
     terminal::Frame,
     text::{Span, Spans, Text},
-    widgets::{Block, Borders, Paragraph, Row, Table},
+    widgets::{Block, Borders, Cell, Clear, List, ListItem, Paragraph, Row, Table},
 };
 
-use unicode_segmentation::{GraphemeIndices, UnicodeSegmentation};
+use std::cmp::min;
+use unicode_segmentation::UnicodeSegmentation;
 use unicode_width::UnicodeWidthStr;
 
-use once_cell::sync::Lazy;
-
-static PROCESS_HEADERS_HARD_WIDTH_NO_GROUP: Lazy<Vec<Option<u16>>> = Lazy::new(|| {
-    vec![
-        Some(7),
-        None,
-        Some(8),
-        Some(8),
-        Some(8),
-        Some(8),
-        Some(7),
-        Some(8),
-        #[cfg(target_family = "unix")]
-        None,
-        None,
-    ]
-});
-static PROCESS_HEADERS_HARD_WIDTH_GROUPED: Lazy<Vec<Option<u16>>> = Lazy::new(|| {
-    vec![
-        Some(7),
-        None,
-        Some(8),
-        Some(8),
-        Some(8),
-        Some(8),
-        Some(7),
-        Some(8),
-    ]
-});
-
-static PROCESS_HEADERS_SOFT_WIDTH_MAX_GROUPED_COMMAND: Lazy<Vec<Option<f64>>> =
-    Lazy::new(|| vec![None, Some(0.7), None, None, None, None, None, None]);
-static PROCESS_HEADERS_SOFT_WIDTH_MAX_GROUPED_ELSE: Lazy<Vec<Option<f64>>> =
-    Lazy::new(|| vec![None, Some(0.3), None, None, None, None, None, None]);
-
-static PROCESS_HEADERS_SOFT_WIDTH_MAX_NO_GROUP_COMMAND: Lazy<Vec<Option<f64>>> = Lazy::new(|| {
-    vec![
-        None,
-        Some(0.7),
-        None,
-        None,
-        None,
-        None,
-        None,
-        None,
-        #[cfg(target_family = "unix")]
-        Some(0.05),
-        Some(0.2),
-    ]
-});
-static PROCESS_HEADERS_SOFT_WIDTH_MAX_NO_GROUP_TREE: Lazy<Vec<Option<f64>>> = Lazy::new(|| {
-    vec![
-        None,
-        Some(0.5),
-        None,
-        None,
-        None,
-        None,
-        None,
-        None,
-        #[cfg(target_family = "unix")]
-        Some(0.05),
-        Some(0.2),
-    ]
-});
-static PROCESS_HEADERS_SOFT_WIDTH_MAX_NO_GROUP_ELSE: Lazy<Vec<Option<f64>>> = Lazy::new(|| {
-    vec![
-        None,
-        Some(0.3),
-        None,
-        None,
-        None,
-        None,
-        None,
-        None,
-        #[cfg(target_family = "unix")]
-        Some(0.05),
-        Some(0.2),
-    ]
-});
+/// Skips the first `offset` graphemes of `entry` and cuts it down to `width` graphemes, marking
+/// anything cut off on either side with an ellipsis.
+fn scroll_entry(entry: &str, offset: usize, width: usize) -> String {
+    let graphemes = UnicodeSegmentation::graphemes(entry, true).collect::<Vec<&str>>();
+    let start = min(offset, graphemes.len());
+
+    let mut visible = graphemes[start..].to_vec();
+    if start > 0 && !visible.is_empty() {
+        visible[0] = "…";
+    }
+    if visible.len() > width && width > 1 {
+        visible.truncate(width - 1);
+        visible.push("…");
+    }
+
+    visible.concat()
+}
+
+/// Returns the hard width of a process column, if it has one.
+fn get_column_hard_width(column: &ProcessSorting) -> Option<u16> {
+    match column {
+        ProcessSorting::Pid | ProcessSorting::Count => Some(7),
+        ProcessSorting::CpuPercent
+        | ProcessSorting::Mem
+        | ProcessSorting::MemPercent
+        | ProcessSorting::ReadPerSecond
+        | ProcessSorting::WritePerSecond
+        | ProcessSorting::TotalWrite => Some(8),
+        ProcessSorting::TotalRead => Some(7),
+        ProcessSorting::ProcessName
+        | ProcessSorting::Command
+        | ProcessSorting::User
+        | ProcessSorting::State => None,
+    }
+}
+
+/// Returns the maximum soft width of a process column as a fraction of the table, if it has one.
+fn get_column_soft_width_max(
+    column: &ProcessSorting, is_using_command: bool, is_tree_mode: bool,
+) -> Option<f64> {
+    match column {
+        ProcessSorting::ProcessName | ProcessSorting::Command => {
+            if is_using_command {
+                Some(0.7)
+            } else if is_tree_mode {
+                Some(0.5)
+            } else {
+                Some(0.3)
+            }
+        }
+        ProcessSorting::User => Some(0.05),
+        ProcessSorting::State => Some(0.2),
+        _ => None,
+    }
+}
 
 pub trait ProcessTableWidget {
     /// Draws and handles all process-related drawing.  Use this.
@@ -135,6 +116,14 @@ pub trait ProcessTableWidget {
         &self, f: &mut Frame<'_, B>, app_state: &mut App, draw_loc: Rect, draw_border: bool,
         widget_id: u64,
     );
+
+    /// Draws the search completion popup over the bottom of the process table.
+    /// - `widget_id` represents the widget ID of the process widget itself.
+    ///
+    /// This should not be directly called.
+    fn draw_search_completion<B: Backend>(
+        &self, f: &mut Frame<'_, B>, app_state: &mut App, draw_loc: Rect, widget_id: u64,
+    );
 }
 
 impl ProcessTableWidget for Painter {
@@ -146,6 +135,8 @@ impl ProcessTableWidget for Painter {
             let search_height = if draw_border { 5 } else { 3 };
             let is_sort_open = process_widget_state.is_sort_open;
             let header_len = process_widget_state.columns.longest_header_len;
+            let is_completion_showing = app_state.current_widget.widget_id == widget_id + 1
+                && process_widget_state.is_completion_showing();
 
             let mut proc_draw_loc = draw_loc;
             if process_widget_state.is_search_enabled() {
@@ -181,6 +172,10 @@ impl ProcessTableWidget for Painter {
             }
 
             self.draw_processes_table(f, app_state, proc_draw_loc, draw_border, widget_id);
+
+            if is_completion_showing {
+                self.draw_search_completion(f, app_state, proc_draw_loc, widget_id);
+            }
         }
     }
 
@@ -239,6 +234,50 @@ impl ProcessTableWidget for Painter {
                 " Processes ".to_string()
             };
 
+            let title_base = match proc_widget_state.grouping_type.get_description() {
+                Some(grouping_description) => {
+                    let title = format!("{}── Grouped {} ", title_base, grouping_description);
+                    if UnicodeSegmentation::graphemes(title.as_str(), true).count()
+                        <= draw_loc.width as usize
+                    {
+                        title
+                    } else {
+                        title_base
+                    }
+                }
+                None => title_base,
+            };
+
+            // Say what is being followed, and make it stand out if it's gone.
+            let follow_title = proc_widget_state.follow_state.as_ref().map(|follow_state| {
+                let target = match &follow_state.target {
+                    FollowTarget::Pid(pid) => format!("PID {}", pid),
+                    FollowTarget::Group(name) => name.clone(),
+                };
+                if follow_state.has_exited {
+                    (
+                        format!("── {} exited ", target),
+                        self.colours.invalid_query_style,
+                    )
+                } else {
+                    (
+                        format!("── Following {} ", target),
+                        self.colours.widget_title_style,
+                    )
+                }
+            });
+            let follow_title = follow_title.filter(|(follow_title, _)| {
+                UnicodeSegmentation::graphemes(
+                    format!("{}{}", title_base, follow_title).as_str(),
+                    true,
+                )
+                .count()
+                    <= draw_loc.width as usize
+            });
+            let follow_title_len = follow_title.as_ref().map_or(0, |(follow_title, _)| {
+                UnicodeSegmentation::graphemes(follow_title.as_str(), true).count()
+            });
+
             let title = if app_state.is_expanded
                 && !proc_widget_state
                     .process_search_state
@@ -248,40 +287,50 @@ impl ProcessTableWidget for Painter {
             {
                 const ESCAPE_ENDING: &str = "── Esc to go back ";
 
-                let (chosen_title_base, expanded_title_base) = {
+                let (chosen_title_base, expanded_title_base, follow_title) = {
                     let temp_title_base = format!("{}{}", title_base, ESCAPE_ENDING);
 
-                    if temp_title_base.len() > draw_loc.width as usize {
+                    if temp_title_base.len() + follow_title_len > draw_loc.width as usize {
                         (
                             " Processes ".to_string(),
                             format!("{}{}", " Processes ".to_string(), ESCAPE_ENDING),
+                            None,
                         )
                     } else {
-                        (title_base, temp_title_base)
+                        (title_base, temp_title_base, follow_title)
                     }
                 };
+                let follow_title_len = follow_title.as_ref().map_or(0, |_| follow_title_len);
 
-                Spans::from(vec![
-                    Span::styled(chosen_title_base, self.colours.widget_title_style),
-                    Span::styled(
-                        format!(
-                            "─{}─ Esc to go back ",
-                            "─".repeat(
-                                usize::from(draw_loc.width).saturating_sub(
-                                    UnicodeSegmentation::graphemes(
-                                        expanded_title_base.as_str(),
-                                        true
-                                    )
+                let mut title_spans = vec![Span::styled(
+                    chosen_title_base,
+                    self.colours.widget_title_style,
+                )];
+                if let Some((follow_title, follow_style)) = follow_title {
+                    title_spans.push(Span::styled(follow_title, follow_style));
+                }
+                title_spans.push(Span::styled(
+                    format!(
+                        "─{}─ Esc to go back ",
+                        "─".repeat(
+                            usize::from(draw_loc.width).saturating_sub(
+                                UnicodeSegmentation::graphemes(expanded_title_base.as_str(), true)
                                     .count()
-                                        + 2
-                                )
+                                    + follow_title_len
+                                    + 2
                             )
-                        ),
-                        border_style,
+                        )
                     ),
-                ])
+                    border_style,
+                ));
+                Spans::from(title_spans)
             } else {
-                Spans::from(Span::styled(title_base, self.colours.widget_title_style))
+                let mut title_spans =
+                    vec![Span::styled(title_base, self.colours.widget_title_style)];
+                if let Some((follow_title, follow_style)) = follow_title {
+                    title_spans.push(Span::styled(follow_title, follow_style));
+                }
+                Spans::from(title_spans)
             };
 
             let process_block = if draw_border {
@@ -326,6 +375,15 @@ impl ProcessTableWidget for Painter {
                 };
 
                 let sliced_vec = &process_data[start_position..];
+                // The stringified rows line up with the finalized ones, which know if they're new.
+                let mut new_rows = app_state
+                    .canvas_data
+                    .finalized_process_data_map
+                    .get(&widget_id)
+                    .and_then(|finalized_process_data| finalized_process_data.get(start_position..))
+                    .unwrap_or_default()
+                    .iter()
+                    .map(|process| process.is_new);
                 let processed_sliced_vec = sliced_vec.iter().map(|(data, disabled)| {
                     (
                         data.iter()
@@ -335,6 +393,7 @@ impl ProcessTableWidget for Painter {
                     )
                 });
 
+                let sort_keys = proc_widget_state.get_sort_keys();
                 let proc_table_state = &mut proc_widget_state.scroll_state.table_state;
                 proc_table_state.select(Some(
                     proc_widget_state
@@ -344,18 +403,23 @@ impl ProcessTableWidget for Painter {
                 ));
 
                 // Draw!
-                let process_headers = proc_widget_state.columns.get_column_headers(
-                    &proc_widget_state.process_sorting_type,
-                    proc_widget_state.is_process_sort_descending,
-                );
+                let process_headers = proc_widget_state.columns.get_column_headers(&sort_keys);
 
                 // Calculate widths
                 // FIXME: See if we can move this into the recalculate block?  I want to move column widths into the column widths
-                let hard_widths = if proc_widget_state.is_grouped {
-                    &*PROCESS_HEADERS_HARD_WIDTH_GROUPED
-                } else {
-                    &*PROCESS_HEADERS_HARD_WIDTH_NO_GROUP
-                };
+                let shown_columns = proc_widget_state.columns.get_shown_columns();
+                let is_using_command = proc_widget_state.is_using_command;
+                let is_tree_mode = proc_widget_state.is_tree_mode;
+                let hard_widths = shown_columns
+                    .iter()
+                    .map(get_column_hard_width)
+                    .collect::<Vec<_>>();
+
+                proc_widget_state.table_width_state.column_names = shown_columns
+                    .iter()
+                    .map(|column| column.to_string())
+                    .collect();
+                let width_overrides = proc_widget_state.table_width_state.get_width_overrides();
 
                 if recalculate_column_widths {
                     let mut column_widths = process_headers
@@ -386,7 +450,7 @@ impl ProcessTableWidget for Painter {
                         .table_width_state
                         .desired_column_widths
                         .iter()
-                        .zip(hard_widths)
+                        .zip(&hard_widths)
                         .map(|(current, hard)| {
                             if let Some(hard) = hard {
                                 if *hard > *current {
@@ -400,34 +464,26 @@ impl ProcessTableWidget for Painter {
                         })
                         .collect::<Vec<_>>();
 
-                    let soft_widths_max = if proc_widget_state.is_grouped {
-                        // Note grouped trees are not a thing.
-
-                        if proc_widget_state.is_using_command {
-                            &*PROCESS_HEADERS_SOFT_WIDTH_MAX_GROUPED_COMMAND
-                        } else {
-                            &*PROCESS_HEADERS_SOFT_WIDTH_MAX_GROUPED_ELSE
-                        }
-                    } else if proc_widget_state.is_using_command {
-                        &*PROCESS_HEADERS_SOFT_WIDTH_MAX_NO_GROUP_COMMAND
-                    } else if proc_widget_state.is_tree_mode {
-                        &*PROCESS_HEADERS_SOFT_WIDTH_MAX_NO_GROUP_TREE
-                    } else {
-                        &*PROCESS_HEADERS_SOFT_WIDTH_MAX_NO_GROUP_ELSE
-                    };
+                    let soft_widths_max = shown_columns
+                        .iter()
+                        .map(|column| {
+                            get_column_soft_width_max(column, is_using_command, is_tree_mode)
+                        })
+                        .collect::<Vec<_>>();
 
                     proc_widget_state.table_width_state.calculated_column_widths =
-                        get_column_widths(
+                        get_column_widths_with_overrides(
                             draw_loc.width,
-                            hard_widths,
+                            &hard_widths,
                             &soft_widths_min,
-                            soft_widths_max,
+                            &soft_widths_max,
                             &(proc_widget_state
                                 .table_width_state
                                 .desired_column_widths
                                 .iter()
                                 .map(|width| Some(*width))
                                 .collect::<Vec<_>>()),
+                            &width_overrides,
                             true,
                         );
 
@@ -444,13 +500,50 @@ impl ProcessTableWidget for Painter {
                 let dcw = &proc_widget_state.table_width_state.desired_column_widths;
                 let ccw = &proc_widget_state.table_width_state.calculated_column_widths;
 
+                // Only the name/command column scrolls horizontally, and only as far as the
+                // longest entry (including any tree prefix) needs to show its end.
+                let name_column = shown_columns.iter().position(|column| {
+                    matches!(
+                        column,
+                        ProcessSorting::ProcessName | ProcessSorting::Command
+                    )
+                });
+                if let Some(name_column) = name_column {
+                    let column_width = ccw.get(name_column).cloned().unwrap_or(0);
+                    let max_offset = sliced_vec
+                        .iter()
+                        .filter_map(|(data, _disabled)| data.get(name_column))
+                        .map(|(entry, _alternative)| {
+                            UnicodeSegmentation::graphemes(entry.as_str(), true).count()
+                        })
+                        .max()
+                        .unwrap_or(0)
+                        .saturating_sub(usize::from(column_width));
+                    proc_widget_state.horizontal_scroll_offset =
+                        min(proc_widget_state.horizontal_scroll_offset, max_offset);
+                }
+                let horizontal_scroll_offset = proc_widget_state.horizontal_scroll_offset;
+
                 let process_rows = sliced_vec.iter().map(|(data, disabled)| {
-                    let truncated_data = data.iter().zip(hard_widths).enumerate().map(
+                    let is_new = new_rows.next().unwrap_or(false);
+                    let truncated_data = data.iter().zip(&hard_widths).enumerate().map(
                         |(itx, ((entry, alternative), width))| {
+                            if horizontal_scroll_offset > 0 && name_column == Some(itx) {
+                                if let Some(calculated_col_width) = ccw.get(itx) {
+                                    return Text::raw(scroll_entry(
+                                        entry,
+                                        horizontal_scroll_offset,
+                                        usize::from(*calculated_col_width),
+                                    ));
+                                }
+                            }
+
                             if let (Some(desired_col_width), Some(calculated_col_width)) =
                                 (dcw.get(itx), ccw.get(itx))
                             {
-                                if width.is_none() {
+                                let is_overridden =
+                                    matches!(width_overrides.get(itx), Some(Some(_)));
+                                if width.is_none() || is_overridden {
                                     if *desired_col_width > *calculated_col_width
                                         && *calculated_col_width > 0
                                     {
@@ -485,11 +578,31 @@ impl ProcessTableWidget for Painter {
 
                     if *disabled {
                         Row::new(truncated_data).style(self.colours.disabled_text_style)
+                    } else if is_new {
+                        Row::new(truncated_data).style(self.colours.new_process_style)
                     } else {
                         Row::new(truncated_data)
                     }
                 });
 
+                // Highlight the header of the column being resized, if any.
+                let selected_column = if is_on_widget {
+                    proc_widget_state.table_width_state.selected_column
+                } else {
+                    None
+                };
+                let process_headers =
+                    process_headers
+                        .into_iter()
+                        .enumerate()
+                        .map(|(itx, header)| {
+                            if selected_column == Some(itx) {
+                                Cell::from(header).style(self.colours.currently_selected_text_style)
+                            } else {
+                                Cell::from(header)
+                            }
+                        });
+
                 f.render_stateful_widget(
                     Table::new(process_rows)
                         .header(
@@ -519,38 +632,24 @@ impl ProcessTableWidget for Painter {
 
             // Check if we need to update columnar bounds...
             if recalculate_column_widths
-                || proc_widget_state.columns.column_header_x_locs.is_none()
-                || proc_widget_state.columns.column_header_y_loc.is_none()
+                || proc_widget_state
+                    .table_width_state
+                    .column_header_x_locs
+                    .is_none()
+                || proc_widget_state
+                    .table_width_state
+                    .column_header_y_loc
+                    .is_none()
             {
                 // y location is just the y location of the widget + border size (1 normally, 0 in basic)
-                proc_widget_state.columns.column_header_y_loc =
+                proc_widget_state.table_width_state.column_header_y_loc =
                     Some(draw_loc.y + if draw_border { 1 } else { 0 });
 
-                // x location is determined using the x locations of the widget; just offset from the left bound
-                // as appropriate, and use the right bound as limiter.
-
-                let mut current_x_left = draw_loc.x + 1;
-                let max_x_right = draw_loc.x + draw_loc.width - 1;
-
-                let mut x_locs = vec![];
-
-                for width in proc_widget_state
-                    .table_width_state
-                    .calculated_column_widths
-                    .iter()
-                {
-                    let right_bound = current_x_left + width;
-
-                    if right_bound < max_x_right {
-                        x_locs.push((current_x_left, right_bound));
-                        current_x_left = right_bound + 1;
-                    } else {
-                        x_locs.push((current_x_left, max_x_right));
-                        break;
-                    }
-                }
-
-                proc_widget_state.columns.column_header_x_locs = Some(x_locs);
+                proc_widget_state.table_width_state.column_header_x_locs =
+                    Some(get_column_x_bounds(
+                        draw_loc,
+                        &proc_widget_state.table_width_state.calculated_column_widths,
+                    ));
             }
 
             if app_state.should_get_widget_bounds() {
@@ -570,44 +669,6 @@ impl ProcessTableWidget for Painter {
         &self, f: &mut Frame<'_, B>, app_state: &mut App, draw_loc: Rect, draw_border: bool,
         widget_id: u64,
     ) {
-        fn build_query<'a>(
-            is_on_widget: bool, grapheme_indices: GraphemeIndices<'a>, start_position: usize,
-            cursor_position: usize, query: &str, currently_selected_text_style: tui::style::Style,
-            text_style: tui::style::Style,
-        ) -> Vec<Span<'a>> {
-            let mut current_grapheme_posn = 0;
-
-            if is_on_widget {
-                let mut res = grapheme_indices
-                    .filter_map(|grapheme| {
-                        current_grapheme_posn += UnicodeWidthStr::width(grapheme.1);
-
-                        if current_grapheme_posn <= start_position {
-                            None
-                        } else {
-                            let styled = if grapheme.0 == cursor_position {
-                                Span::styled(grapheme.1, currently_selected_text_style)
-                            } else {
-                                Span::styled(grapheme.1, text_style)
-                            };
-                            Some(styled)
-                        }
-                    })
-                    .collect::<Vec<_>>();
-
-                if cursor_position == query.len() {
-                    res.push(Span::styled(" ", currently_selected_text_style))
-                }
-
-                res
-            } else {
-                // This is easier - we just need to get a range of graphemes, rather than
-                // dealing with possibly inserting a cursor (as none is shown!)
-
-                vec![Span::styled(query.to_string(), text_style)]
-            }
-        }
-
         // TODO: Make the cursor scroll back if there's space!
         if let Some(proc_widget_state) =
             app_state.proc_state.widget_states.get_mut(&(widget_id - 1))
@@ -645,8 +706,14 @@ impl ProcessTableWidget for Painter {
                 start_position,
                 cursor_position,
                 query,
+                proc_widget_state
+                    .process_search_state
+                    .search_state
+                    .error_span
+                    .as_ref(),
                 self.colours.currently_selected_text_style,
                 self.colours.text_style,
+                self.colours.invalid_query_style,
             );
 
             let mut search_text = vec![Spans::from({
@@ -799,15 +866,18 @@ impl ProcessTableWidget for Painter {
                 .columns
                 .ordered_columns
                 .iter()
-                .filter(|column_type| {
-                    proc_widget_state
+                .filter_map(|column_type| {
+                    let mapping = proc_widget_state
                         .columns
                         .column_mapping
                         .get(column_type)
-                        .unwrap()
-                        .enabled
+                        .unwrap();
+                    if mapping.enabled {
+                        Some((column_type.to_string(), mapping.is_hidden))
+                    } else {
+                        None
+                    }
                 })
-                .map(|column_type| column_type.to_string())
                 .collect::<Vec<_>>();
 
             let table_gap = if draw_loc.height < TABLE_GAP_HEIGHT_LIMIT {
@@ -834,9 +904,14 @@ impl ProcessTableWidget for Painter {
 
             let sliced_vec = &sort_string[start_position..];
 
-            let sort_options = sliced_vec
-                .iter()
-                .map(|column| Row::new(vec![column.as_str()]));
+            // Hidden columns are still listed so they can be shown again, but are greyed out.
+            let sort_options = sliced_vec.iter().map(|(column, is_hidden)| {
+                if *is_hidden {
+                    Row::new(vec![column.as_str()]).style(self.colours.disabled_text_style)
+                } else {
+                    Row::new(vec![column.as_str()])
+                }
+            });
 
             let column_state = &mut proc_widget_state.columns.column_state;
             column_state.select(Some(
@@ -908,4 +983,52 @@ impl ProcessTableWidget for Painter {
             }
         }
     }
+
+    fn draw_search_completion<B: Backend>(
+        &self, f: &mut Frame<'_, B>, app_state: &mut App, draw_loc: Rect, widget_id: u64,
+    ) {
+        const MAX_SHOWN_CANDIDATES: usize = 8;
+
+        if let Some(proc_widget_state) = app_state.proc_state.widget_states.get_mut(&widget_id) {
+            let completion_state = &mut proc_widget_state.process_search_state.completion_state;
+            let candidates = &completion_state.candidates;
+
+            let longest_candidate = candidates
+                .iter()
+                .map(|candidate| UnicodeWidthStr::width(candidate.as_str()))
+                .max()
+                .unwrap_or(0);
+            let width = std::cmp::min(longest_candidate as u16 + 2, draw_loc.width);
+            let height = std::cmp::min(
+                std::cmp::min(candidates.len(), MAX_SHOWN_CANDIDATES) as u16 + 2,
+                draw_loc.height,
+            );
+            let completion_draw_loc = Rect::new(
+                draw_loc.x,
+                draw_loc.y + draw_loc.height - height,
+                width,
+                height,
+            );
+
+            let items = candidates
+                .iter()
+                .map(|candidate| {
+                    ListItem::new(Span::styled(candidate.as_str(), self.colours.text_style))
+                })
+                .collect::<Vec<_>>();
+
+            f.render_widget(Clear, completion_draw_loc);
+            f.render_stateful_widget(
+                List::new(items)
+                    .block(
+                        Block::default()
+                            .borders(Borders::ALL)
+                            .border_style(self.colours.highlighted_border_style),
+                    )
+                    .highlight_style(self.colours.currently_selected_text_style),
+                completion_draw_loc,
+                &mut completion_state.list_state,
+            );
+        }
+    }
 }
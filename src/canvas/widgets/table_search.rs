@@ -0,0 +1,195 @@
+use crate::{
+    app::AppSearchState,
+    canvas::{drawing_utils::get_search_start_position, Painter},
+    constants::*,
+};
+
+use std::ops::Range;
+
+use tui::{
+    backend::Backend,
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::Modifier,
+    terminal::Frame,
+    text::{Span, Spans},
+    widgets::{Block, Borders, Paragraph},
+};
+
+use unicode_segmentation::{GraphemeIndices, UnicodeSegmentation};
+use unicode_width::UnicodeWidthStr;
+
+/// Builds the spans for a search query, showing the cursor if the search is selected and marking
+/// whatever part of the query is invalid.
+#[allow(clippy::too_many_arguments)]
+pub fn build_query<'a>(
+    is_on_widget: bool, grapheme_indices: GraphemeIndices<'a>, start_position: usize,
+    cursor_position: usize, query: &str, error_span: Option<&Range<usize>>,
+    currently_selected_text_style: tui::style::Style, text_style: tui::style::Style,
+    invalid_query_style: tui::style::Style,
+) -> Vec<Span<'a>> {
+    let mut current_grapheme_posn = 0;
+    let mut current_char_posn = 0;
+
+    // Underline whatever part of the query caused it to be invalid.
+    let error_style = invalid_query_style.add_modifier(Modifier::UNDERLINED);
+
+    if is_on_widget {
+        let mut res = grapheme_indices
+            .filter_map(|grapheme| {
+                current_grapheme_posn += UnicodeWidthStr::width(grapheme.1);
+                let grapheme_char_posn = current_char_posn;
+                current_char_posn += grapheme.1.chars().count();
+
+                if current_grapheme_posn <= start_position {
+                    None
+                } else {
+                    let styled = if grapheme.0 == cursor_position {
+                        Span::styled(grapheme.1, currently_selected_text_style)
+                    } else if matches!(error_span, Some(span) if span.contains(&grapheme_char_posn))
+                    {
+                        Span::styled(grapheme.1, error_style)
+                    } else {
+                        Span::styled(grapheme.1, text_style)
+                    };
+                    Some(styled)
+                }
+            })
+            .collect::<Vec<_>>();
+
+        if cursor_position == query.len() {
+            res.push(Span::styled(" ", currently_selected_text_style))
+        }
+
+        res
+    } else if let Some(error_span) = error_span {
+        // Split up the query around the erroneous span so that it can be styled separately.
+        let to_byte_index = |char_index: usize| {
+            query
+                .char_indices()
+                .nth(char_index)
+                .map_or(query.len(), |(byte_index, _)| byte_index)
+        };
+        let (error_start, error_end) = (
+            to_byte_index(error_span.start),
+            to_byte_index(error_span.end),
+        );
+
+        vec![
+            Span::styled(query[..error_start].to_string(), text_style),
+            Span::styled(query[error_start..error_end].to_string(), error_style),
+            Span::styled(query[error_end..].to_string(), text_style),
+        ]
+    } else {
+        // This is easier - we just need to get a range of graphemes, rather than
+        // dealing with possibly inserting a cursor (as none is shown!)
+
+        vec![Span::styled(query.to_string(), text_style)]
+    }
+}
+
+/// A search bar for tables that only need a query, like the disk, temperature and user tables.
+pub trait TableSearchWidget {
+    /// Returns how tall the search bar is.
+    fn get_table_search_height(&self, draw_border: bool) -> u16;
+
+    fn draw_table_search<B: Backend>(
+        &self, f: &mut Frame<'_, B>, search_state: &mut AppSearchState, draw_loc: Rect,
+        draw_border: bool, is_on_widget: bool, is_force_redraw: bool,
+    );
+}
+
+impl TableSearchWidget for Painter {
+    fn get_table_search_height(&self, draw_border: bool) -> u16 {
+        if draw_border {
+            4
+        } else {
+            2
+        }
+    }
+
+    fn draw_table_search<B: Backend>(
+        &self, f: &mut Frame<'_, B>, search_state: &mut AppSearchState, draw_loc: Rect,
+        draw_border: bool, is_on_widget: bool, is_force_redraw: bool,
+    ) {
+        let search_title = "> ";
+        let start_position = get_search_start_position(
+            usize::from(draw_loc.width).saturating_sub(search_title.len() + 5),
+            &search_state.cursor_direction,
+            &mut search_state.cursor_bar,
+            search_state.char_cursor_position,
+            is_force_redraw,
+        );
+
+        let query = search_state.current_search_query.as_str();
+        let query_with_cursor = build_query(
+            is_on_widget,
+            UnicodeSegmentation::grapheme_indices(query, true),
+            start_position,
+            search_state.grapheme_cursor.cur_cursor(),
+            query,
+            search_state.error_span.as_ref(),
+            self.colours.currently_selected_text_style,
+            self.colours.text_style,
+            self.colours.invalid_query_style,
+        );
+
+        let mut search_vec = vec![Span::styled(
+            search_title,
+            if is_on_widget {
+                self.colours.table_header_style
+            } else {
+                self.colours.text_style
+            },
+        )];
+        search_vec.extend(query_with_cursor);
+
+        let search_text = vec![
+            Spans::from(search_vec),
+            Spans::from(Span::styled(
+                search_state.error_message.as_deref().unwrap_or(""),
+                self.colours.invalid_query_style,
+            )),
+        ];
+
+        let current_border_style = if search_state.is_invalid_search {
+            self.colours.invalid_query_style
+        } else if is_on_widget {
+            self.colours.highlighted_border_style
+        } else {
+            self.colours.border_style
+        };
+
+        let search_block = if draw_border {
+            const TITLE_BASE: &str = " Esc to close ";
+            let repeat_num =
+                usize::from(draw_loc.width).saturating_sub(TITLE_BASE.chars().count() + 2);
+            Block::default()
+                .title(Span::styled(
+                    format!("{}{}", "─".repeat(repeat_num), TITLE_BASE),
+                    current_border_style,
+                ))
+                .borders(Borders::ALL)
+                .border_style(current_border_style)
+        } else if is_on_widget {
+            Block::default()
+                .borders(*SIDE_BORDERS)
+                .border_style(current_border_style)
+        } else {
+            Block::default().borders(Borders::NONE)
+        };
+
+        let margined_draw_loc = Layout::default()
+            .constraints([Constraint::Percentage(100)])
+            .horizontal_margin(if is_on_widget || draw_border { 0 } else { 1 })
+            .direction(Direction::Horizontal)
+            .split(draw_loc)[0];
+
+        f.render_widget(
+            Paragraph::new(search_text)
+                .block(search_block)
+                .style(self.colours.text_style)
+                .alignment(Alignment::Left),
+            margined_draw_loc,
+        );
+    }
+}
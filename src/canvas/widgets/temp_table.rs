@@ -5,13 +5,16 @@ use tui::{
     terminal::Frame,
     text::Span,
     text::{Spans, Text},
-    widgets::{Block, Borders, Row, Table},
+    widgets::{Block, Borders, Cell, Row, Table},
 };
 
 use crate::{
     app,
     canvas::{
-        drawing_utils::{get_column_widths, get_start_position},
+        drawing_utils::{
+            get_column_widths_with_overrides, get_column_x_bounds, get_start_position,
+        },
+        widgets::TableSearchWidget,
         Painter,
     },
     constants::*,
@@ -41,6 +44,30 @@ impl TempTableWidget for Painter {
     ) {
         let recalculate_column_widths = app_state.should_get_widget_bounds();
         if let Some(temp_widget_state) = app_state.temp_state.widget_states.get_mut(&widget_id) {
+            let is_on_widget = widget_id == app_state.current_widget.widget_id;
+            let draw_loc = if temp_widget_state.search_state.is_enabled {
+                let temp_chunk = Layout::default()
+                    .direction(Direction::Vertical)
+                    .constraints([
+                        Constraint::Min(0),
+                        Constraint::Length(self.get_table_search_height(draw_border)),
+                    ])
+                    .split(draw_loc);
+
+                self.draw_table_search(
+                    f,
+                    &mut temp_widget_state.search_state,
+                    temp_chunk[1],
+                    draw_border,
+                    is_on_widget,
+                    app_state.is_force_redraw,
+                );
+
+                temp_chunk[0]
+            } else {
+                draw_loc
+            };
+
             let table_gap = if draw_loc.height < TABLE_GAP_HEIGHT_LIMIT {
                 0
             } else {
@@ -55,7 +82,6 @@ impl TempTableWidget for Painter {
                 temp_widget_state.scroll_state.current_scroll_position,
                 app_state.is_force_redraw,
             );
-            let is_on_widget = widget_id == app_state.current_widget.widget_id;
             let temp_table_state = &mut temp_widget_state.scroll_state.table_state;
             temp_table_state.select(Some(
                 temp_widget_state
@@ -63,10 +89,20 @@ impl TempTableWidget for Painter {
                     .current_scroll_position
                     .saturating_sub(start_position),
             ));
-            let sliced_vec = &app_state.canvas_data.temp_sensor_data[start_position..];
+            let temp_sensor_data = app_state
+                .canvas_data
+                .temp_sensor_data_map
+                .get(&widget_id)
+                .map_or(&[][..], |data| data.as_slice());
+            let sliced_vec = &temp_sensor_data[start_position..];
 
             // Calculate widths
             let hard_widths = [None, None];
+            temp_widget_state.table_width_state.column_names = TEMP_HEADERS
+                .iter()
+                .map(|header| header.to_string())
+                .collect();
+            let width_overrides = temp_widget_state.table_width_state.get_width_overrides();
             if recalculate_column_widths {
                 temp_widget_state.table_width_state.desired_column_widths = {
                     let mut column_widths = TEMP_HEADERS_LENS.clone();
@@ -80,22 +116,24 @@ impl TempTableWidget for Painter {
 
                     column_widths
                 };
-                temp_widget_state.table_width_state.calculated_column_widths = get_column_widths(
-                    draw_loc.width,
-                    &hard_widths,
-                    &(TEMP_HEADERS_LENS
-                        .iter()
-                        .map(|width| Some(*width))
-                        .collect::<Vec<_>>()),
-                    &[Some(0.80), Some(-1.0)],
-                    &temp_widget_state
-                        .table_width_state
-                        .desired_column_widths
-                        .iter()
-                        .map(|width| Some(*width))
-                        .collect::<Vec<_>>(),
-                    false,
-                );
+                temp_widget_state.table_width_state.calculated_column_widths =
+                    get_column_widths_with_overrides(
+                        draw_loc.width,
+                        &hard_widths,
+                        &(TEMP_HEADERS_LENS
+                            .iter()
+                            .map(|width| Some(*width))
+                            .collect::<Vec<_>>()),
+                        &[Some(0.80), Some(-1.0)],
+                        &temp_widget_state
+                            .table_width_state
+                            .desired_column_widths
+                            .iter()
+                            .map(|width| Some(*width))
+                            .collect::<Vec<_>>(),
+                        &width_overrides,
+                        false,
+                    );
             }
 
             let dcw = &temp_widget_state.table_width_state.desired_column_widths;
@@ -104,7 +142,8 @@ impl TempTableWidget for Painter {
                 sliced_vec.iter().map(|temp_row| {
                     let truncated_data = temp_row.iter().zip(&hard_widths).enumerate().map(
                         |(itx, (entry, width))| {
-                            if width.is_none() {
+                            let is_overridden = matches!(width_overrides.get(itx), Some(Some(_)));
+                            if width.is_none() || is_overridden {
                                 if let (Some(desired_col_width), Some(calculated_col_width)) =
                                     (dcw.get(itx), ccw.get(itx))
                                 {
@@ -157,7 +196,7 @@ impl TempTableWidget for Painter {
                         .scroll_state
                         .current_scroll_position
                         .saturating_add(1),
-                    app_state.canvas_data.temp_sensor_data.len()
+                    temp_sensor_data.len()
                 );
 
                 if title_string.len() <= draw_loc.width as usize {
@@ -227,13 +266,23 @@ impl TempTableWidget for Painter {
                 .direction(Direction::Horizontal)
                 .split(draw_loc)[0];
 
+            let selected_column = temp_widget_state.table_width_state.selected_column;
+
             // Draw
             f.render_stateful_widget(
                 Table::new(temperature_rows)
                     .header(
-                        Row::new(TEMP_HEADERS.to_vec())
-                            .style(self.colours.table_header_style)
-                            .bottom_margin(table_gap),
+                        Row::new(TEMP_HEADERS.iter().enumerate().map(|(itx, header)| {
+                            // Highlight the header of the column being resized, if any.
+                            if is_on_widget && selected_column == Some(itx) {
+                                Cell::from(*header)
+                                    .style(self.colours.currently_selected_text_style)
+                            } else {
+                                Cell::from(*header)
+                            }
+                        }))
+                        .style(self.colours.table_header_style)
+                        .bottom_margin(table_gap),
                     )
                     .block(temp_block)
                     .highlight_style(highlight_style)
@@ -250,6 +299,27 @@ impl TempTableWidget for Painter {
                 temp_table_state,
             );
 
+            // Check if we need to update columnar bounds...
+            if recalculate_column_widths
+                || temp_widget_state
+                    .table_width_state
+                    .column_header_x_locs
+                    .is_none()
+                || temp_widget_state
+                    .table_width_state
+                    .column_header_y_loc
+                    .is_none()
+            {
+                // y location is just the y location of the widget + border size (1 normally, 0 in basic)
+                temp_widget_state.table_width_state.column_header_y_loc =
+                    Some(draw_loc.y + if draw_border { 1 } else { 0 });
+                temp_widget_state.table_width_state.column_header_x_locs =
+                    Some(get_column_x_bounds(
+                        draw_loc,
+                        &temp_widget_state.table_width_state.calculated_column_widths,
+                    ));
+            }
+
             if app_state.should_get_widget_bounds() {
                 // Update draw loc in widget map
                 // Note there is no difference between this and using draw_loc, but I'm too lazy to fix it.
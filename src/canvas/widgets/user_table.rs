@@ -0,0 +1,329 @@
+use tui::{
+    backend::Backend,
+    layout::{Constraint, Direction, Layout, Rect},
+    terminal::Frame,
+    text::Span,
+    text::{Spans, Text},
+    widgets::{Block, Borders, Cell, Row, Table},
+};
+
+use crate::{
+    app::{self, USER_COLUMNS},
+    canvas::{
+        drawing_utils::{
+            get_column_widths_with_overrides, get_column_x_bounds, get_start_position,
+        },
+        widgets::TableSearchWidget,
+        Painter,
+    },
+    constants::*,
+    data_conversion::stringify_user_data,
+};
+use unicode_segmentation::UnicodeSegmentation;
+
+pub trait UserTableWidget {
+    fn draw_user_table<B: Backend>(
+        &self, f: &mut Frame<'_, B>, app_state: &mut app::App, draw_loc: Rect, draw_border: bool,
+        widget_id: u64,
+    );
+}
+
+impl UserTableWidget for Painter {
+    fn draw_user_table<B: Backend>(
+        &self, f: &mut Frame<'_, B>, app_state: &mut app::App, draw_loc: Rect, draw_border: bool,
+        widget_id: u64,
+    ) {
+        let recalculate_column_widths = app_state.should_get_widget_bounds();
+        if let Some(user_widget_state) = app_state.user_state.widget_states.get_mut(&widget_id) {
+            let is_on_widget = app_state.current_widget.widget_id == widget_id;
+            let draw_loc = if user_widget_state.search_state.is_enabled {
+                let user_chunk = Layout::default()
+                    .direction(Direction::Vertical)
+                    .constraints([
+                        Constraint::Min(0),
+                        Constraint::Length(self.get_table_search_height(draw_border)),
+                    ])
+                    .split(draw_loc);
+
+                self.draw_table_search(
+                    f,
+                    &mut user_widget_state.search_state,
+                    user_chunk[1],
+                    draw_border,
+                    is_on_widget,
+                    app_state.is_force_redraw,
+                );
+
+                user_chunk[0]
+            } else {
+                draw_loc
+            };
+
+            let table_gap = if draw_loc.height < TABLE_GAP_HEIGHT_LIMIT {
+                0
+            } else {
+                app_state.app_config_fields.table_gap
+            };
+            let start_position = get_start_position(
+                usize::from(
+                    (draw_loc.height + (1 - table_gap)).saturating_sub(self.table_height_offset),
+                ),
+                &user_widget_state.scroll_state.scroll_direction,
+                &mut user_widget_state.scroll_state.previous_scroll_position,
+                user_widget_state.scroll_state.current_scroll_position,
+                app_state.is_force_redraw,
+            );
+            let user_data = app_state
+                .canvas_data
+                .user_data_map
+                .get(&widget_id)
+                .map_or(&[][..], |data| data.as_slice());
+            let sliced_vec = user_data[start_position..]
+                .iter()
+                .map(stringify_user_data)
+                .collect::<Vec<_>>();
+
+            let user_headers = user_widget_state.get_column_headers();
+            let user_headers_lens = user_headers
+                .iter()
+                .map(|header| UnicodeSegmentation::graphemes(header.as_str(), true).count() as u16)
+                .collect::<Vec<_>>();
+
+            // Calculate widths
+            let hard_widths = [None, Some(6), Some(7), Some(7), Some(7), Some(8), Some(8)];
+            user_widget_state.table_width_state.column_names = USER_COLUMNS
+                .iter()
+                .map(|column| column.to_string())
+                .collect();
+            let width_overrides = user_widget_state.table_width_state.get_width_overrides();
+            if recalculate_column_widths {
+                user_widget_state.table_width_state.desired_column_widths = {
+                    let mut column_widths = user_headers_lens.clone();
+                    for row in &sliced_vec {
+                        for (col, entry) in row.iter().enumerate() {
+                            if entry.len() as u16 > column_widths[col] {
+                                column_widths[col] = entry.len() as u16;
+                            }
+                        }
+                    }
+                    column_widths
+                        .iter()
+                        .zip(&hard_widths)
+                        .map(|(current, hard)| match hard {
+                            Some(hard) if *hard > *current => *hard,
+                            _ => *current,
+                        })
+                        .collect::<Vec<_>>()
+                };
+
+                user_widget_state.table_width_state.calculated_column_widths =
+                    get_column_widths_with_overrides(
+                        draw_loc.width,
+                        &hard_widths,
+                        &(user_headers_lens
+                            .iter()
+                            .map(|w| Some(*w))
+                            .collect::<Vec<_>>()),
+                        &[Some(0.3), None, None, None, None, None, None],
+                        &(user_widget_state
+                            .table_width_state
+                            .desired_column_widths
+                            .iter()
+                            .map(|w| Some(*w))
+                            .collect::<Vec<_>>()),
+                        &width_overrides,
+                        true,
+                    );
+            }
+
+            let dcw = &user_widget_state.table_width_state.desired_column_widths;
+            let ccw = &user_widget_state.table_width_state.calculated_column_widths;
+            let user_rows =
+                sliced_vec.iter().map(|user_row| {
+                    let truncated_data = user_row.iter().zip(&hard_widths).enumerate().map(
+                        |(itx, (entry, width))| {
+                            let is_overridden = matches!(width_overrides.get(itx), Some(Some(_)));
+                            if width.is_none() || is_overridden {
+                                if let (Some(desired_col_width), Some(calculated_col_width)) =
+                                    (dcw.get(itx), ccw.get(itx))
+                                {
+                                    if *desired_col_width > *calculated_col_width
+                                        && *calculated_col_width > 0
+                                    {
+                                        let graphemes =
+                                            UnicodeSegmentation::graphemes(entry.as_str(), true)
+                                                .collect::<Vec<&str>>();
+
+                                        if graphemes.len() > *calculated_col_width as usize
+                                            && *calculated_col_width > 1
+                                        {
+                                            // Truncate with ellipsis
+                                            let first_n = graphemes
+                                                [..(*calculated_col_width as usize - 1)]
+                                                .concat();
+                                            return Text::raw(format!("{}…", first_n));
+                                        }
+                                    }
+                                }
+                            }
+
+                            Text::raw(entry.as_str())
+                        },
+                    );
+
+                    Row::new(truncated_data)
+                });
+
+            let (border_style, highlight_style) = if is_on_widget {
+                (
+                    self.colours.highlighted_border_style,
+                    self.colours.currently_selected_text_style,
+                )
+            } else {
+                (self.colours.border_style, self.colours.text_style)
+            };
+
+            let title_base = if app_state.app_config_fields.show_table_scroll_position {
+                let title_string = format!(
+                    " Users ({} of {}) ",
+                    user_widget_state
+                        .scroll_state
+                        .current_scroll_position
+                        .saturating_add(1),
+                    user_data.len()
+                );
+
+                if title_string.len() <= draw_loc.width as usize {
+                    title_string
+                } else {
+                    " Users ".to_string()
+                }
+            } else {
+                " Users ".to_string()
+            };
+
+            let title = if app_state.is_expanded {
+                const ESCAPE_ENDING: &str = "── Esc to go back ";
+
+                let (chosen_title_base, expanded_title_base) = {
+                    let temp_title_base = format!("{}{}", title_base, ESCAPE_ENDING);
+
+                    if temp_title_base.len() > draw_loc.width as usize {
+                        (" Users ".to_string(), format!(" Users {}", ESCAPE_ENDING))
+                    } else {
+                        (title_base, temp_title_base)
+                    }
+                };
+
+                Spans::from(vec![
+                    Span::styled(chosen_title_base, self.colours.widget_title_style),
+                    Span::styled(
+                        format!(
+                            "─{}─ Esc to go back ",
+                            "─".repeat(
+                                usize::from(draw_loc.width).saturating_sub(
+                                    UnicodeSegmentation::graphemes(
+                                        expanded_title_base.as_str(),
+                                        true
+                                    )
+                                    .count()
+                                        + 2
+                                )
+                            )
+                        ),
+                        border_style,
+                    ),
+                ])
+            } else {
+                Spans::from(Span::styled(title_base, self.colours.widget_title_style))
+            };
+
+            let user_block = if draw_border {
+                Block::default()
+                    .title(title)
+                    .borders(Borders::ALL)
+                    .border_style(border_style)
+            } else if is_on_widget {
+                Block::default()
+                    .borders(*SIDE_BORDERS)
+                    .border_style(self.colours.highlighted_border_style)
+            } else {
+                Block::default().borders(Borders::NONE)
+            };
+
+            let margined_draw_loc = Layout::default()
+                .constraints([Constraint::Percentage(100)])
+                .horizontal_margin(if is_on_widget || draw_border { 0 } else { 1 })
+                .direction(Direction::Horizontal)
+                .split(draw_loc)[0];
+
+            let selected_column = user_widget_state.table_width_state.selected_column;
+            let current_scroll_position = user_widget_state.scroll_state.current_scroll_position;
+            let user_table_state = &mut user_widget_state.scroll_state.table_state;
+            user_table_state.select(Some(current_scroll_position.saturating_sub(start_position)));
+
+            // Draw!
+            f.render_stateful_widget(
+                Table::new(user_rows)
+                    .block(user_block)
+                    .header(
+                        Row::new(user_headers.iter().enumerate().map(|(itx, header)| {
+                            // Highlight the header of the column being resized, if any.
+                            if is_on_widget && selected_column == Some(itx) {
+                                Cell::from(header.as_str())
+                                    .style(self.colours.currently_selected_text_style)
+                            } else {
+                                Cell::from(header.as_str())
+                            }
+                        }))
+                        .style(self.colours.table_header_style)
+                        .bottom_margin(table_gap),
+                    )
+                    .highlight_style(highlight_style)
+                    .style(self.colours.text_style)
+                    .widths(
+                        &(user_widget_state
+                            .table_width_state
+                            .calculated_column_widths
+                            .iter()
+                            .map(|calculated_width| Constraint::Length(*calculated_width))
+                            .collect::<Vec<_>>()),
+                    ),
+                margined_draw_loc,
+                user_table_state,
+            );
+
+            // Check if we need to update columnar bounds...
+            if recalculate_column_widths
+                || user_widget_state
+                    .table_width_state
+                    .column_header_x_locs
+                    .is_none()
+                || user_widget_state
+                    .table_width_state
+                    .column_header_y_loc
+                    .is_none()
+            {
+                // y location is just the y location of the widget + border size (1 normally, 0 in basic)
+                user_widget_state.table_width_state.column_header_y_loc =
+                    Some(draw_loc.y + if draw_border { 1 } else { 0 });
+                user_widget_state.table_width_state.column_header_x_locs =
+                    Some(get_column_x_bounds(
+                        draw_loc,
+                        &user_widget_state.table_width_state.calculated_column_widths,
+                    ));
+            }
+
+            if app_state.should_get_widget_bounds() {
+                // Update draw loc in widget map
+                if let Some(widget) = app_state.widget_map.get_mut(&widget_id) {
+                    widget.top_left_corner = Some((margined_draw_loc.x, margined_draw_loc.y));
+                    widget.bottom_right_corner = Some((
+                        margined_draw_loc.x + margined_draw_loc.width,
+                        margined_draw_loc.y + margined_draw_loc.height,
+                    ));
+                }
+            }
+        }
+    }
+}
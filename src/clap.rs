@@ -9,10 +9,14 @@ const TEMPLATE: &str = "\
 USAGE:{usage}
 
 FLAGS:
-{unified}";
+{unified}
+
+SUBCOMMANDS:
+{subcommands}";
 
 const USAGE: &str = "
-    btm [FLAG]";
+    btm [FLAG]
+    btm [FLAG] ps [OPTIONS]";
 
 const DEFAULT_WIDGET_TYPE_STR: &str = if cfg!(feature = "battery") {
     "\
@@ -219,6 +223,108 @@ Completely hides the time scale from being shown.\n\n",
             ",
         );
 
+    let json = Arg::with_name("json")
+        .long("json")
+        .conflicts_with("snapshot")
+        .help("Prints collected data as JSON lines instead of the TUI.")
+        .long_help(
+            "\
+Instead of opening the TUI, collects data at the refresh rate and
+prints each collection to stdout as a single line of JSON.  Only
+data used by the layout is collected, and any disk, temperature,
+and network filters in the config file are applied.\n\n",
+        );
+    let iterations = Arg::with_name("iterations")
+        .short("n")
+        .long("iterations")
+        .takes_value(true)
+        .value_name("N")
+        .requires("json")
+        .help("Stops after printing N collections with --json.")
+        .long_help(
+            "\
+Stops after printing N collections when used with --json.  By
+default, data is printed until bottom is killed.\n\n",
+        );
+    let snapshot = Arg::with_name("snapshot")
+        .long("snapshot")
+        .help("Prints a plain text report of the system and exits.")
+        .long_help(
+            "\
+Instead of opening the TUI, collects data once and prints a plain
+text report of CPU, memory, swap, network, disk, and temperature
+usage, along with the top processes.  Processes are sorted and
+filtered the same way as the default process widget, including
+by --query.\n\n",
+        );
+    let top = Arg::with_name("top")
+        .long("top")
+        .takes_value(true)
+        .value_name("N")
+        .requires("snapshot")
+        .help("Sets how many processes --snapshot shows.")
+        .long_help(
+            "\
+Sets how many processes --snapshot shows.  Defaults to 10.\n\n",
+        );
+    let serve_metrics = Arg::with_name("serve_metrics")
+        .long("serve-metrics")
+        .takes_value(true)
+        .value_name("ADDRESS")
+        .help("Serves Prometheus metrics at the given address.")
+        .long_help(
+            "\
+Serves collected data as Prometheus metrics over HTTP at the given
+address, for example '127.0.0.1:9100'.  Metrics are available at
+/metrics, and are updated at the refresh rate.  Everything is
+collected while serving metrics, regardless of the layout.\n\n",
+        );
+    let headless = Arg::with_name("headless")
+        .long("headless")
+        .help("Serves, exports, or records data without opening the TUI.")
+        .long_help(
+            "\
+Runs without opening the TUI, only collecting data to serve with
+--serve-metrics, push to the [export] target in the config file,
+or save with --record until bottom is killed.\n\n",
+        );
+    let record = Arg::with_name("record")
+        .long("record")
+        .takes_value(true)
+        .value_name("FILE")
+        .conflicts_with("replay")
+        .help("Appends every collection to a recording file.")
+        .long_help(
+            "\
+Appends every collection to the given file, so it can be looked at
+later with --replay.  Each collection is a line of JSON, the same
+as with --json.  Everything is collected while recording,
+regardless of the layout.\n\n",
+        );
+    let replay = Arg::with_name("replay")
+        .long("replay")
+        .takes_value(true)
+        .value_name("FILE")
+        .conflicts_with_all(&["serve_metrics", "headless", "json", "snapshot"])
+        .help("Replays a recording made with --record.")
+        .long_help(
+            "\
+Replays a recording made with --record in the TUI, instead of
+collecting new data.  While replaying, Alt-p pauses, Alt-Left and
+Alt-Right seek back and forward, and Alt-, and Alt-. slow down
+and speed up the replay.\n\n",
+        );
+    let replay_speed = Arg::with_name("replay_speed")
+        .long("replay-speed")
+        .takes_value(true)
+        .value_name("SPEED")
+        .requires("replay")
+        .help("Sets how fast --replay plays back.")
+        .long_help(
+            "\
+Sets how fast --replay plays back, as a multiple of the recorded
+pace, from 0.25 up to 64.  Defaults to 1.\n\n",
+        );
     let left_legend = Arg::with_name("left_legend")
         .short("l")
         .long("left_legend")
@@ -327,6 +433,34 @@ Defaults to \"default\".
             "nord-light",
         ])
         .hide_possible_values(true);
+    let group_by = Arg::with_name("group_by")
+        .long("group_by")
+        .takes_value(true)
+        .value_name("GROUPING")
+        .help("Groups processes by default, use --help for supported values.")
+        .long_help(
+            "\
+Groups processes together by default.  Supported values are:
+
++------------------------------------------------+
+| name (processes with the same name or command) |
++------------------------------------------------+
+| user (processes owned by the same user)        |
++------------------------------------------------+
+| parent (processes with the same parent)        |
++------------------------------------------------+
+| tty (processes on the same terminal)           |
++------------------------------------------------+
+| cgroup (processes in the same cgroup, Linux)   |
++------------------------------------------------+
+| none                                           |
++------------------------------------------------+
+
+Overrides --group.
+\n\n",
+        )
+        .possible_values(&["none", "name", "user", "parent", "tty", "cgroup"])
+        .hide_possible_values(true);
     let mem_as_value = Arg::with_name("mem_as_value")
         .long("mem_as_value")
         .help("Defaults to showing process memory usage by value.")
@@ -377,6 +511,17 @@ use CPU (3) as the default instead.
         .value_name("WIDGET TYPE")
         .help("Sets the default widget type, use --help for more info.")
         .long_help(DEFAULT_WIDGET_TYPE_STR);
+    let query = Arg::with_name("query")
+        .long("query")
+        .takes_value(true)
+        .value_name("QUERY")
+        .help("Sets the default search query for process widgets.")
+        .long_help(
+            "\
+Sets the default search query for all process widgets.  This can
+either be a query, or the name of a saved query from the [queries]
+section of the config file.  Overrides any per-widget default queries.\n\n",
+        );
     let rate = Arg::with_name("rate")
         .short("r")
         .long("rate")
@@ -400,6 +545,20 @@ The amount of time in milliseconds changed when zooming in/out.
 The minimum is 1s (1000), and defaults to 15s (15000).\n\n\n",
         );
 
+    let retention = Arg::with_name("retention")
+        .long("retention")
+        .takes_value(true)
+        .value_name("MS")
+        .help("How long graph data is kept in ms.")
+        .long_help(
+            "\
+How long graph data is kept in milliseconds, which is as far as
+graphs can be zoomed out.  The minimum is 30s (30000), the maximum
+is a week (604800000), and defaults to 10m (600000).  Anything
+older than 10 minutes is downsampled, so keeping a day (86400000)
+doesn't take much more memory than keeping an hour.\n\n\n",
+        );
+
     let tree = Arg::with_name("tree")
         .short("T")
         .long("tree")
@@ -435,6 +594,8 @@ Displays the network widget with binary prefixes (i.e. kibibits, mebibits) rathe
 
     let app = App::new(crate_name!())
         .setting(AppSettings::UnifiedHelpMessage)
+        .setting(AppSettings::VersionlessSubcommands)
+        .setting(AppSettings::DisableHelpSubcommand)
         .version(crate_version!())
         .author(crate_authors!())
         .about(crate_description!())
@@ -452,6 +613,7 @@ Displays the network widget with binary prefixes (i.e. kibibits, mebibits) rathe
         .arg(process_command)
         .arg(config_location)
         .arg(color)
+        .arg(group_by)
         .arg(mem_as_value)
         .arg(default_time_value)
         .arg(default_widget_count)
@@ -463,8 +625,19 @@ Displays the network widget with binary prefixes (i.e. kibibits, mebibits) rathe
         .arg(hide_table_gap)
         .arg(hide_time)
         .arg(show_table_scroll_position)
+        .arg(serve_metrics)
+        .arg(headless)
+        .arg(record)
+        .arg(replay)
+        .arg(replay_speed)
+        .arg(retention)
+        .arg(snapshot)
+        .arg(top)
+        .arg(iterations)
+        .arg(json)
         .arg(left_legend)
         .arg(disable_advanced_kill)
+        .arg(query)
         .arg(rate)
         .arg(regex)
         .arg(time_delta)
@@ -474,7 +647,8 @@ Displays the network widget with binary prefixes (i.e. kibibits, mebibits) rathe
         .arg(network_use_binary_prefix)
         .arg(current_usage)
         .arg(use_old_network_legend)
-        .arg(whole_word);
+        .arg(whole_word)
+        .subcommand(build_ps_subcommand());
 
     let app = if cfg!(feature = "battery") {
         let battery = Arg::with_name("battery")
@@ -492,3 +666,81 @@ custom layouts.\n\n",
 
     app
 }
+
+fn build_ps_subcommand() -> App<'static, 'static> {
+    let query = Arg::with_name("query")
+        .short("q")
+        .long("query")
+        .takes_value(true)
+        .value_name("QUERY")
+        .help("Only lists processes matching the query.")
+        .long_help(
+            "\
+Only lists processes matching the query, using the same syntax as
+the process widget's search.  This can either be a query, or the
+name of a saved query from the [queries] section of the config
+file.  Lists all processes if not set.\n\n",
+        );
+    let sort = Arg::with_name("sort")
+        .short("s")
+        .long("sort")
+        .takes_value(true)
+        .multiple(true)
+        .number_of_values(1)
+        .value_name("KEY")
+        .help("Sorts processes by a column, can be repeated.")
+        .long_help(
+            "\
+Sorts processes by a column, optionally followed by \"asc\" or
+\"desc\".  Can be repeated to break ties with other columns, for
+example '--sort \"user asc\" --sort cpu%'.\n\n",
+        );
+    let columns = Arg::with_name("columns")
+        .short("c")
+        .long("columns")
+        .takes_value(true)
+        .use_delimiter(true)
+        .value_name("COLUMNS")
+        .help("Sets which columns to list, separated by commas.")
+        .long_help(
+            "\
+Sets which columns to list and their order, separated by commas,
+for example 'pid,name,mem'.  Defaults to the same columns as the
+process widget.\n\n",
+        );
+    let format = Arg::with_name("format")
+        .short("f")
+        .long("format")
+        .takes_value(true)
+        .value_name("FORMAT")
+        .possible_values(&["table", "json", "csv"])
+        .default_value("table")
+        .help("Sets the output format.")
+        .long_help(
+            "\
+Sets the output format.  JSON and CSV output use unformatted
+values, such as memory in bytes.\n\n",
+        );
+    let signal = Arg::with_name("signal")
+        .long("signal")
+        .takes_value(true)
+        .value_name("SIGNAL")
+        .requires("query")
+        .help("Sends a signal to every matching process.")
+        .long_help(
+            "\
+After listing them, sends a signal to every matching process.  The
+signal can be a number or a name like TERM or KILL.  On Windows,
+matching processes are always terminated.  A query is required, so
+that every process can't be signalled by accident.\n\n",
+        );
+
+    SubCommand::with_name("ps")
+        .about("Lists processes matching a query, and optionally signals them.")
+        .help_message("Prints help information.  Use --help for more info.")
+        .arg(query)
+        .arg(sort)
+        .arg(columns)
+        .arg(format)
+        .arg(signal)
+}
@@ -5,7 +5,9 @@ use once_cell::sync::Lazy;
 pub const DEFAULT_WIDGET_ID: u64 = 56709;
 
 // How long to store data.
-pub const STALE_MAX_MILLISECONDS: u64 = 600 * 1000; // Keep 10 minutes of data.
+pub const STALE_MAX_MILLISECONDS: u64 = 600 * 1000; // Keep 10 minutes of data by default, and at full resolution.
+pub const MAX_RETENTION_MILLISECONDS: u64 = 7 * 24 * 60 * 60 * 1000; // Keep at most a week of data.
+pub const DOWNSAMPLED_BUCKET_COUNT: u64 = 600; // How many buckets anything older than STALE_MAX is downsampled into
 
 // How much data is SHOWN
 pub const DEFAULT_TIME_MILLISECONDS: u64 = 60 * 1000; // Defaults to 1 min.
@@ -18,6 +20,58 @@ pub const TICK_RATE_IN_MILLISECONDS: u64 = 200;
 pub const DEFAULT_REFRESH_RATE_IN_MILLISECONDS: u64 = 1000;
 pub const MAX_KEY_TIMEOUT_IN_MILLISECONDS: u64 = 1000;
 
+// How many data updates a newly spawned process stays highlighted for
+pub const NEW_PROCESS_HIGHLIGHT_TICKS: usize = 3;
+// How many exited processes to remember
+pub const MAX_EXITED_PROCESSES: usize = 100;
+// How many processes a snapshot shows by default
+pub const DEFAULT_SNAPSHOT_PROCESS_COUNT: usize = 10;
+// How many of the top processes by CPU usage are exported as metrics
+pub const METRICS_PROCESS_COUNT: usize = 10;
+// What exported metric names start with by default
+pub const DEFAULT_EXPORT_PREFIX: &str = "bottom";
+// How long a failure to export, record, or save history stays shown after it last happened
+pub const COLLECTION_ERROR_DISPLAY_MILLISECONDS: u64 = 10000;
+// How far seeking moves during a replay
+pub const REPLAY_SEEK_MILLISECONDS: u64 = 60000;
+// The slowest and fastest that recordings can be replayed
+pub const MIN_REPLAY_SPEED: f64 = 0.25;
+pub const MAX_REPLAY_SPEED: f64 = 64.0;
+
+// Signal names that can be used instead of numbers when sending signals from the command line
+#[cfg(target_family = "unix")]
+pub const SIGNAL_NAMES: [(&str, libc::c_int); 29] = [
+    ("HUP", libc::SIGHUP),
+    ("INT", libc::SIGINT),
+    ("QUIT", libc::SIGQUIT),
+    ("ILL", libc::SIGILL),
+    ("TRAP", libc::SIGTRAP),
+    ("ABRT", libc::SIGABRT),
+    ("BUS", libc::SIGBUS),
+    ("FPE", libc::SIGFPE),
+    ("KILL", libc::SIGKILL),
+    ("USR1", libc::SIGUSR1),
+    ("SEGV", libc::SIGSEGV),
+    ("USR2", libc::SIGUSR2),
+    ("PIPE", libc::SIGPIPE),
+    ("ALRM", libc::SIGALRM),
+    ("TERM", libc::SIGTERM),
+    ("CHLD", libc::SIGCHLD),
+    ("CONT", libc::SIGCONT),
+    ("STOP", libc::SIGSTOP),
+    ("TSTP", libc::SIGTSTP),
+    ("TTIN", libc::SIGTTIN),
+    ("TTOU", libc::SIGTTOU),
+    ("URG", libc::SIGURG),
+    ("XCPU", libc::SIGXCPU),
+    ("XFSZ", libc::SIGXFSZ),
+    ("VTALRM", libc::SIGVTALRM),
+    ("PROF", libc::SIGPROF),
+    ("WINCH", libc::SIGWINCH),
+    ("IO", libc::SIGIO),
+    ("SYS", libc::SIGSYS),
+];
+
 // Limits for when we should stop showing table gaps/labels (anything less means not shown)
 pub const TABLE_GAP_HEIGHT_LIMIT: u16 = 7;
 pub const TIME_LABEL_HEIGHT_LIMIT: u16 = 7;
@@ -218,7 +272,7 @@ pub static NORD_LIGHT_COLOUR_PALETTE: Lazy<ConfigColours> = Lazy::new(|| ConfigC
 });
 
 // Help text
-pub const HELP_CONTENTS_TEXT: [&str; 8] = [
+pub const HELP_CONTENTS_TEXT: [&str; 10] = [
     "Press the corresponding numbers to jump to the section, or scroll:",
     "1 - General",
     "2 - CPU widget",
@@ -227,16 +281,21 @@ pub const HELP_CONTENTS_TEXT: [&str; 8] = [
     "5 - Process sort widget",
     "6 - Battery widget",
     "7 - Basic memory widget",
+    "8 - Disk and temperature widgets",
+    "9 - User widget",
 ];
 
 // TODO [Help]: Search in help?
 // TODO [Help]: Move to using tables for easier formatting?
-pub const GENERAL_HELP_TEXT: [&str; 31] = [
+pub const GENERAL_HELP_TEXT: [&str; 37] = [
     "1 - General",
     "q, Ctrl-c        Quit",
     "Esc              Close dialog windows, search, widgets, or exit expanded mode",
     "Ctrl-r           Reset display and any collected data",
     "f                Freeze/unfreeze updating with new data",
+    "Alt-p            Pause/resume a replay",
+    "Alt-Left/Right   Seek a replay back/forward by a minute",
+    "Alt-, Alt-.      Slow down/speed up a replay",
     "Ctrl-Left,       ",
     "Shift-Left,      Move widget selection left",
     "H, A             ",
@@ -259,10 +318,13 @@ pub const GENERAL_HELP_TEXT: [&str; 31] = [
     "e                Toggle expanding the currently selected widget",
     "+                Zoom in on chart (decrease time range)",
     "-                Zoom out on chart (increase time range)",
-    "=                Reset zoom",
+    "=                Reset zoom, or reset column widths in a table",
+    ", .              Select the previous/next table column to resize",
+    "<, >             Shrink/grow the selected table column",
     "PgUp, PgDown     Scroll up/down a table by a page",
     "Mouse scroll     Scroll through the tables or zoom in/out of charts by scrolling up/down",
     "Mouse click      Selects the clicked widget, table entry, dialog option, or tab",
+    "Mouse drag       Resize a table column by dragging the space after its header",
 ];
 
 pub const CPU_HELP_TEXT: [&str; 2] = [
@@ -270,7 +332,7 @@ pub const CPU_HELP_TEXT: [&str; 2] = [
     "Mouse scroll     Scrolling over an CPU core/average shows only that entry on the chart",
 ];
 
-pub const PROCESS_HELP_TEXT: [&str; 15] = [
+pub const PROCESS_HELP_TEXT: [&str; 27] = [
     "3 - Process widget",
     "dd, F9           Kill the selected process",
     "c                Sort by CPU usage, press again to reverse sorting order",
@@ -278,17 +340,29 @@ pub const PROCESS_HELP_TEXT: [&str; 15] = [
     "p                Sort by PID name, press again to reverse sorting order",
     "n                Sort by process name, press again to reverse sorting order",
     "Tab              Group/un-group processes with the same name",
+    "Shift-Tab        Cycle between grouping by name, user, parent, tty, and cgroup",
     "Ctrl-f, /        Open process search widget",
     "P                Toggle between showing the full command or just the process name",
+    "Left, Right,     Scroll the name/command column to see long commands",
+    "h, l             ",
     "s, F6            Open process sort widget",
+    "F4, Alt-s        Open saved queries",
     "I                Invert current sort",
+    "F                Follow the selected process as the table changes, press again to stop",
+    "#                Go to a PID, clearing the search or grouping if they hide it",
+    "u                Select the parent of the selected process",
+    "o                Select the first child of the selected process",
+    "x                Show the processes that have exited recently",
     "%                Toggle between values and percentages for memory usage",
     "t, F5            Toggle tree mode",
     "+, -, click      Collapse/expand a branch while in tree mode",
+    "zM, zR           Collapse/expand all branches while in tree mode",
+    "z1 - z9          Expand the tree to the given depth",
+    "zs               Collapse the other branches with the same parent",
     "click on header  Sorts the entries by that column, click again to invert the sort",
 ];
 
-pub const SEARCH_HELP_TEXT: [&str; 48] = [
+pub const SEARCH_HELP_TEXT: [&str; 61] = [
     "4 - Process search widget",
     "Esc              Close the search widget (retains the filter)",
     "Ctrl-a           Skip to the start of the search query",
@@ -303,9 +377,13 @@ pub const SEARCH_HELP_TEXT: [&str; 48] = [
     "Alt-r, F3        Toggle using regex",
     "Left, Alt-h      Move cursor left",
     "Right, Alt-l     Move cursor right",
+    "Up, Down         Go through previous searches",
+    "Tab              Complete a keyword or value, press again to cycle through options",
+    "F4, Alt-s        Open saved queries",
     "",
     "Supported search types:",
     "<by name/cmd>    ex: btm",
+    "name             ex: name = btm",
     "pid              ex: pid 825",
     "cpu, cpu%        ex: cpu > 4.2",
     "mem, mem%        ex: mem < 4.2",
@@ -316,6 +394,10 @@ pub const SEARCH_HELP_TEXT: [&str; 48] = [
     "twrite, t.write  ex: twrite = 1",
     "user            ex: user = root",
     "state            ex: state = running",
+    "time             ex: time > 1h",
+    "ppid             ex: ppid 825",
+    "children         ex: children > 2",
+    "ancestor         ex: ancestor = sshd",
     "",
     "Comparison operators:",
     "=                ex: cpu = 1",
@@ -327,25 +409,33 @@ pub const SEARCH_HELP_TEXT: [&str; 48] = [
     "Logical operators:",
     "and, &&, <Space> ex: btm and cpu > 1 and mem > 1",
     "or, ||           ex: btm or firefox",
+    "not, !           ex: not user = root",
     "",
     "Supported units:",
+    "%                ex: cpu > 50%",
     "B                ex: read > 1 b",
     "KB               ex: read > 1 kb",
     "MB               ex: read > 1 mb",
+    "GB               ex: read > 1 gb",
     "TB               ex: read > 1 tb",
     "KiB              ex: read > 1 kib",
     "MiB              ex: read > 1 mib",
     "GiB              ex: read > 1 gib",
     "TiB              ex: read > 1 tib",
+    "/s               ex: read > 1mb/s",
+    "ms, s, m, h, d   ex: time > 30m",
 ];
 
-pub const SORT_HELP_TEXT: [&str; 6] = [
+pub const SORT_HELP_TEXT: [&str; 9] = [
     "5 - Sort widget\n",
     "Down, 'j'        Scroll down in list",
     "Up, 'k'          Scroll up in list",
     "Mouse scroll     Scroll through sort widget",
     "Esc              Close the sort widget",
     "Enter            Sort by current selected column",
+    "Alt-Enter        Add current selected column as a tie-breaking sort key",
+    "Space            Hide or show the current selected column",
+    "[, ]             Move the current selected column up or down",
 ];
 
 pub const BATTERY_HELP_TEXT: [&str; 3] = [
@@ -359,6 +449,47 @@ pub const BASIC_MEM_HELP_TEXT: [&str; 2] = [
     "%                Toggle between values and percentages for memory usage",
 ];
 
+pub const TABLE_SEARCH_HELP_TEXT: [&str; 17] = [
+    "8 - Disk and temperature widgets",
+    "Ctrl-f, /        Open the search bar to filter the table",
+    "Esc              Close the search bar (retains the filter)",
+    "",
+    "Editing keys and operators are the same as in the process search widget.",
+    "",
+    "Supported search types:",
+    "<by name>        ex: sda1 (disks also match by mount point)",
+    "mount            ex: mount = /var",
+    "fs               ex: fs = ext4",
+    "used%, use%      ex: used% > 80",
+    "used             ex: used > 100 gb",
+    "free             ex: free < 10 gb",
+    "total            ex: total >= 1 tb",
+    "read, r/s        ex: read > 1 mb/s",
+    "write, w/s       ex: write > 1 mb/s",
+    "temp             ex: temp > 70",
+];
+
+pub const USER_HELP_TEXT: [&str; 18] = [
+    "9 - User widget",
+    "Enter            Show the selected user's processes in the process widget",
+    "c                Sort by total CPU usage",
+    "m                Sort by total memory usage",
+    "n                Sort by user name",
+    "[, ]             Sort by the previous or next column",
+    "I                Invert current sort",
+    "Ctrl-f, /        Open the search bar to filter the table",
+    "",
+    "Supported search types:",
+    "<by name>        ex: root",
+    "user             ex: user = root",
+    "count            ex: count > 10",
+    "cpu, cpu%        ex: cpu > 50",
+    "mem, mem%        ex: mem > 10",
+    "memb             ex: memb > 1 gb",
+    "read, r/s        ex: read > 1 mb/s",
+    "write, w/s       ex: write > 1 mb/s",
+];
+
 pub static HELP_TEXT: Lazy<Vec<Vec<&'static str>>> = Lazy::new(|| {
     vec![
         HELP_CONTENTS_TEXT.to_vec(),
@@ -369,6 +500,8 @@ pub static HELP_TEXT: Lazy<Vec<Vec<&'static str>>> = Lazy::new(|| {
         SORT_HELP_TEXT.to_vec(),
         BATTERY_HELP_TEXT.to_vec(),
         BASIC_MEM_HELP_TEXT.to_vec(),
+        TABLE_SEARCH_HELP_TEXT.to_vec(),
+        USER_HELP_TEXT.to_vec(),
     ]
 });
 
@@ -429,6 +562,10 @@ pub const DEFAULT_BATTERY_LAYOUT: &str = r##"
 
 // Config and flags
 pub const DEFAULT_CONFIG_FILE_PATH: &str = "bottom/bottom.toml";
+// Where chart history is saved, relative to the cache directory
+pub const DEFAULT_HISTORY_FILE_PATH: &str = "bottom/history";
+// Where resized column widths are saved, relative to the cache directory
+pub const DEFAULT_COLUMN_WIDTHS_FILE_PATH: &str = "bottom/column_widths.toml";
 
 // TODO: Eventually deprecate this.
 pub const CONFIG_TEXT: &str = r##"# This is a default config file for bottom.  All of the settings are commented
@@ -452,6 +589,8 @@ pub const CONFIG_TEXT: &str = r##"# This is a default config file for bottom.  A
 #current_usage = false
 # Whether to group processes with the same name together by default.
 #group_processes = false
+# How to group processes by default. One of "none", "name", "user", "parent", "tty" or "cgroup". Overrides group_processes.
+#group_by = "name"
 # Whether to make process searching case sensitive by default.
 #case_sensitive = false
 # Whether to make process searching look for matching the entire word by default.
@@ -469,6 +608,8 @@ pub const CONFIG_TEXT: &str = r##"# This is a default config file for bottom.  A
 #default_time_value = 60000
 # The time delta on each zoom in/out action (in milliseconds).
 #time_delta = 15000
+# How long chart data is kept (in milliseconds).  Anything older than 10 minutes is downsampled.
+#retention = 600000
 # Hides the time scale.
 #hide_time = false
 # Override layout default widget
@@ -494,6 +635,15 @@ pub const CONFIG_TEXT: &str = r##"# This is a default config file for bottom.  A
 #show_table_scroll_position = false
 # Show processes as their commands by default in the process widget.
 #process_command = false
+# The default process sort order.  Later columns break ties in earlier ones, and each column
+# can optionally be followed by "asc" or "desc".
+#default_sort = ["user asc", "cpu% desc"]
+# Which columns the process widget shows, in order.
+#columns = ["pid", "name", "cpu%", "mem%", "r/s", "w/s", "t.read", "t.write", "user", "state"]
+# Saves the column widths set by resizing a table to the cache directory.
+#persist_column_widths = false
+# Saves chart history to the cache directory, so charts are filled back in after restarting.
+#persist_history = false
 # Displays the network widget with binary prefixes.
 #network_use_binary_prefix = false
 # Displays the network widget using bytes.
@@ -570,6 +720,8 @@ pub const CONFIG_TEXT: &str = r##"# This is a default config file for bottom.  A
 #  [[row.child]]
 #    type="proc"
 #    default=true
+#    # Process widgets can also start with a search query, or the name of a saved query.
+#    #default_query="cpu > 1"
 
 
 # Filters - you can hide specific temperature sensors, network interfaces, and disks using filters.  This is admittedly
@@ -601,6 +753,27 @@ pub const CONFIG_TEXT: &str = r##"# This is a default config file for bottom.  A
 #regex = true
 #case_sensitive = false
 #whole_word = false
+
+# Saved queries - named process search queries, which can be opened in a process widget with F4 or Alt-s,
+# or set as a process widget's query with --query or default_query.
+#[queries]
+#busy = "cpu > 5 or mem > 10"
+#mine = "not user=root"
+
+# Column widths for the process, disk, temperature and user tables, by column header.  Columns that
+# aren't listed are sized automatically.
+#[column_widths]
+#proc = { Command = 60 }
+#disk = { Mount = 20 }
+
+# Pushes each collection to a metrics collector such as Telegraf, as InfluxDB line protocol or statsd.
+#[export]
+#target = "127.0.0.1:8094"
+#format = "influx"
+#protocol = "udp"
+#prefix = "bottom"
+#tags = { host = "my-machine" }
+#metrics = ["cpu", "memory", "network"]
 "##;
 
 pub const CONFIG_TOP_HEAD: &str = r##"# This is bottom's config file.
@@ -2,13 +2,20 @@
 //! can actually handle.
 use crate::{app::AxisScaling, units::data_units::DataUnit, Pid};
 use crate::{
-    app::{data_farmer, data_harvester, App, ProcWidgetState},
+    app::{
+        data_farmer, data_harvester,
+        query::{DiskQueryTarget, Query, TempQueryTarget},
+        time_series::Series,
+        App, ProcWidgetState, TreeCollapseState,
+    },
+    replay::ReplayStatus,
     utils::{self, gen_util::*},
 };
 use data_harvester::processes::ProcessSorting;
 use fxhash::FxBuildHasher;
 use indexmap::IndexSet;
 use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
 
 /// Point is of time, data
 type Point = (f64, f64);
@@ -64,6 +71,11 @@ pub struct ConvertedProcessData {
     pub process_state: String,
     pub process_char: char,
     pub user: Option<String>,
+    pub time: Duration,
+    /// The controlling terminal of the process, if any.
+    pub tty: Option<String>,
+    /// The cgroup of the process, if known.
+    pub cgroup: Option<String>,
 
     /// Prefix printed before the process when displayed.
     pub process_description_prefix: Option<String>,
@@ -71,6 +83,20 @@ pub struct ConvertedProcessData {
     pub is_disabled_entry: bool,
     /// Whether this entry is collapsed, hiding all its children (for tree mode).
     pub is_collapsed_entry: bool,
+    /// Whether this process was spawned recently, and should be highlighted.
+    pub is_new: bool,
+}
+
+/// The combined usage of every process owned by a single user.
+#[derive(Clone, Default, Debug)]
+pub struct ConvertedUserData {
+    pub user: String,
+    pub process_count: usize,
+    pub cpu_percent_usage: f64,
+    pub mem_percent_usage: f64,
+    pub mem_usage_bytes: u64,
+    pub rps_f64: f64,
+    pub wps_f64: f64,
 }
 
 #[derive(Clone, Default, Debug)]
@@ -83,13 +109,17 @@ pub struct ConvertedCpuData {
     pub legend_value: String,
 }
 
-pub fn convert_temp_row(app: &App) -> Vec<Vec<String>> {
+pub fn convert_temp_row(app: &App, temp_filter: Option<&Query>) -> Vec<Vec<String>> {
     let current_data = &app.data_collection;
     let temp_type = &app.app_config_fields.temperature_type;
 
     let mut sensor_vector: Vec<Vec<String>> = current_data
         .temp_harvest
         .iter()
+        .filter(|sensor| match temp_filter {
+            Some(temp_filter) => temp_filter.check_target(&TempQueryTarget { sensor }),
+            None => true,
+        })
         .map(|temp_harvest| {
             vec![
                 temp_harvest.name.clone(),
@@ -110,14 +140,27 @@ pub fn convert_temp_row(app: &App) -> Vec<Vec<String>> {
     sensor_vector
 }
 
-pub fn convert_disk_row(current_data: &data_farmer::DataCollection) -> Vec<Vec<String>> {
+pub fn convert_disk_row(
+    current_data: &data_farmer::DataCollection, disk_filter: Option<&Query>,
+) -> Vec<Vec<String>> {
     let mut disk_vector: Vec<Vec<String>> = Vec::new();
 
     current_data
         .disk_harvest
         .iter()
         .zip(&current_data.io_labels)
-        .for_each(|(disk, (io_read, io_write))| {
+        .enumerate()
+        .filter(|(itx, (disk, _))| match disk_filter {
+            Some(disk_filter) => disk_filter.check_target(&DiskQueryTarget {
+                disk,
+                io_rates: current_data
+                    .io_labels_and_prev
+                    .get(*itx)
+                    .map(|(io_rates, _)| *io_rates),
+            }),
+            None => true,
+        })
+        .for_each(|(_, (disk, (io_read, io_write)))| {
             let free_space_fmt = if let Some(free_space) = disk.free_space {
                 let converted_free_space = get_decimal_bytes(free_space);
                 format!("{:.*}{}", 0, converted_free_space.0, converted_free_space.1)
@@ -160,6 +203,64 @@ pub fn convert_disk_row(current_data: &data_farmer::DataCollection) -> Vec<Vec<S
     disk_vector
 }
 
+/// Gets how long before `current_time` each harvest was, in milliseconds, up to the harvest at
+/// `current_time`.  These are shared by every series.
+fn get_times_from_start(
+    current_data: &data_farmer::DataCollection, current_time: Instant,
+) -> Vec<f64> {
+    let mut times = Vec::with_capacity(current_data.timed_data.len());
+    for time in current_data.timed_data.instants() {
+        times.push((current_time.duration_since(time).as_millis() as f64).floor());
+        if time == current_time {
+            break;
+        }
+    }
+
+    times
+}
+
+/// Converts a series into points for a graph, with values scaled as needed.  For a downsampled
+/// bucket, a line is also drawn through its point from its lowest to highest value, so spikes
+/// still show when zoomed far out.
+fn get_series_points(
+    current_data: &data_farmer::DataCollection, series: Series, times_from_start: &[f64],
+    scale: impl Fn(f64) -> f64,
+) -> Vec<Point> {
+    let timed_data = &current_data.timed_data;
+    let mut points: Vec<Point> = Vec::with_capacity(times_from_start.len());
+    let (bucket_times, harvest_times) =
+        times_from_start.split_at(timed_data.bucket_count().min(times_from_start.len()));
+    for (time_from_start, (value, (min, max))) in
+        bucket_times.iter().zip(timed_data.bucket_values(series))
+    {
+        if value.is_nan() {
+            continue;
+        }
+
+        let value = scale(value);
+        points.extend(&[
+            (-time_from_start, value),
+            (-time_from_start, scale(min)),
+            (-time_from_start, scale(max)),
+            (-time_from_start, value),
+        ]);
+    }
+
+    // Harvests are read a slice at a time, as there are far more of them.
+    let (first_values, second_values) = timed_data.harvest_values(series);
+    let (first_times, second_times) =
+        harvest_times.split_at(first_values.len().min(harvest_times.len()));
+    for (times, values) in [(first_times, first_values), (second_times, second_values)] {
+        for (time_from_start, value) in times.iter().zip(values) {
+            if !value.is_nan() {
+                points.push((-time_from_start, scale(f64::from(*value))));
+            }
+        }
+    }
+
+    points
+}
+
 pub fn convert_cpu_data_points(
     current_data: &data_farmer::DataCollection, existing_cpu_data: &mut Vec<ConvertedCpuData>,
     is_frozen: bool,
@@ -175,7 +276,7 @@ pub fn convert_cpu_data_points(
     };
 
     // Initialize cpu_data_vector if the lengths don't match...
-    if let Some((_time, data)) = &current_data.timed_data_vec.last() {
+    if let Some((_time, data)) = &current_data.timed_data.last() {
         if data.cpu_data.len() + 1 != existing_cpu_data.len() {
             *existing_cpu_data = vec![ConvertedCpuData {
                 cpu_name: "All".to_string(),
@@ -225,25 +326,16 @@ pub fn convert_cpu_data_points(
         }
     }
 
-    for (time, data) in &current_data.timed_data_vec {
-        let time_from_start: f64 = (current_time.duration_since(*time).as_millis() as f64).floor();
-
-        for (itx, cpu) in data.cpu_data.iter().enumerate() {
-            if let Some(cpu_data) = existing_cpu_data.get_mut(itx + 1) {
-                cpu_data.cpu_data.push((-time_from_start, *cpu));
-            }
-        }
-
-        if *time == current_time {
-            break;
-        }
+    let times_from_start = get_times_from_start(current_data, current_time);
+    for (itx, cpu_data) in existing_cpu_data.iter_mut().skip(1).enumerate() {
+        cpu_data.cpu_data =
+            get_series_points(current_data, Series::Cpu(itx), &times_from_start, |cpu| cpu);
     }
 }
 
 pub fn convert_mem_data_points(
     current_data: &data_farmer::DataCollection, is_frozen: bool,
 ) -> Vec<Point> {
-    let mut result: Vec<Point> = Vec::new();
     let current_time = if is_frozen {
         if let Some(frozen_instant) = current_data.frozen_instant {
             frozen_instant
@@ -254,24 +346,13 @@ pub fn convert_mem_data_points(
         current_data.current_instant
     };
 
-    for (time, data) in &current_data.timed_data_vec {
-        if let Some(mem_data) = data.mem_data {
-            let time_from_start: f64 =
-                (current_time.duration_since(*time).as_millis() as f64).floor();
-            result.push((-time_from_start, mem_data));
-            if *time == current_time {
-                break;
-            }
-        }
-    }
-
-    result
+    let times_from_start = get_times_from_start(current_data, current_time);
+    get_series_points(current_data, Series::Mem, &times_from_start, |mem| mem)
 }
 
 pub fn convert_swap_data_points(
     current_data: &data_farmer::DataCollection, is_frozen: bool,
 ) -> Vec<Point> {
-    let mut result: Vec<Point> = Vec::new();
     let current_time = if is_frozen {
         if let Some(frozen_instant) = current_data.frozen_instant {
             frozen_instant
@@ -282,18 +363,8 @@ pub fn convert_swap_data_points(
         current_data.current_instant
     };
 
-    for (time, data) in &current_data.timed_data_vec {
-        if let Some(swap_data) = data.swap_data {
-            let time_from_start: f64 =
-                (current_time.duration_since(*time).as_millis() as f64).floor();
-            result.push((-time_from_start, swap_data));
-            if *time == current_time {
-                break;
-            }
-        }
-    }
-
-    result
+    let times_from_start = get_times_from_start(current_data, current_time);
+    get_series_points(current_data, Series::Swap, &times_from_start, |swap| swap)
 }
 
 pub fn convert_mem_labels(
@@ -370,9 +441,6 @@ pub fn get_rx_tx_data_points(
     current_data: &data_farmer::DataCollection, is_frozen: bool, network_scale_type: &AxisScaling,
     network_unit_type: &DataUnit, network_use_binary_prefix: bool,
 ) -> (Vec<Point>, Vec<Point>) {
-    let mut rx: Vec<Point> = Vec::new();
-    let mut tx: Vec<Point> = Vec::new();
-
     let current_time = if is_frozen {
         if let Some(frozen_instant) = current_data.frozen_instant {
             frozen_instant
@@ -383,42 +451,34 @@ pub fn get_rx_tx_data_points(
         current_data.current_instant
     };
 
-    for (time, data) in &current_data.timed_data_vec {
-        let time_from_start: f64 = (current_time.duration_since(*time).as_millis() as f64).floor();
-
-        let (rx_data, tx_data) = match network_scale_type {
-            AxisScaling::Log => {
-                if network_use_binary_prefix {
-                    match network_unit_type {
-                        DataUnit::Byte => {
-                            // As dividing by 8 is equal to subtracting 4 in base 2!
-                            ((data.rx_data).log2() - 4.0, (data.tx_data).log2() - 4.0)
-                        }
-                        DataUnit::Bit => ((data.rx_data).log2(), (data.tx_data).log2()),
-                    }
-                } else {
-                    match network_unit_type {
-                        DataUnit::Byte => {
-                            ((data.rx_data / 8.0).log10(), (data.tx_data / 8.0).log10())
-                        }
-                        DataUnit::Bit => ((data.rx_data).log10(), (data.tx_data).log10()),
+    let scale = |value: f64| match network_scale_type {
+        AxisScaling::Log => {
+            if network_use_binary_prefix {
+                match network_unit_type {
+                    DataUnit::Byte => {
+                        // As dividing by 8 is equal to subtracting 4 in base 2!
+                        value.log2() - 4.0
                     }
+                    DataUnit::Bit => value.log2(),
+                }
+            } else {
+                match network_unit_type {
+                    DataUnit::Byte => (value / 8.0).log10(),
+                    DataUnit::Bit => value.log10(),
                 }
             }
-            AxisScaling::Linear => match network_unit_type {
-                DataUnit::Byte => (data.rx_data / 8.0, data.tx_data / 8.0),
-                DataUnit::Bit => (data.rx_data, data.tx_data),
-            },
-        };
-
-        rx.push((-time_from_start, rx_data));
-        tx.push((-time_from_start, tx_data));
-        if *time == current_time {
-            break;
         }
-    }
+        AxisScaling::Linear => match network_unit_type {
+            DataUnit::Byte => value / 8.0,
+            DataUnit::Bit => value,
+        },
+    };
 
-    (rx, tx)
+    let times_from_start = get_times_from_start(current_data, current_time);
+    (
+        get_series_points(current_data, Series::Rx, &times_from_start, scale),
+        get_series_points(current_data, Series::Tx, &times_from_start, scale),
+    )
 }
 
 pub fn convert_network_data_points(
@@ -550,9 +610,60 @@ pub fn convert_network_data_points(
     }
 }
 
+/// How processes are grouped together into single rows in the process widget.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum ProcessGroupingType {
-    Grouped,
     Ungrouped,
+    Name,
+    User,
+    Parent,
+    Tty,
+    Cgroup,
+}
+
+impl ProcessGroupingType {
+    /// The grouping type that follows this one when cycling through them.
+    pub fn next(self) -> Self {
+        match self {
+            ProcessGroupingType::Ungrouped => ProcessGroupingType::Name,
+            ProcessGroupingType::Name => ProcessGroupingType::User,
+            ProcessGroupingType::User => ProcessGroupingType::Parent,
+            ProcessGroupingType::Parent => ProcessGroupingType::Tty,
+            ProcessGroupingType::Tty => ProcessGroupingType::Cgroup,
+            ProcessGroupingType::Cgroup => ProcessGroupingType::Ungrouped,
+        }
+    }
+
+    /// A short description of the grouping, used in the process widget's title.
+    pub fn get_description(self) -> Option<&'static str> {
+        match self {
+            ProcessGroupingType::Ungrouped => None,
+            ProcessGroupingType::Name => Some("by name"),
+            ProcessGroupingType::User => Some("by user"),
+            ProcessGroupingType::Parent => Some("by parent"),
+            ProcessGroupingType::Tty => Some("by tty"),
+            ProcessGroupingType::Cgroup => Some("by cgroup"),
+        }
+    }
+}
+
+impl std::str::FromStr for ProcessGroupingType {
+    type Err = crate::utils::error::BottomError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "none" => Ok(ProcessGroupingType::Ungrouped),
+            "name" => Ok(ProcessGroupingType::Name),
+            "user" => Ok(ProcessGroupingType::User),
+            "parent" => Ok(ProcessGroupingType::Parent),
+            "tty" | "session" => Ok(ProcessGroupingType::Tty),
+            "cgroup" | "container" => Ok(ProcessGroupingType::Cgroup),
+            _ => Err(crate::utils::error::BottomError::ConfigError(format!(
+                "\"{}\" is an invalid grouping, use one of \"<none|name|user|parent|tty|cgroup>\".",
+                s
+            ))),
+        }
+    }
 }
 
 pub enum ProcessNamingType {
@@ -622,6 +733,10 @@ pub fn convert_process_data(
         );
 
         let mem_usage_str = get_binary_bytes(process.mem_usage_bytes);
+        let is_new = matches!(
+            current_data.tracked_processes.get(&process.pid),
+            Some(tracked) if tracked.is_new()
+        );
 
         let user = {
             #[cfg(target_family = "unix")]
@@ -663,6 +778,10 @@ pub fn convert_process_data(
                 process_entry.process_description_prefix = None;
                 process_entry.is_disabled_entry = false;
                 process_entry.user = user;
+                process_entry.time = process.time;
+                process_entry.tty = process.tty.clone();
+                process_entry.cgroup = process.cgroup.clone();
+                process_entry.is_new = is_new;
             } else {
                 // ...I hate that I can't combine if let and an if statement in one line...
                 *process_entry = ConvertedProcessData {
@@ -690,6 +809,10 @@ pub fn convert_process_data(
                     is_disabled_entry: false,
                     is_collapsed_entry: false,
                     user,
+                    time: process.time,
+                    tty: process.tty.clone(),
+                    cgroup: process.cgroup.clone(),
+                    is_new,
                 };
             }
         } else {
@@ -720,6 +843,10 @@ pub fn convert_process_data(
                     is_disabled_entry: false,
                     is_collapsed_entry: false,
                     user,
+                    time: process.time,
+                    tty: process.tty.clone(),
+                    cgroup: process.cgroup.clone(),
+                    is_new,
                 },
             );
         }
@@ -731,20 +858,284 @@ pub fn convert_process_data(
     })
 }
 
+/// Sums up the usage of the harvested processes for each user.  Processes whose user can't be
+/// determined are counted under "N/A".
+pub fn convert_user_data(
+    current_data: &data_farmer::DataCollection,
+    #[cfg(target_family = "unix")] user_table: &mut data_harvester::processes::UserTable,
+) -> Vec<ConvertedUserData> {
+    let mut user_data: HashMap<String, ConvertedUserData> = HashMap::new();
+
+    for process in &current_data.process_harvest {
+        let user = {
+            #[cfg(target_family = "unix")]
+            {
+                process
+                    .uid
+                    .and_then(|uid| user_table.get_uid_to_username_mapping(uid).ok())
+            }
+            #[cfg(not(target_family = "unix"))]
+            {
+                None
+            }
+        }
+        .unwrap_or_else(|| "N/A".to_string());
+
+        let entry = user_data
+            .entry(user.clone())
+            .or_insert_with(|| ConvertedUserData {
+                user,
+                ..ConvertedUserData::default()
+            });
+        entry.process_count += 1;
+        entry.cpu_percent_usage += process.cpu_usage_percent;
+        entry.mem_percent_usage += process.mem_usage_percent;
+        entry.mem_usage_bytes += process.mem_usage_bytes;
+        entry.rps_f64 += process.read_bytes_per_sec as f64;
+        entry.wps_f64 += process.write_bytes_per_sec as f64;
+    }
+
+    user_data.into_values().collect()
+}
+
+/// Turns a user's usage into the strings displayed in each column of the user widget.
+pub fn stringify_user_data(user_data: &ConvertedUserData) -> Vec<String> {
+    let (rps, wps, _total_read, _total_write) =
+        get_disk_io_strings(user_data.rps_f64 as u64, user_data.wps_f64 as u64, 0, 0);
+    let (mem_value, mem_unit) = get_binary_bytes(user_data.mem_usage_bytes);
+
+    vec![
+        user_data.user.clone(),
+        user_data.process_count.to_string(),
+        format!("{:.1}%", user_data.cpu_percent_usage),
+        if user_data.mem_usage_bytes <= GIBI_LIMIT {
+            format!("{:.0}{}", mem_value, mem_unit)
+        } else {
+            format!("{:.1}{}", mem_value, mem_unit)
+        },
+        format!("{:.1}%", user_data.mem_percent_usage),
+        rps,
+        wps,
+    ]
+}
+
+/// Formats a duration as its two largest units, like `1h 5m` or `42s`.
+pub fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    let (days, hours, mins, secs) = (
+        secs / 86400,
+        (secs / 3600) % 24,
+        (secs / 60) % 60,
+        secs % 60,
+    );
+
+    if days > 0 {
+        format!("{}d {}h", days, hours)
+    } else if hours > 0 {
+        format!("{}h {}m", hours, mins)
+    } else if mins > 0 {
+        format!("{}m {}s", mins, secs)
+    } else {
+        format!("{}s", secs)
+    }
+}
+
+/// Describes where a replay is at, like `Replaying 2021-06-01 03:12:05 UTC (1h 2m of 8h 0m) at 4x`.
+pub fn stringify_replay_status(replay_status: &ReplayStatus) -> String {
+    let recorded_time = time::OffsetDateTime::from_unix_timestamp_nanos(
+        replay_status.timestamp as i128 * 1_000_000,
+    )
+    .ok()
+    .and_then(|recorded_time| {
+        recorded_time
+            .format(&time::macros::format_description!(
+                "[year]-[month]-[day] [hour]:[minute]:[second] UTC"
+            ))
+            .ok()
+    })
+    .unwrap_or_default();
+
+    format!(
+        "Replaying {} ({} of {}) at {}x{}",
+        recorded_time,
+        format_duration(replay_status.position),
+        format_duration(replay_status.duration),
+        replay_status.speed,
+        if replay_status.is_paused {
+            ", paused"
+        } else {
+            ""
+        }
+    )
+}
+
+/// Turns an exited process into the strings displayed in each column of the exited process list.
+pub fn stringify_exited_process(
+    exited_process: &data_farmer::ExitedProcess, current_instant: std::time::Instant,
+) -> Vec<String> {
+    let (mem_value, mem_unit) = get_binary_bytes(exited_process.peak_mem_bytes);
+
+    vec![
+        exited_process.name.clone(),
+        exited_process.pid.to_string(),
+        format_duration(exited_process.lifetime),
+        format!("{:.1}%", exited_process.peak_cpu_percent),
+        if exited_process.peak_mem_bytes <= GIBI_LIMIT {
+            format!("{:.0}{}", mem_value, mem_unit)
+        } else {
+            format!("{:.1}{}", mem_value, mem_unit)
+        },
+        format!(
+            "{} ago",
+            format_duration(current_instant.saturating_duration_since(exited_process.exit_instant))
+        ),
+    ]
+}
+
 const BRANCH_ENDING: char = '└';
 const BRANCH_VERTICAL: char = '│';
 const BRANCH_SPLIT: char = '├';
 const BRANCH_HORIZONTAL: char = '─';
 
+/// Builds up a mapping of each process' PID to the PIDs of its children.  Every process gets an
+/// entry, even if it has no children; parents that aren't in the given processes get one too.
+pub fn get_parent_child_mapping<'a>(
+    process_data: impl Iterator<Item = &'a ConvertedProcessData>,
+) -> HashMap<Pid, IndexSet<Pid, FxBuildHasher>> {
+    let mut parent_child_mapping: HashMap<Pid, IndexSet<Pid, FxBuildHasher>> = HashMap::default();
+
+    process_data.for_each(|process| {
+        // Create a mapping for the process if it DNE.
+        parent_child_mapping
+            .entry(process.pid)
+            .or_insert_with(|| IndexSet::with_hasher(FxBuildHasher::default()));
+
+        // Insert its mapping to the process' parent if needed (create if it DNE).
+        if let Some(ppid) = process.ppid {
+            parent_child_mapping
+                .entry(ppid)
+                .or_insert_with(|| IndexSet::with_hasher(FxBuildHasher::default()))
+                .insert(process.pid);
+        }
+    });
+
+    parent_child_mapping
+}
+
+/// A process' value for one sort key.  Text is lowercased up front, so that it's done once per
+/// process rather than on every comparison.
+enum ProcessSortValue {
+    Number(f64),
+    Text(String),
+    User(Option<String>),
+}
+
+impl ProcessSortValue {
+    fn new(process: &ConvertedProcessData, sort_type: &ProcessSorting) -> Self {
+        match sort_type {
+            ProcessSorting::CpuPercent => ProcessSortValue::Number(process.cpu_percent_usage),
+            ProcessSorting::Mem => ProcessSortValue::Number(process.mem_usage_bytes as f64),
+            ProcessSorting::MemPercent => ProcessSortValue::Number(process.mem_percent_usage),
+            ProcessSorting::ProcessName => ProcessSortValue::Text(process.name.to_lowercase()),
+            ProcessSorting::Command => ProcessSortValue::Text(process.command.to_lowercase()),
+            ProcessSorting::Pid => ProcessSortValue::Number(process.pid as f64),
+            ProcessSorting::ReadPerSecond => ProcessSortValue::Number(process.rps_f64),
+            ProcessSorting::WritePerSecond => ProcessSortValue::Number(process.wps_f64),
+            ProcessSorting::TotalRead => ProcessSortValue::Number(process.tr_f64),
+            ProcessSorting::TotalWrite => ProcessSortValue::Number(process.tw_f64),
+            ProcessSorting::State => ProcessSortValue::Text(process.process_state.to_lowercase()),
+            ProcessSorting::User => {
+                ProcessSortValue::User(process.user.as_ref().map(|user| user.to_lowercase()))
+            }
+            ProcessSorting::Count => ProcessSortValue::Number(process.group_pids.len() as f64),
+        }
+    }
+
+    fn compare(&self, other: &Self, is_sort_descending: bool) -> std::cmp::Ordering {
+        match (self, other) {
+            (ProcessSortValue::Number(a), ProcessSortValue::Number(b)) => {
+                get_ordering(a, b, is_sort_descending)
+            }
+            (ProcessSortValue::Text(a), ProcessSortValue::Text(b)) => {
+                get_ordering(a, b, is_sort_descending)
+            }
+            (ProcessSortValue::User(a), ProcessSortValue::User(b)) => match (a, b) {
+                (Some(user_a), Some(user_b)) => get_ordering(user_a, user_b, is_sort_descending),
+                // Processes without a user always go at the bottom.
+                (Some(_), None) => std::cmp::Ordering::Less,
+                (None, Some(_)) => std::cmp::Ordering::Greater,
+                (None, None) => std::cmp::Ordering::Equal,
+            },
+            _ => std::cmp::Ordering::Equal,
+        }
+    }
+}
+
+/// Sorts by each sort key in turn, with later keys breaking ties.  The sort is stable, so
+/// anything left tied keeps its order.
+pub fn sort_process_data<T>(
+    to_sort_vec: &mut Vec<T>, sort_keys: &[(ProcessSorting, bool)],
+    get_process: impl Fn(&T) -> &ConvertedProcessData,
+) {
+    let mut keyed_vec = to_sort_vec
+        .drain(..)
+        .map(|item| {
+            let process = get_process(&item);
+            let sort_values = sort_keys
+                .iter()
+                .map(|(sort_type, _is_descending)| ProcessSortValue::new(process, sort_type))
+                .collect::<Vec<_>>();
+            (sort_values, item)
+        })
+        .collect::<Vec<_>>();
+
+    keyed_vec.sort_by(|(a_values, _a), (b_values, _b)| {
+        a_values.iter().zip(b_values).zip(sort_keys).fold(
+            std::cmp::Ordering::Equal,
+            |ordering, ((a_value, b_value), (_sort_type, is_descending))| {
+                ordering.then_with(|| a_value.compare(b_value, *is_descending))
+            },
+        )
+    });
+
+    to_sort_vec.extend(keyed_vec.into_iter().map(|(_sort_values, item)| item));
+}
+
+/// Sorts the user widget's rows by one of its columns, breaking ties by user name.
+pub fn sort_user_data(
+    user_data: &mut [ConvertedUserData], sort_type: &ProcessSorting, is_sort_descending: bool,
+) {
+    user_data.sort_by_cached_key(|user| user.user.to_lowercase());
+    user_data.sort_by(|a, b| match sort_type {
+        ProcessSorting::Count => get_ordering(a.process_count, b.process_count, is_sort_descending),
+        ProcessSorting::CpuPercent => {
+            get_ordering(a.cpu_percent_usage, b.cpu_percent_usage, is_sort_descending)
+        }
+        ProcessSorting::Mem => {
+            get_ordering(a.mem_usage_bytes, b.mem_usage_bytes, is_sort_descending)
+        }
+        ProcessSorting::MemPercent => {
+            get_ordering(a.mem_percent_usage, b.mem_percent_usage, is_sort_descending)
+        }
+        ProcessSorting::ReadPerSecond => get_ordering(a.rps_f64, b.rps_f64, is_sort_descending),
+        ProcessSorting::WritePerSecond => get_ordering(a.wps_f64, b.wps_f64, is_sort_descending),
+        _ => get_ordering(
+            a.user.to_lowercase(),
+            b.user.to_lowercase(),
+            is_sort_descending,
+        ),
+    });
+}
+
 pub fn tree_process_data(
     filtered_process_data: &[ConvertedProcessData], is_using_command: bool,
-    sorting_type: &ProcessSorting, is_sort_descending: bool,
+    sort_keys: &[(ProcessSorting, bool)], collapse_state: &TreeCollapseState,
 ) -> Vec<ConvertedProcessData> {
     // TODO: [TREE] Option to sort usage by total branch usage or individual value usage?
 
     // Let's first build up a (really terrible) parent -> child mapping...
     // At the same time, let's make a mapping of PID -> process data!
-    let mut parent_child_mapping: HashMap<Pid, IndexSet<Pid, FxBuildHasher>> = HashMap::default();
+    let mut parent_child_mapping = get_parent_child_mapping(filtered_process_data.iter());
     let mut pid_process_mapping: HashMap<Pid, &ConvertedProcessData> = HashMap::default(); // We actually already have this stored, but it's unfiltered... oh well.
     let mut orphan_set: IndexSet<Pid, FxBuildHasher> =
         IndexSet::with_hasher(FxBuildHasher::default());
@@ -759,23 +1150,10 @@ pub fn tree_process_data(
     });
 
     filtered_process_data.iter().for_each(|process| {
-        // Create a mapping for the process if it DNE.
-        parent_child_mapping
-            .entry(process.pid)
-            .or_insert_with(|| IndexSet::with_hasher(FxBuildHasher::default()));
         pid_process_mapping.insert(process.pid, process);
 
-        if process.is_collapsed_entry {
-            collapsed_set.insert(process.pid);
-        }
-
-        // Insert its mapping to the process' parent if needed (create if it DNE).
-        if let Some(ppid) = process.ppid {
+        if process.ppid.is_some() {
             orphan_set.remove(&process.pid);
-            parent_child_mapping
-                .entry(ppid)
-                .or_insert_with(|| IndexSet::with_hasher(FxBuildHasher::default()))
-                .insert(process.pid);
         }
     });
 
@@ -836,7 +1214,7 @@ pub fn tree_process_data(
     }
 
     fn sort_remaining_pids(
-        current_pid: Pid, sort_type: &ProcessSorting, is_sort_descending: bool,
+        current_pid: Pid, sort_keys: &[(ProcessSorting, bool)],
         parent_child_mapping: &mut HashMap<Pid, IndexSet<Pid, FxBuildHasher>>,
         pid_process_mapping: &HashMap<Pid, &ConvertedProcessData>,
     ) {
@@ -860,15 +1238,14 @@ pub fn tree_process_data(
                 }
                 sort_remaining_pids(
                     child_pid,
-                    sort_type,
-                    is_sort_descending,
+                    sort_keys,
                     parent_child_mapping,
                     pid_process_mapping,
                 );
             }
 
             // Now let's sort the immediate children!
-            sort_vec(&mut to_sort_vec, sort_type, is_sort_descending);
+            sort_vec(&mut to_sort_vec, sort_keys);
 
             // Need to reverse what we got, apparently...
             if let Some(current_mapping) = parent_child_mapping.get_mut(&current_pid) {
@@ -882,116 +1259,31 @@ pub fn tree_process_data(
     }
 
     fn sort_vec(
-        to_sort_vec: &mut Vec<(Pid, &ConvertedProcessData)>, sort_type: &ProcessSorting,
-        is_sort_descending: bool,
+        to_sort_vec: &mut Vec<(Pid, &ConvertedProcessData)>, sort_keys: &[(ProcessSorting, bool)],
     ) {
-        // Sort by PID first (descending)
+        // Sort by PID first (ascending), then by each sort key in turn.
         to_sort_vec.sort_by(|a, b| utils::gen_util::get_ordering(a.1.pid, b.1.pid, false));
-
-        match sort_type {
-            ProcessSorting::CpuPercent => {
-                to_sort_vec.sort_by(|a, b| {
-                    utils::gen_util::get_ordering(
-                        a.1.cpu_percent_usage,
-                        b.1.cpu_percent_usage,
-                        is_sort_descending,
-                    )
-                });
-            }
-            ProcessSorting::Mem => {
-                to_sort_vec.sort_by(|a, b| {
-                    utils::gen_util::get_ordering(
-                        a.1.mem_usage_bytes,
-                        b.1.mem_usage_bytes,
-                        is_sort_descending,
-                    )
-                });
-            }
-            ProcessSorting::MemPercent => {
-                to_sort_vec.sort_by(|a, b| {
-                    utils::gen_util::get_ordering(
-                        a.1.mem_percent_usage,
-                        b.1.mem_percent_usage,
-                        is_sort_descending,
-                    )
-                });
-            }
-            ProcessSorting::ProcessName => {
-                to_sort_vec.sort_by(|a, b| {
-                    utils::gen_util::get_ordering(
-                        &a.1.name.to_lowercase(),
-                        &b.1.name.to_lowercase(),
-                        is_sort_descending,
-                    )
-                });
-            }
-            ProcessSorting::Command => to_sort_vec.sort_by(|a, b| {
-                utils::gen_util::get_ordering(
-                    &a.1.command.to_lowercase(),
-                    &b.1.command.to_lowercase(),
-                    is_sort_descending,
-                )
-            }),
-            ProcessSorting::Pid => {
-                if is_sort_descending {
-                    to_sort_vec.sort_by(|a, b| {
-                        utils::gen_util::get_ordering(a.0, b.0, is_sort_descending)
-                    });
-                }
-            }
-            ProcessSorting::ReadPerSecond => {
-                to_sort_vec.sort_by(|a, b| {
-                    utils::gen_util::get_ordering(a.1.rps_f64, b.1.rps_f64, is_sort_descending)
-                });
-            }
-            ProcessSorting::WritePerSecond => {
-                to_sort_vec.sort_by(|a, b| {
-                    utils::gen_util::get_ordering(a.1.wps_f64, b.1.wps_f64, is_sort_descending)
-                });
-            }
-            ProcessSorting::TotalRead => {
-                to_sort_vec.sort_by(|a, b| {
-                    utils::gen_util::get_ordering(a.1.tr_f64, b.1.tr_f64, is_sort_descending)
-                });
-            }
-            ProcessSorting::TotalWrite => {
-                to_sort_vec.sort_by(|a, b| {
-                    utils::gen_util::get_ordering(a.1.tw_f64, b.1.tw_f64, is_sort_descending)
-                });
-            }
-            ProcessSorting::State => to_sort_vec.sort_by(|a, b| {
-                utils::gen_util::get_ordering(
-                    &a.1.process_state.to_lowercase(),
-                    &b.1.process_state.to_lowercase(),
-                    is_sort_descending,
-                )
-            }),
-            ProcessSorting::User => to_sort_vec.sort_by(|a, b| match (&a.1.user, &b.1.user) {
-                (Some(user_a), Some(user_b)) => utils::gen_util::get_ordering(
-                    user_a.to_lowercase(),
-                    user_b.to_lowercase(),
-                    is_sort_descending,
-                ),
-                (Some(_), None) => std::cmp::Ordering::Less,
-                (None, Some(_)) => std::cmp::Ordering::Greater,
-                (None, None) => std::cmp::Ordering::Less,
-            }),
-            ProcessSorting::Count => {
-                // Should never occur in this case, tree mode explicitly disables grouping.
-            }
-        }
+        sort_process_data(to_sort_vec, sort_keys, |(_pid, process)| *process);
     }
 
     /// A DFS traversal to correctly build the prefix lines (the pretty '├' and '─' lines) and
-    /// the correct order to the PID tree as a vector.
+    /// the correct order to the PID tree as a vector.  Also marks which PIDs are collapsed.
     fn build_explored_pids(
-        current_pid: Pid, parent_child_mapping: &HashMap<Pid, IndexSet<Pid, FxBuildHasher>>,
-        prev_drawn_lines: &str, collapsed_set: &IndexSet<Pid, FxBuildHasher>,
+        current_pid: Pid, depth: usize,
+        parent_child_mapping: &HashMap<Pid, IndexSet<Pid, FxBuildHasher>>,
+        pid_process_mapping: &HashMap<Pid, &ConvertedProcessData>, prev_drawn_lines: &str,
+        collapse_state: &TreeCollapseState, collapsed_set: &mut IndexSet<Pid, FxBuildHasher>,
     ) -> (Vec<Pid>, Vec<String>) {
         let mut explored_pids: Vec<Pid> = vec![current_pid];
         let mut lines: Vec<String> = vec![];
 
-        if collapsed_set.contains(&current_pid) {
+        let is_collapsed = match pid_process_mapping.get(&current_pid) {
+            Some(process) => collapse_state.is_collapsed(current_pid, &process.name, depth),
+            None => false,
+        };
+
+        if is_collapsed {
+            collapsed_set.insert(current_pid);
             return (explored_pids, lines);
         } else if let Some(children) = parent_child_mapping.get(&current_pid) {
             for (itx, child) in children.iter().rev().enumerate() {
@@ -1003,8 +1295,11 @@ pub fn tree_process_data(
 
                 let (pid_res, branch_res) = build_explored_pids(
                     *child,
+                    depth + 1,
                     parent_child_mapping,
+                    pid_process_mapping,
                     new_drawn_lines.as_str(),
+                    collapse_state,
                     collapsed_set,
                 );
 
@@ -1100,21 +1395,27 @@ pub fn tree_process_data(
             to_sort_vec.push((pid, *process));
         }
     }
-    sort_vec(&mut to_sort_vec, sorting_type, is_sort_descending);
+    sort_vec(&mut to_sort_vec, sort_keys);
     pids_to_explore = to_sort_vec.iter().map(|(pid, _proc)| *pid).collect();
 
     while let Some(current_pid) = pids_to_explore.pop_front() {
         if !prune_disabled_pids(current_pid, &mut parent_child_mapping, &pid_process_mapping) {
             sort_remaining_pids(
                 current_pid,
-                sorting_type,
-                is_sort_descending,
+                sort_keys,
                 &mut parent_child_mapping,
                 &pid_process_mapping,
             );
 
-            let (pid_res, branch_res) =
-                build_explored_pids(current_pid, &parent_child_mapping, "", &collapsed_set);
+            let (pid_res, branch_res) = build_explored_pids(
+                current_pid,
+                0,
+                &parent_child_mapping,
+                &pid_process_mapping,
+                "",
+                collapse_state,
+                &mut collapsed_set,
+            );
             lines.push(String::default());
             lines.extend(branch_res);
             explored_pids.extend(pid_res);
@@ -1129,6 +1430,11 @@ pub fn tree_process_data(
         .filter_map(|(pid, prefix)| match pid_process_mapping.get(pid) {
             Some(process) => {
                 let mut p = (*process).clone();
+                let has_children = match parent_child_mapping.get(&p.pid) {
+                    Some(children) => !children.is_empty(),
+                    None => false,
+                };
+                p.is_collapsed_entry = has_children && collapsed_set.contains(&p.pid);
                 p.process_description_prefix = Some(format!(
                     "{}{}{}",
                     prefix,
@@ -1146,54 +1452,35 @@ pub fn tree_process_data(
                 // Note that this will technically be "missing" entries, it collapses + sums based on what is visible
                 // since this runs *after* pruning steps.
                 if p.is_collapsed_entry {
-                    if let Some(children) = parent_child_mapping.get(&p.pid) {
-                        // Do some rounding.
-                        p.cpu_percent_usage = (p.cpu_percent_usage * 10.0).round() / 10.0;
-                        p.mem_percent_usage = (p.mem_percent_usage * 10.0).round() / 10.0;
-                        p.rps_f64 = (p.rps_f64 * 10.0).round() / 10.0;
-                        p.wps_f64 = (p.wps_f64 * 10.0).round() / 10.0;
-                        p.tr_f64 = (p.tr_f64 * 10.0).round() / 10.0;
-                        p.tw_f64 = (p.tw_f64 * 10.0).round() / 10.0;
-
-                        for &child_pid in children {
-                            // Let's just do a simple DFS traversal...
-                            let (
-                                child_cpu,
-                                child_mem_percent,
-                                child_mem,
-                                child_rps,
-                                child_wps,
-                                child_total_read,
-                                child_total_write,
-                            ) = get_usage_of_all_children(
-                                child_pid,
-                                &parent_child_mapping,
-                                &pid_process_mapping,
-                            );
-
-                            p.cpu_percent_usage += child_cpu;
-                            p.mem_percent_usage += child_mem_percent;
-                            p.mem_usage_bytes += child_mem;
-                            p.rps_f64 += child_rps;
-                            p.wps_f64 += child_wps;
-                            p.tr_f64 += child_total_read;
-                            p.tw_f64 += child_total_write;
-                        }
-
-                        let disk_io_strings = get_disk_io_strings(
-                            p.rps_f64 as u64,
-                            p.wps_f64 as u64,
-                            p.tr_f64 as u64,
-                            p.tw_f64 as u64,
+                    // The subtree's usage includes the (rounded) usage of the process itself.
+                    let (cpu, mem_percent, mem, rps, wps, total_read, total_write) =
+                        get_usage_of_all_children(
+                            p.pid,
+                            &parent_child_mapping,
+                            &pid_process_mapping,
                         );
 
-                        p.mem_usage_str = get_binary_bytes(p.mem_usage_bytes);
+                    p.cpu_percent_usage = cpu;
+                    p.mem_percent_usage = mem_percent;
+                    p.mem_usage_bytes = mem;
+                    p.rps_f64 = rps;
+                    p.wps_f64 = wps;
+                    p.tr_f64 = total_read;
+                    p.tw_f64 = total_write;
+
+                    let disk_io_strings = get_disk_io_strings(
+                        p.rps_f64 as u64,
+                        p.wps_f64 as u64,
+                        p.tr_f64 as u64,
+                        p.tw_f64 as u64,
+                    );
+
+                    p.mem_usage_str = get_binary_bytes(p.mem_usage_bytes);
 
-                        p.read_per_sec = disk_io_strings.0;
-                        p.write_per_sec = disk_io_strings.1;
-                        p.total_read = disk_io_strings.2;
-                        p.total_write = disk_io_strings.3;
-                    }
+                    p.read_per_sec = disk_io_strings.0;
+                    p.write_per_sec = disk_io_strings.1;
+                    p.total_read = disk_io_strings.2;
+                    p.total_write = disk_io_strings.3;
                 }
 
                 Some(p)
@@ -1207,80 +1494,72 @@ pub fn tree_process_data(
 pub fn stringify_process_data(
     proc_widget_state: &ProcWidgetState, finalized_process_data: &[ConvertedProcessData],
 ) -> Vec<(Vec<(String, Option<String>)>, bool)> {
-    let is_proc_widget_grouped = proc_widget_state.is_grouped;
-    let is_using_command = proc_widget_state.is_using_command;
     let is_tree = proc_widget_state.is_tree_mode;
-    let mem_enabled = proc_widget_state.columns.is_enabled(&ProcessSorting::Mem);
+    let shown_columns = proc_widget_state.columns.get_shown_columns();
 
     finalized_process_data
         .iter()
         .map(|process| {
             (
-                vec![
-                    (
-                        if is_proc_widget_grouped {
-                            process.group_pids.len().to_string()
-                        } else {
-                            process.pid.to_string()
-                        },
-                        None,
-                    ),
-                    (
-                        if is_tree {
+                shown_columns
+                    .iter()
+                    .map(|column| match column {
+                        ProcessSorting::Count => (process.group_pids.len().to_string(), None),
+                        ProcessSorting::Pid => (process.pid.to_string(), None),
+                        ProcessSorting::ProcessName | ProcessSorting::Command if is_tree => (
                             if let Some(prefix) = &process.process_description_prefix {
                                 prefix.clone()
                             } else {
                                 String::default()
-                            }
-                        } else if is_using_command {
-                            process.command.clone()
-                        } else {
-                            process.name.clone()
-                        },
-                        None,
-                    ),
-                    (format!("{:.1}%", process.cpu_percent_usage), None),
-                    (
-                        if mem_enabled {
+                            },
+                            None,
+                        ),
+                        ProcessSorting::ProcessName => (process.name.clone(), None),
+                        ProcessSorting::Command => (process.command.clone(), None),
+                        ProcessSorting::CpuPercent => {
+                            (format!("{:.1}%", process.cpu_percent_usage), None)
+                        }
+                        ProcessSorting::Mem => (
                             if process.mem_usage_bytes <= GIBI_LIMIT {
                                 format!("{:.0}{}", process.mem_usage_str.0, process.mem_usage_str.1)
                             } else {
                                 format!("{:.1}{}", process.mem_usage_str.0, process.mem_usage_str.1)
-                            }
-                        } else {
-                            format!("{:.1}%", process.mem_percent_usage)
-                        },
-                        None,
-                    ),
-                    (process.read_per_sec.clone(), None),
-                    (process.write_per_sec.clone(), None),
-                    (process.total_read.clone(), None),
-                    (process.total_write.clone(), None),
-                    #[cfg(target_family = "unix")]
-                    (
-                        if let Some(user) = &process.user {
-                            user.clone()
-                        } else {
-                            "N/A".to_string()
-                        },
-                        None,
-                    ),
-                    (
-                        process.process_state.clone(),
-                        Some(process.process_char.to_string()),
-                    ),
-                ],
+                            },
+                            None,
+                        ),
+                        ProcessSorting::MemPercent => {
+                            (format!("{:.1}%", process.mem_percent_usage), None)
+                        }
+                        ProcessSorting::ReadPerSecond => (process.read_per_sec.clone(), None),
+                        ProcessSorting::WritePerSecond => (process.write_per_sec.clone(), None),
+                        ProcessSorting::TotalRead => (process.total_read.clone(), None),
+                        ProcessSorting::TotalWrite => (process.total_write.clone(), None),
+                        ProcessSorting::User => (
+                            if let Some(user) = &process.user {
+                                user.clone()
+                            } else {
+                                "N/A".to_string()
+                            },
+                            None,
+                        ),
+                        ProcessSorting::State => (
+                            process.process_state.clone(),
+                            Some(process.process_char.to_string()),
+                        ),
+                    })
+                    .collect(),
                 process.is_disabled_entry,
             )
         })
         .collect()
 }
 
-/// Takes a set of converted process data and groups it together.
-///
-/// To be honest, I really don't like how this is done, even though I've rewritten this like 3 times.
+/// Groups processes into single rows based on the given grouping type, summing up their usage.
+/// `all_process_data` is used to look up parent names when grouping by parent, as a parent may
+/// have been filtered out of `single_process_data`.
 pub fn group_process_data(
-    single_process_data: &[ConvertedProcessData], is_using_command: bool,
+    single_process_data: &[ConvertedProcessData], grouping_type: ProcessGroupingType,
+    is_using_command: bool, all_process_data: &HashMap<Pid, ConvertedProcessData>,
 ) -> Vec<ConvertedProcessData> {
     #[derive(Clone, Default, Debug)]
     struct SingleProcessData {
@@ -1294,17 +1573,45 @@ pub fn group_process_data(
         pub total_read: f64,
         pub total_write: f64,
         pub process_state: String,
+        pub time: Duration,
+        pub is_new: bool,
     }
 
     let mut grouped_hashmap: HashMap<String, SingleProcessData> = std::collections::HashMap::new();
 
     single_process_data.iter().for_each(|process| {
+        let identifier = match grouping_type {
+            ProcessGroupingType::Ungrouped | ProcessGroupingType::Name => {
+                if is_using_command {
+                    process.command.to_string()
+                } else {
+                    process.name.to_string()
+                }
+            }
+            ProcessGroupingType::User => process
+                .user
+                .clone()
+                .unwrap_or_else(|| "(unknown user)".to_string()),
+            ProcessGroupingType::Parent => match process.ppid {
+                Some(ppid) => match all_process_data.get(&ppid) {
+                    Some(parent) if is_using_command => format!("{} ({})", parent.command, ppid),
+                    Some(parent) => format!("{} ({})", parent.name, ppid),
+                    None => format!("({})", ppid),
+                },
+                None => "(no parent)".to_string(),
+            },
+            ProcessGroupingType::Tty => process
+                .tty
+                .clone()
+                .unwrap_or_else(|| "(no tty)".to_string()),
+            ProcessGroupingType::Cgroup => process
+                .cgroup
+                .clone()
+                .unwrap_or_else(|| "(no cgroup)".to_string()),
+        };
+
         let entry = grouped_hashmap
-            .entry(if is_using_command {
-                process.command.to_string()
-            } else {
-                process.name.to_string()
-            })
+            .entry(identifier)
             .or_insert(SingleProcessData {
                 pid: process.pid,
                 ..SingleProcessData::default()
@@ -1318,6 +1625,8 @@ pub fn group_process_data(
         (*entry).write_per_sec += process.wps_f64;
         (*entry).total_read += process.tr_f64;
         (*entry).total_write += process.tw_f64;
+        entry.time = entry.time.max(process.time);
+        entry.is_new |= process.is_new;
     });
 
     grouped_hashmap
@@ -1356,7 +1665,23 @@ pub fn group_process_data(
                 process_char: char::default(),
                 is_disabled_entry: false,
                 is_collapsed_entry: false,
-                user: None,
+                user: if grouping_type == ProcessGroupingType::User {
+                    Some(identifier.to_string())
+                } else {
+                    None
+                },
+                time: p.time,
+                tty: if grouping_type == ProcessGroupingType::Tty {
+                    Some(identifier.to_string())
+                } else {
+                    None
+                },
+                cgroup: if grouping_type == ProcessGroupingType::Cgroup {
+                    Some(identifier.to_string())
+                } else {
+                    None
+                },
+                is_new: p.is_new,
             }
         })
         .collect::<Vec<_>>()
@@ -0,0 +1,549 @@
+//! Pushes each collection to a metrics collector, as InfluxDB line protocol or statsd packets.
+
+use std::{
+    collections::BTreeMap,
+    io::Write,
+    net::{TcpStream, ToSocketAddrs, UdpSocket},
+    sync::mpsc::{self, Receiver, SyncSender, TrySendError},
+    thread,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use crate::{
+    app::data_harvester::{temperature::TemperatureType, Data},
+    constants::METRICS_PROCESS_COUNT,
+    utils::error::{self, BottomError},
+};
+
+/// How long to wait when connecting or sending to the target.
+const SEND_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// The most that is put into a single UDP packet, to stay clear of fragmentation.
+const MAX_PACKET_SIZE: usize = 1400;
+
+/// How many collections can wait to be sent.  Any more are dropped until the target catches up.
+const SEND_QUEUE_SIZE: usize = 4;
+
+/// How long to wait before trying the target again after failing to send to it, which doubles
+/// with each failure in a row up to the maximum.  Until then, collections are dropped.
+const MIN_RETRY_DELAY: Duration = Duration::from_secs(1);
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(60);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExportFormat {
+    /// InfluxDB line protocol.
+    Influx,
+    /// Statsd gauges, with tags in the DogStatsD format.
+    Statsd,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExportProtocol {
+    Udp,
+    Tcp,
+}
+
+/// A group of related values that can be chosen for export.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ExportMetric {
+    Cpu,
+    LoadAvg,
+    Memory,
+    Swap,
+    Network,
+    Disk,
+    Temperature,
+    Battery,
+    Processes,
+}
+
+impl ExportMetric {
+    pub const ALL: [ExportMetric; 9] = [
+        ExportMetric::Cpu,
+        ExportMetric::LoadAvg,
+        ExportMetric::Memory,
+        ExportMetric::Swap,
+        ExportMetric::Network,
+        ExportMetric::Disk,
+        ExportMetric::Temperature,
+        ExportMetric::Battery,
+        ExportMetric::Processes,
+    ];
+}
+
+impl std::str::FromStr for ExportMetric {
+    type Err = BottomError;
+
+    fn from_str(s: &str) -> error::Result<Self> {
+        match s.to_lowercase().as_str() {
+            "cpu" => Ok(ExportMetric::Cpu),
+            "load_avg" => Ok(ExportMetric::LoadAvg),
+            "memory" | "mem" => Ok(ExportMetric::Memory),
+            "swap" => Ok(ExportMetric::Swap),
+            "network" | "net" => Ok(ExportMetric::Network),
+            "disk" => Ok(ExportMetric::Disk),
+            "temperature" | "temp" => Ok(ExportMetric::Temperature),
+            "battery" => Ok(ExportMetric::Battery),
+            "processes" | "proc" => Ok(ExportMetric::Processes),
+            _ => Err(BottomError::ConfigError(format!(
+                "\"{}\" is an invalid export metric, use one of \"<cpu|load_avg|memory|swap|network|disk|temperature|battery|processes>\".",
+                s
+            ))),
+        }
+    }
+}
+
+/// A single measurement, with its tags and fields.
+struct Point {
+    name: &'static str,
+    tags: Vec<(&'static str, String)>,
+    fields: Vec<(&'static str, f64)>,
+}
+
+impl Point {
+    fn new(name: &'static str) -> Self {
+        Point {
+            name,
+            tags: Vec::new(),
+            fields: Vec::new(),
+        }
+    }
+
+    fn tag(mut self, key: &'static str, value: impl Into<String>) -> Self {
+        self.tags.push((key, value.into()));
+        self
+    }
+
+    fn field(mut self, key: &'static str, value: f64) -> Self {
+        self.fields.push((key, value));
+        self
+    }
+
+    fn optional_field(self, key: &'static str, value: Option<u64>) -> Self {
+        match value {
+            Some(value) => self.field(key, value as f64),
+            None => self,
+        }
+    }
+}
+
+enum Connection {
+    Udp(UdpSocket),
+    Tcp(TcpStream),
+}
+
+/// The sending side of the thread that sends to the target, along with the failures it reports.
+struct SendQueue {
+    sender: SyncSender<Vec<String>>,
+    errors: Receiver<String>,
+}
+
+/// Formats collections and sends them to the target.  Sending happens on its own thread, so a
+/// slow or unreachable target never holds up collection.
+pub struct Exporter {
+    format: ExportFormat,
+    protocol: ExportProtocol,
+    target: String,
+    prefix: String,
+    tags: BTreeMap<String, String>,
+    metrics: Vec<ExportMetric>,
+    send_queue: Option<SendQueue>,
+}
+
+impl Exporter {
+    pub fn new(
+        format: ExportFormat, protocol: ExportProtocol, target: String, prefix: String,
+        tags: BTreeMap<String, String>, metrics: Vec<ExportMetric>,
+    ) -> Self {
+        Exporter {
+            format,
+            protocol,
+            target,
+            prefix,
+            tags,
+            metrics,
+            send_queue: None,
+        }
+    }
+
+    /// Formats a collection and queues it to be sent to the target, dropping it if the queue is
+    /// full.  Returns a failure to send if one was reported since the last call.
+    pub fn export(
+        &mut self, data: &Data, temperature_type: &TemperatureType, collection_time: SystemTime,
+    ) -> error::Result<()> {
+        let lines = self.to_lines(data, temperature_type, collection_time);
+        if lines.is_empty() {
+            return Ok(());
+        }
+
+        let (protocol, target) = (self.protocol, &self.target);
+        let send_queue = self
+            .send_queue
+            .get_or_insert_with(|| spawn_sender(protocol, target.clone()));
+        if let Err(TrySendError::Disconnected(_)) = send_queue.sender.try_send(lines) {
+            // The thread is gone, so start another one with the next collection.
+            self.send_queue = None;
+            return Ok(());
+        }
+
+        match send_queue.errors.try_recv() {
+            Ok(err) => Err(BottomError::GenericError(err)),
+            Err(_) => Ok(()),
+        }
+    }
+
+    /// Formats a collection as lines of the export format, without trailing newlines.  Anything
+    /// that wasn't collected or wasn't chosen is left out.
+    pub fn to_lines(
+        &self, data: &Data, temperature_type: &TemperatureType, collection_time: SystemTime,
+    ) -> Vec<String> {
+        let points = self.get_points(data, temperature_type);
+        match self.format {
+            ExportFormat::Influx => {
+                let timestamp = collection_time
+                    .duration_since(UNIX_EPOCH)
+                    .unwrap_or_default()
+                    .as_nanos();
+                points
+                    .iter()
+                    .map(|point| self.to_influx_line(point, timestamp))
+                    .collect()
+            }
+            ExportFormat::Statsd => points
+                .iter()
+                .flat_map(|point| self.to_statsd_lines(point))
+                .collect(),
+        }
+    }
+
+    fn get_points(&self, data: &Data, temperature_type: &TemperatureType) -> Vec<Point> {
+        let mut points = Vec::new();
+        let is_chosen = |metric: ExportMetric| self.metrics.contains(&metric);
+
+        if let Some(cpu) = data.cpu.as_ref().filter(|_| is_chosen(ExportMetric::Cpu)) {
+            for cpu in cpu {
+                let label = match cpu.cpu_count {
+                    Some(index) => index.to_string(),
+                    None => "avg".to_string(),
+                };
+                points.push(
+                    Point::new("cpu")
+                        .tag("cpu", label)
+                        .field("usage_percent", cpu.cpu_usage),
+                );
+            }
+        }
+
+        if let Some([one, five, fifteen]) =
+            data.load_avg.filter(|_| is_chosen(ExportMetric::LoadAvg))
+        {
+            points.push(
+                Point::new("load_average")
+                    .field("load1", one.into())
+                    .field("load5", five.into())
+                    .field("load15", fifteen.into()),
+            );
+        }
+
+        for (name, harvest, metric) in [
+            ("memory", &data.memory, ExportMetric::Memory),
+            ("swap", &data.swap, ExportMetric::Swap),
+        ] {
+            if let Some(harvest) = harvest.as_ref().filter(|_| is_chosen(metric)) {
+                points.push(
+                    Point::new(name)
+                        .field("total_bytes", (harvest.mem_total_in_kib * 1024) as f64)
+                        .field("used_bytes", (harvest.mem_used_in_kib * 1024) as f64),
+                );
+            }
+        }
+
+        if let Some(network) = data
+            .network
+            .as_ref()
+            .filter(|_| is_chosen(ExportMetric::Network))
+        {
+            points.push(
+                Point::new("network")
+                    .field("rx_bits_per_second", network.rx as f64)
+                    .field("tx_bits_per_second", network.tx as f64)
+                    .field("rx_bits_total", network.total_rx as f64)
+                    .field("tx_bits_total", network.total_tx as f64),
+            );
+        }
+
+        if is_chosen(ExportMetric::Disk) {
+            for disk in data.disks.iter().flatten() {
+                points.push(
+                    Point::new("disk")
+                        .tag("disk", disk.name.clone())
+                        .tag("mount", disk.mount_point.clone())
+                        .optional_field("total_bytes", disk.total_space)
+                        .optional_field("used_bytes", disk.used_space)
+                        .optional_field("free_bytes", disk.free_space),
+                );
+            }
+
+            let mut devices = data
+                .io
+                .iter()
+                .flatten()
+                .filter_map(|(device, io)| Some((device, io.as_ref()?)))
+                .collect::<Vec<_>>();
+            devices.sort_by(|a, b| a.0.cmp(b.0));
+            for (device, io) in devices {
+                points.push(
+                    Point::new("disk_io")
+                        .tag("device", device.clone())
+                        .field("read_bytes_total", io.read_bytes as f64)
+                        .field("written_bytes_total", io.write_bytes as f64),
+                );
+            }
+        }
+
+        if is_chosen(ExportMetric::Temperature) {
+            let unit = match temperature_type {
+                TemperatureType::Celsius => "celsius",
+                TemperatureType::Kelvin => "kelvin",
+                TemperatureType::Fahrenheit => "fahrenheit",
+            };
+            for sensor in data.temperature_sensors.iter().flatten() {
+                points.push(
+                    Point::new("temperature")
+                        .tag("sensor", sensor.name.clone())
+                        .tag("unit", unit)
+                        .field("value", sensor.temperature.into()),
+                );
+            }
+        }
+
+        #[cfg(feature = "battery")]
+        {
+            if is_chosen(ExportMetric::Battery) {
+                for (index, battery) in data.list_of_batteries.iter().flatten().enumerate() {
+                    points.push(
+                        Point::new("battery")
+                            .tag("battery", index.to_string())
+                            .field("charge_percent", battery.charge_percent)
+                            .field("power_watts", battery.power_consumption_rate_watts)
+                            .field("health_percent", battery.health_percent),
+                    );
+                }
+            }
+        }
+
+        if let Some(processes) = data
+            .list_of_processes
+            .as_ref()
+            .filter(|_| is_chosen(ExportMetric::Processes))
+        {
+            points.push(Point::new("processes").field("count", processes.len() as f64));
+
+            let mut top_processes = processes.iter().collect::<Vec<_>>();
+            top_processes.sort_by(|a, b| {
+                b.cpu_usage_percent
+                    .partial_cmp(&a.cpu_usage_percent)
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            });
+            for process in top_processes.into_iter().take(METRICS_PROCESS_COUNT) {
+                points.push(
+                    Point::new("process")
+                        .tag("pid", process.pid.to_string())
+                        .tag("name", process.name.clone())
+                        .field("cpu_percent", process.cpu_usage_percent)
+                        .field("memory_bytes", process.mem_usage_bytes as f64),
+                );
+            }
+        }
+
+        points.retain(|point| !point.fields.is_empty());
+        points
+    }
+
+    /// All of a point's tags followed by the configured ones, skipping any that are empty.
+    fn get_tags<'a>(&'a self, point: &'a Point) -> impl Iterator<Item = (&'a str, &'a str)> {
+        point
+            .tags
+            .iter()
+            .map(|(key, value)| (*key, value.as_str()))
+            .chain(
+                self.tags
+                    .iter()
+                    .map(|(key, value)| (key.as_str(), value.as_str())),
+            )
+            .filter(|(key, value)| !key.is_empty() && !value.is_empty())
+    }
+
+    fn get_name(&self, point: &Point, separator: char) -> String {
+        if self.prefix.is_empty() {
+            point.name.to_string()
+        } else {
+            format!("{}{}{}", self.prefix, separator, point.name)
+        }
+    }
+
+    fn to_influx_line(&self, point: &Point, timestamp: u128) -> String {
+        fn escape(value: &str, special_characters: &[char]) -> String {
+            let mut escaped = String::with_capacity(value.len());
+            for c in value.chars() {
+                if special_characters.contains(&c) {
+                    escaped.push('\\');
+                }
+                escaped.push(c);
+            }
+            escaped
+        }
+
+        let mut line = escape(&self.get_name(point, '_'), &[',', ' ']);
+        for (key, value) in self.get_tags(point) {
+            line.push(',');
+            line.push_str(&escape(key, &[',', '=', ' ']));
+            line.push('=');
+            line.push_str(&escape(value, &[',', '=', ' ']));
+        }
+
+        let fields = point
+            .fields
+            .iter()
+            .map(|(key, value)| format!("{}={}", key, value))
+            .collect::<Vec<_>>()
+            .join(",");
+        format!("{} {} {}", line, fields, timestamp)
+    }
+
+    fn to_statsd_lines(&self, point: &Point) -> Vec<String> {
+        // Statsd has no escaping, so just replace anything with a special meaning.
+        fn sanitize(value: &str) -> String {
+            value.replace([':', '|', ',', '#', '@', '\n'], "_")
+        }
+
+        let tags = self
+            .get_tags(point)
+            .map(|(key, value)| format!("{}:{}", sanitize(key), sanitize(value)))
+            .collect::<Vec<_>>()
+            .join(",");
+        let name = sanitize(&self.get_name(point, '.'));
+
+        point
+            .fields
+            .iter()
+            .map(|(key, value)| {
+                if tags.is_empty() {
+                    format!("{}.{}:{}|g", name, key, value)
+                } else {
+                    format!("{}.{}:{}|g|#{}", name, key, value, tags)
+                }
+            })
+            .collect()
+    }
+}
+
+/// Starts the thread that sends queued collections to the target.
+fn spawn_sender(protocol: ExportProtocol, target: String) -> SendQueue {
+    let (sender, receiver) = mpsc::sync_channel::<Vec<String>>(SEND_QUEUE_SIZE);
+    let (error_sender, errors) = mpsc::channel();
+    thread::spawn(move || {
+        let mut target_sender = TargetSender {
+            protocol,
+            target,
+            connection: None,
+        };
+        let mut retry_delay = MIN_RETRY_DELAY;
+        let mut retry_at: Option<Instant> = None;
+
+        // This stops once the exporter is dropped.
+        for lines in receiver {
+            if retry_at.is_some_and(|retry_at| Instant::now() < retry_at) {
+                continue;
+            }
+
+            match target_sender.send(&lines) {
+                Ok(()) => {
+                    retry_delay = MIN_RETRY_DELAY;
+                    retry_at = None;
+                }
+                Err(err) => {
+                    target_sender.connection = None;
+                    // Only the first failure in a row is reported, rather than one per collection.
+                    if retry_at.is_none() {
+                        let message =
+                            format!("Could not export to {}: {}", target_sender.target, err);
+                        if error_sender.send(message).is_err() {
+                            break;
+                        }
+                    }
+                    retry_at = Some(Instant::now() + retry_delay);
+                    retry_delay = std::cmp::min(retry_delay * 2, MAX_RETRY_DELAY);
+                }
+            }
+        }
+    });
+
+    SendQueue { sender, errors }
+}
+
+/// Sends to the target.  The connection is made on the first send, and made again after any
+/// failure.
+struct TargetSender {
+    protocol: ExportProtocol,
+    target: String,
+    connection: Option<Connection>,
+}
+
+impl TargetSender {
+    fn send(&mut self, lines: &[String]) -> std::io::Result<()> {
+        if self.connection.is_none() {
+            self.connection = Some(self.connect()?);
+        }
+
+        match &mut self.connection {
+            Some(Connection::Udp(socket)) => {
+                // Pack as many lines into each packet as will fit.
+                let mut packet = String::new();
+                for line in lines {
+                    if !packet.is_empty() && packet.len() + line.len() + 1 > MAX_PACKET_SIZE {
+                        socket.send(packet.as_bytes())?;
+                        packet.clear();
+                    }
+                    packet.push_str(line);
+                    packet.push('\n');
+                }
+                socket.send(packet.as_bytes())?;
+            }
+            Some(Connection::Tcp(stream)) => {
+                let mut payload = lines.join("\n");
+                payload.push('\n');
+                stream.write_all(payload.as_bytes())?;
+                stream.flush()?;
+            }
+            None => {}
+        }
+
+        Ok(())
+    }
+
+    fn connect(&self) -> std::io::Result<Connection> {
+        let address =
+            self.target.to_socket_addrs()?.next().ok_or_else(|| {
+                std::io::Error::new(std::io::ErrorKind::NotFound, "no address found")
+            })?;
+
+        match self.protocol {
+            ExportProtocol::Udp => {
+                let socket = if address.is_ipv4() {
+                    UdpSocket::bind("0.0.0.0:0")?
+                } else {
+                    UdpSocket::bind("[::]:0")?
+                };
+                socket.set_write_timeout(Some(SEND_TIMEOUT))?;
+                socket.connect(address)?;
+                Ok(Connection::Udp(socket))
+            }
+            ExportProtocol::Tcp => {
+                let stream = TcpStream::connect_timeout(&address, SEND_TIMEOUT)?;
+                stream.set_write_timeout(Some(SEND_TIMEOUT))?;
+                Ok(Connection::Tcp(stream))
+            }
+        }
+    }
+}
@@ -0,0 +1,591 @@
+//! Modes that print out collected data rather than drawing the TUI.
+
+use std::{
+    io::{stdout, ErrorKind, Write},
+    sync::{mpsc, Arc, Condvar, Mutex},
+    thread,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use serde::Serialize;
+
+use crate::{
+    app::{
+        data_harvester::{processes::ProcessSorting, Data, DataCollector},
+        layout_manager::{BottomWidgetType, UsedWidgets},
+        process_killer,
+        query::ProcessQuery,
+        App, AppConfigFields, DataFilters,
+    },
+    create_collection_thread,
+    data_conversion::*,
+    export::Exporter,
+    metrics::SharedMetrics,
+    options::has_non_blank_query,
+    replay::Recorder,
+    update_all_process_lists,
+    utils::error::{self, BottomError},
+    BottomEvent,
+};
+
+/// How `btm ps` prints matching processes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PsFormat {
+    Table,
+    Json,
+    Csv,
+}
+
+/// Options for `btm ps`.
+#[derive(Clone, Debug)]
+pub struct PsOptions {
+    /// Only processes matching this are printed.  All processes are printed if unset.
+    pub query: Option<String>,
+    /// Keys to sort by, starting with the main one, along with whether each is descending.
+    pub sort_keys: Vec<(ProcessSorting, bool)>,
+    /// Columns to print, if not the usual ones.
+    pub columns: Option<Vec<ProcessSorting>>,
+    pub format: PsFormat,
+    /// If set, matching processes are sent this signal after being printed.
+    pub signal: Option<usize>,
+}
+
+/// A single line of `--json` output.
+#[derive(Serialize)]
+struct JsonLine<'a> {
+    /// When the data was collected, in milliseconds since the Unix epoch.
+    timestamp: u64,
+    #[serde(flatten)]
+    data: &'a Data,
+}
+
+/// Serializes a data snapshot as a single line of JSON, without the trailing newline.
+pub fn to_json_line(data: &Data, collection_time: SystemTime) -> error::Result<String> {
+    let timestamp = collection_time
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64;
+
+    Ok(serde_json::to_string(&JsonLine { timestamp, data })?)
+}
+
+fn init_data_collector(
+    app_config_fields: &AppConfigFields, filters: DataFilters, used_widgets: UsedWidgets,
+) -> DataCollector {
+    let mut data_state = DataCollector::new(filters);
+    data_state.set_collected_data(used_widgets);
+    data_state.set_temperature_type(app_config_fields.temperature_type.clone());
+    data_state.set_use_current_cpu_total(app_config_fields.use_current_cpu_total);
+    data_state.set_show_average_cpu(app_config_fields.show_average_cpu);
+    data_state.init();
+
+    data_state
+}
+
+/// Collects once and waits an update interval.  Rates like CPU usage and network speed are
+/// measured against the previous collection, so they're only real from the next one on.
+fn prime_data_collector(data_state: &mut DataCollector, update_rate_in_milliseconds: u64) {
+    futures::executor::block_on(data_state.update_data());
+    thread::sleep(Duration::from_millis(update_rate_in_milliseconds));
+}
+
+/// Collects data at the configured rate and prints each snapshot to stdout as a line of JSON.
+/// Stops after `iterations` snapshots if given, or otherwise when stdout is closed.
+pub fn print_json_lines(
+    app_config_fields: &AppConfigFields, filters: DataFilters, used_widgets: UsedWidgets,
+    iterations: Option<u64>,
+) -> error::Result<()> {
+    let mut data_state = init_data_collector(app_config_fields, filters, used_widgets);
+    prime_data_collector(
+        &mut data_state,
+        app_config_fields.update_rate_in_milliseconds,
+    );
+
+    let stdout = stdout();
+    let mut stdout = stdout.lock();
+    let mut num_printed = 0;
+    loop {
+        futures::executor::block_on(data_state.update_data());
+        let line = to_json_line(&data_state.data, SystemTime::now())?;
+        data_state.data = Data::default();
+
+        if let Err(err) = writeln!(stdout, "{}", line).and_then(|_| stdout.flush()) {
+            // Whatever we were piped into (say, `head`) has stopped reading, so just stop.
+            return if err.kind() == ErrorKind::BrokenPipe {
+                Ok(())
+            } else {
+                Err(err.into())
+            };
+        }
+
+        num_printed += 1;
+        if matches!(iterations, Some(iterations) if num_printed >= iterations) {
+            return Ok(());
+        }
+
+        thread::sleep(Duration::from_millis(
+            app_config_fields.update_rate_in_milliseconds,
+        ));
+    }
+}
+
+/// Runs the collection thread without the TUI, so collected data is only served as metrics,
+/// exported, or recorded.  This only returns if the collection thread stops; otherwise it runs
+/// until bottom is killed.
+pub fn collect_without_tui(
+    app_config_fields: &AppConfigFields, filters: DataFilters, used_widgets: UsedWidgets,
+    metrics: Option<SharedMetrics>, exporter: Option<Exporter>, recorder: Option<Recorder>,
+) {
+    #[allow(clippy::mutex_atomic)]
+    let thread_termination_lock = Arc::new(Mutex::new(false));
+    let thread_termination_cvar = Arc::new(Condvar::new());
+    let (sender, receiver) = mpsc::channel();
+    let (_collection_thread_ctrl_sender, collection_thread_ctrl_receiver) = mpsc::channel();
+    let _collection_thread = create_collection_thread(
+        sender,
+        collection_thread_ctrl_receiver,
+        thread_termination_lock,
+        thread_termination_cvar,
+        app_config_fields,
+        filters,
+        used_widgets,
+        metrics,
+        exporter,
+        recorder,
+    );
+
+    // Nothing else needs the collected data, so only failures to export or record are reported.
+    for event in receiver {
+        if let BottomEvent::CollectionError(collection_error) = event {
+            eprintln!("{}", collection_error);
+        }
+    }
+}
+
+/// Lays out rows as plain text columns, each as wide as its widest entry.
+pub fn format_table(headers: &[String], rows: &[Vec<String>]) -> String {
+    let mut column_widths = headers
+        .iter()
+        .map(|header| header.chars().count())
+        .collect::<Vec<_>>();
+    for row in rows {
+        for (column_width, entry) in column_widths.iter_mut().zip(row) {
+            *column_width = std::cmp::max(*column_width, entry.chars().count());
+        }
+    }
+
+    std::iter::once(headers)
+        .chain(rows.iter().map(|row| row.as_slice()))
+        .map(|row| {
+            let line = row
+                .iter()
+                .zip(&column_widths)
+                .map(|(entry, width)| format!("{:width$}", entry, width = width))
+                .collect::<Vec<_>>()
+                .join("  ");
+            format!("{}\n", line.trim_end())
+        })
+        .collect()
+}
+
+/// Collects data and prints a plain text report of it, with up to `num_processes` processes from
+/// the default process widget.
+pub fn print_snapshot(app: &mut App, num_processes: usize) -> error::Result<()> {
+    // Everything is shown regardless of the layout, but processes still need a process widget.
+    let used_widgets = UsedWidgets {
+        use_cpu: true,
+        use_mem: true,
+        use_net: true,
+        use_proc: true,
+        use_disk: true,
+        use_temp: true,
+        use_user: false,
+        use_battery: false,
+    };
+    let mut data_state =
+        init_data_collector(&app.app_config_fields, app.filters.clone(), used_widgets);
+    prime_data_collector(
+        &mut data_state,
+        app.app_config_fields.update_rate_in_milliseconds,
+    );
+    futures::executor::block_on(data_state.update_data());
+    app.data_collection.eat_data(Box::new(data_state.data));
+
+    let stdout = stdout();
+    let mut stdout = stdout.lock();
+    match write_snapshot(app, num_processes, &mut stdout) {
+        Err(err) if err.kind() == ErrorKind::BrokenPipe => Ok(()),
+        result => Ok(result?),
+    }
+}
+
+fn write_snapshot(
+    app: &mut App, num_processes: usize, out: &mut impl Write,
+) -> std::io::Result<()> {
+    let data = &app.data_collection;
+
+    // CPU
+    let mut cpu_data = Vec::new();
+    convert_cpu_data_points(data, &mut cpu_data, false);
+    writeln!(out, "CPU")?;
+    let cpu_rows = cpu_data
+        .iter()
+        // Skip the entry for toggling all CPUs in the legend.
+        .filter(|cpu| !cpu.legend_value.is_empty())
+        .map(|cpu| vec![cpu.cpu_name.clone(), cpu.legend_value.clone()])
+        .collect::<Vec<_>>();
+    write!(
+        out,
+        "{}",
+        format_table(&["Name".to_string(), "Use%".to_string()], &cpu_rows)
+    )?;
+
+    #[cfg(target_family = "unix")]
+    {
+        let [one, five, fifteen] = data.load_avg_harvest;
+        writeln!(out, "Load average: {:.2} {:.2} {:.2}", one, five, fifteen)?;
+    }
+
+    // Memory and swap
+    let (mem_labels, swap_labels) = convert_mem_labels(data);
+    writeln!(out)?;
+    for (name, labels) in [("Memory", mem_labels), ("Swap", swap_labels)] {
+        if let Some((percent, fraction)) = labels {
+            writeln!(out, "{}: {} {}", name, percent.trim(), fraction.trim())?;
+        }
+    }
+
+    // Network
+    let network_data = convert_network_data_points(
+        data,
+        false,
+        true,
+        &app.app_config_fields.network_scale_type,
+        &app.app_config_fields.network_unit_type,
+        app.app_config_fields.network_use_binary_prefix,
+    );
+    writeln!(
+        out,
+        "Network: RX {} TX {} (total RX {} TX {})",
+        network_data.rx_display,
+        network_data.tx_display,
+        network_data.total_rx_display.unwrap_or_default(),
+        network_data.total_tx_display.unwrap_or_default(),
+    )?;
+
+    // Disks
+    writeln!(out, "\nDisks")?;
+    let disk_headers = ["Disk", "Mount", "Used", "Free", "Total", "R/s", "W/s"]
+        .iter()
+        .map(|header| header.to_string())
+        .collect::<Vec<_>>();
+    write!(
+        out,
+        "{}",
+        format_table(&disk_headers, &convert_disk_row(data, None))
+    )?;
+
+    // Temperatures
+    writeln!(out, "\nTemperatures")?;
+    write!(
+        out,
+        "{}",
+        format_table(
+            &["Sensor".to_string(), "Temp".to_string()],
+            &convert_temp_row(app, None)
+        )
+    )?;
+
+    // Processes
+    update_all_process_lists(app);
+    let proc_widget = get_default_proc_widget_id(app).and_then(|widget_id| {
+        Some((
+            app.proc_state.get_widget_state(widget_id)?,
+            app.canvas_data
+                .stringified_process_data_map
+                .get(&widget_id)?,
+        ))
+    });
+    if let Some((proc_widget_state, process_data)) = proc_widget {
+        let rows = process_data
+            .iter()
+            .filter(|(_row, is_disabled)| !is_disabled)
+            .map(|(row, _is_disabled)| {
+                row.iter()
+                    .map(|(entry, _alternative)| entry.clone())
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        writeln!(
+            out,
+            "\nProcesses ({} of {})",
+            std::cmp::min(num_processes, rows.len()),
+            rows.len()
+        )?;
+        // The usual headers have shortcut hints that don't make sense outside the TUI.
+        let headers = proc_widget_state
+            .columns
+            .get_shown_columns()
+            .iter()
+            .map(|column| column.to_string())
+            .collect::<Vec<_>>();
+        let shown_rows = &rows[..std::cmp::min(num_processes, rows.len())];
+        write!(out, "{}", format_table(&headers, shown_rows))?;
+    }
+
+    out.flush()
+}
+
+/// Returns the ID of the process widget used by the modes that print processes: the default
+/// widget if it is a process widget, or otherwise the first one.
+fn get_default_proc_widget_id(app: &App) -> Option<u64> {
+    match app.current_widget.widget_type {
+        BottomWidgetType::Proc => Some(app.current_widget.widget_id),
+        _ => app.proc_state.widget_states.keys().min().copied(),
+    }
+}
+
+/// Collects process data, then prints the processes matching the query in the given format and
+/// sends them a signal if asked to.  Returns how many processes matched.
+pub fn print_processes(app: &mut App, options: &PsOptions) -> error::Result<usize> {
+    let widget_id = get_default_proc_widget_id(app).ok_or_else(|| {
+        BottomError::GenericError("the layout needs a process widget to list processes.".into())
+    })?;
+
+    if let Some(proc_widget_state) = app.proc_state.get_mut_widget_state(widget_id) {
+        // Every process gets its own row, so each can be signalled by its PID.
+        proc_widget_state.set_grouping_type(ProcessGroupingType::Ungrouped);
+        proc_widget_state.is_tree_mode = false;
+
+        if let Some(columns) = &options.columns {
+            // Columns that share a slot with another, like memory and memory percentage, switch
+            // to whichever one was asked for.
+            for column in columns {
+                match column {
+                    ProcessSorting::Mem | ProcessSorting::MemPercent => {
+                        let is_mem_percent = *column == ProcessSorting::MemPercent;
+                        let columns = &mut proc_widget_state.columns;
+                        columns.try_set(&ProcessSorting::Mem, !is_mem_percent);
+                        columns.try_set(&ProcessSorting::MemPercent, is_mem_percent);
+                    }
+                    ProcessSorting::ProcessName | ProcessSorting::Command => {
+                        proc_widget_state.is_using_command = *column == ProcessSorting::Command;
+                        proc_widget_state
+                            .toggle_command_and_name(proc_widget_state.is_using_command);
+                    }
+                    _ => {}
+                }
+            }
+            proc_widget_state.columns.set_columns(columns);
+        }
+        if !options.sort_keys.is_empty() {
+            proc_widget_state.set_sort_keys(&options.sort_keys);
+        }
+
+        // Unlike the TUI, an invalid or blank query can't just show everything, as that could end
+        // up signalling every process.
+        if options.signal.is_some() && !has_non_blank_query(&options.query) {
+            return Err(BottomError::GenericError(
+                "a query is needed to signal processes.".into(),
+            ));
+        }
+        let query = options.query.clone().unwrap_or_default();
+        proc_widget_state
+            .process_search_state
+            .search_state
+            .is_enabled = !query.is_empty();
+        proc_widget_state.set_search_query(query);
+        if !proc_widget_state.get_current_search_query().is_empty() {
+            proc_widget_state.parse_query()?;
+        }
+    }
+
+    // Process CPU usage is measured between two collections.
+    let used_widgets = UsedWidgets {
+        use_proc: true,
+        ..UsedWidgets::default()
+    };
+    let mut data_state =
+        init_data_collector(&app.app_config_fields, app.filters.clone(), used_widgets);
+    prime_data_collector(
+        &mut data_state,
+        app.app_config_fields.update_rate_in_milliseconds,
+    );
+    futures::executor::block_on(data_state.update_data());
+    app.data_collection.eat_data(Box::new(data_state.data));
+    update_all_process_lists(app);
+
+    let columns = app
+        .proc_state
+        .get_widget_state(widget_id)
+        .map(|proc_widget_state| proc_widget_state.columns.get_shown_columns())
+        .unwrap_or_default();
+    let processes = app
+        .canvas_data
+        .finalized_process_data_map
+        .get(&widget_id)
+        .map(|processes| processes.as_slice())
+        .unwrap_or_default();
+    let stringified_processes = app
+        .canvas_data
+        .stringified_process_data_map
+        .get(&widget_id)
+        .map(|processes| processes.as_slice())
+        .unwrap_or_default();
+
+    let stdout = stdout();
+    let mut stdout = stdout.lock();
+    let written = match options.format {
+        PsFormat::Table => {
+            let headers = columns
+                .iter()
+                .map(|column| column.to_string())
+                .collect::<Vec<_>>();
+            let rows = stringified_processes
+                .iter()
+                .map(|(row, _is_disabled)| {
+                    row.iter()
+                        .map(|(entry, _alternative)| entry.clone())
+                        .collect::<Vec<_>>()
+                })
+                .collect::<Vec<_>>();
+            write!(stdout, "{}", format_table(&headers, &rows))
+        }
+        PsFormat::Json => {
+            let rows = processes
+                .iter()
+                .map(|process| {
+                    columns
+                        .iter()
+                        .map(|column| {
+                            (
+                                get_process_key(column).to_string(),
+                                get_process_value(column, process),
+                            )
+                        })
+                        .collect::<serde_json::Map<_, _>>()
+                })
+                .collect::<Vec<_>>();
+            writeln!(stdout, "{}", serde_json::to_string(&rows)?)
+        }
+        PsFormat::Csv => {
+            let header = columns
+                .iter()
+                .map(get_process_key)
+                .collect::<Vec<_>>()
+                .join(",");
+            std::iter::once(Ok(header))
+                .chain(processes.iter().map(|process| {
+                    Ok(columns
+                        .iter()
+                        .map(|column| to_csv_field(&get_process_value(column, process)))
+                        .collect::<Vec<_>>()
+                        .join(","))
+                }))
+                .try_for_each(|line: std::io::Result<String>| writeln!(stdout, "{}", line?))
+        }
+    }
+    .and_then(|_| stdout.flush());
+
+    match written {
+        // Keep going even if nobody is reading, so the signal is still sent.
+        Err(err) if err.kind() == ErrorKind::BrokenPipe => {}
+        result => result?,
+    }
+
+    if let Some(signal) = options.signal {
+        signal_processes(processes, signal)?;
+    }
+
+    Ok(processes.len())
+}
+
+/// The key used for a column in JSON and CSV output.
+fn get_process_key(column: &ProcessSorting) -> &'static str {
+    match column {
+        ProcessSorting::CpuPercent => "cpu_percent",
+        ProcessSorting::Mem => "mem_bytes",
+        ProcessSorting::MemPercent => "mem_percent",
+        ProcessSorting::Pid => "pid",
+        ProcessSorting::ProcessName => "name",
+        ProcessSorting::Command => "command",
+        ProcessSorting::ReadPerSecond => "read_bytes_per_sec",
+        ProcessSorting::WritePerSecond => "write_bytes_per_sec",
+        ProcessSorting::TotalRead => "total_read_bytes",
+        ProcessSorting::TotalWrite => "total_write_bytes",
+        ProcessSorting::State => "state",
+        ProcessSorting::User => "user",
+        ProcessSorting::Count => "count",
+    }
+}
+
+/// The unformatted value of a column for JSON and CSV output.
+fn get_process_value(column: &ProcessSorting, process: &ConvertedProcessData) -> serde_json::Value {
+    match column {
+        ProcessSorting::CpuPercent => process.cpu_percent_usage.into(),
+        ProcessSorting::Mem => process.mem_usage_bytes.into(),
+        ProcessSorting::MemPercent => process.mem_percent_usage.into(),
+        ProcessSorting::Pid => process.pid.into(),
+        ProcessSorting::ProcessName => process.name.clone().into(),
+        ProcessSorting::Command => process.command.clone().into(),
+        ProcessSorting::ReadPerSecond => process.rps_f64.into(),
+        ProcessSorting::WritePerSecond => process.wps_f64.into(),
+        ProcessSorting::TotalRead => process.tr_f64.into(),
+        ProcessSorting::TotalWrite => process.tw_f64.into(),
+        ProcessSorting::State => process.process_state.clone().into(),
+        ProcessSorting::User => process.user.clone().into(),
+        ProcessSorting::Count => process.group_pids.len().into(),
+    }
+}
+
+/// Writes a value as a CSV field, quoting it if needed.  Missing values are left empty.
+fn to_csv_field(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::Null => String::new(),
+        serde_json::Value::String(value) => {
+            if value.contains([',', '"', '\n', '\r']) {
+                format!("\"{}\"", value.replace('"', "\"\""))
+            } else {
+                value.clone()
+            }
+        }
+        value => value.to_string(),
+    }
+}
+
+/// Sends a signal to each of the given processes, other than bottom itself.  Failures are
+/// reported as they happen, and result in an error once every process has been tried.
+fn signal_processes(processes: &[ConvertedProcessData], signal: usize) -> error::Result<()> {
+    let own_pid = std::process::id();
+    let mut num_failed = 0;
+    for process in processes {
+        if process.pid as u32 == own_pid {
+            continue;
+        }
+
+        #[cfg(target_family = "unix")]
+        let result = process_killer::kill_process_given_pid(process.pid, signal);
+        #[cfg(target_os = "windows")]
+        let result = {
+            let _ = signal;
+            process_killer::kill_process_given_pid(process.pid)
+        };
+
+        if let Err(err) = result {
+            eprintln!(
+                "Could not signal {} ({}): {}",
+                process.name, process.pid, err
+            );
+            num_failed += 1;
+        }
+    }
+
+    if num_failed > 0 {
+        Err(BottomError::GenericError(format!(
+            "could not signal {} of the matching processes.",
+            num_failed
+        )))
+    } else {
+        Ok(())
+    }
+}
@@ -29,6 +29,7 @@ use crossterm::{
 use app::{
     data_harvester::{self, processes::ProcessSorting},
     layout_manager::{UsedWidgets, WidgetDirection},
+    query::{ProcessRelations, UserQueryTarget},
     App,
 };
 use constants::*;
@@ -46,7 +47,11 @@ pub mod canvas;
 pub mod clap;
 pub mod constants;
 pub mod data_conversion;
+pub mod export;
+pub mod headless;
+pub mod metrics;
 pub mod options;
+pub mod replay;
 pub mod units;
 
 #[cfg(target_family = "windows")]
@@ -61,6 +66,13 @@ pub enum BottomEvent<I, J> {
     MouseInput(J),
     Update(Box<data_harvester::Data>),
     Clean,
+    /// Exporting or recording a collection failed.
+    CollectionError(String),
+    /// Where a replay is at, sent whenever it changes.
+    ReplayStatus(replay::ReplayStatus),
+    /// A replay jumped to somewhere else in the recording, so anything already collected should
+    /// be dropped before the updates that follow.
+    ReplaySeek,
 }
 
 #[derive(Debug)]
@@ -69,6 +81,17 @@ pub enum ThreadControlEvent {
     UpdateConfig(Box<app::AppConfigFields>),
     UpdateUsedWidgets(Box<UsedWidgets>),
     UpdateUpdateTime(u64),
+    Replay(replay::ReplayControl),
+}
+
+/// Passes a replay control on to the replay thread, if replaying.
+fn send_replay_control(
+    app: &App, control_sender: &std::sync::mpsc::Sender<ThreadControlEvent>,
+    control: replay::ReplayControl,
+) {
+    if app.replay_status.is_some() {
+        let _ = control_sender.send(ThreadControlEvent::Replay(control));
+    }
 }
 
 pub fn handle_mouse_event(event: MouseEvent, app: &mut App) {
@@ -87,6 +110,16 @@ pub fn handle_mouse_event(event: MouseEvent, app: &mut App) {
                 }
             }
         }
+        MouseEvent::Drag(crossterm::event::MouseButton::Left, x, _y, _modifiers)
+            if !app.app_config_fields.disable_click =>
+        {
+            app.on_mouse_drag(x);
+        }
+        MouseEvent::Up(crossterm::event::MouseButton::Left, _x, _y, _modifiers)
+            if !app.app_config_fields.disable_click =>
+        {
+            app.on_left_mouse_release();
+        }
         _ => {}
     };
 }
@@ -117,11 +150,13 @@ pub fn handle_key_event_or_break(
             KeyCode::Esc => app.on_esc(),
             KeyCode::Enter => app.on_enter(),
             KeyCode::Tab => app.on_tab(),
+            KeyCode::BackTab => app.on_back_tab(),
             KeyCode::Backspace => app.on_backspace(),
             KeyCode::Delete => app.on_delete(),
             KeyCode::F(1) => app.toggle_ignore_case(),
             KeyCode::F(2) => app.toggle_search_whole_word(),
             KeyCode::F(3) => app.toggle_search_regex(),
+            KeyCode::F(4) => app.open_saved_queries(),
             KeyCode::F(5) => app.toggle_tree_mode(),
             KeyCode::F(6) => app.toggle_sort(),
             KeyCode::F(9) => app.start_killing_process(),
@@ -136,8 +171,25 @@ pub fn handle_key_event_or_break(
                 KeyCode::Char('c') | KeyCode::Char('C') => app.toggle_ignore_case(),
                 KeyCode::Char('w') | KeyCode::Char('W') => app.toggle_search_whole_word(),
                 KeyCode::Char('r') | KeyCode::Char('R') => app.toggle_search_regex(),
+                KeyCode::Char('s') | KeyCode::Char('S') => app.open_saved_queries(),
                 KeyCode::Char('h') => app.on_left_key(),
                 KeyCode::Char('l') => app.on_right_key(),
+                KeyCode::Enter => app.on_alt_enter(),
+                KeyCode::Char('p') => {
+                    send_replay_control(app, reset_sender, replay::ReplayControl::TogglePause)
+                }
+                KeyCode::Left => {
+                    send_replay_control(app, reset_sender, replay::ReplayControl::seek_back())
+                }
+                KeyCode::Right => {
+                    send_replay_control(app, reset_sender, replay::ReplayControl::seek_forward())
+                }
+                KeyCode::Char(',') => {
+                    send_replay_control(app, reset_sender, replay::ReplayControl::SlowDown)
+                }
+                KeyCode::Char('.') => {
+                    send_replay_control(app, reset_sender, replay::ReplayControl::SpeedUp)
+                }
                 _ => {}
             }
         } else if let KeyModifiers::CONTROL = event.modifiers {
@@ -176,6 +228,7 @@ pub fn handle_key_event_or_break(
                 KeyCode::Right => app.move_widget_selection(&WidgetDirection::Right),
                 KeyCode::Up => app.move_widget_selection(&WidgetDirection::Up),
                 KeyCode::Down => app.move_widget_selection(&WidgetDirection::Down),
+                KeyCode::BackTab => app.on_back_tab(),
                 KeyCode::Char(caught_char) => app.on_char_key(caught_char),
                 _ => {}
             }
@@ -313,6 +366,21 @@ pub fn handle_force_redraws(app: &mut App) {
         app.proc_state.force_update = None;
     }
 
+    if let Some(widget_id) = app.disk_state.force_update {
+        update_final_disk_list(app, widget_id);
+        app.disk_state.force_update = None;
+    }
+
+    if let Some(widget_id) = app.temp_state.force_update {
+        update_final_temp_list(app, widget_id);
+        app.temp_state.force_update = None;
+    }
+
+    if let Some(widget_id) = app.user_state.force_update {
+        update_final_user_list(app, widget_id);
+        app.user_state.force_update = None;
+    }
+
     if app.cpu_state.force_update.is_some() {
         convert_cpu_data_points(
             &app.data_collection,
@@ -344,6 +412,104 @@ pub fn handle_force_redraws(app: &mut App) {
     }
 }
 
+pub fn update_all_disk_lists(app: &mut App) {
+    let widget_ids = app
+        .disk_state
+        .widget_states
+        .keys()
+        .cloned()
+        .collect::<Vec<_>>();
+
+    widget_ids.into_iter().for_each(|widget_id| {
+        update_final_disk_list(app, widget_id);
+    });
+}
+
+fn update_final_disk_list(app: &mut App, widget_id: u64) {
+    let disk_filter = app
+        .disk_state
+        .get_widget_state(widget_id)
+        .and_then(|disk_widget_state| disk_widget_state.search_state.get_valid_query());
+    let disk_data = convert_disk_row(&app.data_collection, disk_filter);
+
+    if let Some(disk_widget_state) = app.disk_state.get_mut_widget_state(widget_id) {
+        clamp_scroll_position(&mut disk_widget_state.scroll_state, disk_data.len());
+    }
+    app.canvas_data.disk_data_map.insert(widget_id, disk_data);
+}
+
+pub fn update_all_temp_lists(app: &mut App) {
+    let widget_ids = app
+        .temp_state
+        .widget_states
+        .keys()
+        .cloned()
+        .collect::<Vec<_>>();
+
+    widget_ids.into_iter().for_each(|widget_id| {
+        update_final_temp_list(app, widget_id);
+    });
+}
+
+fn update_final_temp_list(app: &mut App, widget_id: u64) {
+    let temp_filter = app
+        .temp_state
+        .get_widget_state(widget_id)
+        .and_then(|temp_widget_state| temp_widget_state.search_state.get_valid_query());
+    let temp_data = convert_temp_row(app, temp_filter);
+
+    if let Some(temp_widget_state) = app.temp_state.get_mut_widget_state(widget_id) {
+        clamp_scroll_position(&mut temp_widget_state.scroll_state, temp_data.len());
+    }
+    app.canvas_data
+        .temp_sensor_data_map
+        .insert(widget_id, temp_data);
+}
+
+pub fn update_all_user_lists(app: &mut App) {
+    let widget_ids = app
+        .user_state
+        .widget_states
+        .keys()
+        .cloned()
+        .collect::<Vec<_>>();
+
+    widget_ids.into_iter().for_each(|widget_id| {
+        update_final_user_list(app, widget_id);
+    });
+}
+
+fn update_final_user_list(app: &mut App, widget_id: u64) {
+    let mut user_data = convert_user_data(
+        &app.data_collection,
+        #[cfg(target_family = "unix")]
+        &mut app.user_table,
+    );
+
+    if let Some(user_widget_state) = app.user_state.get_mut_widget_state(widget_id) {
+        if let Some(user_filter) = user_widget_state.search_state.get_valid_query() {
+            user_data.retain(|user| user_filter.check_target(&UserQueryTarget { user }));
+        }
+        sort_user_data(
+            &mut user_data,
+            &user_widget_state.sort_type,
+            user_widget_state.is_sort_descending,
+        );
+
+        clamp_scroll_position(&mut user_widget_state.scroll_state, user_data.len());
+    }
+    app.canvas_data.user_data_map.insert(widget_id, user_data);
+}
+
+/// Makes sure the selected row still exists after a list shrinks, say due to filtering.
+fn clamp_scroll_position(scroll_state: &mut app::AppScrollWidgetState, num_rows: usize) {
+    if scroll_state.current_scroll_position >= num_rows {
+        scroll_state.current_scroll_position = num_rows.saturating_sub(1);
+        scroll_state.previous_scroll_position = 0;
+        scroll_state.scroll_direction = app::ScrollDirection::Down;
+    }
+}
+
 #[allow(clippy::needless_collect)]
 pub fn update_all_process_lists(app: &mut App) {
     // According to clippy, I can avoid a collect... but if I follow it,
@@ -374,12 +540,12 @@ fn update_final_process_list(app: &mut App, widget_id: u64) {
                     .search_state
                     .is_invalid_or_blank_search(),
                 process_state.is_using_command,
-                process_state.is_grouped,
+                process_state.grouping_type,
                 process_state.is_tree_mode,
             )
         });
 
-    if let Some((is_invalid_or_blank, is_using_command, is_grouped, is_tree)) = process_states {
+    if let Some((is_invalid_or_blank, is_using_command, grouping_type, is_tree)) = process_states {
         if !app.is_frozen {
             convert_process_data(
                 &app.data_collection,
@@ -389,6 +555,7 @@ fn update_final_process_list(app: &mut App, widget_id: u64) {
             );
         }
         let process_filter = app.get_process_filter(widget_id);
+        let process_relations = ProcessRelations::new(&app.canvas_data.single_process_data);
         let filtered_process_data: Vec<ConvertedProcessData> = if is_tree {
             app.canvas_data
                 .single_process_data
@@ -397,8 +564,11 @@ fn update_final_process_list(app: &mut App, widget_id: u64) {
                     let mut process_clone = process.clone();
                     if !is_invalid_or_blank {
                         if let Some(process_filter) = process_filter {
-                            process_clone.is_disabled_entry =
-                                !process_filter.check(&process_clone, is_using_command);
+                            process_clone.is_disabled_entry = !process_filter.check(
+                                &process_clone,
+                                is_using_command,
+                                &process_relations,
+                            );
                         }
                     }
                     process_clone
@@ -411,7 +581,7 @@ fn update_final_process_list(app: &mut App, widget_id: u64) {
                 .filter_map(|(_pid, process)| {
                     if !is_invalid_or_blank {
                         if let Some(process_filter) = process_filter {
-                            if process_filter.check(process, is_using_command) {
+                            if process_filter.check(process, is_using_command, &process_relations) {
                                 Some(process)
                             } else {
                                 None
@@ -429,14 +599,27 @@ fn update_final_process_list(app: &mut App, widget_id: u64) {
 
         if let Some(proc_widget_state) = app.proc_state.get_mut_widget_state(widget_id) {
             let mut finalized_process_data = if is_tree {
+                let single_process_data = &app.canvas_data.single_process_data;
+                proc_widget_state
+                    .tree_collapse_state
+                    .retain(|pid, name| match single_process_data.get(&pid) {
+                        Some(process) => process.name == name,
+                        None => false,
+                    });
+
                 tree_process_data(
                     &filtered_process_data,
                     is_using_command,
-                    &proc_widget_state.process_sorting_type,
-                    proc_widget_state.is_process_sort_descending,
+                    &proc_widget_state.get_sort_keys(),
+                    &proc_widget_state.tree_collapse_state,
+                )
+            } else if grouping_type != ProcessGroupingType::Ungrouped {
+                group_process_data(
+                    &filtered_process_data,
+                    grouping_type,
+                    is_using_command,
+                    &app.canvas_data.single_process_data,
                 )
-            } else if is_grouped {
-                group_process_data(&filtered_process_data, is_using_command)
             } else {
                 filtered_process_data
             };
@@ -446,6 +629,12 @@ fn update_final_process_list(app: &mut App, widget_id: u64) {
                 sort_process_data(&mut finalized_process_data, proc_widget_state);
             }
 
+            proc_widget_state.select_pending_pid(&finalized_process_data);
+            proc_widget_state.update_follow(
+                app.canvas_data.finalized_process_data_map.get(&widget_id),
+                &finalized_process_data,
+            );
+
             if proc_widget_state.scroll_state.current_scroll_position
                 >= finalized_process_data.len()
             {
@@ -469,126 +658,19 @@ fn update_final_process_list(app: &mut App, widget_id: u64) {
 fn sort_process_data(
     to_sort_vec: &mut Vec<ConvertedProcessData>, proc_widget_state: &app::ProcWidgetState,
 ) {
-    to_sort_vec.sort_by_cached_key(|c| c.name.to_lowercase());
+    // Skip over any keys that don't make sense for the current grouping state.
+    let sort_keys = proc_widget_state
+        .get_sort_keys()
+        .into_iter()
+        .filter(|(sort_type, _)| match sort_type {
+            ProcessSorting::Pid => !proc_widget_state.is_grouped(),
+            ProcessSorting::Count => proc_widget_state.is_grouped(),
+            _ => true,
+        })
+        .collect::<Vec<_>>();
 
-    match &proc_widget_state.process_sorting_type {
-        ProcessSorting::CpuPercent => {
-            to_sort_vec.sort_by(|a, b| {
-                utils::gen_util::get_ordering(
-                    a.cpu_percent_usage,
-                    b.cpu_percent_usage,
-                    proc_widget_state.is_process_sort_descending,
-                )
-            });
-        }
-        ProcessSorting::Mem => {
-            to_sort_vec.sort_by(|a, b| {
-                utils::gen_util::get_ordering(
-                    a.mem_usage_bytes,
-                    b.mem_usage_bytes,
-                    proc_widget_state.is_process_sort_descending,
-                )
-            });
-        }
-        ProcessSorting::MemPercent => {
-            to_sort_vec.sort_by(|a, b| {
-                utils::gen_util::get_ordering(
-                    a.mem_percent_usage,
-                    b.mem_percent_usage,
-                    proc_widget_state.is_process_sort_descending,
-                )
-            });
-        }
-        ProcessSorting::ProcessName => {
-            // Don't repeat if false... it sorts by name by default anyways.
-            if proc_widget_state.is_process_sort_descending {
-                to_sort_vec.sort_by_cached_key(|c| c.name.to_lowercase());
-                if proc_widget_state.is_process_sort_descending {
-                    to_sort_vec.reverse();
-                }
-            }
-        }
-        ProcessSorting::Command => {
-            to_sort_vec.sort_by_cached_key(|c| c.command.to_lowercase());
-            if proc_widget_state.is_process_sort_descending {
-                to_sort_vec.reverse();
-            }
-        }
-        ProcessSorting::Pid => {
-            if !proc_widget_state.is_grouped {
-                to_sort_vec.sort_by(|a, b| {
-                    utils::gen_util::get_ordering(
-                        a.pid,
-                        b.pid,
-                        proc_widget_state.is_process_sort_descending,
-                    )
-                });
-            }
-        }
-        ProcessSorting::ReadPerSecond => {
-            to_sort_vec.sort_by(|a, b| {
-                utils::gen_util::get_ordering(
-                    a.rps_f64,
-                    b.rps_f64,
-                    proc_widget_state.is_process_sort_descending,
-                )
-            });
-        }
-        ProcessSorting::WritePerSecond => {
-            to_sort_vec.sort_by(|a, b| {
-                utils::gen_util::get_ordering(
-                    a.wps_f64,
-                    b.wps_f64,
-                    proc_widget_state.is_process_sort_descending,
-                )
-            });
-        }
-        ProcessSorting::TotalRead => {
-            to_sort_vec.sort_by(|a, b| {
-                utils::gen_util::get_ordering(
-                    a.tr_f64,
-                    b.tr_f64,
-                    proc_widget_state.is_process_sort_descending,
-                )
-            });
-        }
-        ProcessSorting::TotalWrite => {
-            to_sort_vec.sort_by(|a, b| {
-                utils::gen_util::get_ordering(
-                    a.tw_f64,
-                    b.tw_f64,
-                    proc_widget_state.is_process_sort_descending,
-                )
-            });
-        }
-        ProcessSorting::State => {
-            to_sort_vec.sort_by_cached_key(|c| c.process_state.to_lowercase());
-            if proc_widget_state.is_process_sort_descending {
-                to_sort_vec.reverse();
-            }
-        }
-        ProcessSorting::User => to_sort_vec.sort_by(|a, b| match (&a.user, &b.user) {
-            (Some(user_a), Some(user_b)) => utils::gen_util::get_ordering(
-                user_a.to_lowercase(),
-                user_b.to_lowercase(),
-                proc_widget_state.is_process_sort_descending,
-            ),
-            (Some(_), None) => std::cmp::Ordering::Less,
-            (None, Some(_)) => std::cmp::Ordering::Greater,
-            (None, None) => std::cmp::Ordering::Less,
-        }),
-        ProcessSorting::Count => {
-            if proc_widget_state.is_grouped {
-                to_sort_vec.sort_by(|a, b| {
-                    utils::gen_util::get_ordering(
-                        a.group_pids.len(),
-                        b.group_pids.len(),
-                        proc_widget_state.is_process_sort_descending,
-                    )
-                });
-            }
-        }
-    }
+    to_sort_vec.sort_by_cached_key(|c| c.name.to_lowercase());
+    data_conversion::sort_process_data(to_sort_vec, &sort_keys, |process| process);
 }
 
 pub fn create_input_thread(
@@ -634,6 +716,7 @@ pub fn create_input_thread(
     })
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn create_collection_thread(
     sender: std::sync::mpsc::Sender<
         BottomEvent<crossterm::event::KeyEvent, crossterm::event::MouseEvent>,
@@ -641,9 +724,10 @@ pub fn create_collection_thread(
     control_receiver: std::sync::mpsc::Receiver<ThreadControlEvent>,
     termination_ctrl_lock: Arc<Mutex<bool>>, termination_ctrl_cvar: Arc<Condvar>,
     app_config_fields: &app::AppConfigFields, filters: app::DataFilters,
-    used_widget_set: UsedWidgets,
+    used_widget_set: UsedWidgets, metrics: Option<metrics::SharedMetrics>,
+    mut exporter: Option<export::Exporter>, mut recorder: Option<replay::Recorder>,
 ) -> std::thread::JoinHandle<()> {
-    let temp_type = app_config_fields.temperature_type.clone();
+    let mut temp_type = app_config_fields.temperature_type.clone();
     let use_current_cpu_total = app_config_fields.use_current_cpu_total;
     let show_average_cpu = app_config_fields.show_average_cpu;
     let update_rate_in_milliseconds = app_config_fields.update_rate_in_milliseconds;
@@ -652,7 +736,7 @@ pub fn create_collection_thread(
         let mut data_state = data_harvester::DataCollector::new(filters);
 
         data_state.set_collected_data(used_widget_set);
-        data_state.set_temperature_type(temp_type);
+        data_state.set_temperature_type(temp_type.clone());
         data_state.set_use_current_cpu_total(use_current_cpu_total);
         data_state.set_show_average_cpu(show_average_cpu);
 
@@ -676,7 +760,8 @@ pub fn create_collection_thread(
                         data_state.data.cleanup();
                     }
                     ThreadControlEvent::UpdateConfig(app_config_fields) => {
-                        data_state.set_temperature_type(app_config_fields.temperature_type.clone());
+                        temp_type = app_config_fields.temperature_type.clone();
+                        data_state.set_temperature_type(temp_type.clone());
                         data_state
                             .set_use_current_cpu_total(app_config_fields.use_current_cpu_total);
                         data_state.set_show_average_cpu(app_config_fields.show_average_cpu);
@@ -687,6 +772,7 @@ pub fn create_collection_thread(
                     ThreadControlEvent::UpdateUpdateTime(new_time) => {
                         update_time = new_time;
                     }
+                    ThreadControlEvent::Replay(_) => {}
                 }
             }
             futures::executor::block_on(data_state.update_data());
@@ -700,6 +786,36 @@ pub fn create_collection_thread(
                 }
             }
 
+            if let Some(metrics) = &metrics {
+                let text = metrics::to_prometheus_text(&data_state.data, &temp_type);
+                if let Ok(mut latest) = metrics.lock() {
+                    *latest = text;
+                }
+            }
+
+            let collection_time = std::time::SystemTime::now();
+            if let Some(exporter) = &mut exporter {
+                if let Err(err) = exporter.export(&data_state.data, &temp_type, collection_time) {
+                    if sender
+                        .send(BottomEvent::CollectionError(err.to_string()))
+                        .is_err()
+                    {
+                        break;
+                    }
+                }
+            }
+
+            if let Some(recorder) = &mut recorder {
+                if let Err(err) = recorder.record(&data_state.data, collection_time) {
+                    if sender
+                        .send(BottomEvent::CollectionError(err.to_string()))
+                        .is_err()
+                    {
+                        break;
+                    }
+                }
+            }
+
             let event = BottomEvent::Update(Box::from(data_state.data));
             data_state.data = data_harvester::Data::default();
             if sender.send(event).is_err() {
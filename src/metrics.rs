@@ -0,0 +1,346 @@
+//! Serves collected data as Prometheus metrics over HTTP.
+
+use std::{
+    fmt::Write as _,
+    io::{BufRead, BufReader, Write},
+    net::{TcpListener, TcpStream},
+    sync::{Arc, Mutex},
+    thread,
+    time::Duration,
+};
+
+use crate::{
+    app::data_harvester::{temperature::TemperatureType, Data},
+    constants::METRICS_PROCESS_COUNT,
+};
+
+/// The latest collection in the Prometheus text format, shared between the collection thread
+/// and the metrics server.
+pub type SharedMetrics = Arc<Mutex<String>>;
+
+/// How long to wait on a client that isn't sending or reading anything.
+const CLIENT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// A metric family, along with its samples as (labels, value) pairs.
+struct MetricFamily<'a> {
+    name: &'a str,
+    metric_type: &'a str,
+    help: &'a str,
+    samples: Vec<(Vec<(&'a str, String)>, f64)>,
+}
+
+impl<'a> MetricFamily<'a> {
+    fn gauge(name: &'a str, help: &'a str) -> Self {
+        MetricFamily {
+            name,
+            metric_type: "gauge",
+            help,
+            samples: Vec::new(),
+        }
+    }
+
+    fn counter(name: &'a str, help: &'a str) -> Self {
+        MetricFamily {
+            name,
+            metric_type: "counter",
+            help,
+            samples: Vec::new(),
+        }
+    }
+
+    fn sample(mut self, labels: Vec<(&'a str, String)>, value: f64) -> Self {
+        self.samples.push((labels, value));
+        self
+    }
+
+    /// Writes out the family, unless it has no samples.
+    fn write_to(&self, out: &mut String) {
+        if self.samples.is_empty() {
+            return;
+        }
+
+        let _ = writeln!(out, "# HELP {} {}", self.name, self.help);
+        let _ = writeln!(out, "# TYPE {} {}", self.name, self.metric_type);
+        for (labels, value) in &self.samples {
+            out.push_str(self.name);
+            if !labels.is_empty() {
+                let labels = labels
+                    .iter()
+                    .map(|(key, value)| format!("{}=\"{}\"", key, escape_label_value(value)))
+                    .collect::<Vec<_>>()
+                    .join(",");
+                let _ = write!(out, "{{{}}}", labels);
+            }
+            let _ = writeln!(out, " {}", format_value(*value));
+        }
+    }
+}
+
+fn escape_label_value(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+fn format_value(value: f64) -> String {
+    if value.is_nan() {
+        "NaN".to_string()
+    } else if value == f64::INFINITY {
+        "+Inf".to_string()
+    } else if value == f64::NEG_INFINITY {
+        "-Inf".to_string()
+    } else {
+        value.to_string()
+    }
+}
+
+/// Renders a collection in the Prometheus text exposition format.  Anything that wasn't
+/// collected is left out.
+pub fn to_prometheus_text(data: &Data, temperature_type: &TemperatureType) -> String {
+    let mut families = Vec::new();
+
+    if let Some(cpu) = &data.cpu {
+        let mut family = MetricFamily::gauge(
+            "bottom_cpu_usage_percent",
+            "CPU usage, per CPU and on average.",
+        );
+        for cpu in cpu {
+            let label = match cpu.cpu_count {
+                Some(index) => index.to_string(),
+                None => "avg".to_string(),
+            };
+            family = family.sample(vec![("cpu", label)], cpu.cpu_usage);
+        }
+        families.push(family);
+    }
+
+    if let Some([one, five, fifteen]) = data.load_avg {
+        families.push(
+            MetricFamily::gauge("bottom_load_average", "System load average.")
+                .sample(vec![("period", "1m".to_string())], one.into())
+                .sample(vec![("period", "5m".to_string())], five.into())
+                .sample(vec![("period", "15m".to_string())], fifteen.into()),
+        );
+    }
+
+    if let Some(memory) = &data.memory {
+        families.push(
+            MetricFamily::gauge("bottom_memory_total_bytes", "Total memory.")
+                .sample(vec![], (memory.mem_total_in_kib * 1024) as f64),
+        );
+        families.push(
+            MetricFamily::gauge("bottom_memory_used_bytes", "Used memory.")
+                .sample(vec![], (memory.mem_used_in_kib * 1024) as f64),
+        );
+    }
+
+    if let Some(swap) = &data.swap {
+        families.push(
+            MetricFamily::gauge("bottom_swap_total_bytes", "Total swap.")
+                .sample(vec![], (swap.mem_total_in_kib * 1024) as f64),
+        );
+        families.push(
+            MetricFamily::gauge("bottom_swap_used_bytes", "Used swap.")
+                .sample(vec![], (swap.mem_used_in_kib * 1024) as f64),
+        );
+    }
+
+    if let Some(network) = &data.network {
+        families.push(
+            MetricFamily::gauge(
+                "bottom_network_receive_bits_per_second",
+                "Current receive rate across all interfaces.",
+            )
+            .sample(vec![], network.rx as f64),
+        );
+        families.push(
+            MetricFamily::gauge(
+                "bottom_network_transmit_bits_per_second",
+                "Current transmit rate across all interfaces.",
+            )
+            .sample(vec![], network.tx as f64),
+        );
+        families.push(
+            MetricFamily::counter(
+                "bottom_network_receive_bits_total",
+                "Total bits received across all interfaces.",
+            )
+            .sample(vec![], network.total_rx as f64),
+        );
+        families.push(
+            MetricFamily::counter(
+                "bottom_network_transmit_bits_total",
+                "Total bits transmitted across all interfaces.",
+            )
+            .sample(vec![], network.total_tx as f64),
+        );
+    }
+
+    if let Some(disks) = &data.disks {
+        let mut total = MetricFamily::gauge("bottom_disk_total_bytes", "Total disk space.");
+        let mut used = MetricFamily::gauge("bottom_disk_used_bytes", "Used disk space.");
+        let mut free = MetricFamily::gauge("bottom_disk_free_bytes", "Free disk space.");
+        for disk in disks {
+            let labels = vec![
+                ("disk", disk.name.clone()),
+                ("mount", disk.mount_point.clone()),
+            ];
+            if let Some(total_space) = disk.total_space {
+                total = total.sample(labels.clone(), total_space as f64);
+            }
+            if let Some(used_space) = disk.used_space {
+                used = used.sample(labels.clone(), used_space as f64);
+            }
+            if let Some(free_space) = disk.free_space {
+                free = free.sample(labels, free_space as f64);
+            }
+        }
+        families.extend([total, used, free]);
+    }
+
+    if let Some(io) = &data.io {
+        let mut read = MetricFamily::counter("bottom_disk_read_bytes_total", "Total bytes read.");
+        let mut written =
+            MetricFamily::counter("bottom_disk_written_bytes_total", "Total bytes written.");
+        let mut devices = io
+            .iter()
+            .filter_map(|(device, io)| Some((device, io.as_ref()?)))
+            .collect::<Vec<_>>();
+        devices.sort_by(|a, b| a.0.cmp(b.0));
+        for (device, io) in devices {
+            read = read.sample(vec![("device", device.clone())], io.read_bytes as f64);
+            written = written.sample(vec![("device", device.clone())], io.write_bytes as f64);
+        }
+        families.extend([read, written]);
+    }
+
+    if let Some(sensors) = &data.temperature_sensors {
+        let name = match temperature_type {
+            TemperatureType::Celsius => "bottom_temperature_celsius",
+            TemperatureType::Kelvin => "bottom_temperature_kelvin",
+            TemperatureType::Fahrenheit => "bottom_temperature_fahrenheit",
+        };
+        let mut family = MetricFamily::gauge(name, "Sensor temperature.");
+        for sensor in sensors {
+            family = family.sample(
+                vec![("sensor", sensor.name.clone())],
+                sensor.temperature.into(),
+            );
+        }
+        families.push(family);
+    }
+
+    #[cfg(feature = "battery")]
+    {
+        if let Some(batteries) = &data.list_of_batteries {
+            let mut charge =
+                MetricFamily::gauge("bottom_battery_charge_percent", "Battery charge.");
+            let mut power =
+                MetricFamily::gauge("bottom_battery_power_watts", "Battery power consumption.");
+            let mut health =
+                MetricFamily::gauge("bottom_battery_health_percent", "Battery health.");
+            for (index, battery) in batteries.iter().enumerate() {
+                let labels = vec![("battery", index.to_string())];
+                charge = charge.sample(labels.clone(), battery.charge_percent);
+                power = power.sample(labels.clone(), battery.power_consumption_rate_watts);
+                health = health.sample(labels, battery.health_percent);
+            }
+            families.extend([charge, power, health]);
+        }
+    }
+
+    if let Some(processes) = &data.list_of_processes {
+        families.push(
+            MetricFamily::gauge("bottom_processes", "Number of processes.")
+                .sample(vec![], processes.len() as f64),
+        );
+
+        let mut top_processes = processes.iter().collect::<Vec<_>>();
+        top_processes.sort_by(|a, b| {
+            b.cpu_usage_percent
+                .partial_cmp(&a.cpu_usage_percent)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        top_processes.truncate(METRICS_PROCESS_COUNT);
+
+        let mut cpu = MetricFamily::gauge(
+            "bottom_process_cpu_usage_percent",
+            "CPU usage of the processes using the most CPU.",
+        );
+        let mut mem = MetricFamily::gauge(
+            "bottom_process_memory_bytes",
+            "Memory usage of the processes using the most CPU.",
+        );
+        for process in top_processes {
+            let labels = vec![
+                ("pid", process.pid.to_string()),
+                ("name", process.name.clone()),
+            ];
+            cpu = cpu.sample(labels.clone(), process.cpu_usage_percent);
+            mem = mem.sample(labels, process.mem_usage_bytes as f64);
+        }
+        families.extend([cpu, mem]);
+    }
+
+    let mut out = String::new();
+    for family in families {
+        family.write_to(&mut out);
+    }
+    out
+}
+
+/// Answers HTTP requests on the listener in the background, serving the latest metrics at
+/// `/metrics`.
+pub fn create_metrics_server_thread(
+    listener: TcpListener, metrics: SharedMetrics,
+) -> std::thread::JoinHandle<()> {
+    thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            // A misbehaving client only affects its own request.
+            let _ = respond(stream, &metrics);
+        }
+    })
+}
+
+fn respond(stream: TcpStream, metrics: &SharedMetrics) -> std::io::Result<()> {
+    stream.set_read_timeout(Some(CLIENT_TIMEOUT))?;
+    stream.set_write_timeout(Some(CLIENT_TIMEOUT))?;
+
+    let mut reader = BufReader::new(stream);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+
+    // Skip over the headers, as nothing in them matters here.
+    let mut header = String::new();
+    while reader.read_line(&mut header)? > 0 && header.trim_end() != "" {
+        header.clear();
+    }
+
+    let mut parts = request_line.split_whitespace();
+    let (status, body) = match (parts.next(), parts.next()) {
+        (Some("GET"), Some(path)) if path == "/metrics" || path.starts_with("/metrics?") => {
+            let body = metrics.lock().map(|text| text.clone()).unwrap_or_default();
+            ("200 OK", body)
+        }
+        (Some("GET"), Some("/")) => (
+            "200 OK",
+            "bottom is serving metrics at /metrics.\n".to_string(),
+        ),
+        (Some("GET"), Some(_)) => ("404 Not Found", "Not found.\n".to_string()),
+        _ => (
+            "405 Method Not Allowed",
+            "Only GET is supported.\n".to_string(),
+        ),
+    };
+
+    let mut stream = reader.into_inner();
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: text/plain; version=0.0.4; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    )?;
+    stream.flush()
+}
@@ -2,16 +2,20 @@ use regex::Regex;
 use serde::{Deserialize, Serialize};
 use std::{
     borrow::Cow,
-    collections::{HashMap, HashSet},
-    path::PathBuf,
+    collections::{BTreeMap, HashMap, HashSet},
+    fs,
+    path::{Path, PathBuf},
     str::FromStr,
     time::Instant,
 };
 
 use crate::{
-    app::{layout_manager::*, *},
+    app::{data_harvester::processes::ProcessSorting, layout_manager::*, *},
     canvas::ColourScheme,
     constants::*,
+    data_conversion::ProcessGroupingType,
+    export::{ExportFormat, ExportMetric, ExportProtocol, Exporter},
+    headless::{PsFormat, PsOptions},
     units::data_units::DataUnit,
     utils::error::{self, BottomError},
 };
@@ -33,9 +37,27 @@ pub struct Config {
     pub mount_filter: Option<IgnoreList>,
     pub temp_filter: Option<IgnoreList>,
     pub net_filter: Option<IgnoreList>,
+    pub queries: Option<BTreeMap<String, String>>,
+    pub column_widths: Option<ConfigColumnWidths>,
+    pub export: Option<ConfigExport>,
 }
 
 impl Config {
+    /// Returns all saved queries as (name, query) pairs, ordered by name.
+    pub fn get_saved_queries(&self) -> impl Iterator<Item = (&String, &String)> {
+        self.queries.iter().flatten()
+    }
+
+    /// Returns the saved query with the given name if there is one, otherwise treats the given
+    /// string as a query itself.
+    pub fn resolve_query<'a>(&'a self, name_or_query: &'a str) -> &'a str {
+        self.queries
+            .as_ref()
+            .and_then(|queries| queries.get(name_or_query))
+            .map(|query| query.as_str())
+            .unwrap_or(name_or_query)
+    }
+
     pub fn get_config_as_bytes(&self) -> anyhow::Result<Vec<u8>> {
         let mut config_string: Vec<Cow<'_, str>> = Vec::default();
 
@@ -70,6 +92,9 @@ pub struct ConfigFlags {
     #[builder(default, setter(strip_option))]
     pub group_processes: Option<bool>,
 
+    #[builder(default, setter(strip_option))]
+    pub group_by: Option<String>,
+
     #[builder(default, setter(strip_option))]
     pub case_sensitive: Option<bool>,
 
@@ -88,6 +113,9 @@ pub struct ConfigFlags {
     #[builder(default, setter(strip_option))]
     pub time_delta: Option<u64>,
 
+    #[builder(default, setter(strip_option))]
+    pub retention: Option<u64>,
+
     #[builder(default, setter(strip_option))]
     pub autohide_time: Option<bool>,
 
@@ -155,6 +183,18 @@ pub struct ConfigFlags {
     #[builder(default, setter(strip_option))]
     pub process_command: Option<bool>,
 
+    #[builder(default, setter(strip_option))]
+    pub default_sort: Option<Vec<String>>,
+
+    #[builder(default, setter(strip_option))]
+    pub columns: Option<Vec<String>>,
+
+    #[builder(default, setter(strip_option))]
+    pub persist_column_widths: Option<bool>,
+
+    #[builder(default, setter(strip_option))]
+    pub persist_history: Option<bool>,
+
     #[builder(default, setter(strip_option))]
     pub disable_advanced_kill: Option<bool>,
 
@@ -228,6 +268,75 @@ fn default_as_true() -> bool {
     true
 }
 
+/// Column widths set by the user, by table and then by column header.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct ConfigColumnWidths {
+    pub proc: Option<BTreeMap<String, u16>>,
+    pub disk: Option<BTreeMap<String, u16>>,
+    pub temp: Option<BTreeMap<String, u16>>,
+    pub user: Option<BTreeMap<String, u16>>,
+}
+
+impl ConfigColumnWidths {
+    /// Where widths set by resizing are saved with `persist_column_widths`, kept apart from the
+    /// config file so that it's never rewritten.
+    pub fn default_saved_path() -> Option<PathBuf> {
+        dirs::cache_dir().map(|cache_dir| cache_dir.join(DEFAULT_COLUMN_WIDTHS_FILE_PATH))
+    }
+
+    /// Reads back saved widths.  A missing or unreadable file has none.
+    pub fn load_saved(path: &Path) -> Self {
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|contents| toml::from_str(&contents).ok())
+            .unwrap_or_default()
+    }
+
+    /// Writes the widths out, leaving the old file alone if that fails partway.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let temp_path = path.with_extension("tmp");
+        fs::write(&temp_path, toml::to_string_pretty(self)?)?;
+        fs::rename(&temp_path, path)?;
+        Ok(())
+    }
+
+    /// Replaces the widths for the given table, if it's one that can be resized.
+    pub fn set_widths(&mut self, widget_type: &BottomWidgetType, widths: BTreeMap<String, u16>) {
+        let widths = Some(widths);
+        match widget_type {
+            BottomWidgetType::Proc => self.proc = widths,
+            BottomWidgetType::Disk => self.disk = widths,
+            BottomWidgetType::Temp => self.temp = widths,
+            BottomWidgetType::User => self.user = widths,
+            _ => {}
+        }
+    }
+
+    /// Takes any table in `saved` over the same table here.
+    fn overridden_by(&self, saved: ConfigColumnWidths) -> Self {
+        ConfigColumnWidths {
+            proc: saved.proc.or_else(|| self.proc.clone()),
+            disk: saved.disk.or_else(|| self.disk.clone()),
+            temp: saved.temp.or_else(|| self.temp.clone()),
+            user: saved.user.or_else(|| self.user.clone()),
+        }
+    }
+}
+
+/// Where and how to push each collection, set in the `[export]` table.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct ConfigExport {
+    pub target: String,
+    pub format: Option<String>,
+    pub protocol: Option<String>,
+    pub prefix: Option<String>,
+    pub tags: Option<BTreeMap<String, String>>,
+    pub metrics: Option<Vec<String>>,
+}
+
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
 pub struct IgnoreList {
     #[serde(default = "default_as_true")]
@@ -250,15 +359,19 @@ pub fn build_app(
 ) -> Result<App> {
     use BottomWidgetType::*;
     let autohide_time = get_autohide_time(matches, config);
-    let default_time_value = get_default_time_value(matches, config)
+    let retention =
+        get_retention(matches, config).context("Update 'retention' in your config file.")?;
+    let default_time_value = get_default_time_value(matches, config, retention)
         .context("Update 'default_time_value' in your config file.")?;
     let use_basic_mode = get_use_basic_mode(matches, config);
 
     // For processes
-    let is_grouped = get_app_grouping(matches, config);
+    let grouping_type =
+        get_app_grouping(matches, config).context("Update 'group_by' in your config file.")?;
     let is_case_sensitive = get_app_case_sensitive(matches, config);
     let is_match_whole_word = get_app_match_whole_word(matches, config);
     let is_use_regex = get_app_use_regex(matches, config);
+    let default_query = matches.value_of("query").map(|query| query.to_string());
 
     let mut widget_map = HashMap::new();
     let mut cpu_state_map: HashMap<u64, CpuWidgetState> = HashMap::new();
@@ -267,6 +380,7 @@ pub fn build_app(
     let mut proc_state_map: HashMap<u64, ProcWidgetState> = HashMap::new();
     let mut temp_state_map: HashMap<u64, TempWidgetState> = HashMap::new();
     let mut disk_state_map: HashMap<u64, DiskWidgetState> = HashMap::new();
+    let mut user_state_map: HashMap<u64, UserWidgetState> = HashMap::new();
     let mut battery_state_map: HashMap<u64, BatteryWidgetState> = HashMap::new();
 
     let autohide_timer = if autohide_time {
@@ -283,7 +397,13 @@ pub fn build_app(
     let show_memory_as_values = get_mem_as_value(matches, config);
     let is_default_tree = get_is_default_tree(matches, config);
     let is_default_command = get_is_default_process_command(matches, config);
+    let default_sort_keys =
+        get_default_sort_keys(config).context("Update 'default_sort' in your config file.")?;
     let is_advanced_kill = !get_is_advanced_kill_disabled(matches, config);
+    let persist_column_widths = get_persist_column_widths(config);
+    let (proc_column_widths, disk_column_widths, temp_column_widths, user_column_widths) =
+        get_column_width_overrides(config, persist_column_widths)
+            .context("Update 'column_widths' in your config file.")?;
 
     let network_unit_type = get_network_unit_type(matches, config);
     let network_scale_type = get_network_scale_type(matches, config);
@@ -352,24 +472,67 @@ pub fn build_app(
                             );
                         }
                         Proc => {
-                            proc_state_map.insert(
-                                widget.widget_id,
-                                ProcWidgetState::init(
-                                    is_case_sensitive,
-                                    is_match_whole_word,
-                                    is_use_regex,
-                                    is_grouped,
-                                    show_memory_as_values,
-                                    is_default_tree,
-                                    is_default_command,
-                                ),
+                            let mut proc_widget_state = ProcWidgetState::init(
+                                is_case_sensitive,
+                                is_match_whole_word,
+                                is_use_regex,
+                                grouping_type != ProcessGroupingType::Ungrouped,
+                                show_memory_as_values,
+                                is_default_tree,
+                                is_default_command,
                             );
+                            proc_widget_state.grouping_type = grouping_type;
+
+                            if let Some(columns) = widget.columns.as_ref().or_else(|| {
+                                config
+                                    .flags
+                                    .as_ref()
+                                    .and_then(|flags| flags.columns.as_ref())
+                            }) {
+                                proc_widget_state.columns.set_columns(
+                                    &get_process_columns(columns)
+                                        .context("Update 'columns' in your config file.")?,
+                                );
+                            }
+
+                            if !default_sort_keys.is_empty() {
+                                proc_widget_state.set_sort_keys(&default_sort_keys);
+                            }
+
+                            proc_widget_state.table_width_state.column_width_overrides =
+                                proc_column_widths.clone();
+
+                            if let Some(default_query) =
+                                default_query.as_ref().or(widget.default_query.as_ref())
+                            {
+                                proc_widget_state
+                                    .process_search_state
+                                    .search_state
+                                    .is_enabled = true;
+                                proc_widget_state.set_search_query(
+                                    config.resolve_query(default_query).to_string(),
+                                );
+                            }
+
+                            proc_state_map.insert(widget.widget_id, proc_widget_state);
                         }
                         Disk => {
-                            disk_state_map.insert(widget.widget_id, DiskWidgetState::init());
+                            let mut disk_widget_state = DiskWidgetState::init();
+                            disk_widget_state.table_width_state.column_width_overrides =
+                                disk_column_widths.clone();
+                            disk_state_map.insert(widget.widget_id, disk_widget_state);
                         }
                         Temp => {
-                            temp_state_map.insert(widget.widget_id, TempWidgetState::init());
+                            let mut temp_widget_state = TempWidgetState::init();
+                            temp_widget_state.table_width_state.column_width_overrides =
+                                temp_column_widths.clone();
+                            temp_state_map.insert(widget.widget_id, temp_widget_state);
+                        }
+                        User => {
+                            let mut user_widget_state = UserWidgetState::init();
+                            user_widget_state.table_width_state.column_width_overrides =
+                                user_column_widths.clone();
+                            user_state_map.insert(widget.widget_id, user_widget_state);
                         }
                         Battery => {
                             battery_state_map
@@ -418,8 +581,9 @@ pub fn build_app(
         use_current_cpu_total: get_use_current_cpu_total(matches, config),
         use_basic_mode,
         default_time_value,
-        time_interval: get_time_interval(matches, config)
+        time_interval: get_time_interval(matches, config, retention)
             .context("Update 'time_delta' in your config file.")?,
+        retention,
         hide_time: get_hide_time(matches, config),
         autohide_time,
         use_old_network_legend: get_use_old_network_legend(matches, config),
@@ -433,6 +597,8 @@ pub fn build_app(
         no_write: false,
         show_table_scroll_position: get_show_table_scroll_position(matches, config),
         is_advanced_kill,
+        persist_column_widths,
+        persist_history: get_persist_history(config),
         network_scale_type,
         network_unit_type,
         network_use_binary_prefix,
@@ -442,9 +608,11 @@ pub fn build_app(
         use_cpu: used_widget_set.get(&Cpu).is_some() || used_widget_set.get(&BasicCpu).is_some(),
         use_mem: used_widget_set.get(&Mem).is_some() || used_widget_set.get(&BasicMem).is_some(),
         use_net: used_widget_set.get(&Net).is_some() || used_widget_set.get(&BasicNet).is_some(),
-        use_proc: used_widget_set.get(&Proc).is_some(),
+        // The user widget is built from the harvested processes, so those are needed too.
+        use_proc: used_widget_set.get(&Proc).is_some() || used_widget_set.contains(&User),
         use_disk: used_widget_set.get(&Disk).is_some(),
         use_temp: used_widget_set.get(&Temp).is_some(),
+        use_user: used_widget_set.contains(&User),
         use_battery: used_widget_set.get(&Battery).is_some(),
     };
 
@@ -507,6 +675,7 @@ pub fn build_app(
         .proc_state(ProcState::init(proc_state_map))
         .disk_state(DiskState::init(disk_state_map))
         .temp_state(TempState::init(temp_state_map))
+        .user_state(UserState::init(user_state_map))
         .battery_state(BatteryState::init(battery_state_map))
         .basic_table_widget_state(basic_table_widget_state)
         .current_widget(widget_map.get(&initial_widget_id).unwrap().clone()) // TODO: [UNWRAP] - many of the unwraps are fine (like this one) but do a once-over and/or switch to expect?
@@ -616,6 +785,189 @@ fn get_update_rate_in_milliseconds(
     Ok(update_rate_in_milliseconds as u64)
 }
 
+/// Gets how many times to print data in a headless mode, if limited.
+pub fn get_iterations(matches: &clap::ArgMatches<'static>) -> error::Result<Option<u64>> {
+    match matches.value_of("iterations") {
+        Some(iterations) => match iterations.parse::<u64>()? {
+            0 => Err(BottomError::ConfigError(
+                "set the number of iterations to be at least 1.".to_string(),
+            )),
+            iterations => Ok(Some(iterations)),
+        },
+        None => Ok(None),
+    }
+}
+
+/// Gets how fast to replay a recording.
+pub fn get_replay_speed(matches: &clap::ArgMatches<'static>) -> error::Result<f64> {
+    match matches.value_of("replay_speed") {
+        Some(speed) => match speed.parse::<f64>() {
+            Ok(speed) if (MIN_REPLAY_SPEED..=MAX_REPLAY_SPEED).contains(&speed) => Ok(speed),
+            _ => Err(BottomError::ConfigError(format!(
+                "set your replay speed to be a number from {} to {}.",
+                MIN_REPLAY_SPEED, MAX_REPLAY_SPEED
+            ))),
+        },
+        None => Ok(1.0),
+    }
+}
+
+/// Gets how many processes to print in a snapshot.
+pub fn get_snapshot_process_count(matches: &clap::ArgMatches<'static>) -> error::Result<usize> {
+    match matches.value_of("top") {
+        Some(top) => Ok(top.parse::<usize>()?),
+        None => Ok(DEFAULT_SNAPSHOT_PROCESS_COUNT),
+    }
+}
+
+/// Gets the options for `btm ps` from its subcommand matches.
+pub fn get_ps_options(
+    matches: &clap::ArgMatches<'static>, config: &Config,
+) -> error::Result<PsOptions> {
+    let query = matches
+        .value_of("query")
+        .map(|query| config.resolve_query(query).to_string());
+
+    let sort_keys = match matches.values_of("sort") {
+        Some(sort_keys) => {
+            let sort_keys = sort_keys
+                .map(parse_sort_key)
+                .collect::<error::Result<Vec<_>>>()?;
+            if sort_keys.len() > MAX_SECONDARY_SORT_KEYS + 1 {
+                return Err(BottomError::ConfigError(format!(
+                    "at most {} sort keys may be set with --sort.",
+                    MAX_SECONDARY_SORT_KEYS + 1
+                )));
+            }
+            sort_keys
+        }
+        None => Vec::new(),
+    };
+
+    let columns = match matches.values_of("columns") {
+        Some(columns) => Some(get_process_columns(
+            &columns.map(|column| column.to_string()).collect::<Vec<_>>(),
+        )?),
+        None => None,
+    };
+
+    let format = match matches.value_of("format") {
+        Some("json") => PsFormat::Json,
+        Some("csv") => PsFormat::Csv,
+        _ => PsFormat::Table,
+    };
+
+    let signal = match matches.value_of("signal") {
+        Some(signal) => Some(parse_signal(signal)?),
+        None => None,
+    };
+    if signal.is_some() && !has_non_blank_query(&query) {
+        return Err(BottomError::ConfigError(
+            "--signal needs a query that isn't blank, as a blank one matches every process."
+                .to_string(),
+        ));
+    }
+
+    Ok(PsOptions {
+        query,
+        sort_keys,
+        columns,
+        format,
+        signal,
+    })
+}
+
+/// Gets the exporter set up by the `[export]` table, if there is one.
+pub fn get_exporter(config: &Config) -> error::Result<Option<Exporter>> {
+    let export = match &config.export {
+        Some(export) => export,
+        None => return Ok(None),
+    };
+
+    let format = match export.format.as_deref().map(|format| format.to_lowercase()) {
+        None => ExportFormat::Influx,
+        Some(format) if format == "influx" => ExportFormat::Influx,
+        Some(format) if format == "statsd" => ExportFormat::Statsd,
+        Some(format) => {
+            return Err(BottomError::ConfigError(format!(
+                "\"{}\" is an invalid export format, use \"<influx|statsd>\".",
+                format
+            )));
+        }
+    };
+
+    let protocol = match export
+        .protocol
+        .as_deref()
+        .map(|protocol| protocol.to_lowercase())
+    {
+        None => ExportProtocol::Udp,
+        Some(protocol) if protocol == "udp" => ExportProtocol::Udp,
+        Some(protocol) if protocol == "tcp" => ExportProtocol::Tcp,
+        Some(protocol) => {
+            return Err(BottomError::ConfigError(format!(
+                "\"{}\" is an invalid export protocol, use \"<udp|tcp>\".",
+                protocol
+            )));
+        }
+    };
+
+    let metrics = match &export.metrics {
+        Some(metrics) => metrics
+            .iter()
+            .map(|metric| metric.parse::<ExportMetric>())
+            .collect::<error::Result<Vec<_>>>()?,
+        None => ExportMetric::ALL.to_vec(),
+    };
+
+    Ok(Some(Exporter::new(
+        format,
+        protocol,
+        export.target.clone(),
+        export
+            .prefix
+            .clone()
+            .unwrap_or_else(|| DEFAULT_EXPORT_PREFIX.to_string()),
+        export.tags.clone().unwrap_or_default(),
+        metrics,
+    )))
+}
+
+/// Whether there's a query that doesn't just match every process.
+pub fn has_non_blank_query(query: &Option<String>) -> bool {
+    query
+        .as_deref()
+        .is_some_and(|query| !query.trim().is_empty())
+}
+
+/// Parses a signal given either as a number or as a name, with or without the "SIG" prefix
+/// (for example, `9`, `KILL`, or `SIGKILL`).
+#[cfg(target_family = "unix")]
+fn parse_signal(signal: &str) -> error::Result<usize> {
+    if let Ok(signal) = signal.parse::<usize>() {
+        return Ok(signal);
+    }
+
+    let name = signal.to_uppercase();
+    let name = name.strip_prefix("SIG").unwrap_or(&name);
+    SIGNAL_NAMES
+        .iter()
+        .find(|(signal_name, _)| *signal_name == name)
+        .map(|(_, signal)| *signal as usize)
+        .ok_or_else(|| {
+            BottomError::ConfigError(format!(
+                "\"{}\" is an invalid signal, use a signal number or a name like \"TERM\" or \"KILL\".",
+                signal
+            ))
+        })
+}
+
+/// Windows can only terminate processes, so any signal just kills the process.
+#[cfg(target_os = "windows")]
+fn parse_signal(_signal: &str) -> error::Result<usize> {
+    Ok(15)
+}
+
 fn get_temperature(
     matches: &clap::ArgMatches<'static>, config: &Config,
 ) -> error::Result<data_harvester::temperature::TemperatureType> {
@@ -702,8 +1054,36 @@ fn get_use_basic_mode(matches: &clap::ArgMatches<'static>, config: &Config) -> b
     false
 }
 
+fn get_retention(matches: &clap::ArgMatches<'static>, config: &Config) -> error::Result<u64> {
+    let retention = if let Some(retention) = matches.value_of("retention") {
+        retention.parse::<u128>()?
+    } else if let Some(flags) = &config.flags {
+        if let Some(retention) = flags.retention {
+            retention as u128
+        } else {
+            STALE_MAX_MILLISECONDS as u128
+        }
+    } else {
+        STALE_MAX_MILLISECONDS as u128
+    };
+
+    if retention < STALE_MIN_MILLISECONDS as u128 {
+        return Err(BottomError::ConfigError(format!(
+            "set your retention to be at least {} milliseconds.",
+            STALE_MIN_MILLISECONDS
+        )));
+    } else if retention > MAX_RETENTION_MILLISECONDS as u128 {
+        return Err(BottomError::ConfigError(format!(
+            "set your retention to be at most {} milliseconds.",
+            MAX_RETENTION_MILLISECONDS
+        )));
+    }
+
+    Ok(retention as u64)
+}
+
 fn get_default_time_value(
-    matches: &clap::ArgMatches<'static>, config: &Config,
+    matches: &clap::ArgMatches<'static>, config: &Config, retention: u64,
 ) -> error::Result<u64> {
     let default_time = if let Some(default_time_value) = matches.value_of("default_time_value") {
         default_time_value.parse::<u128>()?
@@ -721,17 +1101,19 @@ fn get_default_time_value(
         return Err(BottomError::ConfigError(
             "set your default value to be at least 30000 milliseconds.".to_string(),
         ));
-    } else if default_time as u128 > STALE_MAX_MILLISECONDS as u128 {
+    } else if default_time as u128 > retention as u128 {
         return Err(BottomError::ConfigError(format!(
-            "set your default value to be at most {} milliseconds.",
-            STALE_MAX_MILLISECONDS
+            "set your default value to be at most {} milliseconds, your retention.",
+            retention
         )));
     }
 
     Ok(default_time as u64)
 }
 
-fn get_time_interval(matches: &clap::ArgMatches<'static>, config: &Config) -> error::Result<u64> {
+fn get_time_interval(
+    matches: &clap::ArgMatches<'static>, config: &Config, retention: u64,
+) -> error::Result<u64> {
     let time_interval = if let Some(time_interval) = matches.value_of("time_delta") {
         time_interval.parse::<u128>()?
     } else if let Some(flags) = &config.flags {
@@ -748,25 +1130,31 @@ fn get_time_interval(matches: &clap::ArgMatches<'static>, config: &Config) -> er
         return Err(BottomError::ConfigError(
             "set your time delta to be at least 1000 milliseconds.".to_string(),
         ));
-    } else if time_interval > STALE_MAX_MILLISECONDS as u128 {
+    } else if time_interval > retention as u128 {
         return Err(BottomError::ConfigError(format!(
-            "set your time delta to be at most {} milliseconds.",
-            STALE_MAX_MILLISECONDS
+            "set your time delta to be at most {} milliseconds, your retention.",
+            retention
         )));
     }
 
     Ok(time_interval as u64)
 }
 
-pub fn get_app_grouping(matches: &clap::ArgMatches<'static>, config: &Config) -> bool {
-    if matches.is_present("group") {
-        return true;
+pub fn get_app_grouping(
+    matches: &clap::ArgMatches<'static>, config: &Config,
+) -> error::Result<ProcessGroupingType> {
+    if let Some(group_by) = matches.value_of("group_by") {
+        return group_by.parse::<ProcessGroupingType>();
+    } else if matches.is_present("group") {
+        return Ok(ProcessGroupingType::Name);
     } else if let Some(flags) = &config.flags {
-        if let Some(grouping) = flags.group_processes {
-            return grouping;
+        if let Some(group_by) = &flags.group_by {
+            return group_by.parse::<ProcessGroupingType>();
+        } else if let Some(true) = flags.group_processes {
+            return Ok(ProcessGroupingType::Name);
         }
     }
-    false
+    Ok(ProcessGroupingType::Ungrouped)
 }
 
 pub fn get_app_case_sensitive(matches: &clap::ArgMatches<'static>, config: &Config) -> bool {
@@ -1020,6 +1408,141 @@ fn get_is_default_tree(matches: &clap::ArgMatches<'static>, config: &Config) ->
     false
 }
 
+fn get_persist_column_widths(config: &Config) -> bool {
+    if let Some(flags) = &config.flags {
+        if let Some(persist_column_widths) = flags.persist_column_widths {
+            return persist_column_widths;
+        }
+    }
+    false
+}
+
+fn get_persist_history(config: &Config) -> bool {
+    if let Some(flags) = &config.flags {
+        if let Some(persist_history) = flags.persist_history {
+            return persist_history;
+        }
+    }
+    false
+}
+
+/// Returns the column widths set for the process, disk, temperature and user tables.  With
+/// `persist_column_widths`, widths saved from resizing a table take the place of that table's
+/// widths in the config.
+fn get_column_width_overrides(
+    config: &Config, persist_column_widths: bool,
+) -> error::Result<(
+    HashMap<String, u16>,
+    HashMap<String, u16>,
+    HashMap<String, u16>,
+    HashMap<String, u16>,
+)> {
+    fn to_overrides(
+        column_widths: &Option<BTreeMap<String, u16>>,
+    ) -> error::Result<HashMap<String, u16>> {
+        let mut overrides = HashMap::new();
+        for (column, width) in column_widths.iter().flatten() {
+            if *width == 0 {
+                return Err(BottomError::ConfigError(format!(
+                    "the width of the \"{}\" column must be at least 1.",
+                    column
+                )));
+            }
+            overrides.insert(column.clone(), *width);
+        }
+        Ok(overrides)
+    }
+
+    let saved_column_widths = if persist_column_widths {
+        ConfigColumnWidths::default_saved_path().map(|path| ConfigColumnWidths::load_saved(&path))
+    } else {
+        None
+    };
+    let column_widths = match (&config.column_widths, saved_column_widths) {
+        (Some(column_widths), Some(saved)) => Some(column_widths.overridden_by(saved)),
+        (None, Some(saved)) => Some(saved),
+        (column_widths, None) => column_widths.clone(),
+    };
+
+    if let Some(column_widths) = &column_widths {
+        Ok((
+            to_overrides(&column_widths.proc)?,
+            to_overrides(&column_widths.disk)?,
+            to_overrides(&column_widths.temp)?,
+            to_overrides(&column_widths.user)?,
+        ))
+    } else {
+        Ok(Default::default())
+    }
+}
+
+/// Parses a list of process columns, where counterpart columns (like "pid" and "count") share a
+/// spot and so can't both be listed.
+fn get_process_columns(columns: &[String]) -> error::Result<Vec<ProcessSorting>> {
+    if columns.is_empty() {
+        return Err(BottomError::ConfigError(
+            "at least one process column must be listed.".to_string(),
+        ));
+    }
+
+    let mut process_columns: Vec<ProcessSorting> = Vec::with_capacity(columns.len());
+    for column in columns {
+        let process_column = column.parse::<ProcessSorting>()?;
+        if process_columns
+            .iter()
+            .any(|existing| ProcColumn::is_same_slot(existing, &process_column))
+        {
+            return Err(BottomError::ConfigError(format!(
+                "\"{}\" is listed more than once in the process columns.",
+                column
+            )));
+        }
+        process_columns.push(process_column);
+    }
+
+    Ok(process_columns)
+}
+
+/// Parses the default process sort order, where each entry is a column optionally followed
+/// by "asc" or "desc" (for example, `"cpu% desc"`).
+fn get_default_sort_keys(config: &Config) -> error::Result<Vec<(ProcessSorting, bool)>> {
+    if let Some(flags) = &config.flags {
+        if let Some(default_sort) = &flags.default_sort {
+            if default_sort.len() > MAX_SECONDARY_SORT_KEYS + 1 {
+                return Err(BottomError::ConfigError(format!(
+                    "at most {} sort keys may be set in default_sort.",
+                    MAX_SECONDARY_SORT_KEYS + 1
+                )));
+            }
+
+            return default_sort
+                .iter()
+                .map(|entry| parse_sort_key(entry))
+                .collect();
+        }
+    }
+    Ok(Vec::new())
+}
+
+/// Parses a single sort key of the form `"<column> [asc|desc]"`, returning the column and
+/// whether it is sorted in descending order.
+fn parse_sort_key(entry: &str) -> error::Result<(ProcessSorting, bool)> {
+    let mut parts = entry.split_whitespace();
+    let sort_type = parts.next().unwrap_or_default().parse::<ProcessSorting>()?;
+    let is_descending = match parts.next().map(|order| order.to_lowercase()) {
+        None => sort_type.is_descending_by_default(),
+        Some(order) if order == "desc" && parts.next().is_none() => true,
+        Some(order) if order == "asc" && parts.next().is_none() => false,
+        Some(_) => {
+            return Err(BottomError::ConfigError(format!(
+                "\"{}\" is an invalid sort key, use \"<column> [asc|desc]\".",
+                entry
+            )));
+        }
+    };
+    Ok((sort_type, is_descending))
+}
+
 fn get_show_table_scroll_position(matches: &clap::ArgMatches<'static>, config: &Config) -> bool {
     if matches.is_present("show_table_scroll_position") {
         return true;
@@ -128,6 +128,8 @@ impl Row {
                                                     .widget_type(BottomWidgetType::Proc)
                                                     .widget_id(proc_id)
                                                     .width_ratio(2)
+                                                    .default_query(widget.default_query.clone())
+                                                    .columns(widget.columns.clone())
                                                     .build(),
                                             ])
                                             .total_widget_ratio(3)
@@ -265,6 +267,8 @@ impl Row {
                                                     .widget_type(BottomWidgetType::Proc)
                                                     .widget_id(proc_id)
                                                     .width_ratio(2)
+                                                    .default_query(widget.default_query.clone())
+                                                    .columns(widget.columns.clone())
                                                     .build(),
                                             ])
                                             .col_row_height_ratio(col_row_height_ratio)
@@ -351,4 +355,6 @@ pub struct FinalWidget {
     #[serde(rename = "type")]
     pub widget_type: String,
     pub default: Option<bool>,
+    pub default_query: Option<String>,
+    pub columns: Option<Vec<String>>,
 }
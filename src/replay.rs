@@ -0,0 +1,365 @@
+//! Records collected data to a file, and replays recordings back through the TUI.
+//!
+//! A recording is just the `--json` output: one line of JSON per collection, each with the
+//! time it was collected.
+
+use std::{
+    fs::{File, OpenOptions},
+    io::{BufRead, BufReader, Seek, SeekFrom, Write},
+    path::{Path, PathBuf},
+    sync::{
+        mpsc::{Receiver, RecvTimeoutError, Sender},
+        Arc, Mutex,
+    },
+    thread,
+    time::{Duration, Instant, SystemTime},
+};
+
+use serde::Deserialize;
+
+use crate::{
+    app::data_harvester::Data,
+    constants::{
+        MAX_REPLAY_SPEED, MIN_REPLAY_SPEED, REPLAY_SEEK_MILLISECONDS, TICK_RATE_IN_MILLISECONDS,
+    },
+    headless::to_json_line,
+    utils::error::{self, BottomError},
+    BottomEvent, ThreadControlEvent,
+};
+
+/// Appends every collection to a recording.
+pub struct Recorder {
+    path: PathBuf,
+    file: File,
+}
+
+impl Recorder {
+    /// Opens the recording to append to, creating it if needed.
+    pub fn new(path: &Path) -> error::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(Recorder {
+            path: path.to_path_buf(),
+            file,
+        })
+    }
+
+    pub fn record(&mut self, data: &Data, collection_time: SystemTime) -> error::Result<()> {
+        let mut line = to_json_line(data, collection_time)?;
+        line.push('\n');
+        self.file.write_all(line.as_bytes()).map_err(|err| {
+            BottomError::GenericError(format!(
+                "Could not record to {}: {}",
+                self.path.display(),
+                err
+            ))
+        })
+    }
+}
+
+#[derive(Deserialize)]
+struct RecordedTimestamp {
+    timestamp: u64,
+}
+
+#[derive(Deserialize)]
+struct RecordedLine {
+    #[serde(flatten)]
+    data: Data,
+}
+
+/// A recording opened for replay.  Only the position and time of each collection is kept in
+/// memory; collections are read from the file as they are replayed.
+pub struct Recording {
+    reader: BufReader<File>,
+    /// When each collection happened, in milliseconds since the Unix epoch.
+    timestamps: Vec<u64>,
+    /// Where each collection starts in the file.
+    offsets: Vec<u64>,
+}
+
+impl Recording {
+    pub fn open(path: &Path) -> error::Result<Self> {
+        let mut reader = BufReader::new(File::open(path)?);
+        let mut timestamps = Vec::new();
+        let mut offsets = Vec::new();
+
+        let mut offset = 0;
+        let mut line = String::new();
+        loop {
+            line.clear();
+            let read = reader.read_line(&mut line)?;
+            if read == 0 {
+                break;
+            }
+
+            // Skip anything unreadable, like a last line cut off when bottom was killed, along
+            // with anything out of order from the clock going backwards.
+            if let Ok(recorded) = serde_json::from_str::<RecordedTimestamp>(&line) {
+                if timestamps
+                    .last()
+                    .is_none_or(|last| *last <= recorded.timestamp)
+                {
+                    timestamps.push(recorded.timestamp);
+                    offsets.push(offset);
+                }
+            }
+            offset += read as u64;
+        }
+
+        if timestamps.is_empty() {
+            return Err(BottomError::GenericError(format!(
+                "{} has nothing to replay.",
+                path.display()
+            )));
+        }
+
+        Ok(Recording {
+            reader,
+            timestamps,
+            offsets,
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.timestamps.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.timestamps.is_empty()
+    }
+
+    /// How long the recording covers.
+    pub fn duration(&self) -> Duration {
+        Duration::from_millis(self.timestamps[self.len() - 1] - self.timestamps[0])
+    }
+
+    /// Returns the index of the last collection at or before the timestamp, or the first one
+    /// if there are none.
+    fn find(&self, timestamp: u64) -> usize {
+        self.timestamps
+            .partition_point(|recorded| *recorded <= timestamp)
+            .saturating_sub(1)
+    }
+
+    fn read(&mut self, index: usize) -> error::Result<Data> {
+        self.reader.seek(SeekFrom::Start(self.offsets[index]))?;
+        let mut line = String::new();
+        self.reader.read_line(&mut line)?;
+        Ok(serde_json::from_str::<RecordedLine>(&line)?.data)
+    }
+}
+
+/// Controls for a running replay.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ReplayControl {
+    TogglePause,
+    /// Moves back or forward by this many milliseconds.
+    Seek(i64),
+    SlowDown,
+    SpeedUp,
+}
+
+impl ReplayControl {
+    pub fn seek_back() -> Self {
+        ReplayControl::Seek(-(REPLAY_SEEK_MILLISECONDS as i64))
+    }
+
+    pub fn seek_forward() -> Self {
+        ReplayControl::Seek(REPLAY_SEEK_MILLISECONDS as i64)
+    }
+}
+
+/// Where a replay is at, for showing in the TUI.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ReplayStatus {
+    /// When the latest replayed collection happened, in milliseconds since the Unix epoch.
+    pub timestamp: u64,
+    /// How far into the recording the latest replayed collection is.
+    pub position: Duration,
+    /// How long the recording covers.
+    pub duration: Duration,
+    pub speed: f64,
+    pub is_paused: bool,
+}
+
+type ReplaySender = Sender<BottomEvent<crossterm::event::KeyEvent, crossterm::event::MouseEvent>>;
+
+/// Feeds a recording through as updates, at the recorded pace multiplied by the speed.
+///
+/// Replayed collections are given instants with the same spacing as when they were recorded,
+/// so the charts look the same at any speed.  After seeking, the charts are filled back in with
+/// up to `retention_millis` of what came before.
+pub fn create_replay_thread(
+    sender: ReplaySender, control_receiver: Receiver<ThreadControlEvent>,
+    termination_ctrl_lock: Arc<Mutex<bool>>, mut recording: Recording, speed: f64,
+    retention_millis: u64,
+) -> std::thread::JoinHandle<()> {
+    thread::spawn(move || {
+        let mut replay = Replay {
+            anchor: (recording.timestamps[0], Instant::now()),
+            next_index: 0,
+            next_due: Some(Instant::now()),
+            speed: speed.clamp(MIN_REPLAY_SPEED, MAX_REPLAY_SPEED),
+            is_paused: false,
+            retention_millis,
+        };
+
+        loop {
+            if let Ok(is_terminated) = termination_ctrl_lock.try_lock() {
+                if *is_terminated {
+                    break;
+                }
+            }
+
+            let timeout = match replay.next_due {
+                Some(due) => due.saturating_duration_since(Instant::now()),
+                None => Duration::from_millis(TICK_RATE_IN_MILLISECONDS),
+            };
+            match control_receiver.recv_timeout(timeout) {
+                Ok(ThreadControlEvent::Replay(control)) => {
+                    if replay.control(control, &mut recording, &sender).is_err() {
+                        break;
+                    }
+                    continue;
+                }
+                Ok(_) | Err(RecvTimeoutError::Timeout) => {}
+                Err(RecvTimeoutError::Disconnected) => break,
+            }
+
+            if !matches!(replay.next_due, Some(due) if due <= Instant::now()) {
+                continue;
+            }
+            if replay.send_next(&mut recording, &sender).is_err() {
+                break;
+            }
+        }
+    })
+}
+
+struct Replay {
+    /// A recorded timestamp and the instant it was replayed at, which all other replayed
+    /// instants are relative to.
+    anchor: (u64, Instant),
+    next_index: usize,
+    /// When the next collection should be sent, unless paused or finished.
+    next_due: Option<Instant>,
+    speed: f64,
+    is_paused: bool,
+    /// How much of what came before a seek is replayed to fill the charts back in.
+    retention_millis: u64,
+}
+
+/// The sending side of the channel is gone, so the replay should stop.
+struct Disconnected;
+
+impl Replay {
+    fn get_instant(&self, timestamp: u64) -> Instant {
+        let (anchor_timestamp, anchor_instant) = self.anchor;
+        if timestamp >= anchor_timestamp {
+            anchor_instant + Duration::from_millis(timestamp - anchor_timestamp)
+        } else {
+            anchor_instant
+                .checked_sub(Duration::from_millis(anchor_timestamp - timestamp))
+                .unwrap_or(anchor_instant)
+        }
+    }
+
+    fn send(
+        &self, index: usize, recording: &mut Recording, sender: &ReplaySender,
+    ) -> Result<(), Disconnected> {
+        let event = match recording.read(index) {
+            Ok(mut data) => {
+                data.last_collection_time = self.get_instant(recording.timestamps[index]);
+                BottomEvent::Update(Box::new(data))
+            }
+            Err(err) => BottomEvent::CollectionError(format!("Could not replay: {}", err)),
+        };
+        sender.send(event).map_err(|_| Disconnected)
+    }
+
+    fn send_status(
+        &self, recording: &Recording, sender: &ReplaySender,
+    ) -> Result<(), Disconnected> {
+        let timestamp = recording.timestamps[self.next_index.saturating_sub(1)];
+        let status = ReplayStatus {
+            timestamp,
+            position: Duration::from_millis(timestamp - recording.timestamps[0]),
+            duration: recording.duration(),
+            speed: self.speed,
+            is_paused: self.is_paused,
+        };
+        sender
+            .send(BottomEvent::ReplayStatus(status))
+            .map_err(|_| Disconnected)
+    }
+
+    fn schedule_next(&mut self, recording: &Recording) {
+        self.next_due = if self.is_paused || self.next_index >= recording.len() {
+            None
+        } else {
+            let gap = recording.timestamps[self.next_index]
+                - recording.timestamps[self.next_index.saturating_sub(1)];
+            Some(Instant::now() + Duration::from_secs_f64(gap as f64 / 1000.0 / self.speed))
+        };
+    }
+
+    fn send_next(
+        &mut self, recording: &mut Recording, sender: &ReplaySender,
+    ) -> Result<(), Disconnected> {
+        self.send(self.next_index, recording, sender)?;
+        self.next_index += 1;
+        self.schedule_next(recording);
+        self.send_status(recording, sender)
+    }
+
+    /// Jumps to the collection at or before the timestamp.  Everything shown so far is dropped,
+    /// and the charts are filled back in with what was recorded within the retention before that
+    /// point.
+    fn seek(
+        &mut self, timestamp: u64, recording: &mut Recording, sender: &ReplaySender,
+    ) -> Result<(), Disconnected> {
+        let index = recording.find(timestamp);
+        let first_index =
+            recording.find(recording.timestamps[index].saturating_sub(self.retention_millis));
+
+        sender
+            .send(BottomEvent::ReplaySeek)
+            .map_err(|_| Disconnected)?;
+        self.anchor = (recording.timestamps[index], Instant::now());
+        for index in first_index..=index {
+            self.send(index, recording, sender)?;
+        }
+        self.next_index = index + 1;
+        self.schedule_next(recording);
+        self.send_status(recording, sender)
+    }
+
+    fn control(
+        &mut self, control: ReplayControl, recording: &mut Recording, sender: &ReplaySender,
+    ) -> Result<(), Disconnected> {
+        match control {
+            ReplayControl::TogglePause => {
+                self.is_paused = !self.is_paused;
+                self.schedule_next(recording);
+            }
+            ReplayControl::Seek(offset) => {
+                let current = recording.timestamps[self.next_index.saturating_sub(1)];
+                let target = if offset < 0 {
+                    current.saturating_sub(offset.unsigned_abs())
+                } else {
+                    current.saturating_add(offset as u64)
+                };
+                return self.seek(target, recording, sender);
+            }
+            ReplayControl::SlowDown => {
+                self.speed = (self.speed / 2.0).max(MIN_REPLAY_SPEED);
+                self.schedule_next(recording);
+            }
+            ReplayControl::SpeedUp => {
+                self.speed = (self.speed * 2.0).min(MAX_REPLAY_SPEED);
+                self.schedule_next(recording);
+            }
+        }
+        self.send_status(recording, sender)
+    }
+}
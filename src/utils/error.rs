@@ -1,4 +1,4 @@
-use std::{borrow::Cow, result};
+use std::{borrow::Cow, ops::Range, result};
 use thiserror::Error;
 
 #[cfg(target_os = "linux")]
@@ -31,9 +31,10 @@ pub enum BottomError {
     /// An error to represent errors with converting between data types.
     #[error("Conversion error, {0}")]
     ConversionError(String),
-    /// An error to represent errors with querying.
+    /// An error to represent errors with querying, along with the character span of the offending
+    /// part of the query, if known.
     #[error("Query error, {0}")]
-    QueryError(Cow<'static, str>),
+    QueryError(Cow<'static, str>, Option<Range<usize>>),
     /// An error that just signifies something minor went wrong; no message.
     #[error("Minor error.")]
     MinorError,
@@ -79,6 +80,12 @@ impl From<toml::de::Error> for BottomError {
     }
 }
 
+impl From<serde_json::Error> for BottomError {
+    fn from(err: serde_json::Error) -> Self {
+        BottomError::ConversionError(err.to_string())
+    }
+}
+
 #[cfg(feature = "fern")]
 impl From<fern::InitError> for BottomError {
     fn from(err: fern::InitError) -> Self {
@@ -110,6 +117,7 @@ impl From<regex::Error> for BottomError {
                 error.last().unwrap_or(&"".to_string().as_str())
             )
             .into(),
+            None,
         )
     }
 }
@@ -80,6 +80,50 @@ fn test_small_delta_time() {
         ));
 }
 
+#[test]
+fn test_large_retention() {
+    Command::new(get_binary_location())
+        .arg("-C")
+        .arg("./tests/empty_config.toml")
+        .arg("--retention")
+        .arg("604800001")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "set your retention to be at most 604800000 milliseconds.",
+        ));
+}
+
+#[test]
+fn test_small_retention() {
+    Command::new(get_binary_location())
+        .arg("-C")
+        .arg("./tests/empty_config.toml")
+        .arg("--retention")
+        .arg("29999")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "set your retention to be at least 30000 milliseconds.",
+        ));
+}
+
+#[test]
+fn test_default_time_past_retention() {
+    Command::new(get_binary_location())
+        .arg("-C")
+        .arg("./tests/empty_config.toml")
+        .arg("--retention")
+        .arg("60000")
+        .arg("-t")
+        .arg("90000")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "set your default value to be at most 60000 milliseconds, your retention.",
+        ));
+}
+
 #[test]
 fn test_large_rate() {
     Command::new(get_binary_location())
@@ -176,3 +220,113 @@ fn test_missing_default_widget_type() {
             "The following required arguments were not provided",
         ));
 }
+
+#[test]
+fn test_zero_iterations() {
+    Command::new(get_binary_location())
+        .arg("-C")
+        .arg("./tests/empty_config.toml")
+        .arg("--json")
+        .arg("-n")
+        .arg("0")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "set the number of iterations to be at least 1.",
+        ));
+}
+
+#[test]
+fn test_iterations_without_json() {
+    Command::new(get_binary_location())
+        .arg("-C")
+        .arg("./tests/empty_config.toml")
+        .arg("-n")
+        .arg("3")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "The following required arguments were not provided",
+        ));
+}
+
+#[test]
+fn test_json_with_snapshot() {
+    Command::new(get_binary_location())
+        .arg("-C")
+        .arg("./tests/empty_config.toml")
+        .arg("--json")
+        .arg("--snapshot")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("cannot be used with"));
+}
+
+#[test]
+fn test_ps_invalid_signal() {
+    Command::new(get_binary_location())
+        .arg("-C")
+        .arg("./tests/empty_config.toml")
+        .arg("ps")
+        .arg("--query")
+        .arg("btm")
+        .arg("--signal")
+        .arg("NOTASIGNAL")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("is an invalid signal"));
+}
+
+#[test]
+fn test_ps_signal_without_query() {
+    Command::new(get_binary_location())
+        .arg("-C")
+        .arg("./tests/empty_config.toml")
+        .arg("ps")
+        .arg("--signal")
+        .arg("TERM")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("--query"));
+}
+
+#[test]
+fn test_ps_signal_with_blank_query() {
+    Command::new(get_binary_location())
+        .arg("-C")
+        .arg("./tests/empty_config.toml")
+        .arg("ps")
+        .arg("--query")
+        .arg("  ")
+        .arg("--signal")
+        .arg("TERM")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("isn't blank"));
+}
+
+#[test]
+fn test_ps_invalid_query() {
+    Command::new(get_binary_location())
+        .arg("-C")
+        .arg("./tests/empty_config.toml")
+        .arg("ps")
+        .arg("--query")
+        .arg("cpu >")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Query error"));
+}
+
+#[test]
+fn test_ps_invalid_sort() {
+    Command::new(get_binary_location())
+        .arg("-C")
+        .arg("./tests/empty_config.toml")
+        .arg("ps")
+        .arg("--sort")
+        .arg("cpu sideways")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("is an invalid sort key"));
+}
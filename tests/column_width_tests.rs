@@ -0,0 +1,103 @@
+//! Tests for resizing table columns.
+
+use bottom::app::CanvasTableWidthState;
+
+fn get_width_state() -> CanvasTableWidthState {
+    CanvasTableWidthState {
+        calculated_column_widths: vec![5, 10, 20],
+        column_names: vec!["PID".to_string(), "Name".to_string(), "Command".to_string()],
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_change_selected_column() {
+    let mut width_state = get_width_state();
+    assert_eq!(width_state.selected_column, None);
+
+    width_state.change_selected_column(false);
+    assert_eq!(width_state.selected_column, Some(0));
+    width_state.change_selected_column(false);
+    assert_eq!(width_state.selected_column, Some(0));
+
+    width_state.change_selected_column(true);
+    width_state.change_selected_column(true);
+    width_state.change_selected_column(true);
+    assert_eq!(width_state.selected_column, Some(2));
+}
+
+#[test]
+fn test_change_selected_column_width() {
+    let mut width_state = get_width_state();
+
+    // Resizing without a selected column picks the first one.
+    assert!(width_state.change_selected_column_width(2));
+    assert_eq!(width_state.selected_column, Some(0));
+    assert_eq!(width_state.get_width_overrides(), vec![Some(7), None, None]);
+
+    width_state.change_selected_column(true);
+    assert!(width_state.change_selected_column_width(-100));
+    assert_eq!(
+        width_state.get_width_overrides(),
+        vec![Some(7), Some(1), None]
+    );
+}
+
+#[test]
+fn test_overrides_follow_column_names() {
+    let mut width_state = get_width_state();
+    assert!(width_state.set_column_width(2, 40));
+    assert!(!width_state.set_column_width(2, 40));
+
+    // If the columns get reordered, the override should stay with its column.
+    width_state.column_names = vec!["Command".to_string(), "PID".to_string()];
+    assert_eq!(width_state.get_width_overrides(), vec![Some(40), None]);
+
+    assert!(width_state.reset_column_widths());
+    assert!(!width_state.reset_column_widths());
+    assert_eq!(width_state.get_width_overrides(), vec![None, None]);
+}
+
+#[test]
+fn test_drag_column() {
+    let mut width_state = get_width_state();
+    width_state.column_header_y_loc = Some(1);
+    width_state.column_header_x_locs = Some(vec![(1, 6), (7, 17), (18, 38)]);
+
+    // Only the gap right after a header starts a drag.
+    assert!(!width_state.try_start_drag(3, 1));
+    assert!(!width_state.try_start_drag(6, 2));
+    assert!(width_state.try_start_drag(17, 1));
+    assert_eq!(width_state.dragged_column, Some(1));
+
+    assert!(width_state.drag_to(12));
+    assert_eq!(width_state.get_width_overrides(), vec![None, Some(5), None]);
+}
+
+#[test]
+fn test_saved_column_widths() {
+    use bottom::app::layout_manager::BottomWidgetType;
+    use bottom::options::ConfigColumnWidths;
+    use std::collections::BTreeMap;
+
+    let path = std::env::temp_dir().join(format!(
+        "btm_column_widths_{}/column_widths.toml",
+        std::process::id()
+    ));
+    assert!(ConfigColumnWidths::load_saved(&path).proc.is_none());
+
+    let mut saved_widths = ConfigColumnWidths::default();
+    saved_widths.set_widths(
+        &BottomWidgetType::Proc,
+        vec![("Command".to_string(), 40)].into_iter().collect(),
+    );
+    saved_widths.set_widths(&BottomWidgetType::Disk, BTreeMap::new());
+    saved_widths.save(&path).unwrap();
+
+    // A reset table is saved as empty, rather than left out.
+    let loaded = ConfigColumnWidths::load_saved(&path);
+    let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    assert_eq!(loaded.proc.unwrap().get("Command"), Some(&40));
+    assert_eq!(loaded.disk, Some(BTreeMap::new()));
+    assert!(loaded.temp.is_none());
+}
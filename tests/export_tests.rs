@@ -0,0 +1,172 @@
+//! Tests for pushing collected data as InfluxDB line protocol or statsd.
+
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+use std::collections::BTreeMap;
+use std::net::{TcpListener, UdpSocket};
+use std::process::Command;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use bottom::app::data_harvester::{
+    cpu::CpuData, memory::MemHarvest, temperature::TempHarvest, temperature::TemperatureType, Data,
+};
+use bottom::export::{ExportFormat, ExportMetric, ExportProtocol, Exporter};
+
+fn get_binary_location() -> String {
+    env!("CARGO_BIN_EXE_btm").to_string()
+}
+
+fn get_test_data() -> Data {
+    Data {
+        cpu: Some(vec![CpuData {
+            cpu_prefix: "AVG".to_string(),
+            cpu_count: None,
+            cpu_usage: 12.5,
+        }]),
+        memory: Some(MemHarvest {
+            mem_total_in_kib: 100,
+            mem_used_in_kib: 25,
+            use_percent: Some(25.0),
+        }),
+        temperature_sensors: Some(vec![TempHarvest {
+            name: "some sensor, with=specials".to_string(),
+            temperature: 40.0,
+        }]),
+        ..Data::default()
+    }
+}
+
+fn get_exporter(format: ExportFormat, metrics: Vec<ExportMetric>) -> Exporter {
+    let mut tags = BTreeMap::new();
+    tags.insert("host".to_string(), "test".to_string());
+    Exporter::new(
+        format,
+        ExportProtocol::Udp,
+        "127.0.0.1:8094".to_string(),
+        "bottom".to_string(),
+        tags,
+        metrics,
+    )
+}
+
+#[test]
+fn test_influx_lines() {
+    let exporter = get_exporter(ExportFormat::Influx, ExportMetric::ALL.to_vec());
+    let time = UNIX_EPOCH + Duration::from_secs(1);
+
+    // Anything that wasn't collected is left out.
+    assert_eq!(
+        exporter.to_lines(&get_test_data(), &TemperatureType::Celsius, time),
+        vec![
+            "bottom_cpu,cpu=avg,host=test usage_percent=12.5 1000000000",
+            "bottom_memory,host=test total_bytes=102400,used_bytes=25600 1000000000",
+            "bottom_temperature,sensor=some\\ sensor\\,\\ with\\=specials,unit=celsius,host=test value=40 1000000000",
+        ]
+    );
+}
+
+#[test]
+fn test_statsd_lines() {
+    let exporter = get_exporter(
+        ExportFormat::Statsd,
+        vec![ExportMetric::Memory, ExportMetric::Temperature],
+    );
+
+    // Only the chosen metrics are sent.
+    assert_eq!(
+        exporter.to_lines(&get_test_data(), &TemperatureType::Kelvin, UNIX_EPOCH),
+        vec![
+            "bottom.memory.total_bytes:102400|g|#host:test",
+            "bottom.memory.used_bytes:25600|g|#host:test",
+            "bottom.temperature.value:40|g|#sensor:some sensor_ with=specials,unit:kelvin,host:test",
+        ]
+    );
+}
+
+#[test]
+fn test_export_udp() {
+    let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+    socket
+        .set_read_timeout(Some(Duration::from_secs(10)))
+        .unwrap();
+
+    let config = std::env::temp_dir().join(format!("btm_export_test_{}.toml", std::process::id()));
+    std::fs::write(
+        &config,
+        format!(
+            "[export]\ntarget = \"{}\"\nmetrics = [\"memory\"]\n",
+            socket.local_addr().unwrap()
+        ),
+    )
+    .unwrap();
+
+    let mut btm = Command::new(get_binary_location())
+        .arg("-C")
+        .arg(&config)
+        .arg("--headless")
+        .arg("-r")
+        .arg("250")
+        .spawn()
+        .unwrap();
+
+    let mut buffer = [0; 2048];
+    let received = socket.recv(&mut buffer);
+    btm.kill().unwrap();
+    btm.wait().unwrap();
+    let _ = std::fs::remove_file(&config);
+
+    let packet = String::from_utf8_lossy(&buffer[..received.unwrap()]).to_string();
+    assert!(
+        packet.starts_with("bottom_memory total_bytes="),
+        "{}",
+        packet
+    );
+    assert!(packet.contains(",used_bytes="), "{}", packet);
+}
+
+#[test]
+fn test_export_to_unreachable_target() {
+    // Nothing is listening on this port once the listener is dropped.
+    let target = TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap();
+    let mut exporter = Exporter::new(
+        ExportFormat::Influx,
+        ExportProtocol::Tcp,
+        target.to_string(),
+        "bottom".to_string(),
+        BTreeMap::new(),
+        vec![ExportMetric::Memory],
+    );
+
+    // Exporting never waits on the target, and the failure is only reported once.
+    let mut errors = Vec::new();
+    for _ in 0..10 {
+        let start = Instant::now();
+        if let Err(err) = exporter.export(
+            &get_test_data(),
+            &TemperatureType::Celsius,
+            SystemTime::now(),
+        ) {
+            errors.push(err.to_string());
+        }
+        assert!(start.elapsed() < Duration::from_millis(100));
+        std::thread::sleep(Duration::from_millis(50));
+    }
+    assert_eq!(errors.len(), 1, "{:?}", errors);
+    assert!(errors[0].contains("Could not export to"), "{}", errors[0]);
+}
+
+#[test]
+fn test_headless_needs_output() {
+    Command::new(get_binary_location())
+        .arg("-C")
+        .arg("./tests/empty_config.toml")
+        .arg("--headless")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "--headless needs --serve-metrics, --record, or an [export] table",
+        ));
+}
@@ -0,0 +1,111 @@
+//! Tests for making the process selection follow a process as the rows move around.
+
+use bottom::app::{FollowTarget, ProcWidgetState};
+use bottom::data_conversion::{ConvertedProcessData, ProcessGroupingType};
+
+fn processes(pids: &[u32]) -> Vec<ConvertedProcessData> {
+    pids.iter()
+        .map(|pid| ConvertedProcessData {
+            pid: *pid as _,
+            name: format!("process-{}", pid),
+            ..Default::default()
+        })
+        .collect()
+}
+
+/// Returns a widget state that follows the process at the given position of the given rows.
+fn following(rows: &[ConvertedProcessData], position: usize) -> ProcWidgetState {
+    let mut state = ProcWidgetState::init(false, false, false, false, false, false, false);
+    state.scroll_state.current_scroll_position = position;
+    state.toggle_follow(rows.get(position));
+    state
+}
+
+fn update(state: &mut ProcWidgetState, previous: &[u32], current: &[u32]) {
+    state.update_follow(Some(&processes(previous)), &processes(current));
+}
+
+#[test]
+fn test_follow_across_resorts() {
+    let mut state = following(&processes(&[1, 2, 3]), 1);
+    assert_eq!(
+        state.follow_state.as_ref().map(|follow| &follow.target),
+        Some(&FollowTarget::Pid(2))
+    );
+
+    update(&mut state, &[1, 2, 3], &[2, 3, 1]);
+    assert_eq!(state.scroll_state.current_scroll_position, 0);
+
+    update(&mut state, &[2, 3, 1], &[3, 1, 4, 2]);
+    assert_eq!(state.scroll_state.current_scroll_position, 3);
+    assert!(!state.follow_state.as_ref().unwrap().has_exited);
+}
+
+#[test]
+fn test_follow_exited_process() {
+    let mut state = following(&processes(&[1, 2, 3]), 2);
+
+    update(&mut state, &[1, 2, 3], &[3, 1]);
+    assert_eq!(state.scroll_state.current_scroll_position, 0);
+
+    // The process is gone, so the selection stays put.
+    update(&mut state, &[3, 1], &[1, 2]);
+    assert_eq!(state.scroll_state.current_scroll_position, 0);
+    assert!(state.follow_state.as_ref().unwrap().has_exited);
+
+    // It's still followed if it comes back (say, the search no longer filters it out).
+    update(&mut state, &[1, 2], &[1, 2, 3]);
+    assert_eq!(state.scroll_state.current_scroll_position, 2);
+    assert!(!state.follow_state.as_ref().unwrap().has_exited);
+}
+
+#[test]
+fn test_follow_moved_selection() {
+    let mut state = following(&processes(&[1, 2, 3]), 0);
+
+    // Moving the selection by hand follows whatever was moved to.
+    state.scroll_state.current_scroll_position = 2;
+    update(&mut state, &[1, 2, 3], &[3, 2, 1]);
+    assert_eq!(state.scroll_state.current_scroll_position, 0);
+    assert_eq!(
+        state.follow_state.as_ref().map(|follow| &follow.target),
+        Some(&FollowTarget::Pid(3))
+    );
+}
+
+#[test]
+fn test_follow_group() {
+    let mut state = ProcWidgetState::init(false, false, false, true, false, false, false);
+    let rows = processes(&[1, 2]);
+    state.scroll_state.current_scroll_position = 1;
+    state.toggle_follow(rows.get(1));
+    assert_eq!(
+        state.follow_state.as_ref().map(|follow| &follow.target),
+        Some(&FollowTarget::Group("process-2".to_string()))
+    );
+
+    // Group entries don't have a meaningful PID, so only the name counts.
+    let mut moved_rows = processes(&[2, 1]);
+    moved_rows[0].name = "process-1".to_string();
+    moved_rows[1].name = "process-2".to_string();
+    state.update_follow(Some(&rows), &moved_rows);
+    assert_eq!(state.scroll_state.current_scroll_position, 1);
+
+    // Group names mean something else with a different grouping, so stop following.
+    state.set_grouping_type(ProcessGroupingType::User);
+    assert!(state.follow_state.is_none());
+}
+
+#[test]
+fn test_toggle_follow() {
+    let rows = processes(&[1, 2, 3]);
+    let mut state = following(&rows, 1);
+    assert!(state.follow_state.is_some());
+
+    state.toggle_follow(rows.get(1));
+    assert!(state.follow_state.is_none());
+
+    // Nothing to follow in an empty table.
+    state.toggle_follow(None);
+    assert!(state.follow_state.is_none());
+}
@@ -0,0 +1,77 @@
+//! Tests for going to a PID in the process widget.
+
+use bottom::app::{ProcWidgetState, TreeCollapseDefault};
+use bottom::data_conversion::{ConvertedProcessData, ProcessGroupingType};
+use bottom::Pid;
+
+mod util;
+use util::{get_proc_widget_state, get_process_tree};
+
+fn rows(pids: &[Pid]) -> Vec<ConvertedProcessData> {
+    let processes = get_process_tree();
+    pids.iter().map(|pid| processes[pid].clone()).collect()
+}
+
+#[test]
+fn test_go_to_pid() {
+    let mut state = get_proc_widget_state();
+    assert!(state.go_to_pid(3, &get_process_tree()));
+    assert_eq!(state.pid_to_select, Some(3));
+
+    state.select_pending_pid(&rows(&[4, 2, 3, 1]));
+    assert_eq!(state.scroll_state.current_scroll_position, 2);
+    assert_eq!(state.pid_to_select, None);
+}
+
+#[test]
+fn test_go_to_missing_pid() {
+    let mut state = get_proc_widget_state();
+    assert!(!state.go_to_pid(42, &get_process_tree()));
+    assert_eq!(state.pid_to_select, None);
+}
+
+#[test]
+fn test_go_to_pid_clears_hiding_search() {
+    let processes = get_process_tree();
+    let mut state = get_proc_widget_state();
+
+    // A search that still shows the process is left alone...
+    state.set_search_query("bash".to_string());
+    assert!(state.go_to_pid(3, &processes));
+    assert_eq!(
+        state.process_search_state.search_state.current_search_query,
+        "bash"
+    );
+
+    // ...but one that hides it is cleared.
+    assert!(state.go_to_pid(4, &processes));
+    assert_eq!(
+        state.process_search_state.search_state.current_search_query,
+        ""
+    );
+}
+
+#[test]
+fn test_go_to_pid_ungroups() {
+    let mut state = get_proc_widget_state();
+    state.set_grouping_type(ProcessGroupingType::User);
+    assert!(state.go_to_pid(2, &get_process_tree()));
+    assert_eq!(state.grouping_type, ProcessGroupingType::Ungrouped);
+}
+
+#[test]
+fn test_go_to_pid_expands_tree() {
+    let processes = get_process_tree();
+    let mut state = ProcWidgetState::init(false, false, false, false, false, true, false);
+    state
+        .tree_collapse_state
+        .set_default(TreeCollapseDefault::CollapseAll);
+
+    assert!(state.go_to_pid(4, &processes));
+    let collapse_state = &state.tree_collapse_state;
+    assert!(!collapse_state.is_collapsed(1, "init", 0));
+    assert!(!collapse_state.is_collapsed(2, "sshd", 1));
+    assert!(!collapse_state.is_collapsed(3, "bash", 2));
+    // The process itself stays collapsed.
+    assert!(collapse_state.is_collapsed(4, "vim", 3));
+}
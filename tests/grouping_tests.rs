@@ -0,0 +1,135 @@
+//! Tests for grouping processes together.
+
+use std::collections::HashMap;
+
+use bottom::app::data_harvester::processes::ProcessSorting;
+use bottom::data_conversion::{group_process_data, ProcessGroupingType};
+
+mod util;
+use util::{get_proc_widget_state, get_process_tree};
+
+/// Returns each group's name, summed CPU usage, and sorted PIDs, in order of name.
+fn get_groups(grouping_type: ProcessGroupingType) -> Vec<(String, f64, Vec<u32>)> {
+    let processes = get_process_tree();
+    let single_process_data = processes.values().cloned().collect::<Vec<_>>();
+
+    let mut groups = group_process_data(&single_process_data, grouping_type, false, &processes)
+        .into_iter()
+        .map(|group| {
+            let mut pids = group
+                .group_pids
+                .iter()
+                .map(|pid| *pid as u32)
+                .collect::<Vec<_>>();
+            pids.sort_unstable();
+            (group.name, group.cpu_percent_usage, pids)
+        })
+        .collect::<Vec<_>>();
+    groups.sort_by(|a, b| a.0.cmp(&b.0));
+
+    groups
+}
+
+#[test]
+fn test_group_by_name() {
+    assert_eq!(
+        get_groups(ProcessGroupingType::Name),
+        vec![
+            ("bash".to_string(), 8.0, vec![3, 5]),
+            ("init".to_string(), 1.0, vec![1]),
+            ("sshd".to_string(), 2.0, vec![2]),
+            ("vim".to_string(), 4.0, vec![4]),
+        ]
+    );
+}
+
+#[test]
+fn test_group_by_user() {
+    assert_eq!(
+        get_groups(ProcessGroupingType::User),
+        vec![
+            ("alice".to_string(), 7.0, vec![3, 4]),
+            ("bob".to_string(), 5.0, vec![5]),
+            ("root".to_string(), 3.0, vec![1, 2]),
+        ]
+    );
+}
+
+#[test]
+fn test_group_by_parent() {
+    assert_eq!(
+        get_groups(ProcessGroupingType::Parent),
+        vec![
+            ("(0)".to_string(), 1.0, vec![1]),
+            ("bash (3)".to_string(), 4.0, vec![4]),
+            ("init (1)".to_string(), 2.0, vec![2]),
+            ("sshd (2)".to_string(), 8.0, vec![3, 5]),
+        ]
+    );
+}
+
+#[test]
+fn test_group_by_tty() {
+    assert_eq!(
+        get_groups(ProcessGroupingType::Tty),
+        vec![
+            ("(no tty)".to_string(), 3.0, vec![1, 2]),
+            ("pts/0".to_string(), 7.0, vec![3, 4]),
+            ("pts/1".to_string(), 5.0, vec![5]),
+        ]
+    );
+}
+
+#[test]
+fn test_group_by_cgroup() {
+    assert_eq!(
+        get_groups(ProcessGroupingType::Cgroup),
+        vec![
+            ("/user.slice/alice.scope".to_string(), 7.0, vec![3, 4]),
+            ("/user.slice/bob.scope".to_string(), 5.0, vec![5]),
+            ("/user.slice/root.scope".to_string(), 3.0, vec![1, 2]),
+        ]
+    );
+}
+
+#[test]
+fn test_groups_sum_usage() {
+    let processes = get_process_tree();
+    let single_process_data = processes.values().cloned().collect::<Vec<_>>();
+    let groups = group_process_data(
+        &single_process_data,
+        ProcessGroupingType::User,
+        false,
+        &HashMap::new(),
+    );
+
+    let alice = groups
+        .iter()
+        .find(|group| group.name == "alice")
+        .expect("alice should have a group");
+    assert_eq!(alice.mem_usage_bytes, 2048);
+    assert_eq!(alice.rps_f64, 20.0);
+    assert_eq!(alice.user.as_deref(), Some("alice"));
+}
+
+#[test]
+fn test_cycling_grouping_swaps_columns() {
+    let mut proc_widget_state = get_proc_widget_state();
+    assert!(!proc_widget_state.is_grouped());
+    assert!(proc_widget_state.columns.is_enabled(&ProcessSorting::Pid));
+
+    proc_widget_state.set_grouping_type(ProcessGroupingType::Ungrouped.next());
+    assert_eq!(proc_widget_state.grouping_type, ProcessGroupingType::Name);
+    assert!(proc_widget_state.columns.is_enabled(&ProcessSorting::Count));
+    assert!(!proc_widget_state.columns.is_enabled(&ProcessSorting::Pid));
+
+    // Switching between grouping types keeps the grouped columns.
+    proc_widget_state.set_grouping_type(proc_widget_state.grouping_type.next());
+    assert_eq!(proc_widget_state.grouping_type, ProcessGroupingType::User);
+    assert!(proc_widget_state.columns.is_enabled(&ProcessSorting::Count));
+
+    proc_widget_state.set_grouping_type(ProcessGroupingType::Cgroup.next());
+    assert!(!proc_widget_state.is_grouped());
+    assert!(proc_widget_state.columns.is_enabled(&ProcessSorting::Pid));
+    assert!(!proc_widget_state.columns.is_enabled(&ProcessSorting::Count));
+}
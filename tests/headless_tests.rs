@@ -0,0 +1,233 @@
+//! Tests for the modes that print collected data instead of opening the TUI.
+
+use std::process::Command;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use assert_cmd::prelude::*;
+use bottom::app::data_harvester::{memory::MemHarvest, Data};
+use bottom::headless::{format_table, to_json_line};
+
+fn get_binary_location() -> String {
+    env!("CARGO_BIN_EXE_btm").to_string()
+}
+
+#[test]
+fn test_json_line() {
+    let data = Data {
+        memory: Some(MemHarvest {
+            mem_total_in_kib: 100,
+            mem_used_in_kib: 25,
+            use_percent: Some(25.0),
+        }),
+        load_avg: Some([1.0, 0.5, 0.25]),
+        ..Data::default()
+    };
+
+    // Anything that wasn't collected is left out.
+    assert_eq!(
+        to_json_line(&data, UNIX_EPOCH + Duration::from_millis(1500)).unwrap(),
+        r#"{"timestamp":1500,"load_avg":[1.0,0.5,0.25],"memory":{"mem_total_in_kib":100,"mem_used_in_kib":25,"use_percent":25.0}}"#
+    );
+}
+
+#[test]
+fn test_json_iterations() {
+    let output = Command::new(get_binary_location())
+        .arg("-C")
+        .arg("./tests/empty_config.toml")
+        .arg("--json")
+        .arg("-r")
+        .arg("250")
+        .arg("-n")
+        .arg("2")
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+
+    let output = String::from_utf8(output).unwrap();
+    let lines = output.lines().collect::<Vec<_>>();
+    assert_eq!(lines.len(), 2);
+    for line in lines {
+        assert!(
+            line.starts_with(r#"{"timestamp":"#),
+            "unexpected line {}",
+            line
+        );
+        assert!(line.ends_with('}'));
+    }
+}
+
+#[test]
+fn test_json_first_line_is_primed() {
+    let start = SystemTime::now();
+    let output = Command::new(get_binary_location())
+        .arg("-C")
+        .arg("./tests/empty_config.toml")
+        .arg("--json")
+        .arg("-r")
+        .arg("500")
+        .arg("-n")
+        .arg("1")
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+
+    // Rates need something to be measured against, so the first line waits an update interval.
+    let line: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    let timestamp = UNIX_EPOCH + Duration::from_millis(line["timestamp"].as_u64().unwrap());
+    assert!(timestamp.duration_since(start).unwrap() >= Duration::from_millis(500));
+}
+
+#[test]
+fn test_format_table() {
+    let headers = vec!["Name".to_string(), "Use%".to_string()];
+    let rows = vec![
+        vec!["AVG".to_string(), "4%".to_string()],
+        vec!["CPU10".to_string(), "100%".to_string()],
+    ];
+
+    assert_eq!(
+        format_table(&headers, &rows),
+        "Name   Use%\nAVG    4%\nCPU10  100%\n"
+    );
+}
+
+#[test]
+fn test_snapshot() {
+    let output = Command::new(get_binary_location())
+        .arg("-C")
+        .arg("./tests/empty_config.toml")
+        .arg("--snapshot")
+        .arg("--top")
+        .arg("1")
+        .arg("--query")
+        .arg("btm")
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+
+    let output = String::from_utf8(output).unwrap();
+    for section in &["CPU\n", "\nMemory: ", "\nDisks\n", "\nTemperatures\n"] {
+        assert!(
+            output.contains(section),
+            "missing {:?} in {}",
+            section,
+            output
+        );
+    }
+
+    // At least this process matches the query.
+    let processes = output.split("\nProcesses (1 of ").nth(1).unwrap();
+    let mut lines = processes.lines().skip(1);
+    assert!(lines.next().unwrap().starts_with("PID"));
+    assert!(lines.next().unwrap().contains("btm"));
+    assert!(lines.next().is_none());
+}
+
+#[test]
+fn test_snapshot_is_primed() {
+    let start = std::time::Instant::now();
+    Command::new(get_binary_location())
+        .arg("-C")
+        .arg("./tests/empty_config.toml")
+        .arg("--snapshot")
+        .arg("-r")
+        .arg("500")
+        .assert()
+        .success();
+
+    // Rates are measured over an update interval, rather than against nothing.
+    assert!(start.elapsed() >= Duration::from_millis(500));
+}
+
+#[test]
+fn test_ps_csv() {
+    let output = Command::new(get_binary_location())
+        .arg("-C")
+        .arg("./tests/empty_config.toml")
+        .arg("ps")
+        .arg("--query")
+        .arg("btm")
+        .arg("--columns")
+        .arg("pid,name")
+        .arg("--format")
+        .arg("csv")
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+
+    // At least this process matches the query.
+    let output = String::from_utf8(output).unwrap();
+    let mut lines = output.lines();
+    assert_eq!(lines.next(), Some("pid,name"));
+    assert!(lines.any(|line| line.ends_with(",btm")), "{}", output);
+}
+
+#[test]
+fn test_ps_json() {
+    let output = Command::new(get_binary_location())
+        .arg("-C")
+        .arg("./tests/empty_config.toml")
+        .arg("ps")
+        .arg("--query")
+        .arg("btm")
+        .arg("--columns")
+        .arg("name,mem")
+        .arg("--format")
+        .arg("json")
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+
+    let processes: Vec<serde_json::Value> = serde_json::from_slice(&output).unwrap();
+    assert!(!processes.is_empty());
+    for process in processes {
+        let process = process.as_object().unwrap();
+        assert_eq!(process.len(), 2);
+        assert!(process["name"].is_string());
+        assert!(process["mem_bytes"].is_u64());
+    }
+}
+
+#[test]
+fn test_ps_no_matches() {
+    Command::new(get_binary_location())
+        .arg("-C")
+        .arg("./tests/empty_config.toml")
+        .arg("ps")
+        .arg("--query")
+        .arg("name=this_is_not_a_process")
+        .assert()
+        .code(1);
+}
+
+#[cfg(target_family = "unix")]
+#[test]
+fn test_ps_signal() {
+    use std::os::unix::process::ExitStatusExt;
+
+    let mut child = Command::new("sleep").arg("30").spawn().unwrap();
+    Command::new(get_binary_location())
+        .arg("-C")
+        .arg("./tests/empty_config.toml")
+        .arg("--whole_word")
+        .arg("ps")
+        .arg("--query")
+        .arg(format!("pid={}", child.id()))
+        .arg("--signal")
+        .arg("SIGKILL")
+        .assert()
+        .success();
+
+    assert_eq!(child.wait().unwrap().signal(), Some(9));
+}
@@ -0,0 +1,214 @@
+//! Tests for saving chart history across restarts.
+
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use bottom::app::data_farmer::{DataCollection, TimedData, TimedRange};
+use bottom::app::history::History;
+use bottom::app::time_series::TimeSeriesStore;
+
+fn get_temp_path(name: &str) -> PathBuf {
+    let path = std::env::temp_dir()
+        .join(format!("btm_history_{}_{}", name, std::process::id()))
+        .join("history");
+    let _ = std::fs::remove_file(&path);
+    path
+}
+
+fn open_history(path: &Path) -> History {
+    History::open(path.to_path_buf()).unwrap().unwrap()
+}
+
+/// Adds a harvest to the end of the history the same way bottom does, through where it is stored.
+fn append(history: &mut History, instant: Instant, timed_data: &TimedData) {
+    let mut store = TimeSeriesStore::default();
+    store.push(instant, timed_data);
+    let (instant, row) = store.last_harvest().unwrap();
+    history.append_harvest(instant, &row).unwrap();
+}
+
+fn get_timed_data(cpu_data: Vec<f64>, mem_data: Option<f64>) -> TimedData {
+    TimedData {
+        rx_data: 1000.0,
+        tx_data: 500.0,
+        cpu_data,
+        load_avg_data: [1.5, 1.25, 1.0],
+        mem_data,
+        swap_data: None,
+        range: None,
+    }
+}
+
+#[test]
+fn test_history_round_trip() {
+    let path = get_temp_path("round_trip");
+    let now = Instant::now();
+    let first = now.checked_sub(Duration::from_secs(2)).unwrap();
+    let second = now.checked_sub(Duration::from_secs(1)).unwrap();
+
+    let mut history = open_history(&path);
+    append(
+        &mut history,
+        first,
+        &get_timed_data(vec![12.5, 25.0], Some(50.0)),
+    );
+    append(
+        &mut history,
+        second,
+        &get_timed_data(vec![37.5, 75.0], None),
+    );
+
+    drop(history);
+    let loaded = open_history(&path).load(60000).unwrap();
+    let _ = std::fs::remove_dir_all(path.parent().unwrap());
+
+    assert_eq!(loaded.len(), 2);
+    let (loaded_instant, loaded_data) = &loaded[0];
+    assert!(loaded_instant.duration_since(first) < Duration::from_millis(50));
+    assert!(first.duration_since(*loaded_instant) < Duration::from_millis(50));
+    assert_eq!(loaded_data.rx_data, 1000.0);
+    assert_eq!(loaded_data.tx_data, 500.0);
+    assert_eq!(loaded_data.cpu_data, vec![12.5, 25.0]);
+    assert_eq!(loaded_data.load_avg_data, [1.5, 1.25, 1.0]);
+    assert_eq!(loaded_data.mem_data, Some(50.0));
+    assert_eq!(loaded_data.swap_data, None);
+    assert_eq!(loaded[1].1.cpu_data, vec![37.5, 75.0]);
+    assert_eq!(loaded[1].1.mem_data, None);
+}
+
+#[test]
+fn test_history_from_stored_harvest() {
+    let path = get_temp_path("stored_harvest");
+    let now = Instant::now();
+    let mut store = TimeSeriesStore::default();
+    store.push(now, &get_timed_data(vec![10.0, 20.0], None));
+    store.push(now, &get_timed_data(vec![30.0], Some(40.0)));
+
+    let mut history = open_history(&path);
+    let (instant, row) = store.last_harvest().unwrap();
+    history.append_harvest(instant, &row).unwrap();
+    drop(history);
+    let loaded = open_history(&path).load(60000).unwrap();
+    let _ = std::fs::remove_dir_all(path.parent().unwrap());
+
+    // It is saved the same way as what was pushed, without the CPU core that is gone.
+    let (_instant, loaded_data) = &loaded[0];
+    assert_eq!(loaded_data.cpu_data, vec![30.0]);
+    assert_eq!(loaded_data.mem_data, Some(40.0));
+    assert_eq!(loaded_data.swap_data, None);
+    assert_eq!(loaded_data.load_avg_data, [1.5, 1.25, 1.0]);
+    assert_eq!(loaded_data.rx_data, 1000.0);
+}
+
+#[test]
+fn test_history_keeps_downsampled_range() {
+    let path = get_temp_path("downsampled_range");
+    let mut bucket = get_timed_data(vec![50.0], Some(40.0));
+    bucket.range = Some(Box::new(TimedRange {
+        min: get_timed_data(vec![25.0], Some(30.0)),
+        max: get_timed_data(vec![75.0], Some(60.0)),
+    }));
+
+    let mut history = open_history(&path);
+    history.rewrite(vec![(Instant::now(), bucket)]).unwrap();
+    drop(history);
+    let loaded = open_history(&path).load(60000).unwrap();
+    let _ = std::fs::remove_dir_all(path.parent().unwrap());
+
+    let range = loaded[0].1.range.as_ref().unwrap();
+    assert_eq!(loaded[0].1.cpu_data, vec![50.0]);
+    assert_eq!(range.min.cpu_data, vec![25.0]);
+    assert_eq!(range.max.mem_data, Some(60.0));
+}
+
+#[test]
+fn test_history_drops_old_and_cut_off_records() {
+    let path = get_temp_path("drops_old");
+    let now = Instant::now();
+    let old = match now.checked_sub(Duration::from_secs(120)) {
+        Some(old) => old,
+        // Too soon after booting to make something that old.
+        None => return,
+    };
+
+    let mut history = open_history(&path);
+    append(&mut history, old, &get_timed_data(vec![], None));
+    append(&mut history, now, &get_timed_data(vec![], None));
+    let full_size = std::fs::metadata(&path).unwrap().len();
+    drop(history);
+
+    // Cut off the last few bytes, like from being killed partway through writing.
+    let mut contents = std::fs::read(&path).unwrap();
+    contents.truncate(contents.len() - 3);
+    std::fs::write(&path, &contents).unwrap();
+
+    let loaded = open_history(&path).load(60000).unwrap();
+    assert!(loaded.is_empty());
+
+    // The file is rewritten without anything that wasn't loaded.
+    let rewritten_size = std::fs::metadata(&path).unwrap().len();
+    let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    assert!(rewritten_size < full_size / 2, "{}", rewritten_size);
+}
+
+#[test]
+fn test_history_from_other_version() {
+    let path = get_temp_path("other_version");
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(&path, b"BTMHIST\xffsomething else entirely").unwrap();
+
+    let mut history = open_history(&path);
+    assert!(history.load(60000).unwrap().is_empty());
+
+    // It is replaced, so new history can be added.
+    append(
+        &mut history,
+        Instant::now(),
+        &get_timed_data(vec![10.0], None),
+    );
+    drop(history);
+    let loaded = open_history(&path).load(60000).unwrap();
+    let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    assert_eq!(loaded.len(), 1);
+}
+
+#[test]
+fn test_history_used_by_one_instance() {
+    let path = get_temp_path("one_instance");
+
+    let history = open_history(&path);
+    assert!(History::open(path.clone()).unwrap().is_none());
+
+    drop(history);
+    let reopened = History::open(path.clone()).unwrap();
+    let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    assert!(reopened.is_some());
+}
+
+#[test]
+fn test_prepend_history() {
+    let now = Instant::now();
+    let earlier = |secs| now.checked_sub(Duration::from_secs(secs)).unwrap();
+
+    let mut data_collection = DataCollection::default();
+    data_collection
+        .timed_data
+        .push(now, &get_timed_data(vec![1.0, 2.0], None));
+
+    data_collection.prepend_history(vec![
+        // Harvested with a different number of CPUs, so it can't be lined up.
+        (earlier(3), get_timed_data(vec![1.0], None)),
+        (earlier(2), get_timed_data(vec![3.0, 4.0], None)),
+        (earlier(1), get_timed_data(vec![5.0, 6.0], None)),
+    ]);
+
+    let cpu_data = data_collection
+        .timed_data
+        .rows()
+        .map(|(_, timed_data)| timed_data.cpu_data)
+        .collect::<Vec<_>>();
+    assert_eq!(
+        cpu_data,
+        vec![vec![3.0, 4.0], vec![5.0, 6.0], vec![1.0, 2.0]]
+    );
+}
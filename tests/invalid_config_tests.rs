@@ -143,3 +143,53 @@ fn test_invalid_default_widget_count() {
         .failure()
         .stderr(predicate::str::contains("invalid number"));
 }
+
+#[test]
+fn test_invalid_default_sort() {
+    Command::new(get_binary_location())
+        .arg("-C")
+        .arg("./tests/invalid_configs/invalid_default_sort.toml")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("invalid process column"));
+}
+
+#[test]
+fn test_duplicate_process_column() {
+    Command::new(get_binary_location())
+        .arg("-C")
+        .arg("./tests/invalid_configs/duplicate_process_column.toml")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("listed more than once"));
+}
+
+#[test]
+fn test_zero_column_width() {
+    Command::new(get_binary_location())
+        .arg("-C")
+        .arg("./tests/invalid_configs/zero_column_width.toml")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("must be at least 1"));
+}
+
+#[test]
+fn test_invalid_group_by() {
+    Command::new(get_binary_location())
+        .arg("-C")
+        .arg("./tests/invalid_configs/invalid_group_by.toml")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("is an invalid grouping"));
+}
+
+#[test]
+fn test_invalid_export_format() {
+    Command::new(get_binary_location())
+        .arg("-C")
+        .arg("./tests/invalid_configs/invalid_export_format.toml")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("is an invalid export format"));
+}
@@ -0,0 +1,116 @@
+//! Tests for serving collected data as Prometheus metrics.
+
+use std::io::{Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::process::Command;
+use std::thread;
+use std::time::Duration;
+
+use bottom::app::data_harvester::{
+    cpu::CpuData, memory::MemHarvest, temperature::TempHarvest, temperature::TemperatureType, Data,
+};
+use bottom::metrics::to_prometheus_text;
+
+fn get_binary_location() -> String {
+    env!("CARGO_BIN_EXE_btm").to_string()
+}
+
+#[test]
+fn test_prometheus_text() {
+    let data = Data {
+        cpu: Some(vec![
+            CpuData {
+                cpu_prefix: "AVG".to_string(),
+                cpu_count: None,
+                cpu_usage: 12.5,
+            },
+            CpuData {
+                cpu_prefix: "CPU".to_string(),
+                cpu_count: Some(0),
+                cpu_usage: 25.0,
+            },
+        ]),
+        memory: Some(MemHarvest {
+            mem_total_in_kib: 100,
+            mem_used_in_kib: 25,
+            use_percent: Some(25.0),
+        }),
+        temperature_sensors: Some(vec![TempHarvest {
+            name: "some \"quoted\" sensor".to_string(),
+            temperature: 40.0,
+        }]),
+        ..Data::default()
+    };
+
+    // Anything that wasn't collected is left out.
+    assert_eq!(
+        to_prometheus_text(&data, &TemperatureType::Celsius),
+        r#"# HELP bottom_cpu_usage_percent CPU usage, per CPU and on average.
+# TYPE bottom_cpu_usage_percent gauge
+bottom_cpu_usage_percent{cpu="avg"} 12.5
+bottom_cpu_usage_percent{cpu="0"} 25
+# HELP bottom_memory_total_bytes Total memory.
+# TYPE bottom_memory_total_bytes gauge
+bottom_memory_total_bytes 102400
+# HELP bottom_memory_used_bytes Used memory.
+# TYPE bottom_memory_used_bytes gauge
+bottom_memory_used_bytes 25600
+# HELP bottom_temperature_celsius Sensor temperature.
+# TYPE bottom_temperature_celsius gauge
+bottom_temperature_celsius{sensor="some \"quoted\" sensor"} 40
+"#
+    );
+}
+
+#[test]
+fn test_serve_metrics() {
+    // Find a free port to use.
+    let port = TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+
+    let mut btm = Command::new(get_binary_location())
+        .arg("-C")
+        .arg("./tests/empty_config.toml")
+        .arg("--serve-metrics")
+        .arg(format!("127.0.0.1:{}", port))
+        .arg("--headless")
+        .arg("-r")
+        .arg("250")
+        .spawn()
+        .unwrap();
+
+    // Wait for bottom to start listening and finish its first collection.
+    let mut response = String::new();
+    for _ in 0..100 {
+        thread::sleep(Duration::from_millis(100));
+        if let Ok(mut stream) = TcpStream::connect(("127.0.0.1", port)) {
+            response.clear();
+            stream
+                .write_all(b"GET /metrics HTTP/1.1\r\nHost: localhost\r\n\r\n")
+                .unwrap();
+            stream.read_to_string(&mut response).unwrap();
+            if response.contains("bottom_memory_total_bytes") {
+                break;
+            }
+        }
+    }
+    btm.kill().unwrap();
+    btm.wait().unwrap();
+
+    assert!(response.starts_with("HTTP/1.1 200 OK\r\n"), "{}", response);
+    for metric in &[
+        "\nbottom_cpu_usage_percent{cpu=\"avg\"} ",
+        "\nbottom_memory_total_bytes ",
+        "\nbottom_processes ",
+    ] {
+        assert!(
+            response.contains(metric),
+            "missing {} in {}",
+            metric,
+            response
+        );
+    }
+}
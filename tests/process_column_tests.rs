@@ -0,0 +1,133 @@
+//! Tests for choosing, hiding, and reordering process table columns.
+
+use bottom::app::{
+    data_harvester::processes::ProcessSorting, ProcWidgetState, HORIZONTAL_SCROLL_STEP,
+};
+use bottom::data_conversion::{stringify_process_data, ConvertedProcessData};
+
+use ProcessSorting::*;
+
+fn get_proc_widget_state(columns: &[ProcessSorting]) -> ProcWidgetState {
+    let mut proc_widget_state =
+        ProcWidgetState::init(false, false, false, false, false, false, false);
+    proc_widget_state.columns.set_columns(columns);
+    proc_widget_state
+}
+
+#[test]
+fn test_set_columns() {
+    let proc_widget_state = get_proc_widget_state(&[CpuPercent, Pid, State]);
+    assert_eq!(
+        proc_widget_state.columns.get_shown_columns(),
+        vec![CpuPercent, Pid, State]
+    );
+
+    // Hidden columns are kept around (at the end) so they can be shown again.
+    assert_eq!(
+        proc_widget_state.columns.ordered_columns.len(),
+        ProcWidgetState::init(false, false, false, false, false, false, false)
+            .columns
+            .ordered_columns
+            .len()
+    );
+}
+
+#[test]
+fn test_counterparts_follow_columns() {
+    let mut proc_widget_state = get_proc_widget_state(&[MemPercent, Pid]);
+    proc_widget_state.columns.toggle(&Mem);
+    proc_widget_state.columns.toggle(&MemPercent);
+    assert_eq!(
+        proc_widget_state.columns.get_shown_columns(),
+        vec![Mem, Pid]
+    );
+}
+
+#[test]
+fn test_toggle_hidden() {
+    let mut proc_widget_state = get_proc_widget_state(&[Pid, CpuPercent]);
+    assert!(proc_widget_state.columns.toggle_hidden(&User));
+    assert_eq!(
+        proc_widget_state.columns.get_shown_columns(),
+        vec![Pid, CpuPercent, User]
+    );
+
+    assert!(proc_widget_state.columns.toggle_hidden(&Pid));
+    assert!(proc_widget_state.columns.toggle_hidden(&CpuPercent));
+    assert_eq!(proc_widget_state.columns.get_shown_columns(), vec![User]);
+
+    // The last column can't be hidden.
+    assert!(!proc_widget_state.columns.toggle_hidden(&User));
+    assert_eq!(proc_widget_state.columns.get_shown_columns(), vec![User]);
+}
+
+#[test]
+fn test_move_column() {
+    let mut proc_widget_state = get_proc_widget_state(&[Pid, ProcessName, CpuPercent]);
+    assert!(proc_widget_state.columns.move_column(&CpuPercent, true));
+    assert_eq!(
+        proc_widget_state.columns.get_shown_columns(),
+        vec![Pid, CpuPercent, ProcessName]
+    );
+
+    // Can't move past the ends.
+    assert!(!proc_widget_state.columns.move_column(&Pid, true));
+
+    // The hidden counterpart moves along with its column.
+    assert!(proc_widget_state.columns.move_column(&Pid, false));
+    proc_widget_state.columns.toggle(&Pid);
+    proc_widget_state.columns.toggle(&Count);
+    assert_eq!(
+        proc_widget_state.columns.get_shown_columns(),
+        vec![CpuPercent, Count, ProcessName]
+    );
+}
+
+#[test]
+fn test_rows_follow_columns() {
+    let proc_widget_state = get_proc_widget_state(&[State, ProcessName, Pid]);
+    let process = ConvertedProcessData {
+        pid: 42,
+        name: "btm".to_string(),
+        process_state: "Running".to_string(),
+        process_char: 'R',
+        ..Default::default()
+    };
+
+    let rows = stringify_process_data(&proc_widget_state, &[process]);
+    assert_eq!(
+        rows[0].0,
+        vec![
+            ("Running".to_string(), Some("R".to_string())),
+            ("btm".to_string(), None),
+            ("42".to_string(), None),
+        ]
+    );
+
+    let headers = proc_widget_state
+        .columns
+        .get_column_headers(&proc_widget_state.get_sort_keys());
+    assert_eq!(headers, vec!["State ", "Name(n) ", "PID(p) "]);
+}
+
+#[test]
+fn test_scroll_horizontally() {
+    let mut proc_widget_state = get_proc_widget_state(&[Pid, Command]);
+    assert_eq!(proc_widget_state.horizontal_scroll_offset, 0);
+
+    proc_widget_state.scroll_horizontally(false);
+    assert_eq!(proc_widget_state.horizontal_scroll_offset, 0);
+
+    proc_widget_state.scroll_horizontally(true);
+    proc_widget_state.scroll_horizontally(true);
+    assert_eq!(
+        proc_widget_state.horizontal_scroll_offset,
+        2 * HORIZONTAL_SCROLL_STEP
+    );
+
+    proc_widget_state.scroll_horizontally(false);
+    assert_eq!(
+        proc_widget_state.horizontal_scroll_offset,
+        HORIZONTAL_SCROLL_STEP
+    );
+}
@@ -0,0 +1,194 @@
+//! Tests for tracking which processes are new and which have exited.
+
+use std::time::{Duration, Instant};
+
+use bottom::app::data_farmer::DataCollection;
+use bottom::app::data_harvester::{processes::ProcessHarvest, Data};
+use bottom::data_conversion::{format_duration, stringify_exited_process};
+use bottom::Pid;
+
+fn process(pid: Pid, name: &str, cpu: f64, mem_bytes: u64) -> ProcessHarvest {
+    ProcessHarvest {
+        pid,
+        name: name.to_string(),
+        command: format!("/usr/bin/{}", name),
+        cpu_usage_percent: cpu,
+        mem_usage_bytes: mem_bytes,
+        ..Default::default()
+    }
+}
+
+fn eat(data: &mut DataCollection, processes: Vec<ProcessHarvest>, instant: Instant) {
+    data.eat_data(Box::new(Data {
+        last_collection_time: instant,
+        list_of_processes: Some(processes),
+        ..Data::default()
+    }));
+}
+
+fn new_pids(data: &DataCollection) -> Vec<Pid> {
+    let mut pids = data
+        .tracked_processes
+        .iter()
+        .filter(|(_pid, tracked)| tracked.is_new())
+        .map(|(pid, _tracked)| *pid)
+        .collect::<Vec<_>>();
+    pids.sort_unstable();
+    pids
+}
+
+#[test]
+fn test_new_processes() {
+    let mut data = DataCollection::default();
+    let start = Instant::now();
+
+    // Everything in the first harvest was already running.
+    eat(&mut data, vec![process(1, "init", 0.0, 0)], start);
+    assert!(new_pids(&data).is_empty());
+
+    eat(
+        &mut data,
+        vec![process(1, "init", 0.0, 0), process(2, "sh", 0.0, 0)],
+        start,
+    );
+    assert_eq!(new_pids(&data), vec![2]);
+
+    // Highlighting wears off after a few updates.
+    for _ in 0..3 {
+        eat(
+            &mut data,
+            vec![process(1, "init", 0.0, 0), process(2, "sh", 0.0, 0)],
+            start,
+        );
+    }
+    assert!(new_pids(&data).is_empty());
+}
+
+#[test]
+fn test_exited_processes() {
+    let mut data = DataCollection::default();
+    let start = Instant::now();
+
+    eat(
+        &mut data,
+        vec![process(1, "init", 0.0, 0), process(2, "make", 10.0, 300)],
+        start,
+    );
+    eat(
+        &mut data,
+        vec![
+            process(1, "init", 0.0, 0),
+            process(2, "make", 50.0, 100),
+            process(3, "cc", 0.0, 0),
+        ],
+        start + Duration::from_secs(2),
+    );
+    assert!(data.exited_processes.is_empty());
+
+    eat(
+        &mut data,
+        vec![process(1, "init", 0.0, 0)],
+        start + Duration::from_secs(5),
+    );
+
+    let exited = data
+        .exited_processes
+        .iter()
+        .map(|exited| {
+            (
+                exited.pid,
+                exited.name.as_str(),
+                exited.lifetime,
+                exited.peak_cpu_percent,
+                exited.peak_mem_bytes,
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        exited,
+        vec![
+            // Most recent first, and by PID when they exit at the same time.
+            (3, "cc", Duration::from_secs(3), 0.0, 0),
+            (2, "make", Duration::from_secs(5), 50.0, 300),
+        ]
+    );
+}
+
+#[test]
+fn test_reused_pid() {
+    let mut data = DataCollection::default();
+    let start = Instant::now();
+
+    eat(
+        &mut data,
+        vec![process(1, "init", 0.0, 0), process(2, "a", 0.0, 0)],
+        start,
+    );
+    eat(
+        &mut data,
+        vec![process(1, "init", 0.0, 0), process(2, "b", 0.0, 0)],
+        start,
+    );
+
+    assert_eq!(data.exited_processes.len(), 1);
+    assert_eq!(data.exited_processes[0].name, "a");
+    assert_eq!(new_pids(&data), vec![2]);
+}
+
+#[test]
+fn test_exited_processes_are_capped() {
+    let mut data = DataCollection::default();
+    let start = Instant::now();
+
+    for pid in 2..200 {
+        eat(
+            &mut data,
+            vec![process(1, "init", 0.0, 0), process(pid, "job", 0.0, 0)],
+            start,
+        );
+    }
+    assert_eq!(data.exited_processes.len(), 100);
+    assert_eq!(data.exited_processes[0].pid, 198);
+
+    data.reset();
+    assert!(data.exited_processes.is_empty());
+}
+
+#[test]
+fn test_stringify_exited_process() {
+    let mut data = DataCollection::default();
+    let start = Instant::now();
+
+    eat(
+        &mut data,
+        vec![
+            process(1, "init", 0.0, 0),
+            process(2, "make", 12.5, 3 * 1024 * 1024),
+        ],
+        start,
+    );
+    eat(
+        &mut data,
+        vec![process(1, "init", 0.0, 0)],
+        start + Duration::from_secs(90),
+    );
+
+    assert_eq!(
+        stringify_exited_process(&data.exited_processes[0], start + Duration::from_secs(100)),
+        vec!["make", "2", "1m 30s", "12.5%", "3MiB", "10s ago"]
+    );
+}
+
+#[test]
+fn test_format_duration() {
+    assert_eq!(format_duration(Duration::from_secs(0)), "0s");
+    assert_eq!(format_duration(Duration::from_secs(59)), "59s");
+    assert_eq!(
+        format_duration(Duration::from_secs(3600 + 120 + 5)),
+        "1h 2m"
+    );
+    assert_eq!(
+        format_duration(Duration::from_secs(2 * 86400 + 3 * 3600)),
+        "2d 3h"
+    );
+}
@@ -0,0 +1,269 @@
+//! Tests for the process query language, to make sure parsing and matching don't regress.
+
+use bottom::app::{
+    query::{ProcessQuery, ProcessRelations, Query},
+    ProcWidgetState,
+};
+use bottom::data_conversion::ConvertedProcessData;
+use bottom::utils::error::{BottomError, Result};
+use bottom::Pid;
+use std::{collections::HashMap, time::Duration};
+
+fn parse(query: &str) -> Result<Query> {
+    let mut proc_widget_state =
+        ProcWidgetState::init(false, false, false, false, false, false, false);
+    proc_widget_state
+        .process_search_state
+        .search_state
+        .current_search_query = query.to_string();
+
+    proc_widget_state.parse_query()
+}
+
+fn get_error_span(query: &str) -> Option<std::ops::Range<usize>> {
+    match parse(query) {
+        Err(BottomError::QueryError(_, span)) => span,
+        Err(err) => panic!("expected a query error for {:?}, got {:?}", query, err),
+        Ok(parsed) => panic!("expected {:?} to be invalid, got {:?}", query, parsed),
+    }
+}
+
+fn root_process() -> ConvertedProcessData {
+    ConvertedProcessData {
+        pid: 1,
+        name: "systemd".to_string(),
+        command: "/sbin/init".to_string(),
+        cpu_percent_usage: 0.5,
+        process_state: "Sleeping".to_string(),
+        user: Some("root".to_string()),
+        ..ConvertedProcessData::default()
+    }
+}
+
+fn user_process() -> ConvertedProcessData {
+    ConvertedProcessData {
+        pid: 2000,
+        ppid: Some(100),
+        name: "kworker".to_string(),
+        command: "kworker/0:1".to_string(),
+        cpu_percent_usage: 12.0,
+        process_state: "Running".to_string(),
+        user: Some("clement".to_string()),
+        mem_usage_bytes: 1_500_000_000,
+        rps_f64: 2_097_152.0,
+        tr_f64: 5_000.0,
+        time: Duration::from_secs(5400),
+        ..ConvertedProcessData::default()
+    }
+}
+
+fn supervisor_process() -> ConvertedProcessData {
+    ConvertedProcessData {
+        pid: 100,
+        ppid: Some(1),
+        name: "supervisord".to_string(),
+        command: "/usr/bin/supervisord".to_string(),
+        user: Some("root".to_string()),
+        ..ConvertedProcessData::default()
+    }
+}
+
+fn child_process() -> ConvertedProcessData {
+    ConvertedProcessData {
+        pid: 2001,
+        ppid: Some(2000),
+        name: "worker".to_string(),
+        command: "worker --id 1".to_string(),
+        user: Some("clement".to_string()),
+        ..ConvertedProcessData::default()
+    }
+}
+
+/// A small process tree: systemd -> supervisord -> kworker -> worker.
+fn get_processes() -> HashMap<Pid, ConvertedProcessData> {
+    vec![
+        root_process(),
+        supervisor_process(),
+        user_process(),
+        child_process(),
+    ]
+    .into_iter()
+    .map(|process| (process.pid, process))
+    .collect()
+}
+
+fn matches(query: &str, process: &ConvertedProcessData) -> bool {
+    let processes = get_processes();
+    parse(query)
+        .unwrap()
+        .check(process, false, &ProcessRelations::new(&processes))
+}
+
+#[test]
+fn test_not_operator() {
+    assert!(!matches("not user=root", &root_process()));
+    assert!(matches("not user=root", &user_process()));
+    assert!(!matches("!user=root", &root_process()));
+    assert!(matches("!user=root", &user_process()));
+    assert!(matches("NOT kworker", &root_process()));
+    assert!(!matches("not name=kworker", &user_process()));
+}
+
+#[test]
+fn test_not_operator_precedence() {
+    // NOT only binds to the condition directly after it.
+    assert!(matches("not user=root and cpu > 10", &user_process()));
+    assert!(!matches("not user=root and cpu > 50", &user_process()));
+    assert!(matches("not user=root or cpu < 1", &root_process()));
+
+    // ...unless it's a group.
+    assert!(matches("not (user=root or cpu > 50)", &user_process()));
+    assert!(!matches("not (user=root or cpu > 10)", &user_process()));
+
+    // Double negation cancels out.
+    assert!(matches("not !user=root", &root_process()));
+}
+
+#[test]
+fn test_quoted_not() {
+    // Quoting lets you search for something that looks like an operator.
+    let not_process = ConvertedProcessData {
+        name: "not".to_string(),
+        ..ConvertedProcessData::default()
+    };
+    assert!(matches("\"not\"", &not_process));
+}
+
+#[test]
+fn test_missing_negated_condition() {
+    assert_eq!(get_error_span("btm and not"), Some(8..11));
+    assert_eq!(get_error_span("!"), Some(0..1));
+}
+
+#[test]
+fn test_error_spans() {
+    assert_eq!(get_error_span("btm )"), Some(4..5));
+    assert_eq!(get_error_span("(btm"), Some(0..1));
+    assert_eq!(get_error_span("()"), Some(0..2));
+    assert_eq!(get_error_span("\"btm"), Some(0..1));
+    assert_eq!(get_error_span("btm and cpu >"), Some(8..13));
+    assert_eq!(get_error_span("cpu > abc"), Some(6..9));
+    assert_eq!(get_error_span("pid"), Some(0..3));
+    assert_eq!(get_error_span("btm > 1"), Some(4..5));
+}
+
+#[test]
+fn test_error_spans_count_characters() {
+    // Spans are in characters, not bytes.
+    assert_eq!(get_error_span("ü )"), Some(2..3));
+}
+
+#[test]
+fn test_size_units() {
+    let process = user_process();
+
+    assert!(matches("memb > 1GB", &process));
+    assert!(matches("memb > 1 GB", &process));
+    assert!(!matches("memb > 1.5gb", &process));
+    assert!(!matches("memb > 1.4GiB", &process));
+    assert!(matches("memb > 1400 MiB", &process));
+    assert!(matches("tread = 5kb", &process));
+    assert!(matches("tread < 5KiB", &process));
+    assert!(matches("twrite < 1b", &process));
+}
+
+#[test]
+fn test_rate_units() {
+    let process = user_process();
+
+    assert!(matches("read = 2MiB/s", &process));
+    assert!(matches("read = 2 mib/s", &process));
+    assert!(matches("read = 2 MiB", &process));
+    assert!(matches("r/s > 2mb/s", &process));
+    assert!(!matches("read > 2MiB/s", &process));
+}
+
+#[test]
+fn test_duration_units() {
+    let process = user_process();
+
+    assert!(matches("time > 1h", &process));
+    assert!(matches("time = 90 min", &process));
+    assert!(matches("time = 5400", &process));
+    assert!(matches("time < 1d", &process));
+    assert!(!matches("time > 5400000ms", &process));
+}
+
+#[test]
+fn test_percentage_units() {
+    let process = user_process();
+
+    assert!(matches("cpu > 10%", &process));
+    assert!(matches("cpu% >= 12 %", &process));
+    assert!(!matches("cpu < 10%", &process));
+}
+
+#[test]
+fn test_separate_unit_is_optional() {
+    // A word after a value that isn't a unit is just part of the next condition.
+    assert!(matches("cpu > 10 kworker", &user_process()));
+    assert!(!matches("cpu > 10 systemd", &user_process()));
+}
+
+#[test]
+fn test_invalid_units() {
+    assert_eq!(get_error_span("memb > 1xb"), Some(8..10));
+    assert_eq!(get_error_span("cpu > 50mb"), Some(8..10));
+    assert_eq!(get_error_span("memb > 1mb/s"), Some(8..12));
+    assert_eq!(get_error_span("read > 1h"), Some(8..9));
+    assert_eq!(get_error_span("time > 1gb"), Some(8..10));
+    assert_eq!(get_error_span("mem > 5%%"), Some(7..9));
+    assert_eq!(get_error_span("cpu > 1.2.3"), Some(6..11));
+    assert_eq!(get_error_span("cpu > %"), Some(6..7));
+}
+
+#[test]
+fn test_ppid() {
+    assert!(matches("ppid=100", &user_process()));
+    assert!(!matches("ppid=100", &child_process()));
+    assert!(!matches("ppid=1", &root_process()));
+}
+
+#[test]
+fn test_children() {
+    assert!(matches("children = 1", &supervisor_process()));
+    assert!(matches("children > 0", &user_process()));
+    assert!(matches("children = 0", &child_process()));
+    assert!(!matches("children > 0", &child_process()));
+}
+
+#[test]
+fn test_ancestor() {
+    // By name...
+    assert!(matches("ancestor=supervisord", &user_process()));
+    assert!(matches("ancestor=supervisord", &child_process()));
+    assert!(!matches("ancestor=supervisord", &supervisor_process()));
+    assert!(!matches("ancestor=supervisord", &root_process()));
+
+    // ...and by PID, which must match exactly.
+    assert!(matches("ancestor=100", &child_process()));
+    assert!(matches("ancestor=1", &child_process()));
+    assert!(!matches("ancestor=10", &child_process()));
+    assert!(!matches("ancestor=2001", &child_process()));
+}
+
+#[test]
+fn test_ancestor_with_cycle() {
+    // PID reuse can make a process look like its own ancestor; this shouldn't hang.
+    let looping_process = ConvertedProcessData {
+        pid: 5,
+        ppid: Some(5),
+        name: "loop".to_string(),
+        ..ConvertedProcessData::default()
+    };
+    let mut processes = get_processes();
+    processes.insert(looping_process.pid, looping_process.clone());
+
+    let query = parse("ancestor=systemd").unwrap();
+    assert!(!query.check(&looping_process, false, &ProcessRelations::new(&processes)));
+}
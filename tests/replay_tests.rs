@@ -0,0 +1,274 @@
+//! Tests for recording collected data and replaying it.
+
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
+use std::time::{Duration, UNIX_EPOCH};
+
+use bottom::app::data_harvester::{memory::MemHarvest, Data};
+use bottom::replay::{create_replay_thread, Recorder, Recording, ReplayControl, ReplayStatus};
+use bottom::{BottomEvent, ThreadControlEvent};
+
+fn get_binary_location() -> String {
+    env!("CARGO_BIN_EXE_btm").to_string()
+}
+
+fn get_temp_path(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("btm_{}_{}.btm", name, std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    path
+}
+
+/// Records collections at each of the given seconds, each using that many KiB of memory.
+fn record(path: &Path, seconds: &[u64]) {
+    let mut recorder = Recorder::new(path).unwrap();
+    for second in seconds {
+        let data = Data {
+            memory: Some(MemHarvest {
+                mem_total_in_kib: 100,
+                mem_used_in_kib: *second,
+                use_percent: Some(*second as f64),
+            }),
+            ..Data::default()
+        };
+        recorder
+            .record(&data, UNIX_EPOCH + Duration::from_secs(*second))
+            .unwrap();
+    }
+}
+
+#[test]
+fn test_open_recording() {
+    let path = get_temp_path("open_recording");
+    record(&path, &[10, 11, 12]);
+
+    // A line cut off partway through is skipped.
+    std::fs::OpenOptions::new()
+        .append(true)
+        .open(&path)
+        .unwrap()
+        .write_all(b"{\"timestamp\":13000,\"mem")
+        .unwrap();
+
+    let recording = Recording::open(&path).unwrap();
+    let _ = std::fs::remove_file(&path);
+    assert_eq!(recording.len(), 3);
+    assert_eq!(recording.duration(), Duration::from_secs(2));
+}
+
+#[test]
+fn test_replay_thread() {
+    let path = get_temp_path("replay_thread");
+    record(&path, &[0, 1, 2, 3]);
+    let recording = Recording::open(&path).unwrap();
+    let _ = std::fs::remove_file(&path);
+
+    let (sender, receiver) = mpsc::channel();
+    let (control_sender, control_receiver) = mpsc::channel();
+    let _replay_thread = create_replay_thread(
+        sender,
+        control_receiver,
+        Arc::new(Mutex::new(false)),
+        recording,
+        64.0,
+        60000,
+    );
+
+    // Wait for the replay to finish, keeping each update.
+    let mut updates = Vec::new();
+    let mut latest_status = None;
+    while latest_status
+        .as_ref()
+        .is_none_or(|status: &ReplayStatus| status.position < Duration::from_secs(3))
+    {
+        match receiver.recv_timeout(Duration::from_secs(5)).unwrap() {
+            BottomEvent::Update(data) => updates.push(data),
+            BottomEvent::ReplayStatus(status) => latest_status = Some(status),
+            _ => {}
+        }
+    }
+
+    // Updates keep the recorded spacing, even when replayed faster.
+    assert_eq!(updates.len(), 4);
+    assert_eq!(
+        updates[3]
+            .last_collection_time
+            .duration_since(updates[0].last_collection_time),
+        Duration::from_secs(3)
+    );
+    let status = latest_status.unwrap();
+    assert_eq!(status.timestamp, 3000);
+    assert_eq!(status.duration, Duration::from_secs(3));
+    assert!(!status.is_paused);
+
+    // Seeking back drops what was replayed, then fills in from the start of the recording.
+    control_sender
+        .send(ThreadControlEvent::Replay(ReplayControl::Seek(-1500)))
+        .unwrap();
+    assert!(matches!(
+        receiver.recv_timeout(Duration::from_secs(5)).unwrap(),
+        BottomEvent::ReplaySeek
+    ));
+    let mut used_memory = Vec::new();
+    loop {
+        match receiver.recv_timeout(Duration::from_secs(5)).unwrap() {
+            BottomEvent::Update(data) => {
+                used_memory.push(data.memory.unwrap().mem_used_in_kib);
+            }
+            BottomEvent::ReplayStatus(status) => {
+                assert_eq!(status.timestamp, 1000);
+                break;
+            }
+            _ => {}
+        }
+    }
+    assert_eq!(used_memory, vec![0, 1]);
+
+    control_sender
+        .send(ThreadControlEvent::Replay(ReplayControl::TogglePause))
+        .unwrap();
+    loop {
+        if let BottomEvent::ReplayStatus(status) =
+            receiver.recv_timeout(Duration::from_secs(5)).unwrap()
+        {
+            if status.is_paused {
+                break;
+            }
+        }
+    }
+}
+
+#[test]
+fn test_replay_seek_fills_in_retention() {
+    let path = get_temp_path("replay_seek_retention");
+    record(&path, &[0, 1, 2, 3, 4, 5]);
+    let recording = Recording::open(&path).unwrap();
+    let _ = std::fs::remove_file(&path);
+
+    let (sender, receiver) = mpsc::channel();
+    let (control_sender, control_receiver) = mpsc::channel();
+    let _replay_thread = create_replay_thread(
+        sender,
+        control_receiver,
+        Arc::new(Mutex::new(false)),
+        recording,
+        64.0,
+        2000,
+    );
+    loop {
+        if let BottomEvent::ReplayStatus(status) =
+            receiver.recv_timeout(Duration::from_secs(5)).unwrap()
+        {
+            if status.position >= Duration::from_secs(5) {
+                break;
+            }
+        }
+    }
+
+    // Only as much as the retention covers is filled back in.
+    control_sender
+        .send(ThreadControlEvent::Replay(ReplayControl::Seek(-1000)))
+        .unwrap();
+    let mut used_memory = Vec::new();
+    loop {
+        match receiver.recv_timeout(Duration::from_secs(5)).unwrap() {
+            BottomEvent::Update(data) => {
+                used_memory.push(data.memory.unwrap().mem_used_in_kib);
+            }
+            BottomEvent::ReplayStatus(status) if !used_memory.is_empty() => {
+                assert_eq!(status.timestamp, 4000);
+                break;
+            }
+            _ => {}
+        }
+    }
+    assert_eq!(used_memory, vec![2, 3, 4]);
+}
+
+#[test]
+fn test_record_headless() {
+    let path = get_temp_path("record_headless");
+
+    let mut btm = Command::new(get_binary_location())
+        .arg("-C")
+        .arg("./tests/empty_config.toml")
+        .arg("--record")
+        .arg(&path)
+        .arg("--headless")
+        .arg("-r")
+        .arg("250")
+        .spawn()
+        .unwrap();
+
+    let mut contents = String::new();
+    for _ in 0..100 {
+        thread::sleep(Duration::from_millis(100));
+        contents = std::fs::read_to_string(&path).unwrap_or_default();
+        if contents.lines().count() >= 2 {
+            break;
+        }
+    }
+    btm.kill().unwrap();
+    btm.wait().unwrap();
+
+    // Everything is recorded, regardless of the layout.
+    let recording = Recording::open(&path).unwrap();
+    let _ = std::fs::remove_file(&path);
+    assert!(recording.len() >= 2, "{}", contents);
+    let first_line: serde_json::Value =
+        serde_json::from_str(contents.lines().next().unwrap()).unwrap();
+    for key in &["timestamp", "cpu", "memory", "list_of_processes"] {
+        assert!(first_line.get(key).is_some(), "missing {}", key);
+    }
+}
+
+#[test]
+fn test_replay_empty_recording() {
+    let path = get_temp_path("replay_empty");
+    std::fs::write(&path, "").unwrap();
+
+    Command::new(get_binary_location())
+        .arg("-C")
+        .arg("./tests/empty_config.toml")
+        .arg("--replay")
+        .arg(&path)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("has nothing to replay"));
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn test_replay_conflicts_with_headless() {
+    Command::new(get_binary_location())
+        .arg("-C")
+        .arg("./tests/empty_config.toml")
+        .arg("--replay")
+        .arg("session.btm")
+        .arg("--headless")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("cannot be used with"));
+}
+
+#[test]
+fn test_invalid_replay_speed() {
+    let path = get_temp_path("invalid_replay_speed");
+    record(&path, &[0]);
+
+    Command::new(get_binary_location())
+        .arg("-C")
+        .arg("./tests/empty_config.toml")
+        .arg("--replay")
+        .arg(&path)
+        .arg("--replay-speed")
+        .arg("100")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("set your replay speed"));
+    let _ = std::fs::remove_file(&path);
+}
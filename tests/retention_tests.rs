@@ -0,0 +1,101 @@
+//! Tests for keeping graph data longer than the default, downsampling anything older.
+
+use std::time::{Duration, Instant};
+
+use bottom::app::data_farmer::{DataCollection, TimedData};
+use bottom::data_conversion::convert_mem_data_points;
+
+const HOUR_MILLISECONDS: u64 = 60 * 60 * 1000;
+
+/// Fills a collection with a harvest every second going back the given number of seconds,
+/// with memory usage going from 0 to 99 and back, or nothing if it's too soon after booting to
+/// go back that far.
+fn get_data_collection(seconds: u64) -> Option<DataCollection> {
+    let now = Instant::now();
+    let mut data_collection = DataCollection {
+        current_instant: now,
+        ..DataCollection::default()
+    };
+    for second in (0..seconds).rev() {
+        data_collection.timed_data.push(
+            now.checked_sub(Duration::from_secs(second))?,
+            &TimedData {
+                cpu_data: vec![(second % 100) as f64, 50.0],
+                mem_data: Some((second % 100) as f64),
+                ..TimedData::default()
+            },
+        );
+    }
+    Some(data_collection)
+}
+
+#[test]
+fn test_downsample_old_data() {
+    let mut data_collection = match get_data_collection(30 * 60) {
+        Some(data_collection) => data_collection,
+        None => return,
+    };
+    data_collection.clean_data(HOUR_MILLISECONDS);
+
+    // The last 10 minutes are kept as is, and the 20 minutes before that are put into buckets
+    // of 5 seconds each, as 600 buckets cover the other 50 minutes of an hour.  The last few
+    // harvests before the 10 minutes aren't enough for a whole bucket yet, so they're kept too.
+    let timed_data_vec = data_collection.timed_data.rows().collect::<Vec<_>>();
+    let (buckets, harvests): (Vec<_>, Vec<_>) = timed_data_vec
+        .iter()
+        .partition(|(_instant, timed_data)| timed_data.range.is_some());
+    assert_eq!(harvests.len(), 605);
+    assert_eq!(buckets.len(), 239);
+    assert!(timed_data_vec.windows(2).all(|pair| pair[0].0 <= pair[1].0));
+
+    // The oldest harvests were from 1799 seconds ago back down to 1795.
+    let (_instant, oldest) = buckets[0];
+    let range = oldest.range.as_ref().unwrap();
+    assert_eq!(oldest.mem_data, Some(97.0));
+    assert_eq!(range.min.mem_data, Some(95.0));
+    assert_eq!(range.max.mem_data, Some(99.0));
+    assert_eq!(oldest.cpu_data, vec![97.0, 50.0]);
+    assert_eq!(range.min.cpu_data, vec![95.0, 50.0]);
+
+    // Cleaning again doesn't change anything.
+    let len = timed_data_vec.len();
+    data_collection.clean_data(HOUR_MILLISECONDS);
+    assert_eq!(data_collection.timed_data.len(), len);
+}
+
+#[test]
+fn test_no_downsampling_by_default() {
+    let mut data_collection = match get_data_collection(15 * 60) {
+        Some(data_collection) => data_collection,
+        None => return,
+    };
+    data_collection.clean_data(10 * 60 * 1000);
+
+    assert!(data_collection
+        .timed_data
+        .rows()
+        .all(|(_instant, timed_data)| timed_data.range.is_none()));
+    assert_eq!(data_collection.timed_data.len(), 601);
+}
+
+#[test]
+fn test_graph_downsampled_range() {
+    let mut data_collection = match get_data_collection(15 * 60) {
+        Some(data_collection) => data_collection,
+        None => return,
+    };
+    data_collection.clean_data(HOUR_MILLISECONDS);
+
+    // A bucket is drawn as a line from its average down to its lowest value, up to its highest,
+    // then back to its average.
+    let points = convert_mem_data_points(&data_collection, false);
+    let values = points[..4]
+        .iter()
+        .map(|(_time, value)| *value)
+        .collect::<Vec<_>>();
+    assert_eq!(values, vec![97.0, 95.0, 99.0, 97.0]);
+    assert!(points[..4]
+        .iter()
+        .all(|(time, _value)| *time == points[0].0));
+    assert!(points[4].0 > points[0].0);
+}
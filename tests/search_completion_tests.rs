@@ -0,0 +1,122 @@
+//! Tests for tab completion in the process search widget.
+
+use bottom::app::{
+    completion::{get_completion, CompletionKind, CompletionValues},
+    query::PrefixType,
+    ProcWidgetState,
+};
+
+fn get_values() -> CompletionValues {
+    let mut values = CompletionValues {
+        names: vec![
+            "firefox".to_string(),
+            "fish".to_string(),
+            "Web Content".to_string(),
+            "firefox".to_string(),
+        ],
+        users: vec!["root".to_string(), "clement".to_string()],
+        states: vec!["Running".to_string(), "Sleeping".to_string()],
+    };
+    values.dedup();
+    values
+}
+
+fn complete(query: &str) -> (CompletionKind, Vec<String>) {
+    let completion = get_completion(query, query.len(), &get_values());
+    (completion.kind, completion.candidates)
+}
+
+#[test]
+fn test_complete_prefix() {
+    let (kind, candidates) = complete("us");
+    assert_eq!(kind, CompletionKind::Condition);
+    assert_eq!(candidates, vec!["user"]);
+
+    // Prefixes come first, then process names.
+    let (_, candidates) = complete("cpu > 5 and f");
+    assert_eq!(candidates, vec!["firefox", "fish"]);
+
+    let (_, candidates) = complete("(t");
+    assert_eq!(candidates, vec!["time", "tread", "twrite"]);
+}
+
+#[test]
+fn test_complete_comparison() {
+    let (kind, candidates) = complete("cpu ");
+    assert_eq!(kind, CompletionKind::Comparison(PrefixType::PCpu));
+    assert_eq!(candidates, vec!["=", ">", "<", ">=", "<="]);
+
+    let (_, candidates) = complete("mem >");
+    assert_eq!(candidates, vec![">", ">="]);
+
+    let (kind, candidates) = complete("state ");
+    assert_eq!(kind, CompletionKind::Comparison(PrefixType::State));
+    assert_eq!(candidates, vec!["="]);
+}
+
+#[test]
+fn test_complete_value() {
+    let (kind, candidates) = complete("user=r");
+    assert_eq!(kind, CompletionKind::Value(PrefixType::User));
+    assert_eq!(candidates, vec!["root"]);
+
+    let (_, candidates) = complete("user = ");
+    assert_eq!(candidates, vec!["clement", "root"]);
+
+    let (_, candidates) = complete("state=");
+    assert_eq!(candidates, vec!["Running", "Sleeping"]);
+
+    let (_, candidates) = complete("not state s");
+    assert_eq!(candidates, vec!["Sleeping"]);
+
+    // Numbers can't be completed.
+    let (kind, candidates) = complete("cpu > ");
+    assert_eq!(kind, CompletionKind::Value(PrefixType::PCpu));
+    assert!(candidates.is_empty());
+}
+
+#[test]
+fn test_complete_quoted_value() {
+    // Values that need quotes get them...
+    let (_, candidates) = complete("name=w");
+    assert_eq!(candidates, vec!["\"Web Content\""]);
+
+    // ...and already started quotes get closed.
+    let (kind, candidates) = complete("name=\"Web C");
+    assert_eq!(kind, CompletionKind::Value(PrefixType::Name));
+    assert_eq!(candidates, vec!["Web Content\""]);
+}
+
+#[test]
+fn test_complete_search() {
+    let mut proc_widget_state =
+        ProcWidgetState::init(false, false, false, false, false, false, false);
+    let values = get_values();
+
+    // A single candidate is filled in.
+    proc_widget_state.set_search_query("user=cl".to_string());
+    proc_widget_state.complete_search(&values);
+    assert_eq!(proc_widget_state.get_current_search_query(), "user=clement");
+    assert!(!proc_widget_state.is_completion_showing());
+
+    // Multiple candidates fill in what they share, and then get cycled through.
+    proc_widget_state.set_search_query("f".to_string());
+    proc_widget_state.complete_search(&values);
+    assert_eq!(proc_widget_state.get_current_search_query(), "fi");
+    assert!(proc_widget_state.is_completion_showing());
+
+    proc_widget_state.complete_search(&values);
+    assert_eq!(proc_widget_state.get_current_search_query(), "firefox");
+    proc_widget_state.complete_search(&values);
+    assert_eq!(proc_widget_state.get_current_search_query(), "fish");
+    proc_widget_state.change_completion_position(-1);
+    assert_eq!(proc_widget_state.get_current_search_query(), "firefox");
+    assert_eq!(
+        proc_widget_state.get_search_cursor_position(),
+        "firefox".len()
+    );
+
+    // Editing the query closes the candidates.
+    proc_widget_state.set_search_query("firefox ".to_string());
+    assert!(!proc_widget_state.is_completion_showing());
+}
@@ -0,0 +1,94 @@
+//! Tests for the process search history and saved queries.
+
+use bottom::app::ProcWidgetState;
+use bottom::options::Config;
+
+fn get_proc_widget_state() -> ProcWidgetState {
+    ProcWidgetState::init(false, false, false, false, false, false, false)
+}
+
+fn search(proc_widget_state: &mut ProcWidgetState, query: &str) {
+    proc_widget_state.set_search_query(query.to_string());
+    proc_widget_state.add_query_to_history();
+}
+
+fn current_query(proc_widget_state: &ProcWidgetState) -> &str {
+    proc_widget_state.get_current_search_query()
+}
+
+#[test]
+fn test_history_navigation() {
+    let mut proc_widget_state = get_proc_widget_state();
+    search(&mut proc_widget_state, "btm");
+    search(&mut proc_widget_state, "cpu > 5");
+    proc_widget_state.set_search_query("user=ro".to_string());
+
+    proc_widget_state.change_history_position(-1);
+    assert_eq!(current_query(&proc_widget_state), "cpu > 5");
+    proc_widget_state.change_history_position(-1);
+    assert_eq!(current_query(&proc_widget_state), "btm");
+
+    // Can't go past the oldest entry.
+    proc_widget_state.change_history_position(-1);
+    assert_eq!(current_query(&proc_widget_state), "btm");
+
+    // Going past the newest entry brings back what was being typed.
+    proc_widget_state.change_history_position(1);
+    proc_widget_state.change_history_position(1);
+    assert_eq!(current_query(&proc_widget_state), "user=ro");
+    proc_widget_state.change_history_position(1);
+    assert_eq!(current_query(&proc_widget_state), "user=ro");
+}
+
+#[test]
+fn test_history_skips_invalid_and_duplicates() {
+    let mut proc_widget_state = get_proc_widget_state();
+    search(&mut proc_widget_state, "btm");
+    search(&mut proc_widget_state, "cpu >");
+    search(&mut proc_widget_state, "   ");
+    search(&mut proc_widget_state, "mem > 1");
+    search(&mut proc_widget_state, "btm");
+
+    assert_eq!(
+        proc_widget_state
+            .process_search_state
+            .history
+            .iter()
+            .collect::<Vec<_>>(),
+        vec!["mem > 1", "btm"]
+    );
+}
+
+#[test]
+fn test_edited_history_entry_becomes_draft() {
+    let mut proc_widget_state = get_proc_widget_state();
+    search(&mut proc_widget_state, "btm");
+    search(&mut proc_widget_state, "cpu > 5");
+
+    proc_widget_state.change_history_position(-1);
+    proc_widget_state.set_search_query("cpu > 50".to_string());
+
+    // Editing an entry starts over from the newest entry, remembering the edit.
+    proc_widget_state.change_history_position(-1);
+    assert_eq!(current_query(&proc_widget_state), "cpu > 5");
+    proc_widget_state.change_history_position(1);
+    assert_eq!(current_query(&proc_widget_state), "cpu > 50");
+}
+
+#[test]
+fn test_resolve_saved_query() {
+    let config: Config = toml::from_str(
+        r#"
+        [queries]
+        busy = "cpu > 5 or mem > 10"
+        "#,
+    )
+    .unwrap();
+
+    assert_eq!(config.resolve_query("busy"), "cpu > 5 or mem > 10");
+    assert_eq!(config.resolve_query("btm"), "btm");
+    assert_eq!(
+        config.get_saved_queries().collect::<Vec<_>>(),
+        vec![(&"busy".to_string(), &"cpu > 5 or mem > 10".to_string())]
+    );
+}
@@ -0,0 +1,152 @@
+//! Tests for sorting the process table by multiple columns.
+
+use bottom::app::{data_harvester::processes::ProcessSorting, ProcWidgetState};
+use bottom::data_conversion::{sort_process_data, ConvertedProcessData};
+
+mod util;
+use util::{get_proc_widget_state, process};
+
+/// Moves the sort widget's cursor onto the given column.
+fn select_column(proc_widget_state: &mut ProcWidgetState, column: &ProcessSorting) {
+    proc_widget_state
+        .columns
+        .set_to_sorted_index_from_type(column);
+}
+
+fn sorted_pids(
+    mut processes: Vec<ConvertedProcessData>, sort_keys: &[(ProcessSorting, bool)],
+) -> Vec<u32> {
+    sort_process_data(&mut processes, sort_keys, |process| process);
+    processes.into_iter().map(|p| p.pid as u32).collect()
+}
+
+#[test]
+fn test_adding_sort_keys() {
+    let mut proc_widget_state = get_proc_widget_state();
+    select_column(&mut proc_widget_state, &ProcessSorting::User);
+    proc_widget_state.update_sorting_with_columns();
+    select_column(&mut proc_widget_state, &ProcessSorting::CpuPercent);
+    proc_widget_state.add_sort_key_with_columns();
+    select_column(&mut proc_widget_state, &ProcessSorting::Pid);
+    proc_widget_state.add_sort_key_with_columns();
+
+    assert_eq!(
+        proc_widget_state.get_sort_keys(),
+        vec![
+            (ProcessSorting::User, true),
+            (ProcessSorting::CpuPercent, true),
+            (ProcessSorting::Pid, false),
+        ]
+    );
+
+    // Reselecting a secondary key reverses it...
+    select_column(&mut proc_widget_state, &ProcessSorting::CpuPercent);
+    proc_widget_state.add_sort_key_with_columns();
+    assert_eq!(
+        proc_widget_state.secondary_sort_keys[0],
+        (ProcessSorting::CpuPercent, false)
+    );
+
+    // ...adding past the limit replaces the last key...
+    select_column(&mut proc_widget_state, &ProcessSorting::State);
+    proc_widget_state.add_sort_key_with_columns();
+    assert_eq!(
+        proc_widget_state.secondary_sort_keys,
+        vec![
+            (ProcessSorting::CpuPercent, false),
+            (ProcessSorting::State, false)
+        ]
+    );
+
+    // ...and picking a new main column starts over.
+    select_column(&mut proc_widget_state, &ProcessSorting::Mem);
+    proc_widget_state.update_sorting_with_columns();
+    assert!(proc_widget_state.secondary_sort_keys.is_empty());
+}
+
+#[test]
+fn test_main_key_is_not_added_again() {
+    let mut proc_widget_state = get_proc_widget_state();
+    select_column(&mut proc_widget_state, &ProcessSorting::CpuPercent);
+    proc_widget_state.add_sort_key_with_columns();
+    assert!(proc_widget_state.secondary_sort_keys.is_empty());
+}
+
+#[test]
+fn test_set_sort_keys_skips_hidden_and_repeated_columns() {
+    let mut proc_widget_state = get_proc_widget_state();
+    proc_widget_state.set_sort_keys(&[
+        (ProcessSorting::Count, true),
+        (ProcessSorting::State, false),
+        (ProcessSorting::State, true),
+        (ProcessSorting::Mem, true),
+        (ProcessSorting::Pid, false),
+    ]);
+
+    // Count and Mem aren't shown by default.
+    assert_eq!(
+        proc_widget_state.get_sort_keys(),
+        vec![(ProcessSorting::State, false), (ProcessSorting::Pid, false)]
+    );
+}
+
+#[test]
+fn test_column_headers() {
+    let mut proc_widget_state = get_proc_widget_state();
+    proc_widget_state.set_sort_keys(&[
+        (ProcessSorting::User, false),
+        (ProcessSorting::CpuPercent, true),
+    ]);
+
+    let headers = proc_widget_state
+        .columns
+        .get_column_headers(&proc_widget_state.get_sort_keys());
+    assert!(headers.contains(&"User▲".to_string()));
+    assert!(headers.contains(&"CPU%(c)▼2".to_string()));
+    assert!(headers.contains(&"PID(p) ".to_string()));
+}
+
+#[test]
+fn test_multi_key_ordering() {
+    let processes = vec![
+        process(1, None, "init", "root", 1.0),
+        process(2, None, "bash", "alice", 5.0),
+        process(3, None, "sshd", "root", 9.0),
+        process(4, None, "vim", "alice", 5.0),
+    ];
+
+    assert_eq!(
+        sorted_pids(
+            processes.clone(),
+            &[
+                (ProcessSorting::User, false),
+                (ProcessSorting::CpuPercent, true),
+                (ProcessSorting::Pid, true),
+            ]
+        ),
+        vec![4, 2, 3, 1]
+    );
+    assert_eq!(
+        sorted_pids(
+            processes,
+            &[
+                (ProcessSorting::CpuPercent, false),
+                (ProcessSorting::Pid, false)
+            ]
+        ),
+        vec![1, 2, 4, 3]
+    );
+}
+
+#[test]
+fn test_parsing_sort_columns() {
+    assert_eq!(
+        "CPU%".parse::<ProcessSorting>().unwrap(),
+        ProcessSorting::CpuPercent
+    );
+    assert_eq!(
+        "t.write".parse::<ProcessSorting>().unwrap(),
+        ProcessSorting::TotalWrite
+    );
+    assert!("bogus".parse::<ProcessSorting>().is_err());
+}
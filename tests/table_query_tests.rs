@@ -0,0 +1,138 @@
+//! Tests for searching the disk and temperature tables with the query language.
+
+use bottom::app::{
+    data_harvester::{disks::DiskHarvest, temperature::TempHarvest},
+    query::{parse_query, DiskQueryTarget, PrefixSet, Query, TempQueryTarget},
+    DiskWidgetState,
+};
+use bottom::utils::error::BottomError;
+
+fn parse(query: &str, prefix_set: PrefixSet) -> Query {
+    parse_query(query, prefix_set, false, true, false)
+        .unwrap_or_else(|err| panic!("expected {:?} to be valid, got {:?}", query, err))
+}
+
+fn root_disk() -> DiskHarvest {
+    DiskHarvest {
+        name: "/dev/sda1".to_string(),
+        mount_point: "/".to_string(),
+        file_system: "ext4".to_string(),
+        free_space: Some(10_000_000_000),
+        used_space: Some(90_000_000_000),
+        total_space: Some(100_000_000_000),
+    }
+}
+
+fn var_disk() -> DiskHarvest {
+    DiskHarvest {
+        name: "/dev/sdb1".to_string(),
+        mount_point: "/var".to_string(),
+        file_system: "xfs".to_string(),
+        free_space: Some(400_000_000_000),
+        used_space: Some(100_000_000_000),
+        total_space: Some(500_000_000_000),
+    }
+}
+
+fn matching_disks(query: &str) -> Vec<String> {
+    let query = parse(query, PrefixSet::Disk);
+    vec![root_disk(), var_disk()]
+        .into_iter()
+        .filter(|disk| {
+            query.check_target(&DiskQueryTarget {
+                disk,
+                io_rates: Some((2_000_000, 0)),
+            })
+        })
+        .map(|disk| disk.mount_point)
+        .collect()
+}
+
+fn matching_sensors(query: &str) -> Vec<String> {
+    let query = parse(query, PrefixSet::Temp);
+    vec![
+        TempHarvest {
+            name: "k10temp: Tctl".to_string(),
+            temperature: 72.0,
+        },
+        TempHarvest {
+            name: "nvme: Composite".to_string(),
+            temperature: 41.0,
+        },
+    ]
+    .into_iter()
+    .filter(|sensor| query.check_target(&TempQueryTarget { sensor }))
+    .map(|sensor| sensor.name)
+    .collect()
+}
+
+#[test]
+fn test_disk_names() {
+    assert_eq!(matching_disks("sdb"), vec!["/var"]);
+    assert_eq!(matching_disks("var"), vec!["/var"]);
+    assert_eq!(matching_disks("SDA"), vec!["/"]);
+}
+
+#[test]
+fn test_disk_string_prefixes() {
+    assert_eq!(matching_disks("mount=/var"), vec!["/var"]);
+    assert_eq!(matching_disks("fs = ext4"), vec!["/"]);
+    assert_eq!(matching_disks("not fs = ext4"), vec!["/var"]);
+}
+
+#[test]
+fn test_disk_numerical_prefixes() {
+    assert_eq!(matching_disks("used% > 80"), vec!["/"]);
+    assert_eq!(matching_disks("use% <= 20%"), vec!["/var"]);
+    assert_eq!(matching_disks("free < 50 GB"), vec!["/"]);
+    assert_eq!(matching_disks("total >= 0.5tb"), vec!["/var"]);
+    assert_eq!(matching_disks("used = 100gb"), vec!["/var"]);
+    assert_eq!(matching_disks("read > 1 mb/s").len(), 2);
+    assert!(matching_disks("write > 0").is_empty());
+}
+
+#[test]
+fn test_disk_combined() {
+    assert_eq!(matching_disks("used% > 10 and mount = /var"), vec!["/var"]);
+    assert_eq!(matching_disks("fs = xfs or used% > 80").len(), 2);
+}
+
+#[test]
+fn test_temp_prefixes() {
+    assert_eq!(matching_sensors("temp > 70"), vec!["k10temp: Tctl"]);
+    assert_eq!(matching_sensors("nvme"), vec!["nvme: Composite"]);
+    assert_eq!(matching_sensors("tctl or temp < 50").len(), 2);
+}
+
+#[test]
+fn test_process_prefixes_are_names_elsewhere() {
+    // Process prefixes have no meaning for disks, so they're treated as names instead.
+    assert!(matching_disks("cpu").is_empty());
+    assert!(matching_sensors("mount").is_empty());
+}
+
+#[test]
+fn test_invalid_table_queries() {
+    assert!(matches!(
+        parse_query("used% > 80gb", PrefixSet::Disk, false, true, false),
+        Err(BottomError::QueryError(_, Some(span))) if span == (10..12)
+    ));
+    assert!(matches!(
+        parse_query("temp > hot", PrefixSet::Temp, false, true, false),
+        Err(BottomError::QueryError(_, Some(_)))
+    ));
+}
+
+#[test]
+fn test_widget_query_state() {
+    let mut disk_widget_state = DiskWidgetState::init();
+    disk_widget_state.search_state.current_search_query = "used% >".to_string();
+    disk_widget_state.update_query();
+    assert!(disk_widget_state.search_state.is_invalid_search);
+    assert!(disk_widget_state.search_state.get_valid_query().is_none());
+
+    disk_widget_state.search_state.current_search_query = "used% > 50".to_string();
+    disk_widget_state.update_query();
+    assert!(!disk_widget_state.search_state.is_invalid_search);
+    assert!(disk_widget_state.search_state.get_valid_query().is_some());
+}
@@ -0,0 +1,155 @@
+//! Tests for the column-based store behind the charts.
+
+use std::time::{Duration, Instant};
+
+use bottom::app::data_farmer::{TimedData, TimedRange};
+use bottom::app::time_series::{Series, TimeSeriesStore};
+
+fn get_timed_data(cpu_data: Vec<f64>, mem_data: Option<f64>) -> TimedData {
+    TimedData {
+        rx_data: 100.0,
+        tx_data: 50.0,
+        cpu_data,
+        load_avg_data: [0.5, 0.25, 0.125],
+        mem_data,
+        ..TimedData::default()
+    }
+}
+
+#[test]
+fn test_store_rows() {
+    let now = Instant::now();
+    let mut store = TimeSeriesStore::default();
+    store.push(now, &get_timed_data(vec![10.0, 20.0], Some(30.0)));
+    store.push(
+        now + Duration::from_secs(1),
+        &get_timed_data(vec![40.0, 50.0], None),
+    );
+
+    assert_eq!(store.len(), 2);
+    assert_eq!(store.first_instant(), Some(now));
+    let rows = store.rows().collect::<Vec<_>>();
+    assert_eq!(rows[0].1.cpu_data, vec![10.0, 20.0]);
+    assert_eq!(rows[0].1.mem_data, Some(30.0));
+    assert_eq!(rows[0].1.load_avg_data, [0.5, 0.25, 0.125]);
+    assert_eq!(rows[1].1.mem_data, None);
+
+    let (last_instant, last) = store.last().unwrap();
+    assert_eq!(last_instant, now + Duration::from_secs(1));
+    assert_eq!(last.cpu_data, vec![40.0, 50.0]);
+    assert_eq!(last.rx_data, 100.0);
+
+    // Missing values are skipped when reading back a series.
+    let mem = store
+        .points(Series::Mem)
+        .map(|(_instant, value, _range)| value)
+        .collect::<Vec<_>>();
+    assert_eq!(mem, vec![30.0]);
+}
+
+#[test]
+fn test_store_cpu_count_change() {
+    let now = Instant::now();
+    let mut store = TimeSeriesStore::default();
+    store.push(now, &get_timed_data(vec![1.0], None));
+    store.push(
+        now + Duration::from_secs(1),
+        &get_timed_data(vec![2.0, 3.0], None),
+    );
+    store.push(
+        now + Duration::from_secs(2),
+        &get_timed_data(vec![4.0], None),
+    );
+
+    let second_cpu = store
+        .points(Series::Cpu(1))
+        .map(|(instant, value, _range)| (instant, value))
+        .collect::<Vec<_>>();
+    assert_eq!(second_cpu, vec![(now + Duration::from_secs(1), 3.0)]);
+    assert_eq!(store.last().unwrap().1.cpu_data, vec![4.0]);
+    assert_eq!(store.points(Series::Cpu(2)).count(), 0);
+
+    let (last_instant, last_harvest) = store.last_harvest().unwrap();
+    assert_eq!(last_instant, now + Duration::from_secs(2));
+    assert_eq!(last_harvest.cpu_count(), 1);
+    assert_eq!(last_harvest.get(Series::Cpu(0)), 4.0);
+    assert!(last_harvest.get(Series::Cpu(1)).is_nan());
+    assert!(last_harvest.get(Series::Mem).is_nan());
+    assert_eq!(last_harvest.load_avg(), [0.5, 0.25, 0.125]);
+}
+
+#[test]
+fn test_store_remove_and_downsample() {
+    let start = Instant::now();
+    let mut store = TimeSeriesStore::default();
+    for second in 0..10 {
+        store.push(
+            start + Duration::from_secs(second),
+            &get_timed_data(vec![second as f64], None),
+        );
+    }
+
+    store.remove_older_than(start + Duration::from_secs(9), Duration::from_secs(7));
+    assert_eq!(store.first_instant(), Some(start + Duration::from_secs(2)));
+
+    // Seconds 2 through 7 make up two whole buckets, while 8 is too new to be downsampled.
+    store.downsample(start + Duration::from_secs(9), Duration::from_secs(3));
+    let points = store.points(Series::Cpu(0)).collect::<Vec<_>>();
+    assert_eq!(
+        points,
+        vec![
+            (start + Duration::from_secs(3), 3.0, Some((2.0, 4.0))),
+            (start + Duration::from_secs(6), 6.0, Some((5.0, 7.0))),
+            (start + Duration::from_secs(8), 8.0, None),
+            (start + Duration::from_secs(9), 9.0, None),
+        ]
+    );
+
+    let (_instant, bucket) = store.rows().next().unwrap();
+    let range = bucket.range.unwrap();
+    assert_eq!(bucket.cpu_data, vec![3.0]);
+    assert_eq!(range.min.cpu_data, vec![2.0]);
+    assert_eq!(range.max.cpu_data, vec![4.0]);
+}
+
+#[test]
+fn test_store_bucket_after_harvest() {
+    let now = Instant::now();
+    let mut bucket = get_timed_data(vec![5.0], None);
+    bucket.range = Some(Box::new(TimedRange {
+        min: get_timed_data(vec![0.0], None),
+        max: get_timed_data(vec![10.0], None),
+    }));
+
+    let mut store = TimeSeriesStore::default();
+    store.push(now, &get_timed_data(vec![1.0], None));
+    store.push(now + Duration::from_secs(1), &bucket);
+
+    // Buckets have to come first, so this one is only kept as its averages.
+    let (_instant, last) = store.last().unwrap();
+    assert_eq!(last.cpu_data, vec![5.0]);
+    assert!(last.range.is_none());
+}
+
+#[test]
+fn test_store_drops_old_harvests_once_shrunk() {
+    let start = Instant::now();
+    let mut store = TimeSeriesStore::default();
+    for second in 0..3 {
+        store.push(
+            start + Duration::from_secs(second),
+            &get_timed_data(vec![second as f64], None),
+        );
+    }
+
+    // Once the store knows how long to keep harvests, older ones are dropped as more are added.
+    store.shrink(Duration::from_secs(2));
+    store.push(
+        start + Duration::from_secs(3),
+        &get_timed_data(vec![3.0], None),
+    );
+    assert_eq!(store.first_instant(), Some(start + Duration::from_secs(1)));
+
+    let (first, second) = store.harvest_values(Series::Cpu(0));
+    assert_eq!([first, second].concat(), vec![1.0, 2.0, 3.0]);
+}
@@ -0,0 +1,112 @@
+//! Tests for collapsing and expanding branches of the process tree.
+
+use bottom::app::{
+    data_harvester::processes::ProcessSorting, TreeCollapseDefault, TreeCollapseState,
+};
+use bottom::data_conversion::{tree_process_data, ConvertedProcessData};
+
+mod util;
+use util::process;
+
+/// A tree of 1 -> (2 -> 4, 3).
+fn get_processes() -> Vec<ConvertedProcessData> {
+    vec![
+        process(1, None, "proc1", "root", 1.0),
+        process(2, Some(1), "proc2", "root", 2.0),
+        process(3, Some(1), "proc3", "root", 3.0),
+        process(4, Some(2), "proc4", "root", 4.0),
+    ]
+}
+
+fn shown_pids(collapse_state: &TreeCollapseState) -> Vec<u32> {
+    tree_process_data(
+        &get_processes(),
+        false,
+        &[(ProcessSorting::Pid, false)],
+        collapse_state,
+    )
+    .into_iter()
+    .map(|p| p.pid as u32)
+    .collect()
+}
+
+#[test]
+fn test_toggle_branch() {
+    let mut collapse_state = TreeCollapseState::default();
+    assert_eq!(shown_pids(&collapse_state), vec![1, 2, 4, 3]);
+
+    collapse_state.toggle(2, "proc2");
+    assert_eq!(shown_pids(&collapse_state), vec![1, 2, 3]);
+
+    collapse_state.toggle(2, "proc2");
+    assert_eq!(shown_pids(&collapse_state), vec![1, 2, 4, 3]);
+}
+
+#[test]
+fn test_collapse_and_expand_all() {
+    let mut collapse_state = TreeCollapseState::default();
+    collapse_state.toggle(2, "proc2");
+
+    collapse_state.set_default(TreeCollapseDefault::CollapseAll);
+    assert_eq!(shown_pids(&collapse_state), vec![1]);
+
+    // Toggling a branch while everything is collapsed expands just that branch.
+    collapse_state.toggle(1, "proc1");
+    assert_eq!(shown_pids(&collapse_state), vec![1, 2, 3]);
+
+    collapse_state.set_default(TreeCollapseDefault::ExpandAll);
+    assert_eq!(shown_pids(&collapse_state), vec![1, 2, 4, 3]);
+}
+
+#[test]
+fn test_expand_to_depth() {
+    let mut collapse_state = TreeCollapseState::default();
+
+    collapse_state.set_default(TreeCollapseDefault::ExpandToDepth(1));
+    assert_eq!(shown_pids(&collapse_state), vec![1]);
+
+    collapse_state.set_default(TreeCollapseDefault::ExpandToDepth(2));
+    assert_eq!(shown_pids(&collapse_state), vec![1, 2, 3]);
+
+    collapse_state.set_default(TreeCollapseDefault::ExpandToDepth(3));
+    assert_eq!(shown_pids(&collapse_state), vec![1, 2, 4, 3]);
+}
+
+#[test]
+fn test_collapsed_branches_show_subtree_usage() {
+    let mut collapse_state = TreeCollapseState::default();
+    collapse_state.toggle(1, "proc1");
+
+    let processes = tree_process_data(
+        &get_processes(),
+        false,
+        &[(ProcessSorting::Pid, false)],
+        &collapse_state,
+    );
+    assert_eq!(processes.len(), 1);
+    assert!(processes[0].is_collapsed_entry);
+    assert!((processes[0].cpu_percent_usage - 10.0).abs() < f64::EPSILON);
+
+    // Leaves can't be collapsed, so they aren't marked as such.
+    collapse_state.set_default(TreeCollapseDefault::CollapseAll);
+    collapse_state.toggle(1, "proc1");
+    let processes = tree_process_data(
+        &get_processes(),
+        false,
+        &[(ProcessSorting::Pid, false)],
+        &collapse_state,
+    );
+    let leaf = processes.iter().find(|p| p.pid == 3).unwrap();
+    assert!(!leaf.is_collapsed_entry);
+}
+
+#[test]
+fn test_reused_pid_does_not_inherit_state() {
+    let mut collapse_state = TreeCollapseState::default();
+    collapse_state.toggle(2, "proc2");
+    assert!(collapse_state.is_collapsed(2, "proc2", 1));
+    assert!(!collapse_state.is_collapsed(2, "something_else", 1));
+
+    collapse_state.retain(|pid, _name| pid != 2);
+    assert!(!collapse_state.is_collapsed(2, "proc2", 1));
+}
@@ -0,0 +1,204 @@
+//! Tests for the per-user resource summary widget.
+
+use std::collections::HashMap;
+
+use bottom::app::{
+    data_harvester::processes::ProcessSorting,
+    query::{parse_query, PrefixSet, ProcessRelations, UserQueryTarget},
+    UserWidgetState,
+};
+use bottom::data_conversion::{sort_user_data, stringify_user_data, ConvertedUserData};
+
+mod util;
+use util::{get_proc_widget_state, process};
+
+fn user(name: &str, process_count: usize, cpu: f64, mem_bytes: u64) -> ConvertedUserData {
+    ConvertedUserData {
+        user: name.to_string(),
+        process_count,
+        cpu_percent_usage: cpu,
+        mem_percent_usage: mem_bytes as f64 / 1_000_000.0,
+        mem_usage_bytes: mem_bytes,
+        rps_f64: 2_000_000.0,
+        wps_f64: 0.0,
+    }
+}
+
+fn get_users() -> Vec<ConvertedUserData> {
+    vec![
+        user("root", 120, 12.5, 400_000),
+        user("alice", 30, 40.0, 200_000),
+        user("Bob", 5, 1.0, 900_000),
+    ]
+}
+
+fn sorted_names(sort_type: ProcessSorting, is_sort_descending: bool) -> Vec<String> {
+    let mut users = get_users();
+    sort_user_data(&mut users, &sort_type, is_sort_descending);
+    users.into_iter().map(|user| user.user).collect()
+}
+
+fn matching_users(query: &str) -> Vec<String> {
+    let query = parse_query(query, PrefixSet::User, false, true, false)
+        .unwrap_or_else(|err| panic!("expected {:?} to be valid, got {:?}", query, err));
+    get_users()
+        .into_iter()
+        .filter(|user| query.check_target(&UserQueryTarget { user }))
+        .map(|user| user.user)
+        .collect()
+}
+
+#[cfg(target_family = "unix")]
+#[test]
+fn test_convert_user_data() {
+    use bottom::app::data_farmer::DataCollection;
+    use bottom::app::data_harvester::processes::{ProcessHarvest, UserTable};
+    use bottom::data_conversion::convert_user_data;
+
+    fn process(uid: Option<u32>, cpu: f64, mem_bytes: u64) -> ProcessHarvest {
+        ProcessHarvest {
+            uid,
+            cpu_usage_percent: cpu,
+            mem_usage_bytes: mem_bytes,
+            read_bytes_per_sec: 100,
+            ..Default::default()
+        }
+    }
+
+    let data = DataCollection {
+        process_harvest: vec![
+            process(Some(50_000), 1.0, 10),
+            process(Some(50_001), 2.0, 20),
+            process(Some(50_000), 3.0, 30),
+            process(None, 4.0, 40),
+        ],
+        ..Default::default()
+    };
+    let mut user_table = UserTable::default();
+    user_table
+        .uid_user_mapping
+        .insert(50_000, "alice".to_string());
+    user_table
+        .uid_user_mapping
+        .insert(50_001, "bob".to_string());
+
+    let mut users = convert_user_data(&data, &mut user_table);
+    sort_user_data(&mut users, &ProcessSorting::User, false);
+    let users = users
+        .into_iter()
+        .map(|user| {
+            (
+                user.user,
+                user.process_count,
+                user.cpu_percent_usage,
+                user.mem_usage_bytes,
+                user.rps_f64,
+            )
+        })
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        users,
+        vec![
+            ("alice".to_string(), 2, 4.0, 40, 200.0),
+            ("bob".to_string(), 1, 2.0, 20, 100.0),
+            ("N/A".to_string(), 1, 4.0, 40, 100.0),
+        ]
+    );
+}
+
+#[test]
+fn test_stringify_user_data() {
+    assert_eq!(
+        stringify_user_data(&user("root", 120, 12.5, 4 * 1024 * 1024)),
+        vec!["root", "120", "12.5%", "4MiB", "4.2%", "2MB/s", "0B/s"]
+    );
+}
+
+#[test]
+fn test_sort_user_data() {
+    assert_eq!(
+        sorted_names(ProcessSorting::CpuPercent, true),
+        vec!["alice", "root", "Bob"]
+    );
+    assert_eq!(
+        sorted_names(ProcessSorting::Count, false),
+        vec!["Bob", "alice", "root"]
+    );
+    assert_eq!(
+        sorted_names(ProcessSorting::Mem, true),
+        vec!["Bob", "root", "alice"]
+    );
+    // Names sort case-insensitively.
+    assert_eq!(
+        sorted_names(ProcessSorting::User, false),
+        vec!["alice", "Bob", "root"]
+    );
+}
+
+#[test]
+fn test_user_query() {
+    assert_eq!(matching_users("root"), vec!["root"]);
+    assert_eq!(matching_users("user = alice"), vec!["alice"]);
+    assert_eq!(matching_users("count > 10"), vec!["root", "alice"]);
+    assert_eq!(matching_users("cpu >= 12.5"), vec!["root", "alice"]);
+    assert_eq!(matching_users("memb > 300 kb"), vec!["root", "Bob"]);
+    assert_eq!(
+        matching_users("read > 1 mb/s and not user = bob"),
+        vec!["root", "alice"]
+    );
+}
+
+#[test]
+fn test_user_sort_columns() {
+    let mut state = UserWidgetState::init();
+    assert_eq!(state.sort_type, ProcessSorting::CpuPercent);
+    assert!(state.is_sort_descending);
+
+    // Sorting by the same column again reverses it.
+    state.set_sort_type(ProcessSorting::CpuPercent);
+    assert!(!state.is_sort_descending);
+
+    state.set_sort_type(ProcessSorting::User);
+    assert_eq!(state.sort_type, ProcessSorting::User);
+    assert!(state.is_sort_descending);
+
+    // Can't move past the first column.
+    state.move_sort_column(true);
+    assert_eq!(state.sort_type, ProcessSorting::User);
+
+    state.move_sort_column(false);
+    assert_eq!(state.sort_type, ProcessSorting::Count);
+    assert!(state.is_sort_descending);
+    assert_eq!(state.get_column_headers()[1], "Count▼");
+}
+
+#[test]
+fn test_search_for_user_is_exact() {
+    let processes = [
+        process(1, None, "init", "root", 0.0),
+        process(2, None, "sshd", "chroot", 0.0),
+        process(3, None, "bash", "root2", 0.0),
+        process(4, None, "vim", "a.b", 0.0),
+        process(5, None, "vim", "axb", 0.0),
+    ];
+    let matching_pids = |user: &str, is_using_regex: bool| {
+        let mut state = get_proc_widget_state();
+        if is_using_regex {
+            state.process_search_state.search_toggle_regex();
+        }
+        state.search_for_user(user);
+        let query = state.process_search_state.search_state.query.unwrap();
+        let no_processes = HashMap::new();
+        let relations = ProcessRelations::new(&no_processes);
+        processes
+            .iter()
+            .filter(|process| query.check(process, false, &relations))
+            .map(|process| process.pid)
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(matching_pids("root", false), vec![1]);
+    assert_eq!(matching_pids("root", true), vec![1]);
+    assert_eq!(matching_pids("a.b", true), vec![4]);
+}
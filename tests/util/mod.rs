@@ -0,0 +1,60 @@
+//! Helpers shared by the integration tests.  Not every test uses all of them.
+
+#![allow(dead_code)]
+
+use std::collections::HashMap;
+
+use bottom::app::ProcWidgetState;
+use bottom::data_conversion::ConvertedProcessData;
+use bottom::Pid;
+
+/// A process widget with every option off.
+pub fn get_proc_widget_state() -> ProcWidgetState {
+    ProcWidgetState::init(false, false, false, false, false, false, false)
+}
+
+/// A process run from `/usr/bin` by `user`, with everything not given left blank.
+pub fn process(
+    pid: Pid, ppid: Option<Pid>, name: &str, user: &str, cpu: f64,
+) -> ConvertedProcessData {
+    ConvertedProcessData {
+        pid,
+        ppid,
+        name: name.to_string(),
+        command: format!("/usr/bin/{}", name),
+        user: Some(user.to_string()),
+        cpu_percent_usage: cpu,
+        ..Default::default()
+    }
+}
+
+/// A small tree of processes, keyed by PID:
+///
+/// - 1 `init`, run by root
+///   - 2 `sshd`, run by root
+///     - 3 `bash`, run by alice on pts/0
+///       - 4 `vim`, run by alice on pts/0
+///     - 5 `bash`, run by bob on pts/1
+///
+/// Each uses as much CPU as its PID, 1 KiB of memory, and reads 10 bytes a second, and is in
+/// its user's cgroup.
+pub fn get_process_tree() -> HashMap<Pid, ConvertedProcessData> {
+    let process = |pid, ppid, name, user, tty: Option<&str>| ConvertedProcessData {
+        tty: tty.map(|tty| tty.to_string()),
+        cgroup: Some(format!("/user.slice/{}.scope", user)),
+        mem_usage_bytes: 1024,
+        rps_f64: 10.0,
+        ..process(pid, Some(ppid), name, user, pid as f64)
+    };
+
+    vec![
+        process(1, 0, "init", "root", None),
+        process(2, 1, "sshd", "root", None),
+        process(3, 2, "bash", "alice", Some("pts/0")),
+        process(4, 3, "vim", "alice", Some("pts/0")),
+        process(5, 2, "bash", "bob", Some("pts/1")),
+    ]
+    .into_iter()
+    .map(|process| (process.pid, process))
+    .collect()
+}